@@ -68,6 +68,14 @@ pub struct Parameters {
     /// Tonic network settings.
     #[serde(default = "TonicParameters::default")]
     pub tonic: TonicParameters,
+
+    /// Leader schedule settings.
+    ///
+    /// NOTE: unlike other fields, these settings must be identical across all authorities of the
+    /// committee. Authorities electing different leaders can commit different sequences. Only
+    /// change them on networks where every authority is configured the same way.
+    #[serde(default = "LeaderScheduleParameters::default")]
+    pub leader_schedule: LeaderScheduleParameters,
 }
 
 impl Parameters {
@@ -140,6 +148,7 @@ impl Default for Parameters {
             commit_sync_batches_ahead: Parameters::default_commit_sync_batches_ahead(),
             anemo: AnemoParameters::default(),
             tonic: TonicParameters::default(),
+            leader_schedule: LeaderScheduleParameters::default(),
         }
     }
 }
@@ -201,3 +210,89 @@ impl Default for TonicParameters {
         }
     }
 }
+
+/// Strategies to elect leaders, and to adjust the election based on past leader performance.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderScheduleStrategyType {
+    /// Stake weighted election which never changes during the epoch.
+    #[default]
+    StakeBased,
+    /// Stake weighted election, where authorities with the least committed leaders in the last
+    /// schedule have their leader slots given to the authorities with the most.
+    ReputationSwap,
+    /// Election weighted by stake multiplied by the leader success rate of each authority,
+    /// exponentially decayed across schedules.
+    DecayedSuccessRate,
+    /// Stake weighted election, excluding authorities whose last leader slots were repeatedly
+    /// skipped, usually because of leader timeouts.
+    TimeoutExclusion,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LeaderScheduleParameters {
+    /// The strategy used to elect leaders.
+    ///
+    /// If unspecified, this will default to `stake_based`.
+    #[serde(default)]
+    pub strategy: LeaderScheduleStrategyType,
+
+    /// Number of commits between updates of the leader schedule. Must be positive, except with
+    /// `stake_based` which does not use it.
+    ///
+    /// If unspecified, this will default to 300.
+    #[serde(default = "LeaderScheduleParameters::default_commits_per_schedule")]
+    pub commits_per_schedule: u64,
+
+    /// Percentage of total stake, in range [0, 33], with their leader slots swapped by
+    /// `reputation_swap`.
+    ///
+    /// If unspecified, this will default to 33.
+    #[serde(default = "LeaderScheduleParameters::default_swap_stake_threshold")]
+    pub swap_stake_threshold: u64,
+
+    /// Percentage in range [0, 100] of the previous success rate kept at each schedule update
+    /// by `decayed_success_rate`. Higher values make the schedule react slower.
+    ///
+    /// If unspecified, this will default to 50.
+    #[serde(default = "LeaderScheduleParameters::default_success_rate_decay")]
+    pub success_rate_decay: u64,
+
+    /// Number of consecutive skipped leader slots after which `timeout_exclusion` excludes an
+    /// authority from the next schedule.
+    ///
+    /// If unspecified, this will default to 3.
+    #[serde(default = "LeaderScheduleParameters::default_max_consecutive_skipped_slots")]
+    pub max_consecutive_skipped_slots: u64,
+}
+
+impl LeaderScheduleParameters {
+    fn default_commits_per_schedule() -> u64 {
+        300
+    }
+
+    fn default_swap_stake_threshold() -> u64 {
+        33
+    }
+
+    fn default_success_rate_decay() -> u64 {
+        50
+    }
+
+    fn default_max_consecutive_skipped_slots() -> u64 {
+        3
+    }
+}
+
+impl Default for LeaderScheduleParameters {
+    fn default() -> Self {
+        Self {
+            strategy: LeaderScheduleStrategyType::default(),
+            commits_per_schedule: LeaderScheduleParameters::default_commits_per_schedule(),
+            swap_stake_threshold: LeaderScheduleParameters::default_swap_stake_threshold(),
+            success_rate_decay: LeaderScheduleParameters::default_success_rate_decay(),
+            max_consecutive_skipped_slots:
+                LeaderScheduleParameters::default_max_consecutive_skipped_slots(),
+        }
+    }
+}
@@ -14,13 +14,14 @@ use crate::{
     block_manager::BlockManager,
     block_verifier::SignedBlockVerifier,
     broadcaster::Broadcaster,
+    commit_archive::CommitArchiver,
     commit_observer::CommitObserver,
     commit_syncer::{CommitSyncer, CommitVoteMonitor},
     context::{Clock, Context},
     core::{Core, CoreSignals},
     core_thread::{ChannelCoreThreadDispatcher, CoreThreadHandle},
     dag_state::DagState,
-    leader_schedule::{LeaderSchedule, LeaderSwapTable},
+    leader_schedule::LeaderSchedule,
     leader_timeout::{LeaderTimeoutTask, LeaderTimeoutTaskHandle},
     metrics::initialise_metrics,
     network::{
@@ -123,6 +124,7 @@ where
     commit_syncer: CommitSyncer<N::Client>,
     leader_timeout_handle: LeaderTimeoutTaskHandle,
     core_thread_handle: CoreThreadHandle,
+    commit_archiver: Option<CommitArchiver>,
     // Only one of broadcaster and subscriber gets created, depending on
     // if streaming is supported.
     broadcaster: Option<Broadcaster>,
@@ -144,7 +146,7 @@ where
         protocol_keypair: ProtocolKeyPair,
         network_keypair: NetworkKeyPair,
         transaction_verifier: Arc<dyn TransactionVerifier>,
-        commit_consumer: CommitConsumer,
+        mut commit_consumer: CommitConsumer,
         registry: Registry,
     ) -> Self {
         info!(
@@ -201,16 +203,23 @@ where
         let block_manager =
             BlockManager::new(context.clone(), dag_state.clone(), block_verifier.clone());
 
-        let commit_observer = CommitObserver::new(
+        let commit_archiver = commit_consumer.archive_store.take().map(|archive_store| {
+            CommitArchiver::start(context.clone(), archive_store, store.clone())
+        });
+
+        let mut commit_observer = CommitObserver::new(
             context.clone(),
             commit_consumer,
             dag_state.clone(),
             store.clone(),
         );
+        if let Some(commit_archiver) = &commit_archiver {
+            commit_observer.set_archiver(commit_archiver.client());
+        }
 
-        let leader_schedule = Arc::new(LeaderSchedule::new(
+        let leader_schedule = Arc::new(LeaderSchedule::from_store(
             context.clone(),
-            LeaderSwapTable::default(),
+            dag_state.clone(),
         ));
 
         let core = Core::new(
@@ -288,6 +297,7 @@ where
             commit_syncer,
             leader_timeout_handle,
             core_thread_handle,
+            commit_archiver,
             broadcaster,
             subscriber,
             network_manager,
@@ -307,6 +317,10 @@ where
         // Shutdown Core to stop block productions and broadcast.
         // When using streaming, all subscribers to broadcasted blocks stop after this.
         self.core_thread_handle.stop().await;
+        // Core owned the last client of the archiver, so it can finish uploading now.
+        if let Some(commit_archiver) = self.commit_archiver.take() {
+            commit_archiver.stop().await;
+        }
         if let Some(mut broadcaster) = self.broadcaster.take() {
             broadcaster.stop();
         }
@@ -397,7 +397,6 @@ impl Display for BaseCommitter {
 #[cfg(test)]
 mod base_committer_builder {
     use super::*;
-    use crate::leader_schedule::LeaderSwapTable;
 
     pub(crate) struct BaseCommitterBuilder {
         context: Arc<Context>,
@@ -444,10 +443,7 @@ mod base_committer_builder {
             };
             BaseCommitter::new(
                 self.context.clone(),
-                Arc::new(LeaderSchedule::new(
-                    self.context,
-                    LeaderSwapTable::default(),
-                )),
+                Arc::new(LeaderSchedule::new(self.context)),
                 self.dag_state,
                 options,
             )
@@ -13,6 +13,7 @@ use bytes::Bytes;
 use consensus_config::{AuthorityIndex, DefaultHashFunction, DIGEST_LENGTH};
 use enum_dispatch::enum_dispatch;
 use fastcrypto::hash::{Digest, HashFunction as _};
+use object_store::DynObjectStore;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::UnboundedSender;
 
@@ -391,6 +392,8 @@ pub struct CommitConsumer {
     // First commit in the replayed sequence will have index last_processed_commit_index + 1.
     // Set 0 to replay from the start (as generated commit sequence starts at index = 1).
     pub last_processed_commit_index: CommitIndex,
+    // When set, committed sub-dags are also archived into this object store.
+    pub(crate) archive_store: Option<Arc<DynObjectStore>>,
 }
 
 impl CommitConsumer {
@@ -403,8 +406,16 @@ impl CommitConsumer {
             sender,
             last_processed_commit_round,
             last_processed_commit_index,
+            archive_store: None,
         }
     }
+
+    /// Archives committed sub-dags into the object store, in addition to sending them
+    /// to the consumer. See `commit_archive` for the archive format.
+    pub fn with_archive_store(mut self, archive_store: Arc<DynObjectStore>) -> Self {
+        self.archive_store = Some(archive_store);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -0,0 +1,779 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Archival of committed sub-dags to an object store.
+//!
+//! Blocks are garbage collected from the consensus store, so the archive is the only
+//! long term record of what consensus committed. It complements the checkpoint archive
+//! written by `sui-archival`.
+//!
+//! Archive layout:
+//! ```text
+//! - epoch_0/
+//!    - 1.cmt
+//!    - 1001.cmt
+//!    - ...
+//! - epoch_1/
+//!    - 1.cmt
+//!    - ...
+//! ```
+//! Each file is named after the index of the first commit it contains, and holds a
+//! contiguous range of commits of the epoch.
+//!
+//! File format:
+//! ```text
+//! ┌──────────────────────────────┐
+//! │       magic <4 byte>         │
+//! ├──────────────────────────────┤
+//! │  format version <1 byte>     │
+//! ├──────────────────────────────┤
+//! │  bcs(Vec<ArchivedCommit>)    │
+//! ├──────────────────────────────┤
+//! │  checksum <32 byte>          │
+//! └──────────────────────────────┘
+//! ```
+//! The checksum is the Blake2b256 digest of all preceding bytes of the file.
+
+use std::{collections::BTreeMap, ops::RangeInclusive, sync::Arc, time::Duration};
+
+use bytes::{BufMut as _, Bytes, BytesMut};
+use consensus_config::{DefaultHashFunction, Epoch, DIGEST_LENGTH};
+use fastcrypto::hash::HashFunction as _;
+use object_store::{path::Path, DynObjectStore};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::mpsc::{self, error::TrySendError},
+    task::JoinHandle,
+    time::{Instant, MissedTickBehavior},
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    block::{BlockAPI as _, BlockRef, BlockTimestampMs, SignedBlock, VerifiedBlock},
+    commit::{CommitAPI as _, CommitIndex, CommittedSubDag, TrustedCommit, GENESIS_COMMIT_INDEX},
+    context::Context,
+    dag_inspector::ReputationScoresSummary,
+    error::{ConsensusError, ConsensusResult},
+    leader_scoring::ReputationScores,
+    storage::Store,
+};
+
+/// Identifies a commit archive file.
+const COMMIT_ARCHIVE_FILE_MAGIC: u32 = 0x00C0_3317;
+/// Version of the file format. Must be bumped on any change to `ArchivedCommit`.
+const COMMIT_ARCHIVE_FORMAT_VERSION: u8 = 1;
+const COMMIT_ARCHIVE_FILE_SUFFIX: &str = "cmt";
+const EPOCH_DIR_PREFIX: &str = "epoch_";
+
+/// Maximum number of commits written into a single archive file.
+const MAX_COMMITS_PER_FILE: usize = 1000;
+/// Maximum time commits are buffered before being uploaded, even if a file is not full.
+const MAX_FILE_INTERVAL: Duration = Duration::from_secs(60);
+/// Capacity of the channel from `CommitObserver` to the archiver. Commits that do not
+/// fit are read back from the store by the archiver.
+const ARCHIVER_CHANNEL_CAPACITY: usize = 10_000;
+/// Maximum number of commits waiting to be uploaded. Once reached, e.g. while the archive
+/// store is down, received commits are dropped and read back from the store once uploads
+/// succeed again.
+const MAX_PENDING_COMMITS: usize = 10 * MAX_COMMITS_PER_FILE;
+/// Number of attempts to upload a file before giving up until the next flush.
+const MAX_UPLOAD_ATTEMPTS: u32 = 3;
+const UPLOAD_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// A committed sub-dag as stored in the commit archive.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedCommit {
+    /// Index of the commit.
+    pub commit_index: CommitIndex,
+    /// A reference to the leader of the sub-dag.
+    pub leader: BlockRef,
+    /// The timestamp of the commit, obtained from the timestamp of the leader block.
+    pub timestamp_ms: BlockTimestampMs,
+    /// Serialized signed blocks of the sub-dag, in commit order.
+    pub blocks: Vec<Bytes>,
+    /// Reputation scores of the leader schedule the commit was decided under. None for
+    /// commits the archiver had to read back from the store, where they are not available.
+    pub reputation_scores: Option<ReputationScoresSummary>,
+}
+
+impl ArchivedCommit {
+    pub(crate) fn new(
+        sub_dag: &CommittedSubDag,
+        reputation_scores: Option<&ReputationScores>,
+    ) -> Self {
+        Self {
+            commit_index: sub_dag.commit_index,
+            leader: sub_dag.leader,
+            timestamp_ms: sub_dag.timestamp_ms,
+            blocks: sub_dag
+                .blocks
+                .iter()
+                .map(|block| block.serialized().clone())
+                .collect(),
+            reputation_scores: reputation_scores.map(|scores| ReputationScoresSummary {
+                commit_range: (scores.commit_range.start(), scores.commit_range.end()),
+                scores_per_authority: scores.scores_per_authority.clone(),
+            }),
+        }
+    }
+
+    /// Rebuilds the committed sub-dag from the archived blocks. Block signatures are not
+    /// verified again, the archive is trusted once its checksum has been checked.
+    pub fn to_committed_sub_dag(&self) -> ConsensusResult<CommittedSubDag> {
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|serialized| {
+                let signed_block: SignedBlock =
+                    bcs::from_bytes(serialized).map_err(ConsensusError::MalformedBlock)?;
+                Ok(VerifiedBlock::new_verified(
+                    signed_block,
+                    serialized.clone(),
+                ))
+            })
+            .collect::<ConsensusResult<Vec<_>>>()?;
+        if !blocks.iter().any(|block| block.reference() == self.leader) {
+            return Err(ConsensusError::MalformedCommitArchive(format!(
+                "leader {} of commit {} is not among its blocks",
+                self.leader, self.commit_index
+            )));
+        }
+        Ok(CommittedSubDag::new(
+            self.leader,
+            blocks,
+            self.timestamp_ms,
+            self.commit_index,
+        ))
+    }
+}
+
+/// Serializes commits into the archive file format.
+fn encode_archive_file(commits: &[ArchivedCommit]) -> ConsensusResult<Bytes> {
+    let payload = bcs::to_bytes(commits).map_err(ConsensusError::SerializationFailure)?;
+    let mut buf = BytesMut::with_capacity(4 + 1 + payload.len() + DIGEST_LENGTH);
+    buf.put_u32(COMMIT_ARCHIVE_FILE_MAGIC);
+    buf.put_u8(COMMIT_ARCHIVE_FORMAT_VERSION);
+    buf.put_slice(&payload);
+    let checksum = DefaultHashFunction::digest(&buf);
+    buf.put_slice(checksum.as_ref());
+    Ok(buf.freeze())
+}
+
+/// Verifies and deserializes a file in the archive file format.
+pub fn decode_archive_file(bytes: &[u8]) -> ConsensusResult<Vec<ArchivedCommit>> {
+    let malformed = |reason: &str| ConsensusError::MalformedCommitArchive(reason.to_string());
+    if bytes.len() < 4 + 1 + DIGEST_LENGTH {
+        return Err(malformed("file is too short"));
+    }
+    let (content, checksum) = bytes.split_at(bytes.len() - DIGEST_LENGTH);
+    if DefaultHashFunction::digest(content).as_ref() != checksum {
+        return Err(malformed("checksum mismatch"));
+    }
+    let magic = u32::from_be_bytes(content[0..4].try_into().unwrap());
+    if magic != COMMIT_ARCHIVE_FILE_MAGIC {
+        return Err(malformed(&format!("unexpected magic {magic:#x}")));
+    }
+    let version = content[4];
+    if version != COMMIT_ARCHIVE_FORMAT_VERSION {
+        return Err(malformed(&format!("unsupported format version {version}")));
+    }
+    bcs::from_bytes(&content[5..]).map_err(|e| malformed(&e.to_string()))
+}
+
+fn epoch_dir(epoch: Epoch) -> Path {
+    Path::from(format!("{EPOCH_DIR_PREFIX}{epoch}"))
+}
+
+fn archive_file_path(epoch: Epoch, first_commit_index: CommitIndex) -> Path {
+    epoch_dir(epoch).child(format!("{first_commit_index}.{COMMIT_ARCHIVE_FILE_SUFFIX}"))
+}
+
+fn store_failure(e: object_store::Error) -> ConsensusError {
+    ConsensusError::CommitArchiveStoreFailure(e.to_string())
+}
+
+/// Reads commits back from a commit archive.
+pub struct CommitArchiveReader {
+    archive_store: Arc<DynObjectStore>,
+}
+
+impl CommitArchiveReader {
+    pub fn new(archive_store: Arc<DynObjectStore>) -> Self {
+        Self { archive_store }
+    }
+
+    /// Returns the epochs present in the archive, in ascending order.
+    pub async fn epochs(&self) -> ConsensusResult<Vec<Epoch>> {
+        let entries = self
+            .archive_store
+            .list_with_delimiter(None)
+            .await
+            .map_err(store_failure)?;
+        let mut epochs: Vec<Epoch> = entries
+            .common_prefixes
+            .iter()
+            .filter_map(|dir| dir.filename()?.strip_prefix(EPOCH_DIR_PREFIX)?.parse().ok())
+            .collect();
+        epochs.sort();
+        Ok(epochs)
+    }
+
+    /// Returns the archive files of an epoch, keyed by the index of their first commit.
+    pub async fn files(&self, epoch: Epoch) -> ConsensusResult<BTreeMap<CommitIndex, Path>> {
+        let entries = self
+            .archive_store
+            .list_with_delimiter(Some(&epoch_dir(epoch)))
+            .await
+            .map_err(store_failure)?;
+        Ok(entries
+            .objects
+            .into_iter()
+            .filter_map(|object| {
+                let first_commit_index = object
+                    .location
+                    .filename()?
+                    .strip_suffix(COMMIT_ARCHIVE_FILE_SUFFIX)?
+                    .strip_suffix('.')?
+                    .parse()
+                    .ok()?;
+                Some((first_commit_index, object.location))
+            })
+            .collect())
+    }
+
+    /// Reads a single archive file, verifying its checksum.
+    pub async fn read_file(&self, path: &Path) -> ConsensusResult<Vec<ArchivedCommit>> {
+        let bytes = self
+            .archive_store
+            .get(path)
+            .await
+            .map_err(store_failure)?
+            .bytes()
+            .await
+            .map_err(store_failure)?;
+        decode_archive_file(&bytes).map_err(|e| match e {
+            ConsensusError::MalformedCommitArchive(reason) => {
+                ConsensusError::MalformedCommitArchive(format!("{path}: {reason}"))
+            }
+            e => e,
+        })
+    }
+
+    /// Returns the archived commits of an epoch within the index range, in order. The result
+    /// stops at the last archived commit if the archive does not cover the whole range yet.
+    /// Fails if a commit is missing in between.
+    pub async fn read_commits(
+        &self,
+        epoch: Epoch,
+        range: RangeInclusive<CommitIndex>,
+    ) -> ConsensusResult<Vec<ArchivedCommit>> {
+        let files = self.files(epoch).await?;
+        // Start from the last file beginning at or before the range, which may contain its
+        // first commits.
+        let first_file = files
+            .range(..=*range.start())
+            .next_back()
+            .map(|(index, _)| *index)
+            .unwrap_or(*range.start());
+
+        let mut result: Vec<ArchivedCommit> = vec![];
+        let mut next_index = *range.start();
+        for path in files.range(first_file..=*range.end()).map(|(_, path)| path) {
+            for commit in self.read_file(path).await? {
+                // Overlapping files can be written if the archiver could not find out where
+                // the archive ended when it started.
+                if commit.commit_index < next_index {
+                    continue;
+                }
+                if commit.commit_index > *range.end() {
+                    break;
+                }
+                if commit.commit_index != next_index {
+                    return Err(ConsensusError::MalformedCommitArchive(format!(
+                        "commit {next_index} of epoch {epoch} is missing from the archive"
+                    )));
+                }
+                next_index += 1;
+                result.push(commit);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Writes committed sub-dags of the current epoch to the commit archive.
+///
+/// Commits are handed over by `CommitObserver` through a bounded channel and never block
+/// it. Gaps, from commits that did not fit into the channel or were committed before the
+/// archiver started, are filled by reading the commits back from the store.
+pub(crate) struct CommitArchiver {
+    sender: mpsc::Sender<ArchivedCommit>,
+    join_handle: JoinHandle<()>,
+}
+
+impl CommitArchiver {
+    pub(crate) fn start(
+        context: Arc<Context>,
+        archive_store: Arc<DynObjectStore>,
+        store: Arc<dyn Store>,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel(ARCHIVER_CHANNEL_CAPACITY);
+        let writer = CommitArchiveWriter {
+            context,
+            archive_store,
+            store,
+            receiver,
+            next_index: None,
+            pending: vec![],
+        };
+        let join_handle = tokio::spawn(writer.run());
+        Self {
+            sender,
+            join_handle,
+        }
+    }
+
+    pub(crate) fn client(&self) -> CommitArchiverClient {
+        CommitArchiverClient {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Waits for all commits to be uploaded. All clients must have been dropped, otherwise
+    /// this waits forever.
+    pub(crate) async fn stop(self) {
+        drop(self.sender);
+        self.join_handle.await.ok();
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct CommitArchiverClient {
+    sender: mpsc::Sender<ArchivedCommit>,
+}
+
+impl CommitArchiverClient {
+    /// Hands a committed sub-dag over to the archiver without blocking. Returns false if
+    /// the archiver is lagging and the commit was not accepted.
+    pub(crate) fn archive(
+        &self,
+        sub_dag: &CommittedSubDag,
+        reputation_scores: &ReputationScores,
+    ) -> bool {
+        match self
+            .sender
+            .try_send(ArchivedCommit::new(sub_dag, Some(reputation_scores)))
+        {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => false,
+            // The archiver only stops after consensus has shut down.
+            Err(TrySendError::Closed(_)) => true,
+        }
+    }
+}
+
+struct CommitArchiveWriter {
+    context: Arc<Context>,
+    archive_store: Arc<DynObjectStore>,
+    store: Arc<dyn Store>,
+    receiver: mpsc::Receiver<ArchivedCommit>,
+    // Index of the next commit to archive. None when it could not be determined from the
+    // archive, in which case archiving starts from the first received commit.
+    next_index: Option<CommitIndex>,
+    // Commits waiting to be uploaded, in index order.
+    pending: Vec<ArchivedCommit>,
+}
+
+impl CommitArchiveWriter {
+    async fn run(mut self) {
+        self.next_index = self.find_resume_index().await;
+        info!(
+            "Commit archiver started for epoch {}, next commit to archive: {:?}",
+            self.context.committee.epoch(),
+            self.next_index
+        );
+
+        let mut flush_timer =
+            tokio::time::interval_at(Instant::now() + MAX_FILE_INTERVAL, MAX_FILE_INTERVAL);
+        flush_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                commit = self.receiver.recv() => {
+                    let Some(commit) = commit else {
+                        break;
+                    };
+                    self.append(commit).await;
+                    self.flush(false).await;
+                }
+                _ = flush_timer.tick() => {
+                    self.flush(true).await;
+                }
+            }
+        }
+
+        self.flush(true).await;
+        if !self.pending.is_empty() {
+            warn!(
+                "Commit archiver stopped with {} commits not uploaded, starting at commit {}",
+                self.pending.len(),
+                self.pending[0].commit_index
+            );
+        }
+    }
+
+    /// Finds the index following the last archived commit of the epoch.
+    async fn find_resume_index(&self) -> Option<CommitIndex> {
+        let epoch = self.context.committee.epoch();
+        let reader = CommitArchiveReader::new(self.archive_store.clone());
+        let result = async {
+            let Some((_, last_file)) = reader.files(epoch).await?.pop_last() else {
+                return Ok(GENESIS_COMMIT_INDEX + 1);
+            };
+            let last_commit = reader.read_file(&last_file).await?.pop().ok_or_else(|| {
+                ConsensusError::MalformedCommitArchive(format!("{last_file} is empty"))
+            })?;
+            ConsensusResult::Ok(last_commit.commit_index + 1)
+        }
+        .await;
+        match result {
+            Ok(index) => Some(index),
+            Err(e) => {
+                warn!("Failed to find the last archived commit of epoch {epoch}: {e}");
+                None
+            }
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.pending.len() >= MAX_PENDING_COMMITS
+    }
+
+    async fn append(&mut self, commit: ArchivedCommit) {
+        let next_index = *self.next_index.get_or_insert(commit.commit_index);
+        if commit.commit_index < next_index {
+            // Already archived.
+            return;
+        }
+        if self.is_full() {
+            self.context
+                .metrics
+                .node_metrics
+                .commit_archive_dropped_commits
+                .inc();
+            return;
+        }
+        if commit.commit_index > next_index {
+            if let Err(e) = self.backfill(next_index, commit.commit_index).await {
+                warn!("Failed to backfill commits starting at {next_index}: {e}");
+            }
+            if self.next_index != Some(commit.commit_index) {
+                // The commit is read back from the store with the rest of the gap later.
+                return;
+            }
+        }
+        self.next_index = Some(commit.commit_index + 1);
+        self.pending.push(commit);
+    }
+
+    /// Reads commits in [start, end) back from the store, until the pending commits are
+    /// full.
+    async fn backfill(&mut self, start: CommitIndex, end: CommitIndex) -> ConsensusResult<()> {
+        debug!("Backfilling commits [{start}, {end}) into the commit archive");
+        let mut batch_start = start;
+        while batch_start < end && !self.is_full() {
+            let batch_end = end.min(batch_start + MAX_COMMITS_PER_FILE as CommitIndex);
+            let commits = self.store.scan_commits((batch_start..batch_end).into())?;
+            for commit in commits {
+                let commit_index = commit.index();
+                let sub_dag = self.read_sub_dag(commit)?;
+                self.pending.push(ArchivedCommit::new(&sub_dag, None));
+                self.next_index = Some(commit_index + 1);
+                self.context
+                    .metrics
+                    .node_metrics
+                    .commit_archive_backfilled_commits
+                    .inc();
+            }
+            self.flush(false).await;
+            batch_start = batch_end;
+        }
+        Ok(())
+    }
+
+    /// Reads the blocks of a commit from the store.
+    fn read_sub_dag(&self, commit: TrustedCommit) -> ConsensusResult<CommittedSubDag> {
+        let blocks = self
+            .store
+            .read_blocks(commit.blocks())?
+            .into_iter()
+            .zip(commit.blocks())
+            .map(|(block, block_ref)| {
+                block.ok_or(ConsensusError::MissingCommittedBlock(*block_ref))
+            })
+            .collect::<ConsensusResult<Vec<_>>>()?;
+        Ok(CommittedSubDag::new(
+            commit.leader(),
+            blocks,
+            commit.timestamp_ms(),
+            commit.index(),
+        ))
+    }
+
+    /// Uploads full files of pending commits. With `force`, the remaining commits are
+    /// uploaded into a partial file too.
+    async fn flush(&mut self, force: bool) {
+        while self.pending.len() >= MAX_COMMITS_PER_FILE || (force && !self.pending.is_empty()) {
+            let len = self.pending.len().min(MAX_COMMITS_PER_FILE);
+            if let Err(e) = self.upload(&self.pending[..len]).await {
+                warn!(
+                    "Failed to upload commits starting at {} to the commit archive: {e}",
+                    self.pending[0].commit_index
+                );
+                return;
+            }
+            self.pending.drain(..len);
+        }
+    }
+
+    async fn upload(&self, commits: &[ArchivedCommit]) -> ConsensusResult<()> {
+        let metrics = &self.context.metrics.node_metrics;
+        let first_index = commits[0].commit_index;
+        let last_index = commits[commits.len() - 1].commit_index;
+        let path = archive_file_path(self.context.committee.epoch(), first_index);
+        let bytes = encode_archive_file(commits)?;
+        let size = bytes.len();
+
+        let mut attempt = 1;
+        loop {
+            match self.archive_store.put(&path, bytes.clone()).await {
+                Ok(_) => break,
+                Err(e) if attempt < MAX_UPLOAD_ATTEMPTS => {
+                    metrics.commit_archive_upload_failures.inc();
+                    debug!("Attempt {attempt} to upload {path} failed: {e}");
+                    tokio::time::sleep(UPLOAD_RETRY_DELAY * attempt).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    metrics.commit_archive_upload_failures.inc();
+                    return Err(store_failure(e));
+                }
+            }
+        }
+
+        debug!("Uploaded commits [{first_index}, {last_index}] to {path}");
+        metrics.commit_archive_uploaded_files.inc();
+        metrics.commit_archive_uploaded_bytes.inc_by(size as u64);
+        metrics
+            .commit_archive_last_archived_index
+            .set(last_index as i64);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::{
+        block::TestBlock,
+        commit::{CommitDigest, CommitRange, TrustedCommit},
+        storage::{mem_store::MemStore, WriteBatch},
+    };
+
+    /// Writes one single-block commit per index into the store, and returns the sub-dags.
+    fn populate_store(store: &MemStore, num_commits: CommitIndex) -> Vec<CommittedSubDag> {
+        let mut sub_dags = vec![];
+        for index in 1..=num_commits {
+            let leader = VerifiedBlock::new_for_test(
+                TestBlock::new(index, index % 4)
+                    .set_timestamp_ms(index as u64 * 100)
+                    .build(),
+            );
+            let commit = TrustedCommit::new_for_test(
+                index,
+                CommitDigest::MIN,
+                leader.timestamp_ms(),
+                leader.reference(),
+                vec![leader.reference()],
+            );
+            store
+                .write(
+                    WriteBatch::default()
+                        .blocks(vec![leader.clone()])
+                        .commits(vec![commit]),
+                )
+                .unwrap();
+            sub_dags.push(CommittedSubDag::new(
+                leader.reference(),
+                vec![leader.clone()],
+                leader.timestamp_ms(),
+                index,
+            ));
+        }
+        sub_dags
+    }
+
+    fn scores() -> ReputationScores {
+        ReputationScores::new(CommitRange::new(1..10), vec![1, 2, 3, 4])
+    }
+
+    #[test]
+    fn test_archive_file_roundtrip() {
+        let store = MemStore::new();
+        let commits: Vec<_> = populate_store(&store, 3)
+            .iter()
+            .map(|sub_dag| ArchivedCommit::new(sub_dag, Some(&scores())))
+            .collect();
+
+        let bytes = encode_archive_file(&commits).unwrap();
+        assert_eq!(decode_archive_file(&bytes).unwrap(), commits);
+
+        let sub_dag = commits[1].to_committed_sub_dag().unwrap();
+        assert_eq!(sub_dag.commit_index, 2);
+        assert_eq!(sub_dag.leader, commits[1].leader);
+        assert_eq!(sub_dag.blocks.len(), 1);
+
+        // Any corruption is detected.
+        let mut corrupted = bytes.to_vec();
+        corrupted[10] ^= 1;
+        assert!(matches!(
+            decode_archive_file(&corrupted),
+            Err(ConsensusError::MalformedCommitArchive(_))
+        ));
+        assert!(matches!(
+            decode_archive_file(&bytes[..bytes.len() - 1]),
+            Err(ConsensusError::MalformedCommitArchive(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_archiver_backfills_gaps() {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let store = Arc::new(MemStore::new());
+        let archive_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let sub_dags = populate_store(&store, 10);
+
+        let archiver = CommitArchiver::start(context, archive_store.clone(), store.clone());
+        let client = archiver.client();
+        // Commits 1 to 5 were committed before the archiver started, and commit 8 was
+        // not handed over.
+        for sub_dag in &sub_dags[5..] {
+            if sub_dag.commit_index != 8 {
+                assert!(client.archive(sub_dag, &scores()));
+            }
+        }
+        drop(client);
+        archiver.stop().await;
+
+        let reader = CommitArchiveReader::new(archive_store);
+        assert_eq!(reader.epochs().await.unwrap(), vec![0]);
+        let commits = reader.read_commits(0, 1..=10).await.unwrap();
+        assert_eq!(commits.len(), 10);
+        for (commit, sub_dag) in commits.iter().zip(&sub_dags) {
+            assert_eq!(commit.to_committed_sub_dag().unwrap(), *sub_dag);
+            let backfilled = sub_dag.commit_index < 6 || sub_dag.commit_index == 8;
+            assert_eq!(commit.reputation_scores.is_none(), backfilled);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archiver_resumes_from_archive() {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let store = Arc::new(MemStore::new());
+        let archive_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let sub_dags = populate_store(&store, 12);
+
+        let archiver = CommitArchiver::start(context.clone(), archive_store.clone(), store.clone());
+        let client = archiver.client();
+        for sub_dag in &sub_dags[..8] {
+            client.archive(sub_dag, &scores());
+        }
+        drop(client);
+        archiver.stop().await;
+
+        // After a restart, commits are replayed from an earlier index.
+        let archiver = CommitArchiver::start(context, archive_store.clone(), store);
+        let client = archiver.client();
+        for sub_dag in &sub_dags[5..] {
+            client.archive(sub_dag, &scores());
+        }
+        drop(client);
+        archiver.stop().await;
+
+        let reader = CommitArchiveReader::new(archive_store);
+        let files = reader.files(0).await.unwrap();
+        assert_eq!(files.keys().copied().collect::<Vec<_>>(), vec![1, 9]);
+        let commits = reader.read_commits(0, 4..=20).await.unwrap();
+        assert_eq!(
+            commits.iter().map(|c| c.commit_index).collect::<Vec<_>>(),
+            (4..=12).collect::<Vec<_>>()
+        );
+    }
+
+    fn writer(context: Arc<Context>, store: Arc<dyn Store>) -> CommitArchiveWriter {
+        let (_sender, receiver) = mpsc::channel(1);
+        CommitArchiveWriter {
+            context,
+            archive_store: Arc::new(InMemory::new()),
+            store,
+            receiver,
+            next_index: None,
+            pending: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archiver_drops_commits_when_full() {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let store = Arc::new(MemStore::new());
+        let commits: Vec<_> = populate_store(&store, 3)
+            .iter()
+            .map(|sub_dag| ArchivedCommit::new(sub_dag, Some(&scores())))
+            .collect();
+
+        let mut writer = writer(context.clone(), store);
+        writer.next_index = Some(3);
+        writer.pending = vec![commits[1].clone(); MAX_PENDING_COMMITS];
+        writer.append(commits[2].clone()).await;
+        assert_eq!(writer.pending.len(), MAX_PENDING_COMMITS);
+        assert_eq!(writer.next_index, Some(3));
+        assert_eq!(
+            context
+                .metrics
+                .node_metrics
+                .commit_archive_dropped_commits
+                .get(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_archiver_backfill_missing_blocks() {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let store = Arc::new(MemStore::new());
+        let leader = VerifiedBlock::new_for_test(TestBlock::new(1, 0).build());
+        // The commit is stored without its block.
+        store
+            .write(
+                WriteBatch::default().commits(vec![TrustedCommit::new_for_test(
+                    1,
+                    CommitDigest::MIN,
+                    leader.timestamp_ms(),
+                    leader.reference(),
+                    vec![leader.reference()],
+                )]),
+            )
+            .unwrap();
+        let sub_dag = CommittedSubDag::new(leader.reference(), vec![leader], 0, 2);
+
+        let mut writer = writer(context, store);
+        writer.next_index = Some(1);
+        writer
+            .append(ArchivedCommit::new(&sub_dag, Some(&scores())))
+            .await;
+        // Neither commit is archived, and commit 1 is retried with the next gap.
+        assert!(writer.pending.is_empty());
+        assert_eq!(writer.next_index, Some(1));
+    }
+}
@@ -9,6 +9,7 @@ use tokio::sync::mpsc::UnboundedSender;
 use crate::{
     block::{BlockAPI, VerifiedBlock},
     commit::{load_committed_subdag_from_store, CommitAPI, CommitIndex, CommittedSubDag},
+    commit_archive::CommitArchiverClient,
     context::Context,
     dag_state::DagState,
     error::{ConsensusError, ConsensusResult},
@@ -36,6 +37,9 @@ pub(crate) struct CommitObserver {
     sender: UnboundedSender<CommittedSubDag>,
     /// Persistent storage for blocks, commits and other consensus data.
     store: Arc<dyn Store>,
+    dag_state: Arc<RwLock<DagState>>,
+    /// Optionally archives committed sub-dags to an object store.
+    archiver: Option<CommitArchiverClient>,
 }
 
 impl CommitObserver {
@@ -53,18 +57,32 @@ impl CommitObserver {
             commit_interpreter: Linearizer::new(dag_state.clone()),
             sender: commit_consumer.sender,
             store,
+            dag_state,
+            archiver: None,
         };
 
         observer.recover_and_send_commits(commit_consumer.last_processed_commit_index);
         observer
     }
 
+    /// Archives committed sub-dags from now on. Commits sent before, including recovered
+    /// ones, are read back from the store by the archiver.
+    pub(crate) fn set_archiver(&mut self, archiver: CommitArchiverClient) {
+        self.archiver = Some(archiver);
+    }
+
     pub(crate) fn handle_commit(
         &mut self,
         committed_leaders: Vec<VerifiedBlock>,
     ) -> ConsensusResult<Vec<CommittedSubDag>> {
         let committed_sub_dags = self.commit_interpreter.handle_commit(committed_leaders);
         let mut sent_sub_dags = vec![];
+        // The leader schedule only changes after the commits are handled, so these are the
+        // scores all the new commits were decided under.
+        let reputation_scores = self
+            .archiver
+            .as_ref()
+            .map(|_| self.dag_state.read().reputation_scores());
 
         for committed_sub_dag in committed_sub_dags.into_iter() {
             // Failures in sender.send() are assumed to be permanent
@@ -79,6 +97,16 @@ impl CommitObserver {
                 committed_sub_dag.commit_index,
                 committed_sub_dag.leader
             );
+            if let (Some(archiver), Some(reputation_scores)) = (&self.archiver, &reputation_scores)
+            {
+                if !archiver.archive(&committed_sub_dag, reputation_scores) {
+                    self.context
+                        .metrics
+                        .node_metrics
+                        .commit_archive_dropped_commits
+                        .inc();
+                }
+            }
             sent_sub_dags.push(committed_sub_dag);
         }
 
@@ -174,7 +202,7 @@ mod tests {
         commit::DEFAULT_WAVE_LENGTH,
         context::Context,
         dag_state::DagState,
-        leader_schedule::{LeaderSchedule, LeaderSwapTable},
+        leader_schedule::LeaderSchedule,
         storage::mem_store::MemStore,
         test_dag::{build_dag, get_all_uncommitted_leader_blocks},
     };
@@ -189,7 +217,7 @@ mod tests {
             context.clone(),
             mem_store.clone(),
         )));
-        let leader_schedule = LeaderSchedule::new(context.clone(), LeaderSwapTable::default());
+        let leader_schedule = LeaderSchedule::new(context.clone());
         let last_processed_commit_round = 0;
         let last_processed_commit_index = 0;
         let (sender, mut receiver) = unbounded_channel();
@@ -288,7 +316,7 @@ mod tests {
             context.clone(),
             mem_store.clone(),
         )));
-        let leader_schedule = LeaderSchedule::new(context.clone(), LeaderSwapTable::default());
+        let leader_schedule = LeaderSchedule::new(context.clone());
         let last_processed_commit_round = 0;
         let last_processed_commit_index = 0;
         let (sender, mut receiver) = unbounded_channel();
@@ -425,7 +453,7 @@ mod tests {
             context.clone(),
             mem_store.clone(),
         )));
-        let leader_schedule = LeaderSchedule::new(context.clone(), LeaderSwapTable::default());
+        let leader_schedule = LeaderSchedule::new(context.clone());
         let last_processed_commit_round = 0;
         let last_processed_commit_index = 0;
         let (sender, mut receiver) = unbounded_channel();
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(test)]
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Instant, SystemTime};
 
@@ -104,6 +106,10 @@ impl Context {
 /// will remain monotonically increasing.
 pub(crate) struct Clock {
     unix_epoch_instant: Instant,
+    /// When set, the clock reports this timestamp instead of the system time. Allows tests
+    /// to drive time deterministically.
+    #[cfg(test)]
+    manual_timestamp_ms: Option<AtomicU64>,
 }
 
 impl Clock {
@@ -116,12 +122,43 @@ impl Clock {
             };
         let unix_epoch_instant = now.checked_sub(duration_since_unix_epoch).unwrap();
 
-        Self { unix_epoch_instant }
+        Self {
+            unix_epoch_instant,
+            #[cfg(test)]
+            manual_timestamp_ms: None,
+        }
+    }
+
+    /// Creates a clock whose time only changes via `set_timestamp_ms()`.
+    #[cfg(test)]
+    pub(crate) fn new_manual(timestamp_ms: BlockTimestampMs) -> Self {
+        Self {
+            unix_epoch_instant: Instant::now(),
+            manual_timestamp_ms: Some(AtomicU64::new(timestamp_ms)),
+        }
+    }
+
+    /// Sets the time of a manual clock. Time is not allowed to go backwards.
+    #[cfg(test)]
+    pub(crate) fn set_timestamp_ms(&self, timestamp_ms: BlockTimestampMs) {
+        let manual_timestamp_ms = self
+            .manual_timestamp_ms
+            .as_ref()
+            .expect("Only a manual clock can be set");
+        let previous = manual_timestamp_ms.swap(timestamp_ms, Ordering::SeqCst);
+        assert!(
+            previous <= timestamp_ms,
+            "Clock went backwards from {previous} to {timestamp_ms}"
+        );
     }
 
     // Returns the current time expressed as UNIX timestamp in milliseconds.
     // Calculated with Rust Instant to ensure monotonicity.
     pub(crate) fn timestamp_utc_ms(&self) -> BlockTimestampMs {
+        #[cfg(test)]
+        if let Some(manual_timestamp_ms) = &self.manual_timestamp_ms {
+            return manual_timestamp_ms.load(Ordering::SeqCst);
+        }
         Instant::now()
             .checked_duration_since(self.unix_epoch_instant)
             .unwrap()
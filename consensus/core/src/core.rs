@@ -20,6 +20,7 @@ use crate::{
         VerifiedBlock, GENESIS_ROUND,
     },
     block_manager::BlockManager,
+    commit::LeaderStatus,
     commit_observer::CommitObserver,
     context::Context,
     dag_state::DagState,
@@ -441,33 +442,67 @@ impl Core {
             .with_label_values(&["Core::try_commit"])
             .start_timer();
 
+        let mut committed_sub_dags = Vec::new();
         // TODO: Add optimization to abort early without quorum for a round.
-        let sequenced_leaders = self.committer.try_commit(self.last_decided_leader);
+        loop {
+            let commits_until_update = self
+                .leader_schedule
+                .commits_until_leader_schedule_update(self.dag_state.clone());
+            let mut sequenced_leaders = self.committer.try_commit(self.last_decided_leader);
+
+            // Leaders after the last commit of the current schedule have to be decided with the
+            // next schedule, so the sequence is truncated there. The remaining leaders are
+            // decided again after the schedule is updated.
+            let mut schedule_ended = false;
+            let mut truncated = false;
+            if let Some(commits_until_update) = commits_until_update {
+                let last_commit_of_schedule = sequenced_leaders
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, leader)| matches!(leader, LeaderStatus::Commit(_)))
+                    .nth(commits_until_update.saturating_sub(1))
+                    .map(|(position, _)| position);
+                if let Some(position) = last_commit_of_schedule {
+                    schedule_ended = true;
+                    truncated = position + 1 < sequenced_leaders.len();
+                    sequenced_leaders.truncate(position + 1);
+                }
+            }
 
-        if let Some(last) = sequenced_leaders.last() {
-            self.last_decided_leader = last.get_decided_slot();
-            self.context
-                .metrics
-                .node_metrics
-                .last_decided_leader_round
-                .set(self.last_decided_leader.round as i64);
-        }
+            if let Some(last) = sequenced_leaders.last() {
+                self.last_decided_leader = last.get_decided_slot();
+                self.context
+                    .metrics
+                    .node_metrics
+                    .last_decided_leader_round
+                    .set(self.last_decided_leader.round as i64);
+            }
 
-        let committed_leaders = sequenced_leaders
-            .into_iter()
-            .filter_map(|leader| leader.into_committed_block())
-            .collect::<Vec<_>>();
-        if !committed_leaders.is_empty() {
-            debug!(
-                "Committing leaders: {}",
-                committed_leaders
-                    .iter()
-                    .map(|b| b.reference().to_string())
-                    .join(",")
-            );
+            let committed_leaders = sequenced_leaders
+                .into_iter()
+                .filter_map(|leader| leader.into_committed_block())
+                .collect::<Vec<_>>();
+            if !committed_leaders.is_empty() {
+                debug!(
+                    "Committing leaders: {}",
+                    committed_leaders
+                        .iter()
+                        .map(|b| b.reference().to_string())
+                        .join(",")
+                );
+            }
+            committed_sub_dags.extend(self.commit_observer.handle_commit(committed_leaders)?);
+
+            if schedule_ended {
+                self.leader_schedule
+                    .update_leader_schedule(self.dag_state.clone());
+            }
+            if !truncated {
+                break;
+            }
         }
 
-        self.commit_observer.handle_commit(committed_leaders)
+        Ok(committed_sub_dags)
     }
 
     pub(crate) fn get_missing_blocks(&self) -> BTreeSet<BlockRef> {
@@ -654,7 +689,6 @@ mod test {
         block::{genesis_blocks, TestBlock},
         block_verifier::NoopBlockVerifier,
         commit::CommitAPI as _,
-        leader_schedule::LeaderSwapTable,
         storage::{mem_store::MemStore, Store, WriteBatch},
         transaction::TransactionClient,
         CommitConsumer, CommitIndex,
@@ -699,10 +733,7 @@ mod test {
             dag_state.clone(),
             Arc::new(NoopBlockVerifier),
         );
-        let leader_schedule = Arc::new(LeaderSchedule::new(
-            context.clone(),
-            LeaderSwapTable::default(),
-        ));
+        let leader_schedule = Arc::new(LeaderSchedule::new(context.clone()));
 
         let (sender, _receiver) = unbounded_channel();
         let commit_observer = CommitObserver::new(
@@ -814,10 +845,7 @@ mod test {
             dag_state.clone(),
             Arc::new(NoopBlockVerifier),
         );
-        let leader_schedule = Arc::new(LeaderSchedule::new(
-            context.clone(),
-            LeaderSwapTable::default(),
-        ));
+        let leader_schedule = Arc::new(LeaderSchedule::new(context.clone()));
 
         let (sender, _receiver) = unbounded_channel();
         let commit_observer = CommitObserver::new(
@@ -908,10 +936,7 @@ mod test {
         let (signals, signal_receivers) = CoreSignals::new(context.clone());
         // Need at least one subscriber to the block broadcast channel.
         let mut block_receiver = signal_receivers.block_broadcast_receiver();
-        let leader_schedule = Arc::new(LeaderSchedule::new(
-            context.clone(),
-            LeaderSwapTable::default(),
-        ));
+        let leader_schedule = Arc::new(LeaderSchedule::new(context.clone()));
 
         let (sender, _receiver) = unbounded_channel();
         let commit_observer = CommitObserver::new(
@@ -1009,10 +1034,7 @@ mod test {
             dag_state.clone(),
             Arc::new(NoopBlockVerifier),
         );
-        let leader_schedule = Arc::new(LeaderSchedule::new(
-            context.clone(),
-            LeaderSwapTable::default(),
-        ));
+        let leader_schedule = Arc::new(LeaderSchedule::new(context.clone()));
 
         let (_transaction_client, tx_receiver) = TransactionClient::new(context.clone());
         let transaction_consumer = TransactionConsumer::new(tx_receiver, context.clone(), None);
@@ -1320,10 +1342,7 @@ mod test {
                 dag_state.clone(),
                 Arc::new(NoopBlockVerifier),
             );
-            let leader_schedule = Arc::new(LeaderSchedule::new(
-                context.clone(),
-                LeaderSwapTable::default(),
-            ));
+            let leader_schedule = Arc::new(LeaderSchedule::new(context.clone()));
 
             let (_transaction_client, tx_receiver) = TransactionClient::new(context.clone());
             let transaction_consumer = TransactionConsumer::new(tx_receiver, context.clone(), None);
@@ -188,7 +188,7 @@ mod test {
         context::Context,
         core::CoreSignals,
         dag_state::DagState,
-        leader_schedule::{LeaderSchedule, LeaderSwapTable},
+        leader_schedule::LeaderSchedule,
         storage::mem_store::MemStore,
         transaction::{TransactionClient, TransactionConsumer},
         CommitConsumer,
@@ -217,10 +217,7 @@ mod test {
             dag_state.clone(),
             store,
         );
-        let leader_schedule = Arc::new(LeaderSchedule::new(
-            context.clone(),
-            LeaderSwapTable::default(),
-        ));
+        let leader_schedule = Arc::new(LeaderSchedule::new(context.clone()));
         let core = Core::new(
             context.clone(),
             leader_schedule,
@@ -0,0 +1,414 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    path::Path,
+    sync::Arc,
+};
+
+use consensus_config::AuthorityIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::{BlockAPI as _, BlockRef, BlockTimestampMs, Round, VerifiedBlock},
+    commit::{CommitAPI as _, CommitIndex, CommitRange, TrustedCommit, GENESIS_COMMIT_INDEX},
+    error::ConsensusResult,
+    storage::{rocksdb_store::RocksDBStore, Store},
+};
+
+/// Number of commits read from the store at a time, when looking for commits in a round range.
+const COMMIT_SCAN_BATCH_SIZE: CommitIndex = 1000;
+
+/// Provides a read-only view over the DAG persisted by a consensus authority.
+/// It is meant for operator tooling, e.g. to inspect the store of a stalled validator.
+pub struct DagInspector {
+    store: Arc<dyn Store>,
+}
+
+impl DagInspector {
+    /// Opens the consensus RocksDB at `path` as a secondary instance, so it can be
+    /// inspected while the authority owning the database is still running.
+    pub fn open_rocksdb(path: &Path, secondary_path: Option<&Path>) -> ConsensusResult<Self> {
+        let store = RocksDBStore::new_secondary(path, secondary_path)?;
+        Ok(Self::new(Arc::new(store)))
+    }
+
+    pub(crate) fn new(store: Arc<dyn Store>) -> Self {
+        Self { store }
+    }
+
+    /// Returns the round of the last commit's leader, or None if nothing has been committed.
+    pub fn last_committed_round(&self) -> ConsensusResult<Option<Round>> {
+        Ok(self.store.read_last_commit()?.map(|commit| commit.round()))
+    }
+
+    /// Reads blocks and commits with rounds in [start_round, end_round] and summarizes them.
+    pub fn snapshot(&self, start_round: Round, end_round: Round) -> ConsensusResult<DagSnapshot> {
+        let blocks = self.store.scan_blocks_by_rounds(start_round, end_round)?;
+        let commits = self.scan_commits_by_rounds(start_round, end_round)?;
+        let last_reputation_scores =
+            self.store
+                .read_last_commit_info()?
+                .map(|(_, info)| ReputationScoresSummary {
+                    commit_range: (
+                        info.reputation_scores.commit_range.start(),
+                        info.reputation_scores.commit_range.end(),
+                    ),
+                    scores_per_authority: info.reputation_scores.scores_per_authority,
+                });
+        Ok(DagSnapshot::new(
+            start_round,
+            end_round,
+            blocks,
+            commits,
+            last_reputation_scores,
+        ))
+    }
+
+    /// Reads commits whose leaders are in [start_round, end_round], in commit order.
+    /// Leader rounds increase with commit indices, so commits are scanned backwards from
+    /// the last commit until a leader before `start_round` is found.
+    fn scan_commits_by_rounds(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<TrustedCommit>> {
+        let Some(last_commit) = self.store.read_last_commit()? else {
+            return Ok(vec![]);
+        };
+        let mut commits = vec![];
+        let mut end = last_commit.index() + 1;
+        while end > GENESIS_COMMIT_INDEX + 1 {
+            let start = end
+                .saturating_sub(COMMIT_SCAN_BATCH_SIZE)
+                .max(GENESIS_COMMIT_INDEX + 1);
+            let batch = self.store.scan_commits(CommitRange::new(start..end))?;
+            let reached_start = batch
+                .first()
+                .map_or(true, |commit| commit.round() < start_round);
+            commits.extend(
+                batch
+                    .into_iter()
+                    .rev()
+                    .filter(|commit| (start_round..=end_round).contains(&commit.round())),
+            );
+            if reached_start {
+                break;
+            }
+            end = start;
+        }
+        commits.reverse();
+        Ok(commits)
+    }
+}
+
+/// Summary of the DAG within a round range, as read from the consensus store.
+#[derive(Clone, Debug, Serialize)]
+pub struct DagSnapshot {
+    pub start_round: Round,
+    pub end_round: Round,
+    /// Blocks in the round range, ordered by round and author.
+    pub blocks: Vec<BlockSummary>,
+    /// Commits with leaders in the round range, in commit order.
+    pub commits: Vec<CommitSummary>,
+    /// Per authority block production within the round range.
+    pub authority_progress: Vec<AuthorityProgress>,
+    /// Reputation scores stored with the last commit, if any.
+    pub last_reputation_scores: Option<ReputationScoresSummary>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockSummary {
+    pub reference: String,
+    pub round: Round,
+    pub author: AuthorityIndex,
+    pub digest: String,
+    pub timestamp_ms: BlockTimestampMs,
+    pub ancestors: Vec<String>,
+    pub num_transactions: usize,
+    pub commit_votes: Vec<CommitIndex>,
+    /// Index of the commit including this block, if the commit is in the snapshot.
+    pub committed_in: Option<CommitIndex>,
+    /// True if the block is the leader of a commit in the snapshot.
+    pub is_committed_leader: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CommitSummary {
+    pub index: CommitIndex,
+    pub digest: String,
+    pub leader: String,
+    pub leader_round: Round,
+    pub leader_author: AuthorityIndex,
+    pub timestamp_ms: BlockTimestampMs,
+    pub blocks: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AuthorityProgress {
+    pub authority: AuthorityIndex,
+    pub num_blocks: usize,
+    /// Lowest and highest rounds with blocks from the authority, within the round range.
+    pub lowest_round: Round,
+    pub highest_round: Round,
+    /// Rounds in [lowest_round, highest_round] without a block from the authority.
+    pub missing_rounds: Vec<Round>,
+    /// Rounds with more than one block from the authority.
+    pub equivocating_rounds: Vec<Round>,
+    pub committed_leaders: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReputationScoresSummary {
+    /// Range of commits, [start, end], the scores were calculated from.
+    pub commit_range: (CommitIndex, CommitIndex),
+    /// Score per authority. Vec index is the `AuthorityIndex`.
+    pub scores_per_authority: Vec<u64>,
+}
+
+impl DagSnapshot {
+    fn new(
+        start_round: Round,
+        end_round: Round,
+        blocks: Vec<VerifiedBlock>,
+        commits: Vec<TrustedCommit>,
+        last_reputation_scores: Option<ReputationScoresSummary>,
+    ) -> Self {
+        let mut committed_in = BTreeMap::<BlockRef, CommitIndex>::new();
+        let mut committed_leaders = BTreeMap::<AuthorityIndex, usize>::new();
+        for commit in &commits {
+            for block_ref in commit.blocks() {
+                committed_in.insert(*block_ref, commit.index());
+            }
+            *committed_leaders.entry(commit.leader().author).or_default() += 1;
+        }
+        let leaders = commits
+            .iter()
+            .map(|commit| commit.leader())
+            .collect::<BTreeSet<_>>();
+
+        let mut rounds_by_authority = BTreeMap::<AuthorityIndex, BTreeMap<Round, usize>>::new();
+        for block in &blocks {
+            *rounds_by_authority
+                .entry(block.author())
+                .or_default()
+                .entry(block.round())
+                .or_default() += 1;
+        }
+        let authority_progress = rounds_by_authority
+            .into_iter()
+            .map(|(authority, rounds)| {
+                let lowest_round = *rounds.keys().next().unwrap();
+                let highest_round = *rounds.keys().next_back().unwrap();
+                AuthorityProgress {
+                    authority,
+                    num_blocks: rounds.values().sum(),
+                    lowest_round,
+                    highest_round,
+                    missing_rounds: (lowest_round..=highest_round)
+                        .filter(|round| !rounds.contains_key(round))
+                        .collect(),
+                    equivocating_rounds: rounds
+                        .iter()
+                        .filter(|(_, count)| **count > 1)
+                        .map(|(round, _)| *round)
+                        .collect(),
+                    committed_leaders: committed_leaders.get(&authority).copied().unwrap_or(0),
+                }
+            })
+            .collect();
+
+        let blocks = blocks
+            .iter()
+            .map(|block| {
+                let reference = block.reference();
+                BlockSummary {
+                    reference: reference.to_string(),
+                    round: block.round(),
+                    author: block.author(),
+                    digest: format!("{:?}", reference.digest),
+                    timestamp_ms: block.timestamp_ms(),
+                    ancestors: block.ancestors().iter().map(|a| a.to_string()).collect(),
+                    num_transactions: block.transactions().len(),
+                    commit_votes: block.commit_votes().iter().map(|v| v.index).collect(),
+                    committed_in: committed_in.get(&reference).copied(),
+                    is_committed_leader: leaders.contains(&reference),
+                }
+            })
+            .collect();
+
+        let commits = commits
+            .iter()
+            .map(|commit| CommitSummary {
+                index: commit.index(),
+                digest: format!("{:?}", commit.digest()),
+                leader: commit.leader().to_string(),
+                leader_round: commit.round(),
+                leader_author: commit.leader().author,
+                timestamp_ms: commit.timestamp_ms(),
+                blocks: commit.blocks().iter().map(|b| b.to_string()).collect(),
+            })
+            .collect();
+
+        Self {
+            start_round,
+            end_round,
+            blocks,
+            commits,
+            authority_progress,
+            last_reputation_scores,
+        }
+    }
+
+    /// Renders the blocks of the snapshot as a Graphviz DOT graph. Each round is drawn at
+    /// the same rank, committed leaders are highlighted and committed blocks are shaded.
+    /// Only links to ancestors within the snapshot are drawn.
+    pub fn to_dot(&self) -> String {
+        let known: BTreeSet<&str> = self.blocks.iter().map(|b| b.reference.as_str()).collect();
+        let mut dot = String::new();
+        writeln!(dot, "digraph dag {{").unwrap();
+        writeln!(dot, "  rankdir=BT;").unwrap();
+        writeln!(dot, "  node [shape=box, style=filled, fillcolor=white];").unwrap();
+
+        let mut blocks_by_round = BTreeMap::<Round, Vec<&BlockSummary>>::new();
+        for block in &self.blocks {
+            blocks_by_round.entry(block.round).or_default().push(block);
+        }
+        for (round, blocks) in &blocks_by_round {
+            writeln!(dot, "  subgraph round_{round} {{").unwrap();
+            writeln!(dot, "    rank=same;").unwrap();
+            for block in blocks {
+                let fillcolor = if block.is_committed_leader {
+                    "gold"
+                } else if block.committed_in.is_some() {
+                    "lightblue"
+                } else {
+                    "white"
+                };
+                let commit = block
+                    .committed_in
+                    .map(|index| format!("\\ncommit {index}"))
+                    .unwrap_or_default();
+                writeln!(
+                    dot,
+                    "    \"{}\" [label=\"{}\\ntxs {}{}\", fillcolor={}];",
+                    block.reference, block.reference, block.num_transactions, commit, fillcolor
+                )
+                .unwrap();
+            }
+            writeln!(dot, "  }}").unwrap();
+        }
+
+        for block in &self.blocks {
+            for ancestor in &block.ancestors {
+                if known.contains(ancestor.as_str()) {
+                    writeln!(dot, "  \"{}\" -> \"{}\";", block.reference, ancestor).unwrap();
+                }
+            }
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block::TestBlock,
+        commit::CommitDigest,
+        storage::{mem_store::MemStore, WriteBatch},
+    };
+
+    #[test]
+    fn test_snapshot() {
+        let store = Arc::new(MemStore::new());
+
+        // Fully connected blocks for rounds 1 ~ 4 from authorities 0 ~ 2, authority 3 only
+        // produces a block at round 1.
+        let mut ancestors = vec![];
+        let mut blocks = vec![];
+        for round in 1..=4 {
+            let mut references = vec![];
+            for author in 0..4 {
+                if author == 3 && round > 1 {
+                    continue;
+                }
+                let block = VerifiedBlock::new_for_test(
+                    TestBlock::new(round, author)
+                        .set_timestamp_ms(round as BlockTimestampMs * 1000)
+                        .set_ancestors(ancestors.clone())
+                        .build(),
+                );
+                references.push(block.reference());
+                blocks.push(block);
+            }
+            ancestors = references;
+        }
+        store
+            .write(WriteBatch::default().blocks(blocks.clone()))
+            .unwrap();
+
+        // Commit the leader of round 1 and round 2.
+        let commit_1 = TrustedCommit::new_for_test(
+            1,
+            CommitDigest::MIN,
+            1000,
+            blocks[0].reference(),
+            vec![blocks[0].reference()],
+        );
+        let commit_2 = TrustedCommit::new_for_test(
+            2,
+            commit_1.digest(),
+            2000,
+            blocks[5].reference(),
+            blocks[1..=5].iter().map(|b| b.reference()).collect(),
+        );
+        store
+            .write(WriteBatch::default().commits(vec![commit_1, commit_2]))
+            .unwrap();
+
+        let inspector = DagInspector::new(store);
+        assert_eq!(inspector.last_committed_round().unwrap(), Some(2));
+
+        let snapshot = inspector.snapshot(2, 4).unwrap();
+        assert_eq!(snapshot.blocks.len(), 9);
+        assert_eq!(snapshot.commits.len(), 1);
+        assert_eq!(snapshot.commits[0].index, 2);
+        assert_eq!(snapshot.commits[0].leader_round, 2);
+
+        let leader = &snapshot.blocks[1];
+        assert!(leader.is_committed_leader);
+        assert_eq!(leader.committed_in, Some(2));
+        assert!(snapshot.blocks[2..]
+            .iter()
+            .all(|b| b.committed_in.is_none() && !b.is_committed_leader));
+
+        // Authority 3 did not produce blocks within the range.
+        assert_eq!(snapshot.authority_progress.len(), 3);
+        for progress in &snapshot.authority_progress {
+            assert_eq!(progress.num_blocks, 3);
+            assert_eq!(progress.lowest_round, 2);
+            assert_eq!(progress.highest_round, 4);
+            assert!(progress.missing_rounds.is_empty());
+            assert!(progress.equivocating_rounds.is_empty());
+        }
+        assert_eq!(snapshot.authority_progress[1].committed_leaders, 1);
+
+        let dot = snapshot.to_dot();
+        assert!(dot.starts_with("digraph dag {"));
+        // 3 blocks at round 3 and 4, linking to 3 ancestors each within the range.
+        assert_eq!(dot.matches(" -> ").count(), 18);
+    }
+
+    #[test]
+    fn test_open_missing_rocksdb() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let missing = dir.path().join("consensus_db");
+        assert!(DagInspector::open_rocksdb(&missing, None).is_err());
+        // The secondary instance does not create the missing primary.
+        assert!(!missing.exists());
+    }
+}
@@ -18,7 +18,10 @@ use crate::{
         genesis_blocks, BlockAPI, BlockDigest, BlockRef, BlockTimestampMs, Round, Slot,
         VerifiedBlock, GENESIS_ROUND,
     },
-    commit::{CommitAPI as _, CommitDigest, CommitIndex, CommitInfo, CommitVote, TrustedCommit},
+    commit::{
+        CommitAPI as _, CommitDigest, CommitIndex, CommitInfo, CommitRange, CommitVote,
+        TrustedCommit,
+    },
     context::Context,
     leader_scoring::ReputationScores,
     stake_aggregator::{QuorumThreshold, StakeAggregator},
@@ -58,6 +61,9 @@ pub(crate) struct DagState {
     // Last committed rounds per authority.
     last_committed_rounds: Vec<Round>,
 
+    // Reputation scores of the current leader schedule, persisted with the last commit info.
+    reputation_scores: ReputationScores,
+
     // Commit votes pending to be included in new blocks.
     // TODO: limit to 1st commit per round with multi-leader.
     pending_commit_votes: VecDeque<CommitVote>,
@@ -87,15 +93,16 @@ impl DagState {
         let last_commit = store
             .read_last_commit()
             .unwrap_or_else(|e| panic!("Failed to read from storage: {:?}", e));
-        let last_committed_rounds = if let Some(commit) = last_commit.as_ref() {
+        let (last_committed_rounds, reputation_scores) = if let Some(commit) = last_commit.as_ref()
+        {
             let (commit_ref, commit_info) = store
                 .read_last_commit_info()
                 .unwrap_or_else(|e| panic!("Failed to read from storage: {:?}", e))
                 .unwrap_or_else(|| panic!("Last commit info should be available."));
             assert_eq!(commit_ref, commit.reference());
-            commit_info.committed_rounds
+            (commit_info.committed_rounds, commit_info.reputation_scores)
         } else {
-            vec![0; num_authorities]
+            (vec![0; num_authorities], ReputationScores::default())
         };
 
         let mut state = Self {
@@ -107,6 +114,7 @@ impl DagState {
             last_commit,
             last_commit_round_advancement_time: None,
             last_committed_rounds: last_committed_rounds.clone(),
+            reputation_scores,
             pending_commit_votes: VecDeque::new(),
             blocks_to_write: vec![],
             commits_to_write: vec![],
@@ -605,6 +613,25 @@ impl DagState {
         }
     }
 
+    /// Returns the reputation scores of the current leader schedule.
+    pub(crate) fn reputation_scores(&self) -> ReputationScores {
+        self.reputation_scores.clone()
+    }
+
+    /// Sets the reputation scores of a new leader schedule. They are persisted with the
+    /// commit info of the next flush.
+    pub(crate) fn set_reputation_scores(&mut self, reputation_scores: ReputationScores) {
+        self.reputation_scores = reputation_scores;
+    }
+
+    /// Reads commits in the range from storage. Commits that have not been flushed yet are
+    /// not included.
+    pub(crate) fn scan_commits(&self, range: CommitRange) -> Vec<TrustedCommit> {
+        self.store
+            .scan_commits(range)
+            .unwrap_or_else(|e| panic!("Failed to read from storage: {:?}", e))
+    }
+
     /// Last committed round per authority.
     pub(crate) fn last_committed_rounds(&self) -> Vec<Round> {
         self.last_committed_rounds.clone()
@@ -637,10 +664,9 @@ impl DagState {
             None
         } else {
             let last_commit_ref = commits.last().as_ref().unwrap().reference();
-            // TODO: Replace this with calculated reputation scores
             let commit_info = CommitInfo::new(
                 self.last_committed_rounds.clone(),
-                ReputationScores::default(),
+                self.reputation_scores.clone(),
             );
             Some((last_commit_ref, commit_info))
         };
@@ -18,6 +18,9 @@ pub enum ConsensusError {
     #[error("Error deserializing block: {0}")]
     MalformedBlock(bcs::Error),
 
+    #[error("Block stored as {key} has reference {block}")]
+    CorruptedBlock { key: BlockRef, block: BlockRef },
+
     #[error("Error deserializing commit: {0}")]
     MalformedCommit(bcs::Error),
 
@@ -159,6 +162,15 @@ pub enum ConsensusError {
     #[error("Request timeout: {0:?}")]
     NetworkRequestTimeout(String),
 
+    #[error("Commit archive store failure: {0}")]
+    CommitArchiveStoreFailure(String),
+
+    #[error("Block {0} of a commit is missing from the store")]
+    MissingCommittedBlock(BlockRef),
+
+    #[error("Malformed commit archive: {0}")]
+    MalformedCommitArchive(String),
+
     #[error("Consensus has shut down!")]
     Shutdown,
 }
@@ -7,110 +7,363 @@ use std::{
     sync::Arc,
 };
 
-use consensus_config::{AuthorityIndex, Stake};
+use consensus_config::{Authority, AuthorityIndex, LeaderScheduleStrategyType, Stake};
 use parking_lot::RwLock;
-use rand::{prelude::SliceRandom, rngs::StdRng, SeedableRng};
+use rand::{
+    distributions::{Distribution as _, WeightedIndex},
+    prelude::SliceRandom,
+    rngs::StdRng,
+    SeedableRng,
+};
 
-use crate::{commit::CommitRange, context::Context, leader_scoring::ReputationScores, Round};
+use crate::{
+    block::{Slot, GENESIS_ROUND},
+    commit::{CommitAPI as _, CommitIndex, CommitRange},
+    context::Context,
+    dag_state::DagState,
+    leader_scoring::ReputationScores,
+    Round,
+};
 
 /// The `LeaderSchedule` is responsible for producing the leader schedule across
 /// an epoch. The leader schedule is subject to change periodically based on
-/// calculated `ReputationScores` of the authorities.
+/// calculated `ReputationScores` of the authorities, in the way decided by the
+/// configured `LeaderScheduleStrategy`.
 #[derive(Clone)]
 pub(crate) struct LeaderSchedule {
     context: Arc<Context>,
-    #[allow(unused)]
     num_commits_per_schedule: u64,
-    leader_swap_table: Arc<RwLock<LeaderSwapTable>>,
+    strategy: Arc<dyn LeaderScheduleStrategy>,
 }
 
-#[allow(unused)]
 impl LeaderSchedule {
-    /// The window where the schedule change takes place in consensus. It represents
-    /// number of committed sub dags.
-    /// TODO: move this to protocol config
-    const CONSENSUS_COMMITS_PER_SCHEDULE: u64 = 300;
+    pub(crate) fn new(context: Arc<Context>) -> Self {
+        let parameters = &context.parameters.leader_schedule;
+        let strategy: Arc<dyn LeaderScheduleStrategy> = match parameters.strategy {
+            LeaderScheduleStrategyType::StakeBased => {
+                Arc::new(StakeBasedStrategy::new(context.clone()))
+            }
+            LeaderScheduleStrategyType::ReputationSwap => Arc::new(ReputationSwapStrategy::new(
+                context.clone(),
+                parameters.swap_stake_threshold,
+            )),
+            LeaderScheduleStrategyType::DecayedSuccessRate => Arc::new(
+                DecayedSuccessRateStrategy::new(context.clone(), parameters.success_rate_decay),
+            ),
+            LeaderScheduleStrategyType::TimeoutExclusion => {
+                Arc::new(TimeoutExclusionStrategy::new(
+                    context.clone(),
+                    parameters.max_consecutive_skipped_slots,
+                ))
+            }
+        };
+        assert!(
+            strategy.is_static() || parameters.commits_per_schedule > 0,
+            "The commits_per_schedule should be positive"
+        );
+        context
+            .metrics
+            .node_metrics
+            .leader_schedule_strategy
+            .with_label_values(&[strategy.name()])
+            .set(1);
 
-    pub(crate) fn new(context: Arc<Context>, leader_swap_table: LeaderSwapTable) -> Self {
         Self {
+            num_commits_per_schedule: parameters.commits_per_schedule,
             context,
-            num_commits_per_schedule: Self::CONSENSUS_COMMITS_PER_SCHEDULE,
-            leader_swap_table: Arc::new(RwLock::new(leader_swap_table)),
+            strategy,
         }
     }
 
-    pub(crate) fn elect_leader(&self, round: u32, leader_offset: u32) -> AuthorityIndex {
-        cfg_if::cfg_if! {
-            // TODO: we need to differentiate the leader strategy in tests, so for
-            // some type of testing (ex sim tests) we can use the staked approach.
-            if #[cfg(test)] {
-                let leader = AuthorityIndex::new_for_test((round + leader_offset) % self.context.committee.size() as u32);
-                let table = self.leader_swap_table.read();
-                table.swap(leader, round, leader_offset).unwrap_or(leader)
-            } else {
-                let leader = self.elect_leader_stake_based(round, leader_offset);
-                let table = self.leader_swap_table.read();
-                table.swap(leader, round, leader_offset).unwrap_or(leader)
-            }
+    /// Restores the leader schedule from the reputation scores persisted in storage.
+    /// Schedule updates which were due but not persisted before a restart are calculated again.
+    pub(crate) fn from_store(context: Arc<Context>, dag_state: Arc<RwLock<DagState>>) -> Self {
+        let leader_schedule = Self::new(context);
+        if leader_schedule.strategy.is_static() {
+            return leader_schedule;
+        }
+
+        let reputation_scores = dag_state.read().reputation_scores();
+        if !reputation_scores.scores_per_authority.is_empty() {
+            leader_schedule.update(reputation_scores);
         }
+        while leader_schedule.commits_until_leader_schedule_update(dag_state.clone()) == Some(0) {
+            leader_schedule.update_leader_schedule(dag_state.clone());
+        }
+        leader_schedule
     }
 
+    pub(crate) fn elect_leader(&self, round: u32, leader_offset: u32) -> AuthorityIndex {
+        self.strategy.elect_leader(round, leader_offset)
+    }
+
+    #[cfg(test)]
     pub(crate) fn elect_leader_stake_based(&self, round: u32, offset: u32) -> AuthorityIndex {
-        assert!((offset as usize) < self.context.committee.size());
+        elect_leader_stake_based(&self.context, round, offset)
+    }
 
-        // To ensure that we elect different leaders for the same round (using
-        // different offset) we are using the round number as seed to shuffle in
-        // a weighted way the results, but skip based on the offset.
-        // TODO: use a cache in case this proves to be computationally expensive
-        let mut seed_bytes = [0u8; 32];
-        seed_bytes[32 - 4..].copy_from_slice(&(round).to_le_bytes());
-        let mut rng = StdRng::from_seed(seed_bytes);
+    /// Returns the number of commits left until the leader schedule has to be updated, or None
+    /// if the schedule never changes.
+    pub(crate) fn commits_until_leader_schedule_update(
+        &self,
+        dag_state: Arc<RwLock<DagState>>,
+    ) -> Option<usize> {
+        if self.strategy.is_static() {
+            return None;
+        }
+        let last_commit_index = dag_state.read().last_commit_index();
+        let last_schedule_commit_index = self.strategy.reputation_scores().commit_range.end();
+        let commits_since_update = last_commit_index.saturating_sub(last_schedule_commit_index);
+        Some(
+            self.num_commits_per_schedule
+                .saturating_sub(commits_since_update as u64) as usize,
+        )
+    }
 
-        let choices = self
+    /// Updates the leader schedule with reputation scores calculated from the leader slots
+    /// decided during the schedule period that just ended. Every authority updates its schedule
+    /// after the same commit, so leaders of later rounds are elected the same way everywhere.
+    pub(crate) fn update_leader_schedule(&self, dag_state: Arc<RwLock<DagState>>) {
+        let _s = self
             .context
-            .committee
-            .authorities()
-            .map(|(index, authority)| (index, authority.stake as f32))
-            .collect::<Vec<_>>();
-
-        let leader_index = *choices
-            .choose_multiple_weighted(&mut rng, self.context.committee.size(), |item| item.1)
-            .expect("Weighted choice error: stake values incorrect!")
-            .skip(offset as usize)
-            .map(|(index, _)| index)
-            .next()
-            .unwrap();
-
-        leader_index
+            .metrics
+            .node_metrics
+            .scope_processing_time
+            .with_label_values(&["LeaderSchedule::update_leader_schedule"])
+            .start_timer();
+
+        let last_schedule_commit_index = self.strategy.reputation_scores().commit_range.end();
+        let commit_range = CommitRange::new(
+            last_schedule_commit_index + 1
+                ..last_schedule_commit_index + self.num_commits_per_schedule as CommitIndex,
+        );
+        let decided_leaders = self.decided_leaders(&dag_state.read(), &commit_range);
+        let reputation_scores = self
+            .strategy
+            .calculate_scores(commit_range, &decided_leaders);
+
+        let committed = decided_leaders
+            .iter()
+            .filter(|leader| leader.committed)
+            .count();
+        let node_metrics = &self.context.metrics.node_metrics;
+        node_metrics
+            .leader_schedule_slots_total
+            .with_label_values(&[self.strategy.name(), "committed"])
+            .inc_by(committed as u64);
+        node_metrics
+            .leader_schedule_slots_total
+            .with_label_values(&[self.strategy.name(), "skipped"])
+            .inc_by((decided_leaders.len() - committed) as u64);
+        reputation_scores.update_metrics(self.context.clone());
+
+        self.update(reputation_scores.clone());
+        dag_state.write().set_reputation_scores(reputation_scores);
     }
 
-    /// Atomically updates the `LeaderSwapTable` with the new provided one. Any
-    /// leader queried from now on will get calculated according to this swap
-    /// table until a new one is provided again.
-    fn update_leader_swap_table(&self, table: LeaderSwapTable) {
-        let read = self.leader_swap_table.read();
-        let old_commit_range = &read.reputation_scores.commit_range;
-        let new_commit_range = &table.reputation_scores.commit_range;
+    /// Atomically switches to the schedule derived from the provided scores. Any
+    /// leader queried from now on will get calculated according to this schedule
+    /// until new scores are provided again.
+    fn update(&self, reputation_scores: ReputationScores) {
+        let old_commit_range = self.strategy.reputation_scores().commit_range;
+        let new_commit_range = &reputation_scores.commit_range;
 
         // Unless LeaderSchedule is brand new and using the default commit range
-        // of CommitRange(0..0) all future LeaderSwapTables should be calculated
-        // from a CommitRange of equal length and immediately following the
-        // preceding commit range of the old swap table.
-        if *old_commit_range != CommitRange::new(0..0) {
+        // of CommitRange(0..0) all future schedules should be calculated from a
+        // CommitRange of equal length and immediately following the preceding
+        // commit range of the old schedule.
+        if old_commit_range != CommitRange::new(0..0) {
             assert!(
                 old_commit_range.is_next_range(new_commit_range),
-                "The new LeaderSwapTable has an invalid CommitRange. Old LeaderSwapTable {old_commit_range:?} vs new LeaderSwapTable {new_commit_range:?}",
+                "The new leader schedule has an invalid CommitRange. Old schedule {old_commit_range:?} vs new schedule {new_commit_range:?}",
             );
         }
-        drop(read);
 
-        tracing::trace!("Updating {table:?}");
+        tracing::trace!("Updating leader schedule with {reputation_scores:?}");
+
+        self.strategy.update(reputation_scores);
+        self.context
+            .metrics
+            .node_metrics
+            .leader_schedule_updates_total
+            .with_label_values(&[self.strategy.name()])
+            .inc();
+    }
 
-        let mut write = self.leader_swap_table.write();
-        *write = table;
+    /// Scores authorities by their committed leaders within the commit range, the same way
+    /// `stake_based` and `reputation_swap` do at schedule updates.
+    #[cfg(test)]
+    pub(crate) fn committed_leader_scores(
+        &self,
+        dag_state: &DagState,
+        commit_range: CommitRange,
+    ) -> ReputationScores {
+        let decided_leaders = self.decided_leaders(dag_state, &commit_range);
+        ReputationScores::new(
+            commit_range,
+            committed_leaders_per_authority(&self.context, &decided_leaders),
+        )
+    }
+
+    /// Returns the leader slots decided within the commit range, in round order. The slots were
+    /// decided with the current schedule, so the leaders of skipped slots can be elected again.
+    /// Assumes a single leader per round.
+    fn decided_leaders(
+        &self,
+        dag_state: &DagState,
+        commit_range: &CommitRange,
+    ) -> Vec<DecidedLeader> {
+        // The commit preceding the range marks where the decided slots start.
+        let scan_start = commit_range.start().saturating_sub(1).max(1);
+        let commits = dag_state.scan_commits(CommitRange::new(scan_start..commit_range.end() + 1));
+        assert_eq!(
+            commits.last().map(|commit| commit.index()),
+            Some(commit_range.end()),
+            "Commits of {commit_range:?} should be persisted before updating the leader schedule"
+        );
+
+        let mut decided_leaders = vec![];
+        let mut previous_leader_round = GENESIS_ROUND;
+        for commit in commits {
+            let leader = commit.leader();
+            if commit.index() >= commit_range.start() {
+                for round in previous_leader_round + 1..leader.round {
+                    decided_leaders.push(DecidedLeader {
+                        slot: Slot::new(round, self.elect_leader(round, 0)),
+                        committed: false,
+                    });
+                }
+                decided_leaders.push(DecidedLeader {
+                    slot: Slot::new(leader.round, leader.author),
+                    committed: true,
+                });
+            }
+            previous_leader_round = leader.round;
+        }
+        decided_leaders
+    }
+}
+
+/// A leader slot decided by the committer, either committed or skipped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DecidedLeader {
+    pub(crate) slot: Slot,
+    pub(crate) committed: bool,
+}
+
+/// Decides how leaders are elected, and how the election adapts to the performance of
+/// past leaders. Implementations must be deterministic: all authorities have to elect
+/// the same leaders from the same reputation scores.
+pub(crate) trait LeaderScheduleStrategy: Send + Sync {
+    /// Name of the strategy, used in metrics.
+    fn name(&self) -> &'static str;
+
+    /// Whether the schedule stays the same for the whole epoch, in which case it is never updated.
+    fn is_static(&self) -> bool {
+        false
+    }
+
+    /// Elects the leader of a slot with the current schedule.
+    fn elect_leader(&self, round: Round, leader_offset: u32) -> AuthorityIndex;
+
+    /// Calculates reputation scores for the next schedule, from the current scores and the
+    /// leader slots decided with the current schedule.
+    fn calculate_scores(
+        &self,
+        commit_range: CommitRange,
+        decided_leaders: &[DecidedLeader],
+    ) -> ReputationScores;
+
+    /// Switches to the schedule derived from the provided scores.
+    fn update(&self, reputation_scores: ReputationScores);
+
+    /// Returns the scores of the current schedule.
+    fn reputation_scores(&self) -> ReputationScores;
+}
+
+/// Elects the leader of a slot before any adjustment from reputation.
+fn elect_leader_base(context: &Context, round: Round, leader_offset: u32) -> AuthorityIndex {
+    cfg_if::cfg_if! {
+        // TODO: we need to differentiate the leader strategy in tests, so for
+        // some type of testing (ex sim tests) we can use the staked approach.
+        if #[cfg(test)] {
+            AuthorityIndex::new_for_test((round + leader_offset) % context.committee.size() as u32)
+        } else {
+            elect_leader_stake_based(context, round, leader_offset)
+        }
     }
 }
 
+fn elect_leader_stake_based(context: &Context, round: Round, offset: u32) -> AuthorityIndex {
+    assert!((offset as usize) < context.committee.size());
+
+    // To ensure that we elect different leaders for the same round (using
+    // different offset) we are using the round number as seed to shuffle in
+    // a weighted way the results, but skip based on the offset.
+    // TODO: use a cache in case this proves to be computationally expensive
+    let mut seed_bytes = [0u8; 32];
+    seed_bytes[32 - 4..].copy_from_slice(&(round).to_le_bytes());
+    let mut rng = StdRng::from_seed(seed_bytes);
+
+    let choices = context
+        .committee
+        .authorities()
+        .map(|(index, authority)| (index, authority.stake as f32))
+        .collect::<Vec<_>>();
+
+    let leader_index = *choices
+        .choose_multiple_weighted(&mut rng, context.committee.size(), |item| item.1)
+        .expect("Weighted choice error: stake values incorrect!")
+        .skip(offset as usize)
+        .map(|(index, _)| index)
+        .next()
+        .unwrap();
+
+    leader_index
+}
+
+/// Elects a leader with probability proportional to the provided weight of each authority.
+/// Leaders with different offsets in the same round are drawn without replacement, so they
+/// are different authorities.
+fn elect_leader_weighted(
+    context: &Context,
+    round: Round,
+    offset: u32,
+    weight: impl Fn(AuthorityIndex, &Authority) -> f64,
+) -> AuthorityIndex {
+    assert!((offset as usize) < context.committee.size());
+
+    let mut seed_bytes = [0u8; 32];
+    seed_bytes[32 - 4..].copy_from_slice(&(round).to_le_bytes());
+    let mut rng = StdRng::from_seed(seed_bytes);
+
+    let mut choices = context
+        .committee
+        .authorities()
+        .map(|(index, authority)| (index, weight(index, authority)))
+        .collect::<Vec<_>>();
+    loop {
+        let distribution = WeightedIndex::new(choices.iter().map(|(_, weight)| *weight))
+            .expect("Weighted choice error: weights should be positive!");
+        let (leader_index, _) = choices.swap_remove(distribution.sample(&mut rng));
+        if choices.len() + offset as usize == context.committee.size() - 1 {
+            return leader_index;
+        }
+    }
+}
+
+/// Counts the committed leader slots of each authority.
+fn committed_leaders_per_authority(
+    context: &Context,
+    decided_leaders: &[DecidedLeader],
+) -> Vec<u64> {
+    let mut committed = vec![0; context.committee.size()];
+    for leader in decided_leaders.iter().filter(|leader| leader.committed) {
+        committed[leader.slot.authority] += 1;
+    }
+    committed
+}
+
 #[derive(Default, Clone)]
 pub(crate) struct LeaderSwapTable {
     /// The list of `f` (by configurable stake) authorities with best scores as
@@ -297,15 +550,320 @@ impl Debug for LeaderSwapTable {
     }
 }
 
+/// Elects leaders by stake, ignoring the performance of past leaders.
+struct StakeBasedStrategy {
+    context: Arc<Context>,
+    reputation_scores: RwLock<ReputationScores>,
+}
+
+impl StakeBasedStrategy {
+    fn new(context: Arc<Context>) -> Self {
+        Self {
+            context,
+            reputation_scores: RwLock::new(ReputationScores::default()),
+        }
+    }
+}
+
+impl LeaderScheduleStrategy for StakeBasedStrategy {
+    fn name(&self) -> &'static str {
+        "stake_based"
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+
+    fn elect_leader(&self, round: Round, leader_offset: u32) -> AuthorityIndex {
+        elect_leader_base(&self.context, round, leader_offset)
+    }
+
+    fn calculate_scores(
+        &self,
+        commit_range: CommitRange,
+        decided_leaders: &[DecidedLeader],
+    ) -> ReputationScores {
+        ReputationScores::new(
+            commit_range,
+            committed_leaders_per_authority(&self.context, decided_leaders),
+        )
+    }
+
+    fn update(&self, reputation_scores: ReputationScores) {
+        *self.reputation_scores.write() = reputation_scores;
+    }
+
+    fn reputation_scores(&self) -> ReputationScores {
+        self.reputation_scores.read().clone()
+    }
+}
+
+/// Elects leaders by stake, then gives the leader slots of the authorities with the fewest
+/// committed leaders to the authorities with the most, via a `LeaderSwapTable`.
+/// Scores are the number of committed leaders of each authority in the last schedule.
+struct ReputationSwapStrategy {
+    context: Arc<Context>,
+    swap_stake_threshold: u64,
+    leader_swap_table: RwLock<LeaderSwapTable>,
+}
+
+impl ReputationSwapStrategy {
+    fn new(context: Arc<Context>, swap_stake_threshold: u64) -> Self {
+        Self {
+            context,
+            swap_stake_threshold,
+            leader_swap_table: RwLock::new(LeaderSwapTable::default()),
+        }
+    }
+}
+
+impl LeaderScheduleStrategy for ReputationSwapStrategy {
+    fn name(&self) -> &'static str {
+        "reputation_swap"
+    }
+
+    fn elect_leader(&self, round: Round, leader_offset: u32) -> AuthorityIndex {
+        let leader = elect_leader_base(&self.context, round, leader_offset);
+        let table = self.leader_swap_table.read();
+        table.swap(leader, round, leader_offset).unwrap_or(leader)
+    }
+
+    fn calculate_scores(
+        &self,
+        commit_range: CommitRange,
+        decided_leaders: &[DecidedLeader],
+    ) -> ReputationScores {
+        ReputationScores::new(
+            commit_range,
+            committed_leaders_per_authority(&self.context, decided_leaders),
+        )
+    }
+
+    fn update(&self, reputation_scores: ReputationScores) {
+        let table = LeaderSwapTable::new(
+            self.context.clone(),
+            reputation_scores,
+            self.swap_stake_threshold,
+        );
+        *self.leader_swap_table.write() = table;
+    }
+
+    fn reputation_scores(&self) -> ReputationScores {
+        self.leader_swap_table.read().reputation_scores.clone()
+    }
+}
+
+/// Scale of the success rates used as scores by `DecayedSuccessRateStrategy`. A score of
+/// `SUCCESS_RATE_SCALE` means all leader slots of the authority were committed.
+const SUCCESS_RATE_SCALE: u64 = 1_000_000;
+
+/// Success rate used for election weights below it, so authorities with no recent success
+/// are still elected occasionally and get a chance to recover.
+const MIN_SUCCESS_RATE: u64 = SUCCESS_RATE_SCALE / 100;
+
+/// Elects leaders with probability proportional to their stake multiplied by their leader
+/// success rate, i.e. the ratio of their leader slots which were committed. At each schedule
+/// update, `decay` percent of the previous success rate is kept and the rest comes from the
+/// last schedule. Authorities without leader slots in the last schedule keep their rate.
+struct DecayedSuccessRateStrategy {
+    context: Arc<Context>,
+    decay: u64,
+    reputation_scores: RwLock<ReputationScores>,
+}
+
+impl DecayedSuccessRateStrategy {
+    fn new(context: Arc<Context>, decay: u64) -> Self {
+        assert!(
+            decay <= 100,
+            "The success_rate_decay ({decay}) should be in range [0 - 100]"
+        );
+        Self {
+            context,
+            decay,
+            reputation_scores: RwLock::new(ReputationScores::default()),
+        }
+    }
+
+    fn success_rates(&self) -> Vec<u64> {
+        let reputation_scores = self.reputation_scores.read();
+        if reputation_scores.scores_per_authority.is_empty() {
+            // Every authority starts with a perfect record.
+            vec![SUCCESS_RATE_SCALE; self.context.committee.size()]
+        } else {
+            reputation_scores.scores_per_authority.clone()
+        }
+    }
+}
+
+impl LeaderScheduleStrategy for DecayedSuccessRateStrategy {
+    fn name(&self) -> &'static str {
+        "decayed_success_rate"
+    }
+
+    fn elect_leader(&self, round: Round, leader_offset: u32) -> AuthorityIndex {
+        let success_rates = self.success_rates();
+        elect_leader_weighted(&self.context, round, leader_offset, |index, authority| {
+            let success_rate = success_rates[index].max(MIN_SUCCESS_RATE);
+            authority.stake as f64 * (success_rate as f64 / SUCCESS_RATE_SCALE as f64)
+        })
+    }
+
+    fn calculate_scores(
+        &self,
+        commit_range: CommitRange,
+        decided_leaders: &[DecidedLeader],
+    ) -> ReputationScores {
+        let committed = committed_leaders_per_authority(&self.context, decided_leaders);
+        let mut decided = vec![0; self.context.committee.size()];
+        for leader in decided_leaders {
+            decided[leader.slot.authority] += 1;
+        }
+
+        let scores = self
+            .success_rates()
+            .into_iter()
+            .enumerate()
+            .map(|(index, previous_rate)| {
+                if decided[index] == 0 {
+                    return previous_rate;
+                }
+                let rate = committed[index] * SUCCESS_RATE_SCALE / decided[index];
+                (self.decay * previous_rate + (100 - self.decay) * rate) / 100
+            })
+            .collect();
+        ReputationScores::new(commit_range, scores)
+    }
+
+    fn update(&self, reputation_scores: ReputationScores) {
+        *self.reputation_scores.write() = reputation_scores;
+    }
+
+    fn reputation_scores(&self) -> ReputationScores {
+        self.reputation_scores.read().clone()
+    }
+}
+
+/// Elects leaders by stake, but replaces authorities whose last leader slots of the previous
+/// schedule were all skipped, at least `max_consecutive_skipped_slots` times in a row. This
+/// usually happens when the authority is down or too slow, and its slots end in leader
+/// timeouts. Scores are the number of trailing consecutive skipped slots of each authority.
+///
+/// Excluded authorities have no leader slots, so they are given a chance again in the schedule
+/// after. At most `f` stake is excluded, starting from the highest scores.
+struct TimeoutExclusionStrategy {
+    context: Arc<Context>,
+    max_consecutive_skipped_slots: u64,
+    // The scores of the current schedule, and the authorities it excludes.
+    state: RwLock<(ReputationScores, Vec<AuthorityIndex>)>,
+}
+
+impl TimeoutExclusionStrategy {
+    fn new(context: Arc<Context>, max_consecutive_skipped_slots: u64) -> Self {
+        assert!(
+            max_consecutive_skipped_slots > 0,
+            "The max_consecutive_skipped_slots should be positive"
+        );
+        Self {
+            context,
+            max_consecutive_skipped_slots,
+            state: RwLock::new((ReputationScores::default(), vec![])),
+        }
+    }
+}
+
+impl LeaderScheduleStrategy for TimeoutExclusionStrategy {
+    fn name(&self) -> &'static str {
+        "timeout_exclusion"
+    }
+
+    fn elect_leader(&self, round: Round, leader_offset: u32) -> AuthorityIndex {
+        let leader = elect_leader_base(&self.context, round, leader_offset);
+        let state = self.state.read();
+        let excluded = &state.1;
+        if !excluded.contains(&leader) {
+            return leader;
+        }
+
+        // Same as in LeaderSwapTable::swap(), all remaining authorities are equally likely
+        // to replace the excluded leader.
+        let mut seed_bytes = [0u8; 32];
+        seed_bytes[24..28].copy_from_slice(&round.to_le_bytes());
+        seed_bytes[28..32].copy_from_slice(&leader_offset.to_le_bytes());
+        let mut rng = StdRng::from_seed(seed_bytes);
+        let candidates = self
+            .context
+            .committee
+            .authorities()
+            .map(|(index, _)| index)
+            .filter(|index| !excluded.contains(index))
+            .collect::<Vec<_>>();
+        *candidates
+            .choose(&mut rng)
+            .expect("There should be at least one authority not excluded")
+    }
+
+    fn calculate_scores(
+        &self,
+        commit_range: CommitRange,
+        decided_leaders: &[DecidedLeader],
+    ) -> ReputationScores {
+        let mut consecutive_skipped = vec![0; self.context.committee.size()];
+        for leader in decided_leaders {
+            if leader.committed {
+                consecutive_skipped[leader.slot.authority] = 0;
+            } else {
+                consecutive_skipped[leader.slot.authority] += 1;
+            }
+        }
+        ReputationScores::new(commit_range, consecutive_skipped)
+    }
+
+    fn update(&self, reputation_scores: ReputationScores) {
+        let mut excluded = vec![];
+        let mut excluded_stake = 0;
+        for (index, score) in reputation_scores.authorities_by_score_desc(self.context.clone()) {
+            if score < self.max_consecutive_skipped_slots {
+                break;
+            }
+            let stake = self.context.committee.stake(index);
+            // Keep the excluded stake below the validity threshold, i.e. at most f.
+            if self
+                .context
+                .committee
+                .reached_validity(excluded_stake + stake)
+            {
+                break;
+            }
+            excluded_stake += stake;
+            excluded.push(index);
+        }
+        if !excluded.is_empty() {
+            tracing::debug!(
+                "Excluding leaders {excluded:?} for {:?}",
+                reputation_scores.commit_range
+            );
+        }
+        *self.state.write() = (reputation_scores, excluded);
+    }
+
+    fn reputation_scores(&self) -> ReputationScores {
+        self.state.read().0.clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::commit::CommitRange;
+    use crate::{
+        block::{BlockDigest, BlockRef, BlockTimestampMs},
+        commit::{CommitDigest, CommitRange, TrustedCommit},
+        storage::mem_store::MemStore,
+    };
 
     #[test]
     fn test_elect_leader() {
         let context = Arc::new(Context::new_for_test(4).0);
-        let leader_schedule = LeaderSchedule::new(context, LeaderSwapTable::default());
+        let leader_schedule = LeaderSchedule::new(context);
 
         assert_eq!(
             leader_schedule.elect_leader(0, 0),
@@ -329,7 +887,7 @@ mod tests {
     #[test]
     fn test_elect_leader_stake_based() {
         let context = Arc::new(Context::new_for_test(4).0);
-        let leader_schedule = LeaderSchedule::new(context, LeaderSwapTable::default());
+        let leader_schedule = LeaderSchedule::new(context);
 
         assert_eq!(
             leader_schedule.elect_leader_stake_based(0, 0),
@@ -456,74 +1014,277 @@ mod tests {
         LeaderSwapTable::new(context, reputation_scores, swap_stake_threshold);
     }
 
+    fn context_for_strategy(
+        strategy: LeaderScheduleStrategyType,
+        committee_size: usize,
+        commits_per_schedule: u64,
+    ) -> Arc<Context> {
+        let (context, _) = Context::new_for_test(committee_size);
+        let mut parameters = context.parameters.clone();
+        parameters.leader_schedule.strategy = strategy;
+        parameters.leader_schedule.commits_per_schedule = commits_per_schedule;
+        Arc::new(context.with_parameters(parameters))
+    }
+
+    fn decided_leader(round: Round, authority: u32, committed: bool) -> DecidedLeader {
+        DecidedLeader {
+            slot: Slot::new(round, AuthorityIndex::new_for_test(authority)),
+            committed,
+        }
+    }
+
     #[test]
     fn test_update_leader_swap_table() {
         telemetry_subscribers::init_for_testing();
-        let context = Arc::new(Context::new_for_test(4).0);
+        let context = context_for_strategy(LeaderScheduleStrategyType::ReputationSwap, 4, 10);
 
-        let swap_stake_threshold = 33;
         let reputation_scores = ReputationScores::new(
             CommitRange::new(1..10),
             (0..4).map(|i| i as u64).collect::<Vec<_>>(),
         );
-        let leader_swap_table =
-            LeaderSwapTable::new(context.clone(), reputation_scores, swap_stake_threshold);
 
-        let leader_schedule = LeaderSchedule::new(context.clone(), LeaderSwapTable::default());
+        let leader_schedule = LeaderSchedule::new(context.clone());
 
         // Update leader from brand new schedule to first real schedule
-        leader_schedule.update_leader_swap_table(leader_swap_table.clone());
+        leader_schedule.update(reputation_scores);
 
         let reputation_scores = ReputationScores::new(
             CommitRange::new(11..20),
             (0..4).map(|i| i as u64).collect::<Vec<_>>(),
         );
-        let leader_swap_table =
-            LeaderSwapTable::new(context.clone(), reputation_scores, swap_stake_threshold);
 
         // Update leader from old swap table to new valid swap table
-        leader_schedule.update_leader_swap_table(leader_swap_table.clone());
+        leader_schedule.update(reputation_scores);
     }
 
     #[test]
     #[should_panic(
-        expected = "The new LeaderSwapTable has an invalid CommitRange. Old LeaderSwapTable CommitRange(11..20) vs new LeaderSwapTable CommitRange(21..25)"
+        expected = "The new leader schedule has an invalid CommitRange. Old schedule CommitRange(11..20) vs new schedule CommitRange(21..25)"
     )]
     fn test_update_bad_leader_swap_table() {
         telemetry_subscribers::init_for_testing();
-        let context = Arc::new(Context::new_for_test(4).0);
+        let context = context_for_strategy(LeaderScheduleStrategyType::ReputationSwap, 4, 10);
 
-        let swap_stake_threshold = 33;
         let reputation_scores = ReputationScores::new(
             CommitRange::new(1..10),
             (0..4).map(|i| i as u64).collect::<Vec<_>>(),
         );
-        let leader_swap_table =
-            LeaderSwapTable::new(context.clone(), reputation_scores, swap_stake_threshold);
 
-        let leader_schedule = LeaderSchedule::new(context.clone(), LeaderSwapTable::default());
+        let leader_schedule = LeaderSchedule::new(context.clone());
 
         // Update leader from brand new schedule to first real schedule
-        leader_schedule.update_leader_swap_table(leader_swap_table.clone());
+        leader_schedule.update(reputation_scores);
 
         let reputation_scores = ReputationScores::new(
             CommitRange::new(11..20),
             (0..4).map(|i| i as u64).collect::<Vec<_>>(),
         );
-        let leader_swap_table =
-            LeaderSwapTable::new(context.clone(), reputation_scores, swap_stake_threshold);
 
         // Update leader from old swap table to new valid swap table
-        leader_schedule.update_leader_swap_table(leader_swap_table.clone());
+        leader_schedule.update(reputation_scores);
 
         let reputation_scores = ReputationScores::new(
             CommitRange::new(21..25),
             (0..4).map(|i| i as u64).collect::<Vec<_>>(),
         );
-        let leader_swap_table =
-            LeaderSwapTable::new(context.clone(), reputation_scores, swap_stake_threshold);
 
         // Update leader from old swap table to new invalid swap table
-        leader_schedule.update_leader_swap_table(leader_swap_table.clone());
+        leader_schedule.update(reputation_scores);
+    }
+
+    #[test]
+    fn test_reputation_swap_strategy() {
+        let context = context_for_strategy(LeaderScheduleStrategyType::ReputationSwap, 4, 10);
+        let leader_schedule = LeaderSchedule::new(context);
+        leader_schedule.update(ReputationScores::new(
+            CommitRange::new(1..10),
+            (0..4).map(|i| i as u64).collect::<Vec<_>>(),
+        ));
+
+        // The slots of the worst authority go to the best authority.
+        assert_eq!(
+            leader_schedule.elect_leader(4, 0),
+            AuthorityIndex::new_for_test(3)
+        );
+        assert_eq!(
+            leader_schedule.elect_leader(5, 0),
+            AuthorityIndex::new_for_test(1)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The commits_per_schedule should be positive")]
+    fn test_zero_commits_per_schedule() {
+        let context = context_for_strategy(LeaderScheduleStrategyType::ReputationSwap, 4, 0);
+        LeaderSchedule::new(context);
+    }
+
+    #[test]
+    fn test_zero_commits_per_schedule_stake_based() {
+        // The static schedule is never updated, so the number of commits is unused.
+        let context = context_for_strategy(LeaderScheduleStrategyType::StakeBased, 4, 0);
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store)));
+        let leader_schedule = LeaderSchedule::from_store(context, dag_state.clone());
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state),
+            None
+        );
+    }
+
+    #[test]
+    fn test_stake_based_strategy_is_static() {
+        let context = context_for_strategy(LeaderScheduleStrategyType::StakeBased, 4, 10);
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store)));
+        let leader_schedule = LeaderSchedule::new(context);
+
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decayed_success_rate_strategy() {
+        let context = context_for_strategy(LeaderScheduleStrategyType::DecayedSuccessRate, 4, 10);
+        let strategy = DecayedSuccessRateStrategy::new(context, 50);
+
+        // Authority 0 had all its leader slots skipped, the others had theirs committed.
+        let decided_leaders = (1..=8)
+            .map(|round| decided_leader(round, round % 4, round % 4 != 0))
+            .collect::<Vec<_>>();
+        let scores = strategy.calculate_scores(CommitRange::new(1..6), &decided_leaders);
+        assert_eq!(
+            scores.scores_per_authority,
+            vec![
+                SUCCESS_RATE_SCALE / 2,
+                SUCCESS_RATE_SCALE,
+                SUCCESS_RATE_SCALE,
+                SUCCESS_RATE_SCALE
+            ]
+        );
+        strategy.update(scores);
+
+        // Decay continues. Authority 1 has no slot and keeps its rate.
+        let decided_leaders = vec![
+            decided_leader(9, 0, false),
+            decided_leader(10, 2, true),
+            decided_leader(11, 3, false),
+            decided_leader(12, 3, true),
+        ];
+        let scores = strategy.calculate_scores(CommitRange::new(7..12), &decided_leaders);
+        assert_eq!(
+            scores.scores_per_authority,
+            vec![
+                SUCCESS_RATE_SCALE / 4,
+                SUCCESS_RATE_SCALE,
+                SUCCESS_RATE_SCALE,
+                SUCCESS_RATE_SCALE * 3 / 4
+            ]
+        );
+        strategy.update(scores);
+
+        // Leaders are elected in proportion to their success rates.
+        let mut elected = [0; 4];
+        for round in 1..=1000 {
+            elected[strategy.elect_leader(round, 0)] += 1;
+        }
+        assert!(elected[0] < elected[3], "{elected:?}");
+        assert!(elected[3] < elected[1], "{elected:?}");
+        assert!(elected[3] < elected[2], "{elected:?}");
+    }
+
+    #[test]
+    fn test_timeout_exclusion_strategy() {
+        telemetry_subscribers::init_for_testing();
+        let context = context_for_strategy(LeaderScheduleStrategyType::TimeoutExclusion, 7, 10);
+        let strategy = TimeoutExclusionStrategy::new(context, 3);
+
+        // Authorities 4, 5 and 6 end with 3, 4 and 5 skipped slots in a row. Authority 0
+        // had 3 skipped slots, but the last one was committed.
+        let mut decided_leaders = vec![];
+        for round in 1..=6 {
+            decided_leaders.push(decided_leader(round, 0, round > 3 && round != 5));
+        }
+        for (authority, skipped) in [(4, 3), (5, 4), (6, 5)] {
+            decided_leaders.push(decided_leader(10, authority, true));
+            for i in 0..skipped {
+                decided_leaders.push(decided_leader(11 + i, authority, false));
+            }
+        }
+        let scores = strategy.calculate_scores(CommitRange::new(1..10), &decided_leaders);
+        assert_eq!(scores.scores_per_authority, vec![0, 0, 0, 0, 3, 4, 5]);
+        strategy.update(scores);
+
+        // At most f = 2 authorities are excluded, starting from the most skipped slots.
+        for round in 1..=100 {
+            let leader = strategy.elect_leader(round, 0);
+            assert_ne!(leader, AuthorityIndex::new_for_test(5));
+            assert_ne!(leader, AuthorityIndex::new_for_test(6));
+        }
+        assert_eq!(strategy.elect_leader(4, 0), AuthorityIndex::new_for_test(4));
+
+        // Excluded authorities have no slots in the next schedule, so they get another chance.
+        let scores = strategy.calculate_scores(CommitRange::new(11..20), &[]);
+        assert_eq!(scores.scores_per_authority, vec![0; 7]);
+        strategy.update(scores);
+        assert_eq!(strategy.elect_leader(6, 0), AuthorityIndex::new_for_test(6));
+    }
+
+    #[test]
+    fn test_update_leader_schedule_from_commits() {
+        telemetry_subscribers::init_for_testing();
+        let context = context_for_strategy(LeaderScheduleStrategyType::TimeoutExclusion, 4, 9);
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        let leader_schedule = LeaderSchedule::new(context.clone());
+
+        // Leaders of rounds 1 to 12 are committed, except the ones of authority 3 which are
+        // skipped at rounds 3, 7 and 11.
+        let mut commit_index = 0;
+        for round in 1..=12 {
+            let leader = leader_schedule.elect_leader(round, 0);
+            if leader == AuthorityIndex::new_for_test(3) {
+                continue;
+            }
+            commit_index += 1;
+            dag_state.write().add_commit(TrustedCommit::new_for_test(
+                commit_index,
+                CommitDigest::MIN,
+                round as BlockTimestampMs,
+                BlockRef::new(round, leader, BlockDigest::MIN),
+                vec![],
+            ));
+        }
+        dag_state.write().flush();
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state.clone()),
+            Some(0)
+        );
+
+        leader_schedule.update_leader_schedule(dag_state.clone());
+        let expected_scores = ReputationScores::new(CommitRange::new(1..9), vec![0, 0, 0, 3]);
+        assert_eq!(
+            leader_schedule.strategy.reputation_scores(),
+            expected_scores
+        );
+        assert_eq!(dag_state.read().reputation_scores(), expected_scores);
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state.clone()),
+            Some(9)
+        );
+        assert_ne!(
+            leader_schedule.elect_leader(15, 0),
+            AuthorityIndex::new_for_test(3)
+        );
+
+        // The new scores were not persisted yet, so they are calculated again on recovery.
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store)));
+        let recovered_leader_schedule = LeaderSchedule::from_store(context, dag_state);
+        assert_eq!(
+            recovered_leader_schedule.strategy.reputation_scores(),
+            expected_scores
+        );
     }
 }
@@ -9,11 +9,13 @@ mod block_manager;
 mod block_verifier;
 mod broadcaster;
 mod commit;
+mod commit_archive;
 mod commit_observer;
 mod commit_syncer;
 mod context;
 mod core;
 mod core_thread;
+mod dag_inspector;
 mod dag_state;
 mod error;
 mod leader_schedule;
@@ -30,6 +32,8 @@ mod threshold_clock;
 mod transaction;
 mod universal_committer;
 
+#[cfg(test)]
+mod simulator;
 #[cfg(test)]
 mod test_dag;
 #[cfg(test)]
@@ -40,4 +44,9 @@ mod test_dag_parser;
 pub use authority_node::ConsensusAuthority;
 pub use block::{BlockAPI, Round};
 pub use commit::{CommitConsumer, CommitIndex, CommittedSubDag};
+pub use commit_archive::{decode_archive_file, ArchivedCommit, CommitArchiveReader};
+pub use dag_inspector::{
+    AuthorityProgress, BlockSummary, CommitSummary, DagInspector, DagSnapshot,
+    ReputationScoresSummary,
+};
 pub use transaction::{TransactionClient, TransactionVerifier, ValidationError};
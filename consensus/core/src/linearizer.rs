@@ -150,7 +150,7 @@ mod tests {
     use crate::{
         commit::{CommitAPI as _, CommitDigest, DEFAULT_WAVE_LENGTH},
         context::Context,
-        leader_schedule::{LeaderSchedule, LeaderSwapTable},
+        leader_schedule::LeaderSchedule,
         storage::mem_store::MemStore,
         test_dag::{build_dag, get_all_uncommitted_leader_blocks},
     };
@@ -165,7 +165,7 @@ mod tests {
             Arc::new(MemStore::new()),
         )));
         let mut linearizer = Linearizer::new(dag_state.clone());
-        let leader_schedule = LeaderSchedule::new(context.clone(), LeaderSwapTable::default());
+        let leader_schedule = LeaderSchedule::new(context.clone());
 
         // Populate fully connected test blocks for round 0 ~ 10, authorities 0 ~ 3.
         let num_rounds: u32 = 10;
@@ -215,7 +215,7 @@ mod tests {
             context.clone(),
             Arc::new(MemStore::new()),
         )));
-        let leader_schedule = LeaderSchedule::new(context.clone(), LeaderSwapTable::default());
+        let leader_schedule = LeaderSchedule::new(context.clone());
         let mut linearizer = Linearizer::new(dag_state.clone());
         let wave_length = DEFAULT_WAVE_LENGTH;
 
@@ -107,6 +107,9 @@ pub(crate) struct NodeMetrics {
     pub(crate) commit_round_advancement_interval: Histogram,
     pub(crate) last_decided_leader_round: IntGauge,
     pub(crate) leader_timeout_total: IntCounterVec,
+    pub(crate) leader_schedule_strategy: IntGaugeVec,
+    pub(crate) leader_schedule_updates_total: IntCounterVec,
+    pub(crate) leader_schedule_slots_total: IntCounterVec,
     pub(crate) missing_blocks_total: IntCounter,
     pub(crate) missing_blocks_after_fetch_total: IntCounter,
     pub(crate) quorum_receive_latency: Histogram,
@@ -130,6 +133,12 @@ pub(crate) struct NodeMetrics {
     pub(crate) commit_sync_local_index: IntGauge,
     pub(crate) commit_sync_fetch_loop_latency: Histogram,
     pub(crate) commit_sync_fetch_once_latency: Histogram,
+    pub(crate) commit_archive_last_archived_index: IntGauge,
+    pub(crate) commit_archive_dropped_commits: IntCounter,
+    pub(crate) commit_archive_backfilled_commits: IntCounter,
+    pub(crate) commit_archive_uploaded_files: IntCounter,
+    pub(crate) commit_archive_upload_failures: IntCounter,
+    pub(crate) commit_archive_uploaded_bytes: IntCounter,
     pub(crate) uptime: Histogram,
 }
 
@@ -295,6 +304,24 @@ impl NodeMetrics {
                 &["timeout_type"],
                 registry,
             ).unwrap(),
+            leader_schedule_strategy: register_int_gauge_vec_with_registry!(
+                "leader_schedule_strategy",
+                "The leader schedule strategy in use, set to 1 for the configured strategy",
+                &["strategy"],
+                registry,
+            ).unwrap(),
+            leader_schedule_updates_total: register_int_counter_vec_with_registry!(
+                "leader_schedule_updates_total",
+                "Total number of leader schedule updates",
+                &["strategy"],
+                registry,
+            ).unwrap(),
+            leader_schedule_slots_total: register_int_counter_vec_with_registry!(
+                "leader_schedule_slots_total",
+                "Total number of leader slots decided per leader schedule strategy, either committed or skipped. Used to compare the success rate of strategies",
+                &["strategy", "status"],
+                registry,
+            ).unwrap(),
             missing_blocks_total: register_int_counter_with_registry!(
                 "missing_blocks_total",
                 "Total cumulative number of missing blocks",
@@ -420,6 +447,36 @@ impl NodeMetrics {
                 LATENCY_SEC_BUCKETS.to_vec(),
                 registry,
             ).unwrap(),
+            commit_archive_last_archived_index: register_int_gauge_with_registry!(
+                "commit_archive_last_archived_index",
+                "Index of the last commit uploaded to the commit archive",
+                registry,
+            ).unwrap(),
+            commit_archive_dropped_commits: register_int_counter_with_registry!(
+                "commit_archive_dropped_commits",
+                "The number of commits not taken by the commit archiver because it was lagging or had too many commits waiting to be uploaded. These are backfilled from the store",
+                registry,
+            ).unwrap(),
+            commit_archive_backfilled_commits: register_int_counter_with_registry!(
+                "commit_archive_backfilled_commits",
+                "The number of commits the commit archiver read back from the store to fill gaps",
+                registry,
+            ).unwrap(),
+            commit_archive_uploaded_files: register_int_counter_with_registry!(
+                "commit_archive_uploaded_files",
+                "The number of files uploaded to the commit archive",
+                registry,
+            ).unwrap(),
+            commit_archive_upload_failures: register_int_counter_with_registry!(
+                "commit_archive_upload_failures",
+                "The number of failed attempts to upload a file to the commit archive",
+                registry,
+            ).unwrap(),
+            commit_archive_uploaded_bytes: register_int_counter_with_registry!(
+                "commit_archive_uploaded_bytes",
+                "The total size in bytes of files uploaded to the commit archive",
+                registry,
+            ).unwrap(),
             uptime: register_histogram_with_registry!(
                 "uptime",
                 "Total node uptime",
@@ -0,0 +1,768 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A deterministic simulator running a whole committee of consensus authorities in one process.
+//!
+//! Instead of the networking and threading layers of `AuthorityNode`, the simulator drives the
+//! `Core` of every authority directly from a single event loop. Each `Core` is built from the
+//! same components as in `AuthorityNode`, and received blocks are verified from their serialized
+//! form as `AuthorityService` does. Time is simulated with a manual `Clock`, and block broadcasts
+//! and block fetches are delivered as events over a simulated network with configurable latency,
+//! message drops and partitions. Given the same `Scenario`, including its seed, a simulation
+//! always produces the same commits.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BTreeSet, BinaryHeap},
+    fs,
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+use consensus_config::{
+    local_committee_and_keys, AuthorityIndex, LeaderScheduleParameters, Parameters, ProtocolKeyPair,
+};
+use parking_lot::RwLock;
+use rand::{rngs::StdRng, Rng as _, SeedableRng as _};
+use serde::Deserialize;
+use tokio::sync::{broadcast, mpsc::UnboundedReceiver, watch};
+use tracing::{info, warn};
+
+use crate::{
+    block::{
+        Block, BlockAPI as _, BlockRef, BlockTimestampMs, BlockV1, Round, SignedBlock, Transaction,
+        VerifiedBlock,
+    },
+    block_manager::BlockManager,
+    block_verifier::{BlockVerifier, SignedBlockVerifier},
+    commit::CommitRange,
+    commit_observer::CommitObserver,
+    context::{Clock, Context},
+    core::{Core, CoreSignals, CoreSignalsReceivers},
+    dag_state::DagState,
+    leader_schedule::LeaderSchedule,
+    leader_scoring::ReputationScores,
+    storage::mem_store::MemStore,
+    transaction::{NoopTransactionVerifier, TransactionClient, TransactionConsumer},
+    CommitConsumer, CommittedSubDag,
+};
+
+/// Describes the committee, network conditions and faults of a simulation, and the
+/// expected outcome. Scenarios are usually loaded from YAML files.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Scenario {
+    pub(crate) name: String,
+    /// Seed of all randomness in the simulation.
+    #[serde(default)]
+    pub(crate) seed: u64,
+    /// Stake of each authority. The committee size is the number of entries.
+    pub(crate) stakes: Vec<u64>,
+    /// Simulated time to run for.
+    pub(crate) duration_ms: u64,
+    #[serde(default = "Scenario::default_leader_timeout_ms")]
+    pub(crate) leader_timeout_ms: u64,
+    #[serde(default = "Scenario::default_min_round_delay_ms")]
+    pub(crate) min_round_delay_ms: u64,
+    #[serde(default)]
+    pub(crate) leader_schedule: LeaderScheduleParameters,
+    #[serde(default)]
+    pub(crate) network: NetworkConfig,
+    #[serde(default)]
+    pub(crate) partitions: Vec<Partition>,
+    /// Authorities that stop processing and sending messages at the given time.
+    #[serde(default)]
+    pub(crate) crashes: Vec<Crash>,
+    /// Authorities that send a different version of each of their blocks to half of the
+    /// committee.
+    #[serde(default)]
+    pub(crate) equivocators: Vec<u32>,
+    #[serde(default)]
+    pub(crate) expect: Expectations,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct NetworkConfig {
+    /// Minimum one-way latency of a message.
+    pub(crate) latency_ms: u64,
+    /// Maximum random latency added on top of `latency_ms`.
+    #[serde(default)]
+    pub(crate) jitter_ms: u64,
+    /// Probability in [0, 1] that a message is dropped.
+    #[serde(default)]
+    pub(crate) drop_rate: f64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            latency_ms: 50,
+            jitter_ms: 0,
+            drop_rate: 0.0,
+        }
+    }
+}
+
+/// Splits the committee into groups between `start_ms` and `end_ms`. Messages between
+/// authorities of different groups are dropped. Authorities not in any group are isolated.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Partition {
+    pub(crate) start_ms: u64,
+    pub(crate) end_ms: u64,
+    pub(crate) groups: Vec<Vec<u32>>,
+}
+
+impl Partition {
+    fn separates(&self, a: AuthorityIndex, b: AuthorityIndex, now_ms: u64) -> bool {
+        if now_ms < self.start_ms || now_ms >= self.end_ms {
+            return false;
+        }
+        let group_of = |authority: AuthorityIndex| {
+            self.groups
+                .iter()
+                .position(|group| group.contains(&(authority.value() as u32)))
+        };
+        match (group_of(a), group_of(b)) {
+            (Some(group_a), Some(group_b)) => group_a != group_b,
+            _ => true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Crash {
+    pub(crate) authority: u32,
+    pub(crate) at_ms: u64,
+}
+
+/// Expected outcome of a scenario. Safety of commits among authorities that are not
+/// equivocating is always checked.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Expectations {
+    /// Minimum number of commits every authority that has not crashed nor equivocated
+    /// should reach.
+    #[serde(default)]
+    pub(crate) min_commits: u32,
+    /// Authorities expected to have the lowest reputation scores, i.e. no authority outside
+    /// this set may score lower than any authority in it.
+    #[serde(default)]
+    pub(crate) lowest_scores: Vec<u32>,
+}
+
+impl Scenario {
+    pub(crate) fn load(path: &Path) -> Self {
+        let content = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read scenario {}: {e}", path.display()));
+        serde_yaml::from_str(&content)
+            .unwrap_or_else(|e| panic!("Failed to parse scenario {}: {e}", path.display()))
+    }
+
+    fn default_leader_timeout_ms() -> u64 {
+        Parameters::default().leader_timeout.as_millis() as u64
+    }
+
+    fn default_min_round_delay_ms() -> u64 {
+        Parameters::default().min_round_delay.as_millis() as u64
+    }
+
+    fn crash_time_ms(&self, authority: AuthorityIndex) -> Option<u64> {
+        self.crashes
+            .iter()
+            .filter(|crash| crash.authority as usize == authority.value())
+            .map(|crash| crash.at_ms)
+            .min()
+    }
+
+    fn is_equivocator(&self, authority: AuthorityIndex) -> bool {
+        self.equivocators.contains(&(authority.value() as u32))
+    }
+}
+
+/// Interval at which each authority checks for leader timeouts and missing blocks.
+const TICK_INTERVAL_MS: u64 = 10;
+
+enum Event {
+    /// Blocks broadcasted by `from` arrive at `to`.
+    DeliverBlocks {
+        from: AuthorityIndex,
+        to: AuthorityIndex,
+        blocks: Vec<VerifiedBlock>,
+    },
+    /// A round trip where `requester` fetches missing blocks from `peer`.
+    FetchBlocks {
+        requester: AuthorityIndex,
+        peer: AuthorityIndex,
+        refs: BTreeSet<BlockRef>,
+    },
+    /// Periodic processing of an authority, e.g. to propose on leader timeout.
+    Tick { authority: AuthorityIndex },
+}
+
+struct ScheduledEvent {
+    time_ms: u64,
+    // Breaks ties between events scheduled at the same time, in scheduling order.
+    sequence: u64,
+    event: Event,
+}
+
+impl PartialEq for ScheduledEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScheduledEvent {}
+
+impl PartialOrd for ScheduledEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScheduledEvent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.time_ms, self.sequence).cmp(&(other.time_ms, other.sequence))
+    }
+}
+
+/// One simulated authority.
+struct SimNode {
+    context: Arc<Context>,
+    core: Core,
+    dag_state: Arc<RwLock<DagState>>,
+    block_verifier: Arc<SignedBlockVerifier>,
+    leader_schedule: Arc<LeaderSchedule>,
+    protocol_keypair: ProtocolKeyPair,
+    block_receiver: broadcast::Receiver<VerifiedBlock>,
+    commit_receiver: UnboundedReceiver<CommittedSubDag>,
+    new_round_receiver: watch::Receiver<Round>,
+    // Kept alive to keep the transaction channel open.
+    _transaction_client: TransactionClient,
+    _signal_receivers: CoreSignalsReceivers,
+    crash_time_ms: Option<u64>,
+    equivocator: bool,
+    // Threshold clock round last observed, and when it was observed.
+    current_round: Round,
+    current_round_start_ms: u64,
+    commits: Vec<CommittedSubDag>,
+}
+
+impl SimNode {
+    fn new(scenario: &Scenario, index: AuthorityIndex, clock: Arc<Clock>) -> Self {
+        let (committee, mut keypairs) = local_committee_and_keys(0, scenario.stakes.clone());
+        let parameters = Parameters {
+            leader_timeout: Duration::from_millis(scenario.leader_timeout_ms),
+            min_round_delay: Duration::from_millis(scenario.min_round_delay_ms),
+            leader_schedule: scenario.leader_schedule.clone(),
+            ..Default::default()
+        };
+        let (mut context, _) = Context::new_for_test(scenario.stakes.len());
+        context = context
+            .with_committee(committee)
+            .with_authority_index(index)
+            .with_parameters(parameters);
+        context.clock = clock;
+        let context = Arc::new(context);
+
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        let block_verifier = Arc::new(SignedBlockVerifier::new(
+            context.clone(),
+            Arc::new(NoopTransactionVerifier {}),
+        ));
+        let block_manager =
+            BlockManager::new(context.clone(), dag_state.clone(), block_verifier.clone());
+        let leader_schedule = Arc::new(LeaderSchedule::from_store(
+            context.clone(),
+            dag_state.clone(),
+        ));
+        let (transaction_client, tx_receiver) = TransactionClient::new(context.clone());
+        let transaction_consumer = TransactionConsumer::new(tx_receiver, context.clone(), None);
+        let (signals, signal_receivers) = CoreSignals::new(context.clone());
+        let block_receiver = signal_receivers.block_broadcast_receiver();
+        let new_round_receiver = signal_receivers.new_round_receiver();
+        let (commit_sender, commit_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let commit_observer = CommitObserver::new(
+            context.clone(),
+            CommitConsumer::new(commit_sender, 0, 0),
+            dag_state.clone(),
+            store,
+        );
+        let protocol_keypair = keypairs.remove(index.value()).1;
+
+        let core = Core::new(
+            context.clone(),
+            leader_schedule.clone(),
+            transaction_consumer,
+            block_manager,
+            commit_observer,
+            signals,
+            protocol_keypair.clone(),
+            dag_state.clone(),
+        );
+
+        Self {
+            context,
+            core,
+            dag_state,
+            block_verifier,
+            leader_schedule,
+            protocol_keypair,
+            block_receiver,
+            commit_receiver,
+            new_round_receiver,
+            _transaction_client: transaction_client,
+            _signal_receivers: signal_receivers,
+            crash_time_ms: scenario.crash_time_ms(index),
+            equivocator: scenario.is_equivocator(index),
+            current_round: 0,
+            current_round_start_ms: 0,
+            commits: vec![],
+        }
+    }
+
+    fn index(&self) -> AuthorityIndex {
+        self.context.own_index
+    }
+
+    fn is_crashed(&self, now_ms: u64) -> bool {
+        self.crash_time_ms.is_some_and(|at_ms| now_ms >= at_ms)
+    }
+
+    /// Verifies blocks received from a peer from their serialized form, the way
+    /// `AuthorityService` does. Invalid blocks are dropped.
+    fn verify_blocks(&self, blocks: Vec<VerifiedBlock>) -> Vec<VerifiedBlock> {
+        blocks
+            .into_iter()
+            .filter_map(|block| {
+                let serialized = block.serialized().clone();
+                let signed_block: SignedBlock =
+                    bcs::from_bytes(&serialized).expect("Block deserialization failed.");
+                match self.block_verifier.verify(&signed_block) {
+                    Ok(()) => Some(VerifiedBlock::new_verified(signed_block, serialized)),
+                    Err(e) => {
+                        warn!(
+                            "Authority {} rejected block {}: {e}",
+                            self.index(),
+                            block.reference()
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Scores authorities by their committed leaders, using the leader schedule of the node.
+    fn reputation_scores(&self) -> ReputationScores {
+        let (Some(first), Some(last)) = (self.commits.first(), self.commits.last()) else {
+            return ReputationScores::new(
+                CommitRange::default(),
+                vec![0; self.context.committee.size()],
+            );
+        };
+        let mut dag_state = self.dag_state.write();
+        dag_state.flush();
+        self.leader_schedule.committed_leader_scores(
+            &dag_state,
+            CommitRange::new(first.commit_index..last.commit_index),
+        )
+    }
+
+    /// Creates another block for the same slot as `block`, with different content.
+    fn equivocate(&self, block: &VerifiedBlock) -> VerifiedBlock {
+        let equivocating_block = Block::V1(BlockV1::new(
+            block.epoch(),
+            block.round(),
+            block.author(),
+            block.timestamp_ms(),
+            block.ancestors().to_vec(),
+            vec![Transaction::new(block.round().to_le_bytes().to_vec())],
+            block.commit_votes().to_vec(),
+            vec![],
+        ));
+        let signed_block = SignedBlock::new(equivocating_block, &self.protocol_keypair)
+            .expect("Block signing failed.");
+        let serialized = signed_block
+            .serialize()
+            .expect("Block serialization failed.");
+        VerifiedBlock::new_verified(signed_block, serialized)
+    }
+}
+
+/// The outcome of a simulation.
+pub(crate) struct SimulationResult {
+    /// Committed sub-dags of each authority, in commit order.
+    pub(crate) commits: Vec<Vec<CommittedSubDag>>,
+    /// Authorities that followed the protocol, including the ones that crashed.
+    pub(crate) honest: Vec<AuthorityIndex>,
+    /// Authorities that followed the protocol and were running until the end.
+    pub(crate) live: Vec<AuthorityIndex>,
+    /// Committed leaders of each authority, over the commits of the honest authority with most
+    /// commits.
+    pub(crate) reputation_scores: ReputationScores,
+}
+
+impl SimulationResult {
+    /// Checks that honest authorities committed the same sequence, up to their last commit.
+    pub(crate) fn assert_safety(&self) {
+        for a in &self.honest {
+            for b in &self.honest {
+                if a >= b {
+                    continue;
+                }
+                for (commit_a, commit_b) in self.commits[a.value()]
+                    .iter()
+                    .zip(self.commits[b.value()].iter())
+                {
+                    assert_eq!(
+                        commit_a.commit_index, commit_b.commit_index,
+                        "Authorities {a} and {b} have different commit indices"
+                    );
+                    assert_eq!(
+                        commit_a.leader, commit_b.leader,
+                        "Authorities {a} and {b} committed different leaders at commit {}",
+                        commit_a.commit_index
+                    );
+                    assert_eq!(
+                        commit_a
+                            .blocks
+                            .iter()
+                            .map(|block| block.reference())
+                            .collect::<Vec<_>>(),
+                        commit_b
+                            .blocks
+                            .iter()
+                            .map(|block| block.reference())
+                            .collect::<Vec<_>>(),
+                        "Authorities {a} and {b} committed different blocks at commit {}",
+                        commit_a.commit_index
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks that every live authority reached at least `min_commits` commits.
+    pub(crate) fn assert_liveness(&self, min_commits: u32) {
+        for authority in &self.live {
+            let num_commits = self.commits[authority.value()].len();
+            assert!(
+                num_commits >= min_commits as usize,
+                "Authority {authority} only reached {num_commits} commits, expected at least {min_commits}"
+            );
+        }
+    }
+
+    /// Checks that `lowest` authorities score no higher than any other authority.
+    pub(crate) fn assert_lowest_scores(&self, lowest: &[u32]) {
+        if lowest.is_empty() {
+            return;
+        }
+        let scores = &self.reputation_scores.scores_per_authority;
+        let (expected_lowest, others): (Vec<_>, Vec<_>) = scores
+            .iter()
+            .enumerate()
+            .partition(|(index, _)| lowest.contains(&(*index as u32)));
+        let max_lowest = expected_lowest.iter().map(|(_, s)| **s).max().unwrap();
+        let min_others = others.iter().map(|(_, s)| **s).min().unwrap_or(u64::MAX);
+        assert!(
+            max_lowest <= min_others,
+            "Authorities {lowest:?} are expected to have the lowest scores, got {scores:?}"
+        );
+    }
+
+    /// Checks all expectations of the scenario.
+    pub(crate) fn check(&self, expect: &Expectations) {
+        self.assert_safety();
+        self.assert_liveness(expect.min_commits);
+        self.assert_lowest_scores(&expect.lowest_scores);
+    }
+}
+
+pub(crate) struct Simulator {
+    scenario: Scenario,
+    clock: Arc<Clock>,
+    rng: StdRng,
+    nodes: Vec<SimNode>,
+    queue: BinaryHeap<Reverse<ScheduledEvent>>,
+    next_sequence: u64,
+    now_ms: u64,
+}
+
+impl Simulator {
+    pub(crate) fn new(scenario: Scenario) -> Self {
+        assert!(
+            scenario.network.latency_ms > 0,
+            "Latency must be positive, so blocks arrive after they are created"
+        );
+        let clock = Arc::new(Clock::new_manual(0));
+        let nodes = (0..scenario.stakes.len())
+            .map(|i| {
+                SimNode::new(
+                    &scenario,
+                    AuthorityIndex::new_for_test(i as u32),
+                    clock.clone(),
+                )
+            })
+            .collect();
+        Self {
+            rng: StdRng::seed_from_u64(scenario.seed),
+            scenario,
+            clock,
+            nodes,
+            queue: BinaryHeap::new(),
+            next_sequence: 0,
+            now_ms: 0,
+        }
+    }
+
+    /// Runs the simulation until `duration_ms` of simulated time has elapsed.
+    pub(crate) fn run(mut self) -> SimulationResult {
+        info!(
+            "Running scenario {} with seed {}",
+            self.scenario.name, self.scenario.seed
+        );
+        // Authorities proposed their first blocks when their cores were created.
+        for i in 0..self.nodes.len() {
+            let authority = self.nodes[i].index();
+            self.process_outputs(authority);
+            self.schedule(TICK_INTERVAL_MS, Event::Tick { authority });
+        }
+
+        while let Some(Reverse(scheduled)) = self.queue.pop() {
+            if scheduled.time_ms > self.scenario.duration_ms {
+                break;
+            }
+            self.now_ms = scheduled.time_ms;
+            self.clock.set_timestamp_ms(self.now_ms as BlockTimestampMs);
+            match scheduled.event {
+                Event::DeliverBlocks { from, to, blocks } => self.deliver_blocks(from, to, blocks),
+                Event::FetchBlocks {
+                    requester,
+                    peer,
+                    refs,
+                } => self.fetch_blocks(requester, peer, refs),
+                Event::Tick { authority } => self.tick(authority),
+            }
+        }
+
+        self.finish()
+    }
+
+    fn deliver_blocks(
+        &mut self,
+        from: AuthorityIndex,
+        to: AuthorityIndex,
+        blocks: Vec<VerifiedBlock>,
+    ) {
+        let node = &mut self.nodes[to];
+        if node.is_crashed(self.now_ms) {
+            return;
+        }
+        let blocks = node.verify_blocks(blocks);
+        let missing = node
+            .core
+            .add_blocks(blocks)
+            .expect("Adding blocks should not fail");
+        if !missing.is_empty() {
+            self.request_missing_blocks(to, from, missing);
+        }
+        self.process_outputs(to);
+    }
+
+    fn fetch_blocks(
+        &mut self,
+        requester: AuthorityIndex,
+        peer: AuthorityIndex,
+        refs: BTreeSet<BlockRef>,
+    ) {
+        if self.nodes[requester].is_crashed(self.now_ms) || self.nodes[peer].is_crashed(self.now_ms)
+        {
+            return;
+        }
+        let refs = refs.into_iter().collect::<Vec<_>>();
+        // An equivocator cannot accept blocks for its own slots, so it never fetches them.
+        // It stops making progress once the DAG references its equivocating blocks.
+        let blocks = self.nodes[peer]
+            .dag_state
+            .read()
+            .get_blocks(&refs)
+            .into_iter()
+            .flatten()
+            .filter(|block| !self.nodes[requester].equivocator || block.author() != requester)
+            .collect::<Vec<_>>();
+        if blocks.is_empty() {
+            return;
+        }
+        self.deliver_blocks(peer, requester, blocks);
+    }
+
+    fn tick(&mut self, authority: AuthorityIndex) {
+        let now_ms = self.now_ms;
+        let leader_timeout_ms = self.scenario.leader_timeout_ms;
+        let node = &mut self.nodes[authority];
+        if node.is_crashed(now_ms) {
+            return;
+        }
+
+        let round = *node.new_round_receiver.borrow();
+        if round != node.current_round {
+            node.current_round = round;
+            node.current_round_start_ms = now_ms;
+        }
+        // Propose when possible, and forcefully after the leader timeout.
+        let force = now_ms.saturating_sub(node.current_round_start_ms) >= leader_timeout_ms;
+        node.core
+            .new_block(round, force)
+            .expect("Proposing a block should not fail");
+
+        let missing = node.core.get_missing_blocks();
+        if !missing.is_empty() {
+            let peers = (0..self.nodes.len())
+                .map(|i| AuthorityIndex::new_for_test(i as u32))
+                .filter(|peer| *peer != authority)
+                .collect::<Vec<_>>();
+            let peer = peers[self.rng.gen_range(0..peers.len())];
+            self.request_missing_blocks(authority, peer, missing);
+        }
+
+        self.process_outputs(authority);
+        self.schedule(TICK_INTERVAL_MS, Event::Tick { authority });
+    }
+
+    fn request_missing_blocks(
+        &mut self,
+        requester: AuthorityIndex,
+        peer: AuthorityIndex,
+        refs: BTreeSet<BlockRef>,
+    ) {
+        // Lost requests are retried on the next tick.
+        let Some(request_latency) = self.sample_latency(requester, peer) else {
+            return;
+        };
+        let Some(response_latency) = self.sample_latency(peer, requester) else {
+            return;
+        };
+        self.schedule(
+            request_latency + response_latency,
+            Event::FetchBlocks {
+                requester,
+                peer,
+                refs,
+            },
+        );
+    }
+
+    /// Broadcasts blocks proposed by the authority and collects its commits.
+    fn process_outputs(&mut self, authority: AuthorityIndex) {
+        let mut proposed = vec![];
+        while let Ok(block) = self.nodes[authority].block_receiver.try_recv() {
+            proposed.push(block);
+        }
+        while let Ok(commit) = self.nodes[authority].commit_receiver.try_recv() {
+            self.nodes[authority].commits.push(commit);
+        }
+
+        for block in proposed {
+            let equivocating_block = self.nodes[authority]
+                .equivocator
+                .then(|| self.nodes[authority].equivocate(&block));
+            for i in 0..self.nodes.len() {
+                let peer = AuthorityIndex::new_for_test(i as u32);
+                if peer == authority {
+                    continue;
+                }
+                let Some(latency) = self.sample_latency(authority, peer) else {
+                    continue;
+                };
+                let block = match &equivocating_block {
+                    Some(equivocating_block) if i % 2 == 1 => equivocating_block.clone(),
+                    _ => block.clone(),
+                };
+                self.schedule(
+                    latency,
+                    Event::DeliverBlocks {
+                        from: authority,
+                        to: peer,
+                        blocks: vec![block],
+                    },
+                );
+            }
+        }
+    }
+
+    /// Returns the latency of a message from `from` to `to`, or None if it is lost.
+    fn sample_latency(&mut self, from: AuthorityIndex, to: AuthorityIndex) -> Option<u64> {
+        let now_ms = self.now_ms;
+        if self
+            .scenario
+            .partitions
+            .iter()
+            .any(|partition| partition.separates(from, to, now_ms))
+        {
+            return None;
+        }
+        let network = &self.scenario.network;
+        if network.drop_rate > 0.0 && self.rng.gen_bool(network.drop_rate) {
+            return None;
+        }
+        let jitter = if network.jitter_ms > 0 {
+            self.rng.gen_range(0..=network.jitter_ms)
+        } else {
+            0
+        };
+        Some(network.latency_ms + jitter)
+    }
+
+    fn schedule(&mut self, delay_ms: u64, event: Event) {
+        self.queue.push(Reverse(ScheduledEvent {
+            time_ms: self.now_ms + delay_ms,
+            sequence: self.next_sequence,
+            event,
+        }));
+        self.next_sequence += 1;
+    }
+
+    fn finish(self) -> SimulationResult {
+        let end_ms = self.scenario.duration_ms;
+        let honest = self
+            .nodes
+            .iter()
+            .filter(|node| !node.equivocator)
+            .map(|node| node.index())
+            .collect::<Vec<_>>();
+        let live = self
+            .nodes
+            .iter()
+            .filter(|node| !node.equivocator && !node.is_crashed(end_ms))
+            .map(|node| node.index())
+            .collect::<Vec<_>>();
+        let reputation_scores = honest
+            .iter()
+            .map(|authority| &self.nodes[*authority])
+            .max_by_key(|node| node.commits.len())
+            .map(|node| node.reputation_scores())
+            .unwrap_or_default();
+        let commits = self
+            .nodes
+            .into_iter()
+            .map(|node| node.commits)
+            .collect::<Vec<_>>();
+        SimulationResult {
+            commits,
+            honest,
+            live,
+            reputation_scores,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/simulator_tests.rs"]
+mod simulator_tests;
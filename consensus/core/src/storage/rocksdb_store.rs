@@ -4,6 +4,8 @@
 use std::{
     collections::VecDeque,
     ops::Bound::{Excluded, Included},
+    path::Path,
+    sync::Arc,
     time::Duration,
 };
 
@@ -12,9 +14,11 @@ use consensus_config::AuthorityIndex;
 use sui_macros::fail_point;
 use typed_store::{
     metrics::SamplingInterval,
-    reopen,
-    rocks::{default_db_options, open_cf_opts, DBMap, MetricConf, ReadWriteOptions},
-    Map as _,
+    rocks::{
+        default_db_options, open_cf_opts, open_cf_opts_secondary, DBMap, MetricConf,
+        ReadWriteOptions, RocksDB,
+    },
+    Map as _, TypedStoreError,
 };
 
 use super::{CommitInfo, Store, WriteBatch};
@@ -77,21 +81,45 @@ impl RocksDBStore {
         )
         .expect("Cannot open database");
 
-        let (blocks, digests_by_authorities, commits, commit_votes, commit_info) = reopen!(&rocksdb,
-            Self::BLOCKS_CF;<(Round, AuthorityIndex, BlockDigest), bytes::Bytes>,
-            Self::DIGESTS_BY_AUTHORITIES_CF;<(AuthorityIndex, Round, BlockDigest), ()>,
-            Self::COMMITS_CF;<(CommitIndex, CommitDigest), Bytes>,
-            Self::COMMIT_VOTES_CF;<(CommitIndex, CommitDigest, BlockRef), ()>,
-            Self::COMMIT_INFO_CF;<(CommitIndex, CommitDigest), CommitInfo>
-        );
+        Self::from_db(&rocksdb).expect("Cannot open consensus column families")
+    }
 
-        Self {
-            blocks,
-            digests_by_authorities,
-            commits,
-            commit_votes,
-            commit_info,
+    /// Opens an existing consensus database as a RocksDB secondary instance. The primary can
+    /// keep writing to the database while it is being read, which allows inspecting the store
+    /// of a running authority. When `secondary_path` is not set, the secondary instance keeps
+    /// its files in a `SECONDARY` directory next to `path`.
+    pub(crate) fn new_secondary(
+        path: &Path,
+        secondary_path: Option<&Path>,
+    ) -> ConsensusResult<Self> {
+        let mut metrics_conf = MetricConf::new("consensus_secondary");
+        metrics_conf.read_sample_interval = SamplingInterval::new(Duration::from_secs(60), 0);
+        // Opening a secondary instance creates an empty database when the primary is missing.
+        if !path.is_dir() {
+            return Err(TypedStoreError::RocksDBError(format!(
+                "Consensus database not found at {}",
+                path.display()
+            ))
+            .into());
         }
+        // Column families are discovered from the primary and opened with default options.
+        let rocksdb = open_cf_opts_secondary(path, secondary_path, None, metrics_conf, &[])?;
+        Ok(Self::from_db(&rocksdb)?)
+    }
+
+    fn from_db(rocksdb: &Arc<RocksDB>) -> Result<Self, TypedStoreError> {
+        let rw_options = ReadWriteOptions::default();
+        Ok(Self {
+            blocks: DBMap::reopen(rocksdb, Some(Self::BLOCKS_CF), &rw_options)?,
+            digests_by_authorities: DBMap::reopen(
+                rocksdb,
+                Some(Self::DIGESTS_BY_AUTHORITIES_CF),
+                &rw_options,
+            )?,
+            commits: DBMap::reopen(rocksdb, Some(Self::COMMITS_CF), &rw_options)?,
+            commit_votes: DBMap::reopen(rocksdb, Some(Self::COMMIT_VOTES_CF), &rw_options)?,
+            commit_info: DBMap::reopen(rocksdb, Some(Self::COMMIT_INFO_CF), &rw_options)?,
+        })
     }
 }
 
@@ -174,6 +202,33 @@ impl Store for RocksDBStore {
         Ok(blocks)
     }
 
+    fn scan_blocks_by_rounds(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>> {
+        let mut blocks = vec![];
+        for kv in self.blocks.safe_range_iter((
+            Included((start_round, AuthorityIndex::MIN, BlockDigest::MIN)),
+            Included((end_round, AuthorityIndex::MAX, BlockDigest::MAX)),
+        )) {
+            let ((round, author, digest), serialized) = kv?;
+            let signed_block: SignedBlock =
+                bcs::from_bytes(&serialized).map_err(ConsensusError::MalformedBlock)?;
+            let block = VerifiedBlock::new_verified(signed_block, serialized);
+            // Makes sure block data is not corrupted, by comparing digests.
+            let key = BlockRef::new(round, author, digest);
+            if key != block.reference() {
+                return Err(ConsensusError::CorruptedBlock {
+                    key,
+                    block: block.reference(),
+                });
+            }
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
     fn contains_blocks(&self, refs: &[BlockRef]) -> ConsensusResult<Vec<bool>> {
         let refs = refs
             .iter()
@@ -198,6 +198,28 @@ async fn scan_blocks(
             .expect("Scan blocks should not fail");
         assert_eq!(scanned_blocks.len(), 0);
     }
+
+    {
+        let scanned_blocks = store
+            .scan_blocks_by_rounds(11, 13)
+            .expect("Scan blocks should not fail");
+        assert_eq!(scanned_blocks.len(), 5, "{:?}", scanned_blocks);
+        assert_eq!(
+            scanned_blocks,
+            vec![
+                written_blocks[3].clone(),
+                written_blocks[4].clone(),
+                written_blocks[5].clone(),
+                written_blocks[7].clone(),
+                written_blocks[6].clone(),
+            ]
+        );
+
+        let scanned_blocks = store
+            .scan_blocks_by_rounds(20, 30)
+            .expect("Scan blocks should not fail");
+        assert!(scanned_blocks.is_empty(), "{:?}", scanned_blocks);
+    }
 }
 
 #[rstest]
@@ -11,7 +11,7 @@ use crate::{
     commit::{LeaderStatus, DEFAULT_WAVE_LENGTH},
     context::Context,
     dag_state::DagState,
-    leader_schedule::{LeaderSchedule, LeaderSwapTable},
+    leader_schedule::LeaderSchedule,
     storage::mem_store::MemStore,
     test_dag::{build_dag, build_dag_layer},
     universal_committer::universal_committer_builder::UniversalCommitterBuilder,
@@ -777,10 +777,7 @@ fn basic_test_setup() -> (
         context.clone(),
         Arc::new(MemStore::new()),
     )));
-    let leader_schedule = Arc::new(LeaderSchedule::new(
-        context.clone(),
-        LeaderSwapTable::default(),
-    ));
+    let leader_schedule = Arc::new(LeaderSchedule::new(context.clone()));
 
     // Create committer with pipelining and only 1 leader per leader round
     let committer =
@@ -0,0 +1,95 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+
+use crate::{
+    block::BlockRef,
+    commit::CommitIndex,
+    simulator::{Scenario, SimulationResult, Simulator},
+};
+
+fn load_scenario(file_name: &str) -> Scenario {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/scenarios")
+        .join(file_name);
+    Scenario::load(&path)
+}
+
+fn run_scenario(file_name: &str) -> SimulationResult {
+    telemetry_subscribers::init_for_testing();
+    let scenario = load_scenario(file_name);
+    let expect = scenario.expect.clone();
+    let result = Simulator::new(scenario).run();
+    result.check(&expect);
+    result
+}
+
+/// Commit sequence of each authority, reduced to comparable references.
+fn commit_sequences(result: &SimulationResult) -> Vec<Vec<(CommitIndex, BlockRef, Vec<BlockRef>)>> {
+    result
+        .commits
+        .iter()
+        .map(|commits| {
+            commits
+                .iter()
+                .map(|commit| {
+                    (
+                        commit.commit_index,
+                        commit.leader,
+                        commit.blocks.iter().map(|b| b.reference()).collect(),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_happy_path() {
+    run_scenario("happy_path.yaml");
+}
+
+#[tokio::test]
+async fn test_lossy_network() {
+    run_scenario("lossy_network.yaml");
+}
+
+#[tokio::test]
+async fn test_partition_heals() {
+    let result = run_scenario("partition_heals.yaml");
+
+    // Authorities on both sides of the partition caught up after it healed.
+    for authority in &result.live {
+        assert!(!result.commits[authority.value()].is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_crashed_authority() {
+    run_scenario("crashed_authority.yaml");
+}
+
+#[tokio::test]
+async fn test_equivocating_authority() {
+    run_scenario("equivocating_authority.yaml");
+}
+
+#[tokio::test]
+async fn test_determinism() {
+    let scenario = load_scenario("lossy_network.yaml");
+    let first = Simulator::new(scenario.clone()).run();
+    let second = Simulator::new(scenario.clone()).run();
+    assert_eq!(commit_sequences(&first), commit_sequences(&second));
+
+    // A different seed leads to a different execution.
+    let mut other_scenario = scenario;
+    other_scenario.seed += 1;
+    let third = Simulator::new(other_scenario).run();
+    assert_ne!(commit_sequences(&first), commit_sequences(&third));
+}
+
+#[tokio::test]
+async fn test_timeout_exclusion_leader_schedule() {
+    run_scenario("timeout_exclusion.yaml");
+}
@@ -11,7 +11,7 @@ use crate::{
     commit::LeaderStatus,
     context::Context,
     dag_state::DagState,
-    leader_schedule::{LeaderSchedule, LeaderSwapTable},
+    leader_schedule::LeaderSchedule,
     storage::mem_store::MemStore,
     test_dag::{build_dag, build_dag_layer},
     test_dag_builder::DagBuilder,
@@ -360,10 +360,7 @@ fn indirect_commit() {
         dag_builder.context.clone(),
         Arc::new(MemStore::new()),
     )));
-    let leader_schedule = Arc::new(LeaderSchedule::new(
-        dag_builder.context.clone(),
-        LeaderSwapTable::default(),
-    ));
+    let leader_schedule = Arc::new(LeaderSchedule::new(dag_builder.context.clone()));
 
     dag_builder.print();
     dag_builder.persist_all_blocks(dag_state.clone());
@@ -720,10 +717,7 @@ fn basic_test_setup() -> (
         context.clone(),
         Arc::new(MemStore::new()),
     )));
-    let leader_schedule = Arc::new(LeaderSchedule::new(
-        context.clone(),
-        LeaderSwapTable::default(),
-    ));
+    let leader_schedule = Arc::new(LeaderSchedule::new(context.clone()));
 
     // Create committer without pipelining and only 1 leader per leader round
     let committer =
@@ -751,10 +745,7 @@ fn basic_dag_builder_test_setup() -> TestSetup {
         dag_builder.context.clone(),
         Arc::new(MemStore::new()),
     )));
-    let leader_schedule = Arc::new(LeaderSchedule::new(
-        dag_builder.context.clone(),
-        LeaderSwapTable::default(),
-    ));
+    let leader_schedule = Arc::new(LeaderSchedule::new(dag_builder.context.clone()));
 
     // Create committer without pipelining and only 1 leader per leader round
     let committer = UniversalCommitterBuilder::new(
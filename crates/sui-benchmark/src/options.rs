@@ -6,6 +6,7 @@ use clap::*;
 use strum_macros::EnumString;
 
 use crate::drivers::Interval;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 #[derive(Parser)]
@@ -178,6 +179,18 @@ pub enum RunSpec {
         // relative weight of adversarial transactions in the benchmark workload
         #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
         shared_deletion: Vec<u32>,
+        // relative weight of transactions accessing large tables and deep trees of dynamic fields
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        dynamic_field: Vec<u32>,
+        // relative weight of transactions consuming on-chain randomness
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        randomness: Vec<u32>,
+        // relative weight of programmable transactions with many commands on owned and shared objects
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        mixed_ptb: Vec<u32>,
+        // relative weight of transactions replayed from `trace_path`
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        trace_replay: Vec<u32>,
 
         // --- workload-specific options --- (TODO: use subcommands or similar)
         // 100 for max hotness i.e all requests target
@@ -207,6 +220,22 @@ pub enum RunSpec {
         // Default is (0-0.5) implying random load at 50% load. See `AdversarialPayloadType` enum for `adversarial_type`
         #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = ["0-1.0".to_string()])]
         adversarial_cfg: Vec<String>,
+        // number of entries in the table of each object used by the dynamic field workload
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [1000])]
+        dynamic_field_num_entries: Vec<u64>,
+        // depth of the tree of child objects below each object used by the dynamic field workload
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [10])]
+        dynamic_field_tree_depth: Vec<u64>,
+        // number of table entries read and written by each dynamic field transaction
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [10])]
+        dynamic_field_accesses: Vec<u64>,
+        // number of commands in each mixed programmable transaction
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [64])]
+        mixed_ptb_num_commands: Vec<u64>,
+        // trace replayed by the trace replay workload: either a directory of checkpoint files
+        // or a JSON file with a list of transactions (see `TraceTransaction`)
+        #[clap(long)]
+        trace_path: Option<PathBuf>,
 
         // --- generic options ---
         // Target qps
@@ -0,0 +1,96 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module dynamic_fields::dynamic_fields {
+    use sui::dynamic_object_field as dof;
+    use sui::table::{Self, Table};
+
+    /// Key under which each node stores its child
+    const CHILD_KEY: u8 = 0;
+    /// Distance between consecutive keys accessed by `access`, so that accesses
+    /// are spread over the whole table
+    const STRIDE: u64 = 7919;
+
+    /// A shared object with a large table and a chain of nested child objects
+    public struct Root has key {
+        id: UID,
+        table: Table<u64, u64>,
+        size: u64,
+        depth: u64,
+    }
+
+    public struct Node has key, store {
+        id: UID,
+        value: u64,
+    }
+
+    public fun create(ctx: &mut TxContext) {
+        transfer::share_object(Root {
+            id: object::new(ctx),
+            table: table::new(ctx),
+            size: 0,
+            depth: 0,
+        })
+    }
+
+    /// Adds `n` more entries to the table of `root`
+    public fun populate(root: &mut Root, n: u64) {
+        let mut i = 0;
+        while (i < n) {
+            table::add(&mut root.table, root.size, 0);
+            root.size = root.size + 1;
+            i = i + 1;
+        }
+    }
+
+    /// Creates a chain of `depth` nodes below `root`, each node being a dynamic
+    /// object field of the one above it
+    public fun create_tree(root: &mut Root, depth: u64, ctx: &mut TxContext) {
+        assert!(root.depth == 0, 0);
+        if (depth == 0) {
+            return
+        };
+        let mut child = Node { id: object::new(ctx), value: 0 };
+        let mut i = 1;
+        while (i < depth) {
+            let mut parent = Node { id: object::new(ctx), value: 0 };
+            dof::add(&mut parent.id, CHILD_KEY, child);
+            child = parent;
+            i = i + 1;
+        };
+        dof::add(&mut root.id, CHILD_KEY, child);
+        root.depth = depth;
+    }
+
+    /// Increments `n` table entries starting at `seed`
+    public fun access(root: &mut Root, seed: u64, n: u64) {
+        if (root.size == 0) {
+            return
+        };
+        let mut key = seed % root.size;
+        let mut i = 0;
+        while (i < n) {
+            let value = table::borrow_mut(&mut root.table, key);
+            *value = *value + 1;
+            key = (key + STRIDE) % root.size;
+            i = i + 1;
+        }
+    }
+
+    /// Walks down the chain of nodes and increments the deepest one
+    public fun walk_tree(root: &mut Root) {
+        if (root.depth == 0) {
+            return
+        };
+        walk(&mut root.id, root.depth);
+    }
+
+    fun walk(parent: &mut UID, remaining: u64) {
+        let node: &mut Node = dof::borrow_mut(parent, CHILD_KEY);
+        if (remaining == 1) {
+            node.value = node.value + 1;
+        } else {
+            walk(&mut node.id, remaining - 1);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module randomness::randomness {
+    use sui::event;
+    use sui::random::{Self, Random};
+
+    public struct RandomValue has copy, drop {
+        value: u64,
+    }
+
+    /// Emits `n` random values generated from the on-chain randomness
+    entry fun emit_random_values(r: &Random, n: u64, ctx: &mut TxContext) {
+        let mut generator = random::new_generator(r, ctx);
+        let mut i = 0;
+        while (i < n) {
+            event::emit(RandomValue { value: random::generate_u64(&mut generator) });
+            i = i + 1;
+        }
+    }
+}
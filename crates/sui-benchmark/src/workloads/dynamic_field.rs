@@ -0,0 +1,338 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::drivers::Interval;
+use crate::in_memory_wallet::move_call_pt_impl;
+use crate::system_state_observer::SystemStateObserver;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_BUDGET, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{BenchMoveCallArg, ExecutionEffects, ValidatorProxy};
+use async_trait::async_trait;
+use futures::future::join_all;
+use move_core_types::identifier::Identifier;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::crypto::get_key_pair;
+use sui_types::object::Owner;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{ObjectArg, Transaction, TransactionData};
+use sui_types::utils::to_sender_signed_transaction;
+use tracing::{error, info};
+
+const MODULE_NAME: &str = "dynamic_fields";
+
+/// Number of table entries added per transaction while populating a root.
+/// Kept below `object_runtime_max_num_store_entries`.
+const POPULATE_BATCH_SIZE: u64 = 500;
+
+/// Shape of the dynamic field objects each workload creates and accesses.
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicFieldCfg {
+    /// Number of entries in the table of each root object
+    pub num_entries: u64,
+    /// Depth of the chain of child objects below each root object
+    pub tree_depth: u64,
+    /// Number of table entries read and written by each transaction
+    pub num_accesses: u64,
+}
+
+#[derive(Debug)]
+pub struct DynamicFieldTestPayload {
+    package_id: ObjectID,
+    root_id: ObjectID,
+    root_initial_shared_version: SequenceNumber,
+    gas: Gas,
+    num_accesses: u64,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for DynamicFieldTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "dynamic_field")
+    }
+}
+
+impl Payload for DynamicFieldTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!("Dynamic field tx failed... Status: {:?}", effects.status());
+        }
+        self.gas.0 = effects.gas_object().0;
+    }
+
+    fn make_transaction(&mut self) -> Transaction {
+        let gas_price = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let root = builder
+            .obj(ObjectArg::SharedObject {
+                id: self.root_id,
+                initial_shared_version: self.root_initial_shared_version,
+                mutable: true,
+            })
+            .unwrap();
+        let seed = builder.pure(rand::thread_rng().gen::<u64>()).unwrap();
+        let num_accesses = builder.pure(self.num_accesses).unwrap();
+        builder.programmable_move_call(
+            self.package_id,
+            Identifier::new(MODULE_NAME).unwrap(),
+            Identifier::new("access").unwrap(),
+            vec![],
+            vec![root, seed, num_accesses],
+        );
+        builder.programmable_move_call(
+            self.package_id,
+            Identifier::new(MODULE_NAME).unwrap(),
+            Identifier::new("walk_tree").unwrap(),
+            vec![],
+            vec![root],
+        );
+        let data = TransactionData::new_programmable(
+            self.gas.1,
+            vec![self.gas.0],
+            builder.finish(),
+            MAX_BUDGET,
+            gas_price,
+        );
+        to_sender_signed_transaction(data, self.gas.2.as_ref())
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicFieldWorkloadBuilder {
+    num_roots: u64,
+    num_payloads: u64,
+    cfg: DynamicFieldCfg,
+}
+
+impl DynamicFieldWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        cfg: DynamicFieldCfg,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 {
+            None
+        } else {
+            let workload_params = WorkloadParams {
+                group,
+                target_qps,
+                num_workers,
+                max_ops,
+                duration,
+            };
+            let workload_builder = Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(
+                DynamicFieldWorkloadBuilder {
+                    // One root per worker, so that roots see some contention
+                    // without serializing the whole workload.
+                    num_roots: num_workers,
+                    num_payloads: max_ops,
+                    cfg,
+                },
+            ));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for DynamicFieldWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+        // Gas coin for publishing package, followed by one per root object
+        for _i in 0..self.num_roots + 1 {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+        for _i in 0..self.num_payloads {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+
+    async fn build(
+        &self,
+        init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(DynamicFieldWorkload {
+            package_id: None,
+            roots: vec![],
+            init_gas,
+            payload_gas,
+            cfg: self.cfg,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicFieldWorkload {
+    pub package_id: Option<ObjectID>,
+    /// Shared root objects holding the table and the chain of child objects
+    pub roots: Vec<(ObjectID, SequenceNumber)>,
+    pub init_gas: Vec<Gas>,
+    pub payload_gas: Vec<Gas>,
+    pub cfg: DynamicFieldCfg,
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for DynamicFieldWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.package_id.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let (head, tail) = self
+            .init_gas
+            .split_first()
+            .expect("Not enough gas to initialize dynamic field workload");
+
+        info!("Publishing dynamic fields package");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/workloads/data/dynamic_fields");
+        let transaction = TestTransactionBuilder::new(head.1, head.0, gas_price)
+            .publish(path)
+            .build_and_sign(head.2.as_ref());
+        let effects = proxy.execute_transaction_block(transaction).await.unwrap();
+        let package_id = effects
+            .created()
+            .iter()
+            .find(|(_, owner)| matches!(owner, Owner::Immutable))
+            .map(|(reference, _)| reference.0)
+            .unwrap();
+        self.package_id = Some(package_id);
+
+        info!(
+            "Creating {} dynamic field roots with {} entries and depth {}",
+            tail.len(),
+            self.cfg.num_entries,
+            self.cfg.tree_depth
+        );
+        let cfg = self.cfg;
+        let futures = tail.iter().map(|(gas, sender, keypair)| {
+            let proxy = proxy.clone();
+            async move {
+                let execute = |gas_ref, function: &str, args: Vec<BenchMoveCallArg>| {
+                    let transaction = move_call_pt_impl(
+                        *sender,
+                        keypair,
+                        package_id,
+                        MODULE_NAME,
+                        function,
+                        vec![],
+                        args,
+                        &gas_ref,
+                        MAX_BUDGET,
+                        gas_price,
+                    );
+                    let proxy = proxy.clone();
+                    async move {
+                        let effects = proxy
+                            .execute_transaction_block(transaction)
+                            .await
+                            .expect("Failed to initialize dynamic field root");
+                        assert!(
+                            effects.is_ok(),
+                            "Failed to initialize dynamic field root: {}",
+                            effects.status()
+                        );
+                        effects
+                    }
+                };
+
+                let effects = execute(*gas, "create", vec![]).await;
+                let (root_id, root_initial_shared_version) = effects
+                    .created()
+                    .iter()
+                    .find_map(|(reference, owner)| match owner {
+                        Owner::Shared {
+                            initial_shared_version,
+                        } => Some((reference.0, *initial_shared_version)),
+                        _ => None,
+                    })
+                    .unwrap();
+                let root = BenchMoveCallArg::Shared((root_id, root_initial_shared_version, true));
+                let mut gas_ref = effects.gas_object().0;
+
+                let mut remaining = cfg.num_entries;
+                while remaining > 0 {
+                    let batch = remaining.min(POPULATE_BATCH_SIZE);
+                    let effects =
+                        execute(gas_ref, "populate", vec![root.clone(), batch.into()]).await;
+                    gas_ref = effects.gas_object().0;
+                    remaining -= batch;
+                }
+                if cfg.tree_depth > 0 {
+                    execute(gas_ref, "create_tree", vec![root, cfg.tree_depth.into()]).await;
+                }
+                (root_id, root_initial_shared_version)
+            }
+        });
+        self.roots = join_all(futures).await;
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        info!("Creating dynamic field txn payloads, hang tight..");
+        let mut payloads = vec![];
+        for gas in self.payload_gas.iter() {
+            let (root_id, root_initial_shared_version) = self
+                .roots
+                .choose(&mut rand::thread_rng())
+                .expect("Failed to get a random root from the pool");
+            payloads.push(Box::new(DynamicFieldTestPayload {
+                package_id: self.package_id.unwrap(),
+                root_id: *root_id,
+                root_initial_shared_version: *root_initial_shared_version,
+                gas: gas.clone(),
+                num_accesses: self.cfg.num_accesses,
+                system_state_observer: system_state_observer.clone(),
+            }));
+        }
+        payloads
+            .into_iter()
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::drivers::Interval;
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::publish_basics_package;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_BUDGET, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{ExecutionEffects, ValidatorProxy};
+use async_trait::async_trait;
+use futures::future::join_all;
+use move_core_types::identifier::Identifier;
+use rand::seq::SliceRandom;
+use std::sync::Arc;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::crypto::get_key_pair;
+use sui_types::object::Owner;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{Argument, Command, ObjectArg, Transaction, TransactionData};
+use sui_types::utils::to_sender_signed_transaction;
+use tracing::{error, info};
+
+/// Amount split off the gas coin by each split command. All splits are merged
+/// back into the gas coin at the end of the transaction.
+const SPLIT_AMOUNT: u64 = 1;
+
+#[derive(Debug)]
+pub struct MixedPtbTestPayload {
+    package_id: ObjectID,
+    counter_id: ObjectID,
+    counter_initial_shared_version: SequenceNumber,
+    gas: Gas,
+    num_commands: u64,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for MixedPtbTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "mixed_ptb")
+    }
+}
+
+impl Payload for MixedPtbTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!("Mixed PTB tx failed... Status: {:?}", effects.status());
+        }
+        self.gas.0 = effects.gas_object().0;
+    }
+
+    /// Alternates between incrementing the shared counter and splitting the
+    /// owned gas coin, then merges the splits back into the gas coin.
+    fn make_transaction(&mut self) -> Transaction {
+        let gas_price = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let counter = builder
+            .obj(ObjectArg::SharedObject {
+                id: self.counter_id,
+                initial_shared_version: self.counter_initial_shared_version,
+                mutable: true,
+            })
+            .unwrap();
+        let amount = builder.pure(SPLIT_AMOUNT).unwrap();
+        let mut splits = vec![];
+        for i in 0..self.num_commands {
+            if i % 2 == 0 {
+                builder.programmable_move_call(
+                    self.package_id,
+                    Identifier::new("counter").unwrap(),
+                    Identifier::new("increment").unwrap(),
+                    vec![],
+                    vec![counter],
+                );
+            } else {
+                let Argument::Result(split) =
+                    builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]))
+                else {
+                    unreachable!("Commands always return a result");
+                };
+                splits.push(Argument::NestedResult(split, 0));
+            }
+        }
+        if !splits.is_empty() {
+            builder.command(Command::MergeCoins(Argument::GasCoin, splits));
+        }
+        let data = TransactionData::new_programmable(
+            self.gas.1,
+            vec![self.gas.0],
+            builder.finish(),
+            MAX_BUDGET,
+            gas_price,
+        );
+        to_sender_signed_transaction(data, self.gas.2.as_ref())
+    }
+}
+
+#[derive(Debug)]
+pub struct MixedPtbWorkloadBuilder {
+    num_counters: u64,
+    num_payloads: u64,
+    num_commands: u64,
+}
+
+impl MixedPtbWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        num_commands: u64,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 || num_commands == 0 {
+            None
+        } else {
+            let workload_params = WorkloadParams {
+                group,
+                target_qps,
+                num_workers,
+                max_ops,
+                duration,
+            };
+            let workload_builder =
+                Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(MixedPtbWorkloadBuilder {
+                    num_counters: num_workers,
+                    num_payloads: max_ops,
+                    num_commands,
+                }));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for MixedPtbWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+        // Gas coin for publishing package, followed by one per counter
+        for _i in 0..self.num_counters + 1 {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+        for _i in 0..self.num_payloads {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+
+    async fn build(
+        &self,
+        init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(MixedPtbWorkload {
+            basics_package_id: None,
+            counters: vec![],
+            init_gas,
+            payload_gas,
+            num_commands: self.num_commands,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct MixedPtbWorkload {
+    pub basics_package_id: Option<ObjectID>,
+    pub counters: Vec<(ObjectID, SequenceNumber)>,
+    pub init_gas: Vec<Gas>,
+    pub payload_gas: Vec<Gas>,
+    pub num_commands: u64,
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for MixedPtbWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.basics_package_id.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let (head, tail) = self
+            .init_gas
+            .split_first()
+            .expect("Not enough gas to initialize mixed PTB workload");
+
+        info!("Publishing basics package");
+        let package_id = publish_basics_package(head.0, proxy.clone(), head.1, &head.2, gas_price)
+            .await
+            .0;
+        self.basics_package_id = Some(package_id);
+
+        let futures = tail.iter().map(|(gas, sender, keypair)| {
+            let transaction = TestTransactionBuilder::new(*sender, *gas, gas_price)
+                .call_counter_create(package_id)
+                .build_and_sign(keypair.as_ref());
+            let proxy = proxy.clone();
+            async move {
+                let effects = proxy
+                    .execute_transaction_block(transaction)
+                    .await
+                    .expect("Failed to create shared counter!");
+                match effects.created()[0] {
+                    (
+                        (id, _, _),
+                        Owner::Shared {
+                            initial_shared_version,
+                        },
+                    ) => (id, initial_shared_version),
+                    _ => panic!("Counter must be shared"),
+                }
+            }
+        });
+        self.counters = join_all(futures).await;
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        info!("Creating mixed PTB txn payloads, hang tight..");
+        let mut payloads = vec![];
+        for gas in self.payload_gas.iter() {
+            let (counter_id, counter_initial_shared_version) = self
+                .counters
+                .choose(&mut rand::thread_rng())
+                .expect("Failed to get a random counter from the pool");
+            payloads.push(Box::new(MixedPtbTestPayload {
+                package_id: self.basics_package_id.unwrap(),
+                counter_id: *counter_id,
+                counter_initial_shared_version: *counter_initial_shared_version,
+                gas: gas.clone(),
+                num_commands: self.num_commands,
+                system_state_observer: system_state_observer.clone(),
+            }));
+        }
+        payloads
+            .into_iter()
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+}
@@ -4,9 +4,13 @@
 pub mod adversarial;
 pub mod batch_payment;
 pub mod delegation;
+pub mod dynamic_field;
+pub mod mixed_ptb;
 pub mod payload;
+pub mod randomness;
 pub mod shared_counter;
 pub mod shared_object_deletion;
+pub mod trace_replay;
 pub mod transfer_object;
 pub mod workload;
 pub mod workload_configuration;
@@ -0,0 +1,223 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::drivers::Interval;
+use crate::in_memory_wallet::move_call_pt_impl;
+use crate::system_state_observer::SystemStateObserver;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_BUDGET, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{BenchMoveCallArg, ExecutionEffects, ValidatorProxy};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::crypto::get_key_pair;
+use sui_types::object::Owner;
+use sui_types::transaction::Transaction;
+use sui_types::SUI_RANDOMNESS_STATE_OBJECT_ID;
+use tracing::{error, info};
+
+/// Number of random values generated by each transaction
+const NUM_RANDOM_VALUES: u64 = 10;
+
+#[derive(Debug)]
+pub struct RandomnessTestPayload {
+    package_id: ObjectID,
+    randomness_initial_shared_version: SequenceNumber,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for RandomnessTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "randomness")
+    }
+}
+
+impl Payload for RandomnessTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!("Randomness tx failed... Status: {:?}", effects.status());
+        }
+        self.gas.0 = effects.gas_object().0;
+    }
+
+    fn make_transaction(&mut self) -> Transaction {
+        let gas_price = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+        move_call_pt_impl(
+            self.gas.1,
+            &self.gas.2,
+            self.package_id,
+            "randomness",
+            "emit_random_values",
+            vec![],
+            vec![
+                BenchMoveCallArg::Shared((
+                    SUI_RANDOMNESS_STATE_OBJECT_ID,
+                    self.randomness_initial_shared_version,
+                    false,
+                )),
+                NUM_RANDOM_VALUES.into(),
+            ],
+            &self.gas.0,
+            MAX_BUDGET,
+            gas_price,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct RandomnessWorkloadBuilder {
+    num_payloads: u64,
+}
+
+impl RandomnessWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 {
+            None
+        } else {
+            let workload_params = WorkloadParams {
+                group,
+                target_qps,
+                num_workers,
+                max_ops,
+                duration,
+            };
+            let workload_builder = Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(
+                RandomnessWorkloadBuilder {
+                    num_payloads: max_ops,
+                },
+            ));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for RandomnessWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        // Gas coin for publishing package
+        let (address, keypair) = get_key_pair();
+        vec![GasCoinConfig {
+            amount: MAX_GAS_FOR_TESTING,
+            address,
+            keypair: Arc::new(keypair),
+        }]
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+        for _i in 0..self.num_payloads {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+
+    async fn build(
+        &self,
+        mut init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(RandomnessWorkload {
+            package_id: None,
+            randomness_initial_shared_version: None,
+            init_gas: init_gas.pop().unwrap(),
+            payload_gas,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct RandomnessWorkload {
+    pub package_id: Option<ObjectID>,
+    pub randomness_initial_shared_version: Option<SequenceNumber>,
+    pub init_gas: Gas,
+    pub payload_gas: Vec<Gas>,
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for RandomnessWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.package_id.is_some() {
+            return;
+        }
+        let randomness_state = proxy
+            .get_object(SUI_RANDOMNESS_STATE_OBJECT_ID)
+            .await
+            .expect("Randomness state object not found, is randomness enabled on this network?");
+        let Owner::Shared {
+            initial_shared_version,
+        } = randomness_state.owner
+        else {
+            panic!("Randomness state object must be shared");
+        };
+        self.randomness_initial_shared_version = Some(initial_shared_version);
+
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let gas = &self.init_gas;
+        info!("Publishing randomness package");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/workloads/data/randomness");
+        let transaction = TestTransactionBuilder::new(gas.1, gas.0, gas_price)
+            .publish(path)
+            .build_and_sign(gas.2.as_ref());
+        let effects = proxy.execute_transaction_block(transaction).await.unwrap();
+        self.package_id = effects
+            .created()
+            .iter()
+            .find(|(_, owner)| matches!(owner, Owner::Immutable))
+            .map(|(reference, _)| reference.0);
+        info!("Randomness package id {:?}", self.package_id);
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        info!("Creating randomness txn payloads, hang tight..");
+        self.payload_gas
+            .iter()
+            .map(|gas| {
+                Box::<dyn Payload>::from(Box::new(RandomnessTestPayload {
+                    package_id: self.package_id.unwrap(),
+                    randomness_initial_shared_version: self
+                        .randomness_initial_shared_version
+                        .unwrap(),
+                    gas: gas.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                }))
+            })
+            .collect()
+    }
+}
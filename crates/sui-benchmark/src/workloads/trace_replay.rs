@@ -0,0 +1,654 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replays the shape of a trace of real transactions. The transactions of each traced sender are
+//! replayed in order from one benchmark account, and each traced shared object is mapped onto a
+//! benchmark shared counter, so that the replayed transactions contend on shared objects the same
+//! way the traced ones did. Replay speed is controlled by the target qps of the workload.
+//!
+//! Owned objects are not replayed: the traced owned inputs are dropped, and a transaction without
+//! shared objects only splits and merges the gas coin of its benchmark account. The load on owned
+//! objects is therefore one gas coin per account, regardless of the traced owned inputs.
+
+use crate::drivers::Interval;
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::publish_basics_package;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_BUDGET, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use futures::future::join_all;
+use move_core_types::identifier::Identifier;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use sui_storage::blob::Blob;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::crypto::get_key_pair;
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::object::Owner;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{Argument, Command, ObjectArg, Transaction, TransactionData};
+use sui_types::utils::to_sender_signed_transaction;
+use tracing::{error, info};
+
+/// Extension of the checkpoint files in a checkpoint archive directory
+const CHECKPOINT_FILE_EXTENSION: &str = "chk";
+
+/// A traced shared object input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceSharedObject {
+    pub id: ObjectID,
+    pub mutable: bool,
+}
+
+/// The parts of a traced transaction that are replayed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceTransaction {
+    pub sender: SuiAddress,
+    #[serde(default)]
+    pub shared_objects: Vec<TraceSharedObject>,
+}
+
+/// Loads a trace from either a directory of checkpoint files, replayed in checkpoint order, or
+/// a JSON file with a list of `TraceTransaction`s. System transactions are skipped.
+pub fn load_trace(path: &Path) -> anyhow::Result<Vec<TraceTransaction>> {
+    let trace = if path.is_dir() {
+        load_trace_from_checkpoints(path)?
+    } else {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read trace file {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse trace file {}", path.display()))?
+    };
+    if trace.is_empty() {
+        return Err(anyhow!("Trace at {} has no transactions", path.display()));
+    }
+    info!(
+        "Loaded {} transactions from trace at {}",
+        trace.len(),
+        path.display()
+    );
+    Ok(trace)
+}
+
+fn load_trace_from_checkpoints(dir: &Path) -> anyhow::Result<Vec<TraceTransaction>> {
+    let mut files = BTreeMap::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(CHECKPOINT_FILE_EXTENSION) {
+            continue;
+        }
+        let sequence_number = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u64>().ok())
+            .ok_or_else(|| anyhow!("Invalid checkpoint file name {}", path.display()))?;
+        files.insert(sequence_number, path);
+    }
+
+    let mut trace = vec![];
+    for path in files.into_values() {
+        let bytes = std::fs::read(&path)?;
+        let checkpoint = Blob::from_bytes::<CheckpointData>(&bytes)
+            .with_context(|| format!("Failed to read checkpoint file {}", path.display()))?;
+        for tx in checkpoint.transactions {
+            if tx.transaction.is_system_tx() {
+                continue;
+            }
+            trace.push(TraceTransaction {
+                sender: tx.transaction.sender_address(),
+                shared_objects: tx
+                    .transaction
+                    .shared_input_objects()
+                    .map(|object| TraceSharedObject {
+                        id: object.id,
+                        mutable: object.mutable,
+                    })
+                    .collect(),
+            });
+        }
+    }
+    Ok(trace)
+}
+
+/// A traced transaction rewritten onto benchmark objects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ReplayTransaction {
+    /// Counters standing in for the traced shared objects, with their mutability
+    counters: Vec<(ObjectID, SequenceNumber, bool)>,
+}
+
+#[derive(Debug)]
+pub struct TraceReplayTestPayload {
+    package_id: ObjectID,
+    transactions: Vec<ReplayTransaction>,
+    next: usize,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for TraceReplayTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "trace_replay")
+    }
+}
+
+impl Payload for TraceReplayTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!("Trace replay tx failed... Status: {:?}", effects.status());
+        }
+        self.gas.0 = effects.gas_object().0;
+    }
+
+    fn make_transaction(&mut self) -> Transaction {
+        let gas_price = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+        let transaction = &self.transactions[self.next];
+        // Replay the trace in a loop
+        self.next = (self.next + 1) % self.transactions.len();
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        if transaction.counters.is_empty() {
+            // Transactions without shared objects only touch owned objects
+            let amount = builder.pure(1u64).unwrap();
+            let Argument::Result(split) =
+                builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]))
+            else {
+                unreachable!("Commands always return a result");
+            };
+            builder.command(Command::MergeCoins(
+                Argument::GasCoin,
+                vec![Argument::NestedResult(split, 0)],
+            ));
+        }
+        for (id, initial_shared_version, mutable) in &transaction.counters {
+            let counter = builder
+                .obj(ObjectArg::SharedObject {
+                    id: *id,
+                    initial_shared_version: *initial_shared_version,
+                    mutable: *mutable,
+                })
+                .unwrap();
+            let function = if *mutable { "increment" } else { "value" };
+            builder.programmable_move_call(
+                self.package_id,
+                Identifier::new("counter").unwrap(),
+                Identifier::new(function).unwrap(),
+                vec![],
+                vec![counter],
+            );
+        }
+        let data = TransactionData::new_programmable(
+            self.gas.1,
+            vec![self.gas.0],
+            builder.finish(),
+            MAX_BUDGET,
+            gas_price,
+        );
+        to_sender_signed_transaction(data, self.gas.2.as_ref())
+    }
+}
+
+#[derive(Debug)]
+pub struct TraceReplayWorkloadBuilder {
+    num_payloads: u64,
+    trace: Arc<Vec<TraceTransaction>>,
+}
+
+impl TraceReplayWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        trace: Option<Arc<Vec<TraceTransaction>>>,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 {
+            None
+        } else {
+            let trace = trace.expect("A trace is required to run the trace replay workload");
+            let workload_params = WorkloadParams {
+                group,
+                target_qps,
+                num_workers,
+                max_ops,
+                duration,
+            };
+            let workload_builder = Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(
+                TraceReplayWorkloadBuilder {
+                    num_payloads: max_ops,
+                    trace,
+                },
+            ));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+
+    fn num_shared_objects(&self) -> usize {
+        let mut ids: Vec<_> = self
+            .trace
+            .iter()
+            .flat_map(|tx| tx.shared_objects.iter().map(|object| object.id))
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids.len()
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for TraceReplayWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+        // Gas coin for publishing package, followed by one per traced shared object
+        for _i in 0..self.num_shared_objects() + 1 {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+        for _i in 0..self.num_payloads {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+
+    async fn build(
+        &self,
+        init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(TraceReplayWorkload {
+            basics_package_id: None,
+            counters: BTreeMap::new(),
+            trace: self.trace.clone(),
+            init_gas,
+            payload_gas,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct TraceReplayWorkload {
+    pub basics_package_id: Option<ObjectID>,
+    /// Counter standing in for each traced shared object
+    pub counters: BTreeMap<ObjectID, (ObjectID, SequenceNumber)>,
+    pub trace: Arc<Vec<TraceTransaction>>,
+    pub init_gas: Vec<Gas>,
+    pub payload_gas: Vec<Gas>,
+}
+
+impl TraceReplayWorkload {
+    /// Splits the trace by sender over `num_payloads` payloads, so that the transactions of each
+    /// traced sender are replayed in order from a single benchmark account. When there are fewer
+    /// senders than payloads, senders are replayed from several accounts.
+    fn transactions_by_payload(&self, num_payloads: usize) -> Vec<Vec<ReplayTransaction>> {
+        let mut senders = BTreeMap::new();
+        let mut by_sender: Vec<Vec<ReplayTransaction>> = vec![];
+        for tx in self.trace.iter() {
+            let next_index = senders.len();
+            let index = *senders.entry(tx.sender).or_insert(next_index);
+            if index == by_sender.len() {
+                by_sender.push(vec![]);
+            }
+            by_sender[index].push(ReplayTransaction {
+                counters: tx
+                    .shared_objects
+                    .iter()
+                    .map(|object| {
+                        let (id, initial_shared_version) = self.counters[&object.id];
+                        (id, initial_shared_version, object.mutable)
+                    })
+                    .collect(),
+            });
+        }
+
+        let mut payloads = vec![vec![]; num_payloads];
+        for (index, transactions) in by_sender.iter().enumerate() {
+            payloads[index % num_payloads].extend(transactions.iter().cloned());
+        }
+        for (index, transactions) in payloads.iter_mut().enumerate() {
+            if transactions.is_empty() {
+                *transactions = by_sender[index % by_sender.len()].clone();
+            }
+        }
+        payloads
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for TraceReplayWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.basics_package_id.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let (head, tail) = self
+            .init_gas
+            .split_first()
+            .expect("Not enough gas to initialize trace replay workload");
+
+        info!("Publishing basics package");
+        let package_id = publish_basics_package(head.0, proxy.clone(), head.1, &head.2, gas_price)
+            .await
+            .0;
+        self.basics_package_id = Some(package_id);
+
+        let mut shared_objects: Vec<_> = self
+            .trace
+            .iter()
+            .flat_map(|tx| tx.shared_objects.iter().map(|object| object.id))
+            .collect();
+        shared_objects.sort();
+        shared_objects.dedup();
+        info!(
+            "Creating {} counters for the traced shared objects",
+            shared_objects.len()
+        );
+        let futures = tail.iter().map(|(gas, sender, keypair)| {
+            let transaction = TestTransactionBuilder::new(*sender, *gas, gas_price)
+                .call_counter_create(package_id)
+                .build_and_sign(keypair.as_ref());
+            let proxy = proxy.clone();
+            async move {
+                let effects = proxy
+                    .execute_transaction_block(transaction)
+                    .await
+                    .expect("Failed to create shared counter!");
+                match effects.created()[0] {
+                    (
+                        (id, _, _),
+                        Owner::Shared {
+                            initial_shared_version,
+                        },
+                    ) => (id, initial_shared_version),
+                    _ => panic!("Counter must be shared"),
+                }
+            }
+        });
+        let counters = join_all(futures).await;
+        self.counters = shared_objects.into_iter().zip(counters).collect();
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        info!("Creating trace replay txn payloads, hang tight..");
+        self.transactions_by_payload(self.payload_gas.len())
+            .into_iter()
+            .zip(self.payload_gas.iter())
+            .map(|(transactions, gas)| {
+                Box::<dyn Payload>::from(Box::new(TraceReplayTestPayload {
+                    package_id: self.basics_package_id.unwrap(),
+                    transactions,
+                    next: 0,
+                    gas: gas.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                }))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_storage::blob::BlobEncoding;
+    use sui_types::base_types::{random_object_ref, ObjectID};
+    use sui_types::crypto::{get_key_pair, AccountKeyPair, KeypairTraits};
+    use sui_types::effects::TransactionEffects;
+    use sui_types::full_checkpoint_content::CheckpointTransaction;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary, SignedCheckpointSummary,
+    };
+    use sui_types::transaction::VerifiedTransaction;
+    use sui_types::utils::make_committee_key;
+
+    fn shared(id: ObjectID, mutable: bool) -> TraceSharedObject {
+        TraceSharedObject { id, mutable }
+    }
+
+    fn user_transaction(shared_objects: &[TraceSharedObject]) -> (SuiAddress, Transaction) {
+        let (sender, keypair): (_, AccountKeyPair) = get_key_pair();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        for object in shared_objects {
+            let counter = builder
+                .obj(ObjectArg::SharedObject {
+                    id: object.id,
+                    initial_shared_version: SequenceNumber::from_u64(1),
+                    mutable: object.mutable,
+                })
+                .unwrap();
+            builder.programmable_move_call(
+                ObjectID::random(),
+                Identifier::new("counter").unwrap(),
+                Identifier::new("increment").unwrap(),
+                vec![],
+                vec![counter],
+            );
+        }
+        let data = TransactionData::new_programmable(
+            sender,
+            vec![random_object_ref()],
+            builder.finish(),
+            MAX_BUDGET,
+            1000,
+        );
+        (sender, to_sender_signed_transaction(data, &keypair))
+    }
+
+    fn write_checkpoint(dir: &Path, sequence_number: u64, transactions: Vec<Transaction>) {
+        let (keys, committee) = make_committee_key(&mut rand::thread_rng());
+        let contents = CheckpointContents::new_with_digests_only_for_tests(vec![]);
+        let summary = CheckpointSummary::new(
+            0,
+            sequence_number,
+            0,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            0,
+        );
+        let sign_infos: Vec<_> = keys
+            .iter()
+            .map(|k| {
+                let name = k.public().into();
+                SignedCheckpointSummary::sign(committee.epoch, &summary, k, name)
+            })
+            .collect();
+        let checkpoint = CheckpointData {
+            checkpoint_summary: CertifiedCheckpointSummary::new(summary, sign_infos, &committee)
+                .unwrap(),
+            checkpoint_contents: contents,
+            transactions: transactions
+                .into_iter()
+                .map(|transaction| CheckpointTransaction {
+                    transaction,
+                    effects: TransactionEffects::default(),
+                    events: None,
+                    input_objects: vec![],
+                    output_objects: vec![],
+                })
+                .collect(),
+        };
+        let bytes = Blob::encode(&checkpoint, BlobEncoding::Bcs)
+            .unwrap()
+            .to_bytes();
+        std::fs::write(dir.join(format!("{sequence_number}.chk")), bytes).unwrap();
+    }
+
+    fn workload(trace: Vec<TraceTransaction>) -> TraceReplayWorkload {
+        let counters = trace
+            .iter()
+            .flat_map(|tx| tx.shared_objects.iter())
+            .map(|object| (object.id, (ObjectID::random(), SequenceNumber::from_u64(1))))
+            .collect();
+        TraceReplayWorkload {
+            basics_package_id: None,
+            counters,
+            trace: Arc::new(trace),
+            init_gas: vec![],
+            payload_gas: vec![],
+        }
+    }
+
+    #[test]
+    fn test_load_trace_from_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.json");
+        let trace = vec![
+            TraceTransaction {
+                sender: SuiAddress::random_for_testing_only(),
+                shared_objects: vec![shared(ObjectID::random(), true)],
+            },
+            TraceTransaction {
+                sender: SuiAddress::random_for_testing_only(),
+                shared_objects: vec![],
+            },
+        ];
+        std::fs::write(&path, serde_json::to_string(&trace).unwrap()).unwrap();
+        assert_eq!(load_trace(&path).unwrap(), trace);
+
+        // Shared objects may be omitted
+        let sender = SuiAddress::random_for_testing_only();
+        std::fs::write(&path, format!(r#"[{{"sender": "{sender}"}}]"#)).unwrap();
+        assert_eq!(
+            load_trace(&path).unwrap(),
+            vec![TraceTransaction {
+                sender,
+                shared_objects: vec![],
+            }]
+        );
+
+        std::fs::write(&path, "[]").unwrap();
+        assert!(load_trace(&path).is_err());
+    }
+
+    #[test]
+    fn test_load_trace_from_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let objects = [
+            shared(ObjectID::random(), true),
+            shared(ObjectID::random(), false),
+        ];
+        let (sender_1, tx_1) = user_transaction(&objects);
+        let (sender_2, tx_2) = user_transaction(&[]);
+        let (sender_3, tx_3) = user_transaction(&objects[1..]);
+        let system_tx = VerifiedTransaction::new_consensus_commit_prologue(0, 0, 0).into_inner();
+
+        // Checkpoints are replayed in sequence number order, not in file name order
+        write_checkpoint(dir.path(), 10, vec![tx_3]);
+        write_checkpoint(dir.path(), 9, vec![system_tx, tx_1, tx_2]);
+        // Files that are not checkpoints are ignored
+        std::fs::write(dir.path().join("README"), "not a checkpoint").unwrap();
+
+        assert_eq!(
+            load_trace(dir.path()).unwrap(),
+            vec![
+                TraceTransaction {
+                    sender: sender_1,
+                    shared_objects: objects.to_vec(),
+                },
+                TraceTransaction {
+                    sender: sender_2,
+                    shared_objects: vec![],
+                },
+                TraceTransaction {
+                    sender: sender_3,
+                    shared_objects: objects[1..].to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transactions_by_payload() {
+        let sender_a = SuiAddress::random_for_testing_only();
+        let sender_b = SuiAddress::random_for_testing_only();
+        let object_1 = shared(ObjectID::random(), true);
+        let object_2 = shared(ObjectID::random(), false);
+        let workload = workload(vec![
+            TraceTransaction {
+                sender: sender_a,
+                shared_objects: vec![object_1],
+            },
+            TraceTransaction {
+                sender: sender_b,
+                shared_objects: vec![object_2],
+            },
+            TraceTransaction {
+                sender: sender_a,
+                shared_objects: vec![object_2],
+            },
+        ]);
+        let replay = |object: TraceSharedObject| {
+            let (id, initial_shared_version) = workload.counters[&object.id];
+            ReplayTransaction {
+                counters: vec![(id, initial_shared_version, object.mutable)],
+            }
+        };
+        let by_sender_a = vec![replay(object_1), replay(object_2)];
+        let by_sender_b = vec![replay(object_2)];
+
+        // The transactions of a sender stay in trace order on a single payload
+        assert_eq!(
+            workload.transactions_by_payload(2),
+            vec![by_sender_a.clone(), by_sender_b.clone()]
+        );
+        // Senders share payloads when there are more senders than payloads
+        assert_eq!(
+            workload.transactions_by_payload(1),
+            vec![[by_sender_a.clone(), by_sender_b.clone()].concat()]
+        );
+        // Senders are replayed from several payloads when there are fewer senders than payloads
+        assert_eq!(
+            workload.transactions_by_payload(5),
+            vec![
+                by_sender_a.clone(),
+                by_sender_b.clone(),
+                by_sender_a.clone(),
+                by_sender_b,
+                by_sender_a,
+            ]
+        );
+    }
+}
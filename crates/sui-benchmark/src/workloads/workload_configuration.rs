@@ -7,10 +7,14 @@ use crate::options::{Opts, RunSpec};
 use crate::system_state_observer::SystemStateObserver;
 use crate::workloads::batch_payment::BatchPaymentWorkloadBuilder;
 use crate::workloads::delegation::DelegationWorkloadBuilder;
+use crate::workloads::dynamic_field::{DynamicFieldCfg, DynamicFieldWorkloadBuilder};
+use crate::workloads::mixed_ptb::MixedPtbWorkloadBuilder;
+use crate::workloads::randomness::RandomnessWorkloadBuilder;
 use crate::workloads::shared_counter::SharedCounterWorkloadBuilder;
+use crate::workloads::trace_replay::{load_trace, TraceReplayWorkloadBuilder, TraceTransaction};
 use crate::workloads::transfer_object::TransferObjectWorkloadBuilder;
 use crate::workloads::{GroupID, WorkloadBuilderInfo, WorkloadInfo};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -21,6 +25,42 @@ use super::shared_object_deletion::SharedCounterDeletionWorkloadBuilder;
 
 pub struct WorkloadConfiguration;
 
+/// Relative weights of the workloads that run in a benchmark group.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WorkloadWeights {
+    pub shared_counter: u32,
+    pub transfer_object: u32,
+    pub delegation: u32,
+    pub batch_payment: u32,
+    pub shared_deletion: u32,
+    pub adversarial: u32,
+    pub dynamic_field: u32,
+    pub randomness: u32,
+    pub mixed_ptb: u32,
+    pub trace_replay: u32,
+}
+
+impl WorkloadWeights {
+    /// Shared deletion is not part of the total, so adding it does not change how the load is
+    /// split between the other workloads.
+    fn total(&self) -> u32 {
+        self.shared_counter
+            + self.transfer_object
+            + self.delegation
+            + self.batch_payment
+            + self.adversarial
+            + self.dynamic_field
+            + self.randomness
+            + self.mixed_ptb
+            + self.trace_replay
+    }
+
+    /// Share of the group's load that goes to a workload with `weight`.
+    fn ratio(&self, weight: u32) -> f32 {
+        weight as f32 / self.total() as f32
+    }
+}
+
 impl WorkloadConfiguration {
     pub async fn configure(
         bank: BenchmarkBank,
@@ -39,11 +79,20 @@ impl WorkloadConfiguration {
                 delegation,
                 batch_payment,
                 adversarial,
+                dynamic_field,
+                randomness,
+                mixed_ptb,
+                trace_replay,
                 shared_counter_hotness_factor,
                 num_shared_counters,
                 shared_counter_max_tip,
                 batch_payment_size,
                 adversarial_cfg,
+                dynamic_field_num_entries,
+                dynamic_field_tree_depth,
+                dynamic_field_accesses,
+                mixed_ptb_num_commands,
+                trace_path,
                 target_qps,
                 num_workers,
                 in_flight_ratio,
@@ -54,6 +103,17 @@ impl WorkloadConfiguration {
                     num_of_benchmark_groups
                 );
 
+                // The trace is shared by all the benchmark groups that replay it
+                let trace = trace_path
+                    .map(|path| load_trace(&path))
+                    .transpose()?
+                    .map(Arc::new);
+                if trace.is_none() && trace_replay.iter().any(|weight| *weight > 0) {
+                    return Err(anyhow!(
+                        "--trace-path is required for the trace replay workload"
+                    ));
+                }
+
                 // Creating the workload builders for each benchmark group. The workloads for each
                 // benchmark group will run in the same time for the same duration.
                 for workload_group in 0..num_of_benchmark_groups {
@@ -62,13 +122,26 @@ impl WorkloadConfiguration {
                         workload_group,
                         num_workers[i],
                         opts.num_transfer_accounts,
-                        shared_counter[i],
-                        transfer_object[i],
-                        delegation[i],
-                        batch_payment[i],
-                        shared_deletion[i],
-                        adversarial[i],
+                        WorkloadWeights {
+                            shared_counter: shared_counter[i],
+                            transfer_object: transfer_object[i],
+                            delegation: delegation[i],
+                            batch_payment: batch_payment[i],
+                            shared_deletion: shared_deletion[i],
+                            adversarial: adversarial[i],
+                            dynamic_field: dynamic_field[i],
+                            randomness: randomness[i],
+                            mixed_ptb: mixed_ptb[i],
+                            trace_replay: trace_replay[i],
+                        },
                         AdversarialPayloadCfg::from_str(&adversarial_cfg[i]).unwrap(),
+                        DynamicFieldCfg {
+                            num_entries: dynamic_field_num_entries[i],
+                            tree_depth: dynamic_field_tree_depth[i],
+                            num_accesses: dynamic_field_accesses[i],
+                        },
+                        mixed_ptb_num_commands[i],
+                        trace.clone(),
                         batch_payment_size[i],
                         shared_counter_hotness_factor[i],
                         num_shared_counters.as_ref().map(|n| n[i]),
@@ -139,13 +212,11 @@ impl WorkloadConfiguration {
         workload_group: u32,
         num_workers: u64,
         num_transfer_accounts: u64,
-        shared_counter_weight: u32,
-        transfer_object_weight: u32,
-        delegation_weight: u32,
-        batch_payment_weight: u32,
-        shared_deletion_weight: u32,
-        adversarial_weight: u32,
+        weights: WorkloadWeights,
         adversarial_cfg: AdversarialPayloadCfg,
+        dynamic_field_cfg: DynamicFieldCfg,
+        mixed_ptb_num_commands: u64,
+        trace: Option<Arc<Vec<TraceTransaction>>>,
         batch_payment_size: u32,
         shared_counter_hotness_factor: u32,
         num_shared_counters: Option<u64>,
@@ -155,15 +226,10 @@ impl WorkloadConfiguration {
         duration: Interval,
         system_state_observer: Arc<SystemStateObserver>,
     ) -> Vec<Option<WorkloadBuilderInfo>> {
-        let total_weight = shared_counter_weight
-            + transfer_object_weight
-            + delegation_weight
-            + batch_payment_weight
-            + adversarial_weight;
         let reference_gas_price = system_state_observer.state.borrow().reference_gas_price;
         let mut workload_builders = vec![];
         let shared_workload = SharedCounterWorkloadBuilder::from(
-            shared_counter_weight as f32 / total_weight as f32,
+            weights.ratio(weights.shared_counter),
             target_qps,
             num_workers,
             in_flight_ratio,
@@ -176,7 +242,7 @@ impl WorkloadConfiguration {
         );
         workload_builders.push(shared_workload);
         let shared_deletion_workload = SharedCounterDeletionWorkloadBuilder::from(
-            shared_deletion_weight as f32 / total_weight as f32,
+            weights.ratio(weights.shared_deletion),
             target_qps,
             num_workers,
             in_flight_ratio,
@@ -188,7 +254,7 @@ impl WorkloadConfiguration {
         );
         workload_builders.push(shared_deletion_workload);
         let transfer_workload = TransferObjectWorkloadBuilder::from(
-            transfer_object_weight as f32 / total_weight as f32,
+            weights.ratio(weights.transfer_object),
             target_qps,
             num_workers,
             in_flight_ratio,
@@ -198,7 +264,7 @@ impl WorkloadConfiguration {
         );
         workload_builders.push(transfer_workload);
         let delegation_workload = DelegationWorkloadBuilder::from(
-            delegation_weight as f32 / total_weight as f32,
+            weights.ratio(weights.delegation),
             target_qps,
             num_workers,
             in_flight_ratio,
@@ -207,7 +273,7 @@ impl WorkloadConfiguration {
         );
         workload_builders.push(delegation_workload);
         let batch_payment_workload = BatchPaymentWorkloadBuilder::from(
-            batch_payment_weight as f32 / total_weight as f32,
+            weights.ratio(weights.batch_payment),
             target_qps,
             num_workers,
             in_flight_ratio,
@@ -217,7 +283,7 @@ impl WorkloadConfiguration {
         );
         workload_builders.push(batch_payment_workload);
         let adversarial_workload = AdversarialWorkloadBuilder::from(
-            adversarial_weight as f32 / total_weight as f32,
+            weights.ratio(weights.adversarial),
             target_qps,
             num_workers,
             in_flight_ratio,
@@ -226,6 +292,45 @@ impl WorkloadConfiguration {
             workload_group,
         );
         workload_builders.push(adversarial_workload);
+        let dynamic_field_workload = DynamicFieldWorkloadBuilder::from(
+            weights.ratio(weights.dynamic_field),
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            dynamic_field_cfg,
+            duration,
+            workload_group,
+        );
+        workload_builders.push(dynamic_field_workload);
+        let randomness_workload = RandomnessWorkloadBuilder::from(
+            weights.ratio(weights.randomness),
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            duration,
+            workload_group,
+        );
+        workload_builders.push(randomness_workload);
+        let mixed_ptb_workload = MixedPtbWorkloadBuilder::from(
+            weights.ratio(weights.mixed_ptb),
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            mixed_ptb_num_commands,
+            duration,
+            workload_group,
+        );
+        workload_builders.push(mixed_ptb_workload);
+        let trace_replay_workload = TraceReplayWorkloadBuilder::from(
+            weights.ratio(weights.trace_replay),
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            trace,
+            duration,
+            workload_group,
+        );
+        workload_builders.push(trace_replay_workload);
 
         workload_builders
     }
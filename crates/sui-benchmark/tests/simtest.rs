@@ -13,7 +13,10 @@ mod test {
     use sui_benchmark::bank::BenchmarkBank;
     use sui_benchmark::system_state_observer::SystemStateObserver;
     use sui_benchmark::workloads::adversarial::AdversarialPayloadCfg;
-    use sui_benchmark::workloads::workload_configuration::WorkloadConfiguration;
+    use sui_benchmark::workloads::dynamic_field::DynamicFieldCfg;
+    use sui_benchmark::workloads::workload_configuration::{
+        WorkloadConfiguration, WorkloadWeights,
+    };
     use sui_benchmark::{
         drivers::{bench_driver::BenchDriver, driver::Driver, Interval},
         util::get_ed25519_keypair_from_keystore,
@@ -30,7 +33,7 @@ mod test {
         clear_fail_point, nondeterministic, register_fail_point_async, register_fail_point_if,
         register_fail_points, sim_test,
     };
-    use sui_protocol_config::{ProtocolVersion, SupportedProtocolVersions};
+    use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion, SupportedProtocolVersions};
     use sui_simulator::tempfile::TempDir;
     use sui_simulator::{configs::*, SimConfig};
     use sui_storage::blob::Blob;
@@ -98,6 +101,46 @@ mod test {
         test_simulated_load(TestInitData::new(&test_cluster).await, 15).await;
     }
 
+    #[sim_test(config = "test_config()")]
+    async fn test_simulated_load_randomness() {
+        sui_protocol_config::ProtocolConfig::poison_get_for_min_version();
+        // `sui::random` is only available once the random beacon is enabled
+        assert!(
+            ProtocolConfig::get_for_version(ProtocolVersion::MAX, Chain::Unknown).random_beacon()
+        );
+        let test_cluster = init_test_cluster_builder(4, 10_000)
+            .with_protocol_version(ProtocolVersion::MAX)
+            .build()
+            .await;
+        let weights = WorkloadWeights {
+            randomness: 1,
+            ..Default::default()
+        };
+        test_simulated_load_with_weights(TestInitData::new(&test_cluster).await, 30, weights).await;
+    }
+
+    #[sim_test(config = "test_config()")]
+    async fn test_simulated_load_dynamic_field() {
+        sui_protocol_config::ProtocolConfig::poison_get_for_min_version();
+        let test_cluster = build_test_cluster(4, 0).await;
+        let weights = WorkloadWeights {
+            dynamic_field: 1,
+            ..Default::default()
+        };
+        test_simulated_load_with_weights(TestInitData::new(&test_cluster).await, 30, weights).await;
+    }
+
+    #[sim_test(config = "test_config()")]
+    async fn test_simulated_load_mixed_ptb() {
+        sui_protocol_config::ProtocolConfig::poison_get_for_min_version();
+        let test_cluster = build_test_cluster(4, 0).await;
+        let weights = WorkloadWeights {
+            mixed_ptb: 1,
+            ..Default::default()
+        };
+        test_simulated_load_with_weights(TestInitData::new(&test_cluster).await, 30, weights).await;
+    }
+
     #[sim_test(config = "test_config()")]
     async fn test_simulated_load_restarts() {
         sui_protocol_config::ProtocolConfig::poison_get_for_min_version();
@@ -658,6 +701,30 @@ mod test {
     }
 
     async fn test_simulated_load(init_data: TestInitData, test_duration_secs: u64) {
+        let weights = WorkloadWeights {
+            shared_counter: 1,
+            transfer_object: 1,
+            delegation: 1,
+            batch_payment: 1,
+            shared_deletion: 1,
+            // TODO: re-enable this when we figure out why it is causing connection errors and making
+            // tests run for ever
+            adversarial: 0,
+            // Dynamic field, randomness and mixed PTB workloads are covered by their own
+            // `test_simulated_load_*` tests
+            dynamic_field: 0,
+            randomness: 0,
+            mixed_ptb: 0,
+            trace_replay: 0,
+        };
+        test_simulated_load_with_weights(init_data, test_duration_secs, weights).await
+    }
+
+    async fn test_simulated_load_with_weights(
+        init_data: TestInitData,
+        test_duration_secs: u64,
+        weights: WorkloadWeights,
+    ) {
         let TestInitData {
             keystore_path,
             genesis,
@@ -688,20 +755,18 @@ mod test {
         let num_workers = get_var("SIM_STRESS_TEST_WORKERS", 10);
         let in_flight_ratio = get_var("SIM_STRESS_TEST_IFR", 2);
         let batch_payment_size = get_var("SIM_BATCH_PAYMENT_SIZE", 15);
-        let shared_counter_weight = 1;
-        let transfer_object_weight = 1;
         let num_transfer_accounts = 2;
-        let delegation_weight = 1;
-        let batch_payment_weight = 1;
-        let shared_object_deletion_weight = 1;
 
         // Run random payloads at 100% load
         let adversarial_cfg = AdversarialPayloadCfg::from_str("0-1.0").unwrap();
         let duration = Interval::from_str("unbounded").unwrap();
 
-        // TODO: re-enable this when we figure out why it is causing connection errors and making
-        // tests run for ever
-        let adversarial_weight = 0;
+        let dynamic_field_cfg = DynamicFieldCfg {
+            num_entries: 100,
+            tree_depth: 5,
+            num_accesses: 5,
+        };
+        let mixed_ptb_num_commands = 16;
 
         let shared_counter_hotness_factor = 50;
         let num_shared_counters = Some(1);
@@ -712,13 +777,11 @@ mod test {
             0,
             num_workers,
             num_transfer_accounts,
-            shared_counter_weight,
-            transfer_object_weight,
-            delegation_weight,
-            batch_payment_weight,
-            shared_object_deletion_weight,
-            adversarial_weight,
+            weights,
             adversarial_cfg,
+            dynamic_field_cfg,
+            mixed_ptb_num_commands,
+            None,
             batch_payment_size,
             shared_counter_hotness_factor,
             num_shared_counters,
@@ -23,6 +23,9 @@ pub enum EthBridgeEvent {
 abigen!(
     EthSuiBridge,
     "abi/sui_bridge.json",
+    event_derives(serde::Deserialize, serde::Serialize);
+    EthBridgeCommittee,
+    "abi/bridge_committee.json",
     event_derives(serde::Deserialize, serde::Serialize)
 );
 
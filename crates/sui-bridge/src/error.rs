@@ -61,6 +61,8 @@ pub enum BridgeError {
     StorageError(String),
     // Rest API Error
     RestAPIError(String),
+    // No token transfer was indexed for the requested deposit
+    BridgeTransferNotFound,
     // Uncategorized error
     Generic(String),
 }
@@ -28,6 +28,7 @@ enum MockParams {
 #[derive(Clone, Debug)]
 pub struct EthMockProvider {
     responses: Arc<Mutex<HashMap<(String, MockParams), Value>>>,
+    wildcard_responses: Arc<Mutex<HashMap<String, Value>>>,
 }
 
 impl Default for EthMockProvider {
@@ -42,7 +43,8 @@ impl JsonRpcClient for EthMockProvider {
     type Error = MockError;
 
     /// If `method` and `params` match previously set response by
-    /// `add_response`, return the response. Otherwise return
+    /// `add_response`, return the response. Otherwise return the response
+    /// set by `add_wildcard_response` for `method`, if any, or
     /// MockError::EmptyResponses.
     async fn request<P: Serialize + Send + Sync + Debug, R: DeserializeOwned>(
         &self,
@@ -54,13 +56,15 @@ impl JsonRpcClient for EthMockProvider {
         } else {
             MockParams::Value(serde_json::to_value(params)?.to_string())
         };
-        let element = self
-            .responses
-            .lock()
-            .unwrap()
-            .get(&(method.to_owned(), params))
-            .ok_or(MockError::EmptyResponses)?
-            .clone();
+        let element = {
+            let responses = self.responses.lock().unwrap();
+            let wildcard_responses = self.wildcard_responses.lock().unwrap();
+            responses
+                .get(&(method.to_owned(), params))
+                .or_else(|| wildcard_responses.get(method))
+                .ok_or(MockError::EmptyResponses)?
+                .clone()
+        };
         let res: R = serde_json::from_value(element)?;
 
         Ok(res)
@@ -71,6 +75,7 @@ impl EthMockProvider {
     pub fn new() -> Self {
         Self {
             responses: Arc::new(Mutex::new(HashMap::new())),
+            wildcard_responses: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -92,6 +97,21 @@ impl EthMockProvider {
             .insert((method.to_owned(), params), value);
         Ok(())
     }
+
+    /// Sets the response to `method` for any params without a response set
+    /// by `add_response`.
+    pub fn add_wildcard_response<T: Serialize + Send + Sync, K: Borrow<T>>(
+        &self,
+        method: &str,
+        data: K,
+    ) -> Result<(), MockError> {
+        let value = serde_json::to_value(data.borrow())?;
+        self.wildcard_responses
+            .lock()
+            .unwrap()
+            .insert(method.to_owned(), value);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -136,6 +156,28 @@ mod tests {
         assert_eq!(block.as_u64(), 14);
     }
 
+    #[tokio::test]
+    async fn test_wildcard_responses() {
+        let mock = EthMockProvider::new();
+
+        mock.add_wildcard_response("eth_blockNumber", U64::from(12))
+            .unwrap();
+        let block: U64 = mock.request("eth_blockNumber", "foo").await.unwrap();
+        assert_eq!(block.as_u64(), 12);
+        let block: U64 = mock.request("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(block.as_u64(), 12);
+
+        // Responses for exact params take precedence
+        mock.add_response("eth_blockNumber", "bar", U64::from(13))
+            .unwrap();
+        let block: U64 = mock.request("eth_blockNumber", "bar").await.unwrap();
+        assert_eq!(block.as_u64(), 13);
+        let block: U64 = mock.request("eth_blockNumber", "foo").await.unwrap();
+        assert_eq!(block.as_u64(), 12);
+
+        mock.request::<_, U64>("eth_chainId", ()).await.unwrap_err();
+    }
+
     #[tokio::test]
     async fn test_with_provider() {
         let mock = EthMockProvider::new();
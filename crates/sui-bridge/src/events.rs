@@ -14,6 +14,7 @@ use crate::sui_transaction_builder::get_bridge_package_id;
 use crate::types::BridgeAction;
 use crate::types::BridgeActionType;
 use crate::types::BridgeChainId;
+use crate::types::MoveTypeBridgeMessageKey;
 use crate::types::SuiToEthBridgeAction;
 use crate::types::TokenId;
 use ethers::types::Address as EthAddress;
@@ -121,6 +122,77 @@ impl TryFrom<MoveTokenBridgeEvent> for EmittedSuiToEthTokenBridgeV1 {
     }
 }
 
+// This is the event structure defined and emitted in Move when a token
+// transfer is approved by the committee
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoveTokenTransferApproved {
+    pub message_key: MoveTypeBridgeMessageKey,
+}
+
+// This is the event structure defined and emitted in Move when a token
+// transfer is claimed on Sui
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoveTokenTransferClaimed {
+    pub message_key: MoveTypeBridgeMessageKey,
+}
+
+// Sanitized version of MoveTokenTransferApproved
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct TokenTransferApproved {
+    pub nonce: u64,
+    pub source_chain: BridgeChainId,
+}
+
+// Sanitized version of MoveTokenTransferClaimed
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct TokenTransferClaimed {
+    pub nonce: u64,
+    pub source_chain: BridgeChainId,
+}
+
+fn sanitize_token_transfer_message_key(
+    message_key: MoveTypeBridgeMessageKey,
+) -> BridgeResult<(BridgeChainId, u64)> {
+    if message_key.message_type != BridgeActionType::TokenTransfer as u8 {
+        return Err(BridgeError::Generic(format!(
+            "Failed to convert token transfer message key. Expected message type {}, got {}",
+            BridgeActionType::TokenTransfer as u8,
+            message_key.message_type
+        )));
+    }
+    let source_chain = BridgeChainId::try_from(message_key.source_chain).map_err(|_e| {
+        BridgeError::Generic(format!(
+            "Failed to convert token transfer message key. Failed to convert source chain {} to BridgeChainId",
+            message_key.source_chain,
+        ))
+    })?;
+    Ok((source_chain, message_key.bridge_seq_num))
+}
+
+impl TryFrom<MoveTokenTransferApproved> for TokenTransferApproved {
+    type Error = BridgeError;
+
+    fn try_from(event: MoveTokenTransferApproved) -> BridgeResult<Self> {
+        let (source_chain, nonce) = sanitize_token_transfer_message_key(event.message_key)?;
+        Ok(Self {
+            nonce,
+            source_chain,
+        })
+    }
+}
+
+impl TryFrom<MoveTokenTransferClaimed> for TokenTransferClaimed {
+    type Error = BridgeError;
+
+    fn try_from(event: MoveTokenTransferClaimed) -> BridgeResult<Self> {
+        let (source_chain, nonce) = sanitize_token_transfer_message_key(event.message_key)?;
+        Ok(Self {
+            nonce,
+            source_chain,
+        })
+    }
+}
+
 // TODO: update this once we have bridge package on sui framework
 pub fn get_bridge_event_struct_tag() -> &'static str {
     static BRIDGE_EVENT_STRUCT_TAG: OnceCell<String> = OnceCell::new();
@@ -130,8 +202,32 @@ pub fn get_bridge_event_struct_tag() -> &'static str {
     })
 }
 
+pub fn get_token_transfer_approved_event_struct_tag() -> &'static str {
+    static TOKEN_TRANSFER_APPROVED_STRUCT_TAG: OnceCell<String> = OnceCell::new();
+    TOKEN_TRANSFER_APPROVED_STRUCT_TAG.get_or_init(|| {
+        let bridge_package_id = *get_bridge_package_id();
+        format!(
+            "0x{}::bridge::TokenTransferApproved",
+            bridge_package_id.to_hex()
+        )
+    })
+}
+
+pub fn get_token_transfer_claimed_event_struct_tag() -> &'static str {
+    static TOKEN_TRANSFER_CLAIMED_STRUCT_TAG: OnceCell<String> = OnceCell::new();
+    TOKEN_TRANSFER_CLAIMED_STRUCT_TAG.get_or_init(|| {
+        let bridge_package_id = *get_bridge_package_id();
+        format!(
+            "0x{}::bridge::TokenTransferClaimed",
+            bridge_package_id.to_hex()
+        )
+    })
+}
+
 crate::declare_events!(
-    SuiToEthTokenBridgeV1(EmittedSuiToEthTokenBridgeV1) => (get_bridge_event_struct_tag(), MoveTokenBridgeEvent)
+    SuiToEthTokenBridgeV1(EmittedSuiToEthTokenBridgeV1) => (get_bridge_event_struct_tag(), MoveTokenBridgeEvent),
+    TokenTransferApproved(TokenTransferApproved) => (get_token_transfer_approved_event_struct_tag(), MoveTokenTransferApproved),
+    TokenTransferClaimed(TokenTransferClaimed) => (get_token_transfer_claimed_event_struct_tag(), MoveTokenTransferClaimed)
     // Add new event types here. Format: EnumVariantName(Struct) => ("StructTagString", CorrespondingMoveStruct)
 );
 
@@ -185,6 +281,9 @@ impl SuiBridgeEvent {
                     sui_bridge_event: event.clone(),
                 }))
             }
+            SuiBridgeEvent::TokenTransferApproved(_) | SuiBridgeEvent::TokenTransferClaimed(_) => {
+                None
+            }
         }
     }
 }
@@ -0,0 +1,579 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tooling for bridge committee members to propose governance actions:
+//! 1. write the action to a file that committee members add to their
+//!    `approved_governance_actions`
+//! 2. collect signatures from the committee's bridge servers
+//! 3. submit the certified action to the chain that executes it
+
+use crate::abi::{EthBridgeCommittee, EthSuiBridge, Message};
+use crate::client::bridge_authority_aggregator::BridgeAuthorityAggregator;
+use crate::error::{BridgeError, BridgeResult};
+use crate::sui_client::{SuiClient, SuiClientInner};
+use crate::sui_transaction_builder::build_transaction;
+use crate::types::{
+    BridgeAction, BridgeChainId, BridgeCommittee, VerifiedCertifiedBridgeAction,
+    BRIDGE_MESSAGE_PREFIX,
+};
+use anyhow::anyhow;
+use ethers::contract::ContractCall;
+use ethers::providers::Middleware;
+use ethers::types::{Address as EthAddress, Bytes, TransactionReceipt};
+use fastcrypto::traits::ToFromBytes;
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::path::Path;
+use std::sync::Arc;
+use sui_json_rpc_types::SuiTransactionBlockResponse;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::committee::StakeUnit;
+use sui_types::crypto::{Signature, SuiKeyPair};
+use sui_types::object::Owner;
+use sui_types::transaction::Transaction;
+use tracing::info;
+
+/// Length of the message header (type, version, nonce and chain id) that
+/// precedes the payload in `BridgeAction::to_bytes`.
+const MESSAGE_HEADER_LENGTH: usize = 11;
+
+pub fn write_governance_action_file(path: &Path, action: &BridgeAction) -> anyhow::Result<()> {
+    if !action.is_governace_action() {
+        return Err(anyhow!("{:?} is not a governance action", action));
+    }
+    let contents = serde_json::to_string_pretty(action)?;
+    std::fs::write(path, contents)
+        .map_err(|e| anyhow!("Failed to write governance action to {:?}: {:?}", path, e))
+}
+
+pub fn read_governance_action_file(path: &Path) -> anyhow::Result<BridgeAction> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read governance action from {:?}: {:?}", path, e))?;
+    let action: BridgeAction = serde_json::from_str(&contents)?;
+    if !action.is_governace_action() {
+        return Err(anyhow!("{:?} is not a governance action", action));
+    }
+    Ok(action)
+}
+
+/// Requests signatures for `action` from the committee until the stake
+/// required to execute it on chain is reached.
+pub async fn collect_governance_action_signatures(
+    auth_agg: &BridgeAuthorityAggregator,
+    action: BridgeAction,
+) -> BridgeResult<VerifiedCertifiedBridgeAction> {
+    if !action.is_governace_action() {
+        return Err(BridgeError::ActionIsNotGovernanceAction(action));
+    }
+    let threshold = action.approval_threshold();
+    auth_agg
+        .request_committee_signatures(action, threshold)
+        .await
+}
+
+/// Total stake of the authorities that signed `action`.
+pub fn certified_stake(
+    committee: &BridgeCommittee,
+    action: &VerifiedCertifiedBridgeAction,
+) -> StakeUnit {
+    action
+        .auth_sig()
+        .signatures
+        .keys()
+        .filter_map(|name| committee.member(name))
+        .map(|authority| authority.voting_power)
+        .sum()
+}
+
+/// Whether `action` is executed on Sui, as opposed to Ethereum.
+pub fn is_sui_governance_action(action: &BridgeAction) -> bool {
+    matches!(
+        action.chain_id(),
+        BridgeChainId::SuiMainnet
+            | BridgeChainId::SuiTestnet
+            | BridgeChainId::SuiDevnet
+            | BridgeChainId::SuiLocalTest
+    )
+}
+
+pub async fn submit_governance_action_to_sui<C: SuiClientInner>(
+    sui_client: &SuiClient<C>,
+    sui_key: &SuiKeyPair,
+    gas_object_id: ObjectID,
+    action: VerifiedCertifiedBridgeAction,
+) -> anyhow::Result<SuiTransactionBlockResponse> {
+    match action.data() {
+        BridgeAction::EmergencyAction(_) => {}
+        other => {
+            return Err(anyhow!(
+                "Executing {:?} is unsupported on {:?}",
+                other.action_type(),
+                other.chain_id()
+            ))
+        }
+    }
+    let sui_address = SuiAddress::from(&sui_key.public());
+    let (_gas_coin, gas_object_ref, owner) = sui_client
+        .get_gas_data(gas_object_id)
+        .await
+        .map_err(|e| anyhow!("Failed to get gas object {:?}: {:?}", gas_object_id, e))?;
+    if owner != Owner::AddressOwner(sui_address) {
+        return Err(anyhow!(
+            "Gas object {:?} is not owned by {}",
+            gas_object_id,
+            sui_address
+        ));
+    }
+    let tx_data = build_transaction(sui_address, &gas_object_ref, action)
+        .map_err(|e| anyhow!("Failed to build transaction: {:?}", e))?;
+    let sig = Signature::new_secure(
+        &IntentMessage::new(Intent::sui_transaction(), &tx_data),
+        sui_key,
+    );
+    let signed_tx = Transaction::from_data(tx_data, vec![sig]);
+    info!(tx_digest = ?signed_tx.digest(), "Sending governance transaction to Sui");
+    sui_client
+        .execute_transaction_block_with_effects(signed_tx)
+        .await
+        .map_err(|e| anyhow!("Failed to execute transaction: {:?}", e))
+}
+
+/// Converts a certified action into the `Message` and signatures that the
+/// Ethereum bridge contracts verify.
+pub fn eth_message_and_signatures(action: &VerifiedCertifiedBridgeAction) -> (Message, Vec<Bytes>) {
+    let bridge_action = action.data();
+    let bytes = bridge_action.to_bytes();
+    // Skip the prefix and the header, which the contracts read from `Message` fields
+    let payload = bytes[BRIDGE_MESSAGE_PREFIX.len() + MESSAGE_HEADER_LENGTH..].to_vec();
+    let message = Message {
+        message_type: bridge_action.action_type() as u8,
+        version: bytes[BRIDGE_MESSAGE_PREFIX.len() + 1],
+        nonce: bridge_action.seq_number(),
+        chain_id: bridge_action.chain_id() as u8,
+        payload: payload.into(),
+    };
+    let signatures = action
+        .auth_sig()
+        .signatures
+        .values()
+        .map(|sig| Bytes::from(sig.as_bytes().to_vec()))
+        .collect();
+    (message, signatures)
+}
+
+/// The call to the Ethereum bridge contract that executes `action`.
+async fn eth_governance_action_call<M: Middleware + 'static>(
+    eth_client: Arc<M>,
+    bridge_proxy_address: EthAddress,
+    action: &VerifiedCertifiedBridgeAction,
+) -> anyhow::Result<ContractCall<M, ()>> {
+    let contract = EthSuiBridge::new(bridge_proxy_address, eth_client.clone());
+    let (message, signatures) = eth_message_and_signatures(action);
+    match action.data() {
+        BridgeAction::EmergencyAction(_) => {
+            Ok(contract.execute_emergency_op_with_signatures(signatures, message))
+        }
+        BridgeAction::EvmContractUpgradeAction(_) => {
+            Ok(contract.upgrade_bridge_with_signatures(signatures, message))
+        }
+        BridgeAction::BlocklistCommitteeAction(_) => {
+            let committee_address = contract
+                .committee()
+                .call()
+                .await
+                .map_err(|e| anyhow!("Failed to get the bridge committee address: {:?}", e))?;
+            let committee = EthBridgeCommittee::new(committee_address, eth_client);
+            Ok(committee.update_blocklist_with_signatures(signatures, message))
+        }
+        // TODO: support limit and price updates once the limiter contract abi
+        // is available here.
+        other => Err(anyhow!(
+            "Executing {:?} is unsupported on {:?}",
+            other.action_type(),
+            other.chain_id()
+        )),
+    }
+}
+
+pub async fn submit_governance_action_to_eth<M: Middleware + 'static>(
+    eth_client: Arc<M>,
+    bridge_proxy_address: EthAddress,
+    action: VerifiedCertifiedBridgeAction,
+) -> anyhow::Result<TransactionReceipt> {
+    let call = eth_governance_action_call(eth_client, bridge_proxy_address, &action).await?;
+    let pending_tx = call
+        .send()
+        .await
+        .map_err(|e| anyhow!("Failed to send transaction: {:?}", e))?;
+    info!(tx_hash = ?*pending_tx, "Sent governance transaction to Ethereum");
+    pending_tx
+        .await?
+        .ok_or_else(|| anyhow!("Transaction was dropped from the mempool"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::{EthBridgeCommitteeCalls, EthSuiBridgeCalls};
+    use crate::eth_mock_provider::EthMockProvider;
+    use crate::server::mock_handler::BridgeRequestMockHandler;
+    use crate::sui_mock_client::SuiMockClient;
+    use crate::test_utils::{
+        get_test_authorities_and_run_mock_bridge_server, get_test_sui_to_eth_bridge_action,
+    };
+    use crate::types::{
+        AssetPriceUpdateAction, BlocklistCommitteeAction, BlocklistType, EmergencyAction,
+        EmergencyActionType, EvmContractUpgradeAction, LimitUpdateAction, TokenId,
+    };
+    use ethers::abi::{AbiDecode, Token};
+    use ethers::providers::Provider;
+    use ethers::types::{Block, FeeHistory, Transaction as EthTransaction, TxHash, U256, U64};
+    use std::time::Duration;
+    use sui_json_rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffects};
+    use sui_types::base_types::random_object_ref;
+    use sui_types::crypto::get_key_pair;
+    use sui_types::gas_coin::GasCoin;
+
+    fn setup(
+        voting_power: Vec<u64>,
+    ) -> (
+        Vec<tokio::task::JoinHandle<()>>,
+        Arc<BridgeCommittee>,
+        BridgeAuthorityAggregator,
+    ) {
+        let mocks = voting_power
+            .iter()
+            .map(|_| BridgeRequestMockHandler::new())
+            .collect::<Vec<_>>();
+        let (handles, authorities, secrets) =
+            get_test_authorities_and_run_mock_bridge_server(voting_power, mocks.clone());
+        for (mock, secret) in mocks.iter().zip(secrets) {
+            mock.set_signer(secret);
+        }
+        let committee = Arc::new(BridgeCommittee::new(authorities).unwrap());
+        let agg = BridgeAuthorityAggregator::new(committee.clone());
+        (handles, committee, agg)
+    }
+
+    #[test]
+    fn test_governance_action_file_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("action.json");
+        let action = BridgeAction::EmergencyAction(EmergencyAction {
+            nonce: 7,
+            chain_id: BridgeChainId::SuiLocalTest,
+            action_type: EmergencyActionType::Pause,
+        });
+        write_governance_action_file(&path, &action).unwrap();
+        assert_eq!(read_governance_action_file(&path).unwrap(), action);
+
+        // Token transfers are not governance actions
+        let action = get_test_sui_to_eth_bridge_action(None, None, None, None);
+        write_governance_action_file(&path, &action).unwrap_err();
+        std::fs::write(&path, serde_json::to_string(&action).unwrap()).unwrap();
+        read_governance_action_file(&path).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_collect_governance_action_signatures() {
+        telemetry_subscribers::init_for_testing();
+        let (_handles, committee, agg) = setup(vec![2500, 2500, 2500, 2500]);
+
+        // Unpausing needs a super majority
+        let action = BridgeAction::EmergencyAction(EmergencyAction {
+            nonce: 0,
+            chain_id: BridgeChainId::SuiLocalTest,
+            action_type: EmergencyActionType::Unpause,
+        });
+        let certified = collect_governance_action_signatures(&agg, action.clone())
+            .await
+            .unwrap();
+        assert_eq!(certified.data(), &action);
+        assert!(certified_stake(&committee, &certified) >= 5001);
+
+        // Pausing needs much less stake
+        let action = BridgeAction::EmergencyAction(EmergencyAction {
+            nonce: 1,
+            chain_id: BridgeChainId::EthLocalTest,
+            action_type: EmergencyActionType::Pause,
+        });
+        let certified = collect_governance_action_signatures(&agg, action.clone())
+            .await
+            .unwrap();
+        assert!(certified_stake(&committee, &certified) >= 450);
+        assert!(!is_sui_governance_action(&action));
+
+        let (message, signatures) = eth_message_and_signatures(&certified);
+        assert_eq!(message.message_type, action.action_type() as u8);
+        assert_eq!(message.nonce, 1);
+        assert_eq!(message.chain_id, BridgeChainId::EthLocalTest as u8);
+        assert_eq!(
+            message.payload.to_vec(),
+            vec![EmergencyActionType::Pause as u8]
+        );
+        assert_eq!(signatures.len(), certified.auth_sig().signatures.len());
+
+        // All governance actions can be certified, whether or not they can be executed yet
+        let actions = vec![
+            BridgeAction::BlocklistCommitteeAction(BlocklistCommitteeAction {
+                nonce: 2,
+                chain_id: BridgeChainId::SuiLocalTest,
+                blocklist_type: BlocklistType::Blocklist,
+                blocklisted_members: vec![],
+            }),
+            BridgeAction::LimitUpdateAction(LimitUpdateAction {
+                nonce: 3,
+                chain_id: BridgeChainId::EthLocalTest,
+                sending_chain_id: BridgeChainId::SuiLocalTest,
+                new_usd_limit: 1_000_000,
+            }),
+            BridgeAction::AssetPriceUpdateAction(AssetPriceUpdateAction {
+                nonce: 4,
+                chain_id: BridgeChainId::SuiLocalTest,
+                token_id: TokenId::BTC,
+                new_usd_price: 50_000,
+            }),
+            BridgeAction::EvmContractUpgradeAction(EvmContractUpgradeAction {
+                nonce: 5,
+                chain_id: BridgeChainId::EthLocalTest,
+                proxy_address: EthAddress::repeat_byte(1),
+                new_impl_address: EthAddress::repeat_byte(2),
+                call_data: vec![],
+            }),
+        ];
+        for action in actions {
+            let certified = collect_governance_action_signatures(&agg, action.clone())
+                .await
+                .unwrap();
+            assert_eq!(certified.data(), &action);
+            assert!(certified_stake(&committee, &certified) >= action.approval_threshold());
+        }
+
+        // Token transfers are rejected
+        let action = get_test_sui_to_eth_bridge_action(None, None, None, None);
+        let err = collect_governance_action_signatures(&agg, action)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BridgeError::ActionIsNotGovernanceAction(_)));
+    }
+
+    #[tokio::test]
+    async fn test_submit_governance_action_to_sui() {
+        telemetry_subscribers::init_for_testing();
+        // TODO: remove once we don't rely on env var to get object id
+        std::env::set_var("ROOT_BRIDGE_OBJECT_ID", "0x09");
+        std::env::set_var("ROOT_BRIDGE_OBJECT_INITIAL_SHARED_VERSION", "1");
+
+        let (_handles, _committee, agg) = setup(vec![10000]);
+        let mock_client = SuiMockClient::default();
+        let sui_client = SuiClient::new_for_testing(mock_client.clone());
+        let (sui_address, kp): (_, fastcrypto::ed25519::Ed25519KeyPair) = get_key_pair();
+        let sui_key = SuiKeyPair::from(kp);
+        let gas_object_ref = random_object_ref();
+        mock_client.add_gas_object_info(
+            GasCoin::new_for_testing(1_000_000_000_000),
+            gas_object_ref,
+            Owner::AddressOwner(sui_address),
+        );
+
+        let action = BridgeAction::EmergencyAction(EmergencyAction {
+            nonce: 0,
+            chain_id: BridgeChainId::SuiLocalTest,
+            action_type: EmergencyActionType::Pause,
+        });
+        assert!(is_sui_governance_action(&action));
+        let certified = collect_governance_action_signatures(&agg, action)
+            .await
+            .unwrap();
+
+        let mut requested_txes = mock_client.subscribe_to_requested_transactions();
+        let tx_digest = sui_types::digests::TransactionDigest::random();
+        let mut response = SuiTransactionBlockResponse::new(tx_digest);
+        response.effects = Some(SuiTransactionBlockEffects::new_for_testing(
+            tx_digest,
+            SuiExecutionStatus::Success,
+        ));
+        mock_client.set_wildcard_transaction_response(Ok(response));
+        // A gas object that does not exist is an error rather than a panic
+        submit_governance_action_to_sui(
+            &sui_client,
+            &sui_key,
+            ObjectID::random(),
+            certified.clone(),
+        )
+        .await
+        .unwrap_err();
+        submit_governance_action_to_sui(&sui_client, &sui_key, gas_object_ref.0, certified)
+            .await
+            .unwrap();
+        requested_txes.recv().await.unwrap();
+
+        // EVM contract upgrades are not executed on Sui
+        let action = BridgeAction::EvmContractUpgradeAction(EvmContractUpgradeAction {
+            nonce: 0,
+            chain_id: BridgeChainId::SuiLocalTest,
+            proxy_address: EthAddress::repeat_byte(1),
+            new_impl_address: EthAddress::repeat_byte(2),
+            call_data: vec![],
+        });
+        let certified = collect_governance_action_signatures(&agg, action)
+            .await
+            .unwrap();
+        submit_governance_action_to_sui(&sui_client, &sui_key, gas_object_ref.0, certified)
+            .await
+            .unwrap_err();
+
+        // Limit updates are certified but cannot be executed on Sui yet
+        let action = BridgeAction::LimitUpdateAction(LimitUpdateAction {
+            nonce: 1,
+            chain_id: BridgeChainId::SuiLocalTest,
+            sending_chain_id: BridgeChainId::EthLocalTest,
+            new_usd_limit: 1_000_000,
+        });
+        let certified = collect_governance_action_signatures(&agg, action)
+            .await
+            .unwrap();
+        let err =
+            submit_governance_action_to_sui(&sui_client, &sui_key, gas_object_ref.0, certified)
+                .await
+                .unwrap_err();
+        assert!(err.to_string().contains("unsupported on SuiLocalTest"));
+    }
+
+    #[tokio::test]
+    async fn test_submit_governance_action_to_eth() {
+        telemetry_subscribers::init_for_testing();
+        let (_handles, _committee, agg) = setup(vec![10000]);
+        let mock_provider = EthMockProvider::new();
+        let eth_client =
+            Arc::new(Provider::new(mock_provider.clone()).interval(Duration::from_millis(10)));
+        let bridge_proxy_address = EthAddress::random();
+
+        let action = BridgeAction::EmergencyAction(EmergencyAction {
+            nonce: 0,
+            chain_id: BridgeChainId::EthLocalTest,
+            action_type: EmergencyActionType::Pause,
+        });
+        let certified = collect_governance_action_signatures(&agg, action)
+            .await
+            .unwrap();
+
+        // The action is executed by the bridge contract with the committee signatures
+        let call = eth_governance_action_call(eth_client.clone(), bridge_proxy_address, &certified)
+            .await
+            .unwrap();
+        assert_eq!(call.tx.to(), Some(&bridge_proxy_address.into()));
+        let (message, signatures) = eth_message_and_signatures(&certified);
+        match EthSuiBridgeCalls::decode(call.tx.data().unwrap()).unwrap() {
+            EthSuiBridgeCalls::ExecuteEmergencyOpWithSignatures(call) => {
+                assert_eq!(call.message, message);
+                assert_eq!(call.signatures, signatures);
+            }
+            other => panic!("Unexpected call: {:?}", other),
+        }
+
+        mock_provider
+            .add_wildcard_response(
+                "eth_getBlockByNumber",
+                Block::<TxHash> {
+                    number: Some(U64::from(1)),
+                    base_fee_per_gas: Some(U256::from(1)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        mock_provider
+            .add_wildcard_response(
+                "eth_feeHistory",
+                FeeHistory {
+                    base_fee_per_gas: vec![U256::from(1)],
+                    gas_used_ratio: vec![0.5],
+                    oldest_block: U256::from(1),
+                    reward: vec![vec![U256::from(1)]],
+                },
+            )
+            .unwrap();
+        mock_provider
+            .add_wildcard_response("eth_gasPrice", U256::from(1))
+            .unwrap();
+        mock_provider
+            .add_wildcard_response("eth_estimateGas", U256::from(100_000))
+            .unwrap();
+        let tx_hash = TxHash::random();
+        mock_provider
+            .add_wildcard_response("eth_sendTransaction", tx_hash)
+            .unwrap();
+        mock_provider
+            .add_response(
+                "eth_getTransactionByHash",
+                [tx_hash],
+                EthTransaction {
+                    hash: tx_hash,
+                    block_number: Some(U64::from(1)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        mock_provider
+            .add_response(
+                "eth_getTransactionReceipt",
+                [tx_hash],
+                TransactionReceipt {
+                    transaction_hash: tx_hash,
+                    block_number: Some(U64::from(1)),
+                    status: Some(U64::from(1)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let receipt =
+            submit_governance_action_to_eth(eth_client.clone(), bridge_proxy_address, certified)
+                .await
+                .unwrap();
+        assert_eq!(receipt.transaction_hash, tx_hash);
+
+        // Committee blocklist updates are executed by the committee contract
+        let committee_address = EthAddress::random();
+        mock_provider
+            .add_wildcard_response(
+                "eth_call",
+                Bytes::from(ethers::abi::encode(&[Token::Address(committee_address)])),
+            )
+            .unwrap();
+        let action = BridgeAction::BlocklistCommitteeAction(BlocklistCommitteeAction {
+            nonce: 0,
+            chain_id: BridgeChainId::EthLocalTest,
+            blocklist_type: BlocklistType::Blocklist,
+            blocklisted_members: vec![],
+        });
+        let certified = collect_governance_action_signatures(&agg, action)
+            .await
+            .unwrap();
+        let call = eth_governance_action_call(eth_client.clone(), bridge_proxy_address, &certified)
+            .await
+            .unwrap();
+        assert_eq!(call.tx.to(), Some(&committee_address.into()));
+        let (message, signatures) = eth_message_and_signatures(&certified);
+        match EthBridgeCommitteeCalls::decode(call.tx.data().unwrap()).unwrap() {
+            EthBridgeCommitteeCalls::UpdateBlocklistWithSignatures(call) => {
+                assert_eq!(call.message, message);
+                assert_eq!(call.signatures, signatures);
+            }
+            other => panic!("Unexpected call: {:?}", other),
+        }
+
+        // Limit updates are certified but cannot be executed on Ethereum yet
+        let action = BridgeAction::LimitUpdateAction(LimitUpdateAction {
+            nonce: 0,
+            chain_id: BridgeChainId::EthLocalTest,
+            sending_chain_id: BridgeChainId::SuiLocalTest,
+            new_usd_limit: 1_000_000,
+        });
+        let certified = collect_governance_action_signatures(&agg, action)
+            .await
+            .unwrap();
+        let err = submit_governance_action_to_eth(eth_client, bridge_proxy_address, certified)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unsupported on EthLocalTest"));
+    }
+}
@@ -10,6 +10,7 @@ pub mod error;
 pub mod eth_client;
 pub mod eth_syncer;
 pub mod events;
+pub mod governance;
 pub mod node;
 pub mod orchestrator;
 pub mod server;
@@ -17,6 +18,7 @@ pub mod storage;
 pub mod sui_client;
 pub mod sui_syncer;
 pub mod sui_transaction_builder;
+pub mod transfer_indexer;
 pub mod types;
 
 #[cfg(test)]
@@ -8,8 +8,9 @@ use crate::{
     eth_syncer::EthSyncer,
     orchestrator::BridgeOrchestrator,
     server::{handler::BridgeRequestHandler, run_server},
-    storage::BridgeOrchestratorTables,
+    storage::{BridgeOrchestratorTables, BridgeTransferTables},
     sui_syncer::SuiSyncer,
+    transfer_indexer::BridgeTransferIndexer,
 };
 use std::{
     collections::HashMap,
@@ -24,11 +25,12 @@ pub async fn run_bridge_node(config: BridgeNodeConfig) -> anyhow::Result<()> {
     let (server_config, client_config) = config.validate().await?;
 
     // Start Client
-    let _handles = if let Some(client_config) = client_config {
-        start_client_components(client_config).await
+    let (_handles, transfer_indexer) = if let Some(client_config) = client_config {
+        let (handles, transfer_indexer) = start_client_components(client_config).await?;
+        (handles, Some(transfer_indexer))
     } else {
-        Ok(vec![])
-    }?;
+        (vec![], None)
+    };
 
     // Start Server
     let socket_address = SocketAddr::new(
@@ -43,6 +45,7 @@ pub async fn run_bridge_node(config: BridgeNodeConfig) -> anyhow::Result<()> {
             server_config.eth_client,
             server_config.approved_governance_actions,
         ),
+        transfer_indexer,
     )
     .await;
 
@@ -52,9 +55,12 @@ pub async fn run_bridge_node(config: BridgeNodeConfig) -> anyhow::Result<()> {
 // TODO: is there a way to clean up the overrides after it's stored in DB?
 async fn start_client_components(
     client_config: BridgeClientConfig,
-) -> anyhow::Result<Vec<JoinHandle<()>>> {
+) -> anyhow::Result<(Vec<JoinHandle<()>>, Arc<BridgeTransferIndexer>)> {
     let store: std::sync::Arc<BridgeOrchestratorTables> =
         BridgeOrchestratorTables::new(&client_config.db_path.join("client"));
+    let transfer_indexer = Arc::new(BridgeTransferIndexer::new(BridgeTransferTables::new(
+        &client_config.db_path.join("transfers"),
+    )));
     let stored_module_cursors = store
         .get_sui_event_cursors(&client_config.sui_bridge_modules)
         .map_err(|e| anyhow::anyhow!("Unable to get sui event cursors from storage: {e:?}"))?;
@@ -154,9 +160,14 @@ async fn start_client_components(
         client_config.gas_object_ref.0,
     );
 
-    let orchestrator =
-        BridgeOrchestrator::new(sui_client, sui_events_rx, eth_events_rx, store.clone());
+    let orchestrator = BridgeOrchestrator::new(
+        sui_client,
+        sui_events_rx,
+        eth_events_rx,
+        store.clone(),
+        transfer_indexer.clone(),
+    );
 
     all_handles.extend(orchestrator.run(bridge_action_executor));
-    Ok(all_handles)
+    Ok((all_handles, transfer_indexer))
 }
@@ -5,6 +5,7 @@
 //! 1. monitors Sui and Ethereum events with the help of `SuiSyncer` and `EthSyncer`
 //! 2. updates WAL table and cursor tables
 //! 2. hands actions to `BridgeExecutor` for execution
+//! 3. feeds events to `BridgeTransferIndexer` for auditing
 
 use crate::abi::EthBridgeEvent;
 use crate::action_executor::{
@@ -14,6 +15,7 @@ use crate::error::BridgeResult;
 use crate::events::SuiBridgeEvent;
 use crate::storage::BridgeOrchestratorTables;
 use crate::sui_client::{SuiClient, SuiClientInner};
+use crate::transfer_indexer::BridgeTransferIndexer;
 use crate::types::EthLog;
 use ethers::types::Address as EthAddress;
 use mysten_metrics::spawn_logged_monitored_task;
@@ -28,6 +30,7 @@ pub struct BridgeOrchestrator<C> {
     sui_events_rx: mysten_metrics::metered_channel::Receiver<(Identifier, Vec<SuiEvent>)>,
     eth_events_rx: mysten_metrics::metered_channel::Receiver<(EthAddress, u64, Vec<EthLog>)>,
     store: Arc<BridgeOrchestratorTables>,
+    transfer_indexer: Arc<BridgeTransferIndexer>,
 }
 
 impl<C> BridgeOrchestrator<C>
@@ -39,12 +42,14 @@ where
         sui_events_rx: mysten_metrics::metered_channel::Receiver<(Identifier, Vec<SuiEvent>)>,
         eth_events_rx: mysten_metrics::metered_channel::Receiver<(EthAddress, u64, Vec<EthLog>)>,
         store: Arc<BridgeOrchestratorTables>,
+        transfer_indexer: Arc<BridgeTransferIndexer>,
     ) -> Self {
         Self {
             _sui_client: sui_client,
             sui_events_rx,
             eth_events_rx,
             store,
+            transfer_indexer,
         }
     }
 
@@ -62,12 +67,14 @@ where
         let executor_sender_clone = executor_sender.clone();
         task_handles.push(spawn_logged_monitored_task!(Self::run_sui_watcher(
             store_clone,
+            self.transfer_indexer.clone(),
             executor_sender_clone,
             self.sui_events_rx,
         )));
         let store_clone = self.store.clone();
         task_handles.push(spawn_logged_monitored_task!(Self::run_eth_watcher(
             store_clone,
+            self.transfer_indexer,
             executor_sender,
             self.eth_events_rx,
         )));
@@ -77,6 +84,7 @@ where
 
     async fn run_sui_watcher(
         store: Arc<BridgeOrchestratorTables>,
+        transfer_indexer: Arc<BridgeTransferIndexer>,
         executor_tx: mysten_metrics::metered_channel::Sender<BridgeActionExecutionWrapper>,
         mut sui_events_rx: mysten_metrics::metered_channel::Receiver<(Identifier, Vec<SuiEvent>)>,
     ) {
@@ -103,6 +111,9 @@ where
                 }
                 // Unwrap safe: checked above
                 let bridge_event: SuiBridgeEvent = opt_bridge_event.unwrap();
+                transfer_indexer
+                    .index_sui_event(sui_event, &bridge_event)
+                    .expect("Store operation should not fail");
 
                 if let Some(action) = bridge_event
                     .try_into_bridge_action(sui_event.id.tx_digest, sui_event.id.event_seq as u16)
@@ -135,6 +146,7 @@ where
 
     async fn run_eth_watcher(
         store: Arc<BridgeOrchestratorTables>,
+        transfer_indexer: Arc<BridgeTransferIndexer>,
         executor_tx: mysten_metrics::metered_channel::Sender<BridgeActionExecutionWrapper>,
         mut eth_events_rx: mysten_metrics::metered_channel::Receiver<(
             ethers::types::Address,
@@ -166,6 +178,9 @@ where
                 }
                 // Unwrap safe: checked above
                 let bridge_event = opt_bridge_event.unwrap();
+                transfer_indexer
+                    .index_eth_event(log, &bridge_event)
+                    .expect("Store operation should not fail");
 
                 if let Some(action) =
                     bridge_event.try_into_bridge_action(log.tx_hash, log.log_index_in_tx)
@@ -203,6 +218,8 @@ mod tests {
     use std::str::FromStr;
 
     use super::*;
+    use crate::storage::BridgeTransferTables;
+    use crate::transfer_indexer::BridgeTransferStatus;
     use crate::{events::tests::get_test_sui_event_and_action, sui_mock_client::SuiMockClient};
 
     #[tokio::test]
@@ -210,8 +227,15 @@ mod tests {
         // Note: this test may fail beacuse of the following reasons:
         // the SuiEvent's struct tag does not match the ones in events.rs
 
-        let (sui_events_tx, sui_events_rx, _eth_events_tx, eth_events_rx, sui_client, store) =
-            setup();
+        let (
+            sui_events_tx,
+            sui_events_rx,
+            _eth_events_tx,
+            eth_events_rx,
+            sui_client,
+            store,
+            transfer_indexer,
+        ) = setup();
 
         let (executor, mut executor_requested_action_rx) = MockExecutor::new();
         // start orchestrator
@@ -220,6 +244,7 @@ mod tests {
             sui_events_rx,
             eth_events_rx,
             store.clone(),
+            transfer_indexer.clone(),
         )
         .run(executor);
 
@@ -252,6 +277,15 @@ mod tests {
                 store.get_sui_event_cursors(&[identifier]).unwrap()[0].unwrap(),
                 sui_event.id,
             );
+            // The deposit is indexed before the action is written to WAL
+            assert_eq!(
+                transfer_indexer
+                    .get_transfer(&bridge_action.digest())
+                    .unwrap()
+                    .unwrap()
+                    .status,
+                BridgeTransferStatus::Deposited
+            );
             break;
         }
     }
@@ -262,8 +296,15 @@ mod tests {
         // 1. Log and BridgeAction returned from `get_test_log_and_action` are not in sync
         // 2. Log returned from `get_test_log_and_action` is not parseable log (not abigen!, check abi.rs)
 
-        let (_sui_events_tx, sui_events_rx, eth_events_tx, eth_events_rx, sui_client, store) =
-            setup();
+        let (
+            _sui_events_tx,
+            sui_events_rx,
+            eth_events_tx,
+            eth_events_rx,
+            sui_client,
+            store,
+            transfer_indexer,
+        ) = setup();
         let (executor, mut executor_requested_action_rx) = MockExecutor::new();
         // start orchestrator
         let _handles = BridgeOrchestrator::new(
@@ -271,6 +312,7 @@ mod tests {
             sui_events_rx,
             eth_events_rx,
             store.clone(),
+            transfer_indexer.clone(),
         )
         .run(executor);
         let address = EthAddress::random();
@@ -312,6 +354,15 @@ mod tests {
                 store.get_eth_event_cursors(&[address]).unwrap()[0].unwrap(),
                 end_block_num,
             );
+            // The deposit is indexed before the action is written to WAL
+            assert_eq!(
+                transfer_indexer
+                    .get_transfer(&bridge_action.digest())
+                    .unwrap()
+                    .unwrap()
+                    .status,
+                BridgeTransferStatus::Deposited
+            );
             break;
         }
     }
@@ -324,6 +375,7 @@ mod tests {
         mysten_metrics::metered_channel::Receiver<(EthAddress, u64, Vec<EthLog>)>,
         SuiClient<SuiMockClient>,
         Arc<BridgeOrchestratorTables>,
+        Arc<BridgeTransferIndexer>,
     ) {
         telemetry_subscribers::init_for_testing();
         let registry = Registry::new();
@@ -334,6 +386,9 @@ mod tests {
 
         let temp_dir = tempfile::tempdir().unwrap();
         let store = BridgeOrchestratorTables::new(temp_dir.path());
+        let transfer_indexer = Arc::new(BridgeTransferIndexer::new(BridgeTransferTables::new(
+            &temp_dir.path().join("transfers"),
+        )));
 
         let mock_client = SuiMockClient::default();
         let sui_client = SuiClient::new_for_testing(mock_client.clone());
@@ -361,6 +416,7 @@ mod tests {
             eth_events_rx,
             sui_client,
             store,
+            transfer_indexer,
         )
     }
 
@@ -7,10 +7,12 @@ use crate::{
     crypto::BridgeAuthorityPublicKeyBytes,
     error::BridgeError,
     server::handler::{BridgeRequestHandler, BridgeRequestHandlerTrait},
+    transfer_indexer::{BridgeTransferIndexer, BridgeTransferRecord},
     types::{
         AssetPriceUpdateAction, BlocklistCommitteeAction, BlocklistType, BridgeAction,
-        BridgeChainId, EmergencyAction, EmergencyActionType, EvmContractUpgradeAction,
-        LimitUpdateAction, SignedBridgeAction, TokenId,
+        BridgeActionDigest, BridgeChainId, EmergencyAction, EmergencyActionType,
+        EthTransactionHash, EvmContractUpgradeAction, LimitUpdateAction, SignedBridgeAction,
+        TokenId,
     },
 };
 use axum::{
@@ -24,7 +26,9 @@ use fastcrypto::{
     traits::ToFromBytes,
 };
 use std::net::SocketAddr;
+use std::str::FromStr;
 use std::sync::Arc;
+use sui_types::digests::TransactionDigest;
 
 pub mod governance_verifier;
 pub mod handler;
@@ -49,9 +53,23 @@ pub const EVM_CONTRACT_UPGRADE_PATH_WITH_CALLDATA: &str =
 pub const EVM_CONTRACT_UPGRADE_PATH: &str =
     "/sign/upgrade_evm_contract/:chain_id/:nonce/:proxy_address/:new_impl_address";
 
-pub async fn run_server(socket_address: &SocketAddr, handler: BridgeRequestHandler) {
+pub const TRANSFER_STATUS_PATH: &str = "/bridge_tx/status/digest/:action_digest";
+pub const SUI_DEPOSIT_TRANSFER_STATUS_PATH: &str = "/bridge_tx/status/sui/:tx_digest/:event_index";
+pub const ETH_DEPOSIT_TRANSFER_STATUS_PATH: &str = "/bridge_tx/status/eth/:tx_hash/:event_index";
+
+/// Runs the bridge server. Transfer status routes are only served when
+/// `transfer_indexer` is provided, i.e. when the node also runs the client.
+pub async fn run_server(
+    socket_address: &SocketAddr,
+    handler: BridgeRequestHandler,
+    transfer_indexer: Option<Arc<BridgeTransferIndexer>>,
+) {
+    let mut router = make_router(Arc::new(handler));
+    if let Some(transfer_indexer) = transfer_indexer {
+        router = router.merge(make_transfer_status_router(transfer_indexer));
+    }
     axum::Server::bind(socket_address)
-        .serve(make_router(Arc::new(handler)).into_make_service())
+        .serve(router.into_make_service())
         .await
         .unwrap();
 }
@@ -81,9 +99,26 @@ pub(crate) fn make_router(
         .with_state(handler)
 }
 
+pub(crate) fn make_transfer_status_router(transfer_indexer: Arc<BridgeTransferIndexer>) -> Router {
+    Router::new()
+        .route(TRANSFER_STATUS_PATH, get(handle_transfer_status))
+        .route(
+            SUI_DEPOSIT_TRANSFER_STATUS_PATH,
+            get(handle_sui_deposit_transfer_status),
+        )
+        .route(
+            ETH_DEPOSIT_TRANSFER_STATUS_PATH,
+            get(handle_eth_deposit_transfer_status),
+        )
+        .with_state(transfer_indexer)
+}
+
 impl axum::response::IntoResponse for BridgeError {
     // TODO: distinguish client error.
     fn into_response(self) -> axum::response::Response {
+        if self == BridgeError::BridgeTransferNotFound {
+            return (StatusCode::NOT_FOUND, "Bridge transfer not found").into_response();
+        }
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Something went wrong: {:?}", self),
@@ -262,6 +297,43 @@ async fn handle_evm_contract_upgrade(
     Ok(sig)
 }
 
+async fn handle_transfer_status(
+    Path(action_digest): Path<String>,
+    State(transfer_indexer): State<Arc<BridgeTransferIndexer>>,
+) -> Result<Json<BridgeTransferRecord>, BridgeError> {
+    let action_digest = BridgeActionDigest::from_str(&action_digest).map_err(|e| {
+        BridgeError::InvalidBridgeClientRequest(format!("Invalid action digest: {:?}", e))
+    })?;
+    transfer_indexer
+        .get_transfer(&action_digest)?
+        .map(Json)
+        .ok_or(BridgeError::BridgeTransferNotFound)
+}
+
+async fn handle_sui_deposit_transfer_status(
+    Path((tx_digest_base58, event_idx)): Path<(String, u16)>,
+    State(transfer_indexer): State<Arc<BridgeTransferIndexer>>,
+) -> Result<Json<BridgeTransferRecord>, BridgeError> {
+    let tx_digest =
+        TransactionDigest::from_str(&tx_digest_base58).map_err(|_e| BridgeError::InvalidTxHash)?;
+    transfer_indexer
+        .get_transfer_by_sui_deposit(tx_digest, event_idx)?
+        .map(Json)
+        .ok_or(BridgeError::BridgeTransferNotFound)
+}
+
+async fn handle_eth_deposit_transfer_status(
+    Path((tx_hash_hex, event_idx)): Path<(String, u16)>,
+    State(transfer_indexer): State<Arc<BridgeTransferIndexer>>,
+) -> Result<Json<BridgeTransferRecord>, BridgeError> {
+    let tx_hash =
+        EthTransactionHash::from_str(&tx_hash_hex).map_err(|_| BridgeError::InvalidTxHash)?;
+    transfer_indexer
+        .get_transfer_by_eth_deposit(tx_hash, event_idx)?
+        .map(Json)
+        .ok_or(BridgeError::BridgeTransferNotFound)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -349,6 +421,68 @@ mod tests {
         client.request_sign_bridge_action(action).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_bridge_server_handle_transfer_status_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let transfer_indexer = Arc::new(BridgeTransferIndexer::new(
+            crate::storage::BridgeTransferTables::new(temp_dir.path()),
+        ));
+        let identifier = sui_types::Identifier::from_str("bridge").unwrap();
+        let (sui_event, action) = crate::events::tests::get_test_sui_event_and_action(identifier);
+        let bridge_event = crate::events::SuiBridgeEvent::try_from_sui_event(&sui_event)
+            .unwrap()
+            .unwrap();
+        transfer_indexer
+            .index_sui_event(&sui_event, &bridge_event)
+            .unwrap();
+
+        let localhost = sui_config::local_ip_utils::localhost_for_testing();
+        let port = sui_config::local_ip_utils::get_available_port(&localhost);
+        let socket_address = SocketAddr::new(
+            std::net::IpAddr::V4(std::net::Ipv4Addr::new(127, 0, 0, 1)),
+            port,
+        );
+        let server = axum::Server::bind(&socket_address)
+            .serve(make_transfer_status_router(transfer_indexer).into_make_service());
+        let _handle = tokio::spawn(async move { server.await.unwrap() });
+
+        let base_url = format!("http://127.0.0.1:{}", port);
+        let record: BridgeTransferRecord = reqwest::get(format!(
+            "{}/bridge_tx/status/digest/{}",
+            base_url,
+            serde_json::to_value(action.digest())
+                .unwrap()
+                .as_str()
+                .unwrap()
+        ))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+        assert_eq!(record.action, action);
+
+        let record: BridgeTransferRecord = reqwest::get(format!(
+            "{}/bridge_tx/status/sui/{}/{}",
+            base_url, sui_event.id.tx_digest, sui_event.id.event_seq
+        ))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+        assert_eq!(record.action, action);
+
+        let response = reqwest::get(format!(
+            "{}/bridge_tx/status/eth/{:?}/0",
+            base_url,
+            EthTransactionHash::random()
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
     fn setup() -> BridgeClient {
         let mock = BridgeRequestMockHandler::new();
         let (_handles, authorities, mut secrets) =
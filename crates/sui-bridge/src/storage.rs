@@ -14,7 +14,9 @@ use typed_store::Map;
 use typed_store_derive::DBMapUtils;
 
 use crate::error::{BridgeError, BridgeResult};
-use crate::types::{BridgeAction, BridgeActionDigest};
+use crate::transfer_indexer::{BridgeTransferProgress, BridgeTransferRecord, BridgeTxLocation};
+use crate::types::{BridgeAction, BridgeActionDigest, BridgeChainId, EthTransactionHash};
+use sui_types::digests::TransactionDigest;
 
 #[derive(DBMapUtils)]
 pub struct BridgeOrchestratorTables {
@@ -135,6 +137,151 @@ impl BridgeOrchestratorTables {
     }
 }
 
+#[derive(DBMapUtils)]
+pub struct BridgeTransferTables {
+    /// token transfer records keyed by the digest of the deposit BridgeAction
+    pub(crate) transfers: DBMap<BridgeActionDigest, BridgeTransferRecord>,
+    /// (source chain, nonce) of a token transfer to its BridgeAction digest
+    pub(crate) transfer_keys: DBMap<(BridgeChainId, u64), BridgeActionDigest>,
+    /// approvals and claims that were observed before the corresponding deposit
+    pub(crate) unmatched_progress: DBMap<(BridgeChainId, u64), BridgeTransferProgress>,
+    /// Sui deposit (tx digest, event index) to its BridgeAction digest
+    pub(crate) sui_deposit_index: DBMap<(TransactionDigest, u16), BridgeActionDigest>,
+    /// Eth deposit (tx hash, log index) to its BridgeAction digest
+    pub(crate) eth_deposit_index: DBMap<(EthTransactionHash, u16), BridgeActionDigest>,
+}
+
+impl BridgeTransferTables {
+    pub fn new(path: &Path) -> Arc<Self> {
+        Arc::new(Self::open_tables_read_write(
+            path.to_path_buf(),
+            MetricConf::new("bridge_transfers"),
+            None,
+            None,
+        ))
+    }
+
+    /// Writes `record` and its lookup indexes, and drops any unmatched
+    /// progress for the same transfer since it is now folded into the record.
+    pub(crate) fn upsert_transfer(&self, record: &BridgeTransferRecord) -> BridgeResult<()> {
+        let key = record.transfer_key();
+        let mut batch = self.transfers.batch();
+        batch
+            .insert_batch(&self.transfers, [(record.action_digest, record)])
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't insert into transfers: {:?}", e))
+            })?;
+        batch
+            .insert_batch(&self.transfer_keys, [(key, record.action_digest)])
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't insert into transfer_keys: {:?}", e))
+            })?;
+        match &record.deposit {
+            BridgeTxLocation::Sui {
+                tx_digest,
+                event_index,
+                ..
+            } => batch
+                .insert_batch(
+                    &self.sui_deposit_index,
+                    [((*tx_digest, *event_index), record.action_digest)],
+                )
+                .map_err(|e| {
+                    BridgeError::StorageError(format!(
+                        "Couldn't insert into sui_deposit_index: {:?}",
+                        e
+                    ))
+                })?,
+            BridgeTxLocation::Eth {
+                tx_hash, log_index, ..
+            } => batch
+                .insert_batch(
+                    &self.eth_deposit_index,
+                    [((*tx_hash, *log_index), record.action_digest)],
+                )
+                .map_err(|e| {
+                    BridgeError::StorageError(format!(
+                        "Couldn't insert into eth_deposit_index: {:?}",
+                        e
+                    ))
+                })?,
+        };
+        batch
+            .delete_batch(&self.unmatched_progress, [key])
+            .map_err(|e| {
+                BridgeError::StorageError(format!(
+                    "Couldn't delete from unmatched_progress: {:?}",
+                    e
+                ))
+            })?;
+        batch
+            .write()
+            .map_err(|e| BridgeError::StorageError(format!("Couldn't write batch: {:?}", e)))
+    }
+
+    pub(crate) fn insert_unmatched_progress(
+        &self,
+        key: (BridgeChainId, u64),
+        progress: &BridgeTransferProgress,
+    ) -> BridgeResult<()> {
+        self.unmatched_progress.insert(&key, progress).map_err(|e| {
+            BridgeError::StorageError(format!("Couldn't insert into unmatched_progress: {:?}", e))
+        })
+    }
+
+    pub(crate) fn get_unmatched_progress(
+        &self,
+        key: (BridgeChainId, u64),
+    ) -> BridgeResult<Option<BridgeTransferProgress>> {
+        self.unmatched_progress.get(&key).map_err(|e| {
+            BridgeError::StorageError(format!("Couldn't get unmatched_progress: {:?}", e))
+        })
+    }
+
+    pub fn get_transfer(
+        &self,
+        action_digest: &BridgeActionDigest,
+    ) -> BridgeResult<Option<BridgeTransferRecord>> {
+        self.transfers
+            .get(action_digest)
+            .map_err(|e| BridgeError::StorageError(format!("Couldn't get transfers: {:?}", e)))
+    }
+
+    pub fn get_transfer_digest_by_key(
+        &self,
+        source_chain: BridgeChainId,
+        nonce: u64,
+    ) -> BridgeResult<Option<BridgeActionDigest>> {
+        self.transfer_keys
+            .get(&(source_chain, nonce))
+            .map_err(|e| BridgeError::StorageError(format!("Couldn't get transfer_keys: {:?}", e)))
+    }
+
+    pub fn get_transfer_digest_by_sui_deposit(
+        &self,
+        tx_digest: TransactionDigest,
+        event_index: u16,
+    ) -> BridgeResult<Option<BridgeActionDigest>> {
+        self.sui_deposit_index
+            .get(&(tx_digest, event_index))
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't get sui_deposit_index: {:?}", e))
+            })
+    }
+
+    pub fn get_transfer_digest_by_eth_deposit(
+        &self,
+        tx_hash: EthTransactionHash,
+        log_index: u16,
+    ) -> BridgeResult<Option<BridgeActionDigest>> {
+        self.eth_deposit_index
+            .get(&(tx_hash, log_index))
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't get eth_deposit_index: {:?}", e))
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
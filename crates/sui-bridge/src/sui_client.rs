@@ -227,6 +227,15 @@ where
             .get_gas_data_panic_if_not_gas(gas_object_id)
             .await
     }
+
+    /// Like `get_gas_data_panic_if_not_gas`, but returns an error instead of retrying or
+    /// panicking, for gas objects supplied by users.
+    pub async fn get_gas_data(
+        &self,
+        gas_object_id: ObjectID,
+    ) -> BridgeResult<(GasCoin, ObjectRef, Owner)> {
+        self.inner.get_gas_data(gas_object_id).await
+    }
 }
 
 /// Use a trait to abstract over the SuiSDKClient and SuiMockClient for testing.
@@ -264,6 +273,11 @@ pub trait SuiClientInner: Send + Sync {
         &self,
         gas_object_id: ObjectID,
     ) -> (GasCoin, ObjectRef, Owner);
+
+    async fn get_gas_data(
+        &self,
+        gas_object_id: ObjectID,
+    ) -> Result<(GasCoin, ObjectRef, Owner), BridgeError>;
 }
 
 #[async_trait]
@@ -409,6 +423,31 @@ impl SuiClientInner for SuiSdkClient {
             }
         }
     }
+
+    async fn get_gas_data(
+        &self,
+        gas_object_id: ObjectID,
+    ) -> Result<(GasCoin, ObjectRef, Owner), BridgeError> {
+        let gas_obj = self
+            .read_api()
+            .get_object_with_options(
+                gas_object_id,
+                SuiObjectDataOptions::default().with_owner().with_content(),
+            )
+            .await
+            .map_err(|e| {
+                BridgeError::Generic(format!("Can't get gas object {:?}: {:?}", gas_object_id, e))
+            })?
+            .data
+            .ok_or_else(|| {
+                BridgeError::Generic(format!("Gas object {:?} not found", gas_object_id))
+            })?;
+        let owner = gas_obj.owner.expect("Owner is requested");
+        let gas_coin = GasCoin::try_from(&gas_obj).map_err(|err| {
+            BridgeError::Generic(format!("{} is not a gas coin: {err}", gas_object_id))
+        })?;
+        Ok((gas_coin, gas_obj.object_ref(), owner))
+    }
 }
 
 #[cfg(test)]
@@ -229,4 +229,18 @@ impl SuiClientInner for SuiMockClient {
                 )
             })
     }
+
+    async fn get_gas_data(
+        &self,
+        gas_object_id: ObjectID,
+    ) -> Result<(GasCoin, ObjectRef, Owner), BridgeError> {
+        self.get_object_info
+            .lock()
+            .unwrap()
+            .get(&gas_object_id)
+            .cloned()
+            .ok_or_else(|| {
+                BridgeError::Generic(format!("Gas object {:?} not found", gas_object_id))
+            })
+    }
 }
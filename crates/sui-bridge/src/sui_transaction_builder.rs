@@ -96,8 +96,7 @@ pub fn build_transaction(
             unimplemented!()
         }
         BridgeAction::EmergencyAction(_) => {
-            // TODO: handle this case
-            unimplemented!()
+            build_emergency_op_approve_transaction(client_address, gas_object_ref, action)
         }
         BridgeAction::LimitUpdateAction(_) => {
             // TODO: handle this case
@@ -230,3 +229,63 @@ fn build_token_bridge_approve_transaction(
         1500,
     ))
 }
+
+// TODO: pass in gas price
+fn build_emergency_op_approve_transaction(
+    client_address: SuiAddress,
+    gas_object_ref: &ObjectRef,
+    action: VerifiedCertifiedBridgeAction,
+) -> BridgeResult<TransactionData> {
+    let (bridge_action, sigs) = action.into_inner().into_data_and_sig();
+    let mut builder = ProgrammableTransactionBuilder::new();
+
+    let (source_chain, seq_num, action_type) = match bridge_action {
+        BridgeAction::EmergencyAction(a) => (a.chain_id, a.nonce, a.action_type),
+        _ => unreachable!(),
+    };
+
+    let source_chain = builder.pure(source_chain as u8).unwrap();
+    let seq_num = builder.pure(seq_num).unwrap();
+    let op_type = builder.pure(action_type as u8).unwrap();
+
+    let arg_msg = builder.programmable_move_call(
+        *get_bridge_package_id(),
+        ident_str!("message").to_owned(),
+        ident_str!("create_emergency_op_message").to_owned(),
+        vec![],
+        vec![source_chain, seq_num, op_type],
+    );
+
+    // Unwrap: this should not fail
+    let arg_bridge = builder.obj(*get_root_bridge_object_arg()).unwrap();
+
+    let mut sig_bytes = vec![];
+    for (_, sig) in sigs.signatures {
+        sig_bytes.push(sig.as_bytes().to_vec());
+    }
+    let arg_signatures = builder.pure(sig_bytes.clone()).map_err(|e| {
+        BridgeError::BridgeSerializationError(format!(
+            "Failed to serialize signatures: {:?}. Err: {:?}",
+            sig_bytes, e
+        ))
+    })?;
+
+    builder.programmable_move_call(
+        *get_bridge_package_id(),
+        ident_str!("bridge").to_owned(),
+        ident_str!("execute_emergency_op").to_owned(),
+        vec![],
+        vec![arg_bridge, arg_msg, arg_signatures],
+    );
+
+    let pt = builder.finish();
+
+    Ok(TransactionData::new_programmable(
+        client_address,
+        vec![*gas_object_ref],
+        pt,
+        15_000_000,
+        // TODO: use reference gas price
+        1500,
+    ))
+}
@@ -246,6 +246,49 @@ pub fn get_test_log_and_action(
     (log, bridge_action)
 }
 
+/// Returns a `TokensClaimed` log for the transfer `(source_chain_id, nonce)`
+/// claimed on `destination_chain_id`.
+pub fn get_test_eth_claim_log(
+    contract_address: EthAddress,
+    tx_hash: TxHash,
+    source_chain_id: BridgeChainId,
+    nonce: u64,
+    destination_chain_id: BridgeChainId,
+) -> Log {
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::Uint(ethers::types::U256::from(TokenId::Sui as u8)),
+        ethers::abi::Token::Uint(ethers::types::U256::from(10000000u64)),
+        ethers::abi::Token::Bytes(SuiAddress::random_for_testing_only().to_vec()),
+        ethers::abi::Token::Address(EthAddress::random()),
+    ]);
+    Log {
+        address: contract_address,
+        topics: vec![
+            long_signature(
+                "TokensClaimed",
+                &[
+                    ParamType::Uint(8),
+                    ParamType::Uint(64),
+                    ParamType::Uint(8),
+                    ParamType::Uint(8),
+                    ParamType::Uint(256),
+                    ParamType::Bytes,
+                    ParamType::Address,
+                ],
+            ),
+            ethers::types::H256::from_low_u64_be(source_chain_id as u64),
+            ethers::types::H256::from_low_u64_be(nonce),
+            ethers::types::H256::from_low_u64_be(destination_chain_id as u64),
+        ],
+        data: encoded.into(),
+        block_hash: Some(TxHash::random()),
+        block_number: Some(2.into()),
+        transaction_hash: Some(tx_hash),
+        log_index: Some(0.into()),
+        ..Default::default()
+    }
+}
+
 pub async fn publish_bridge_package(context: &WalletContext) -> BTreeMap<TokenId, ObjectRef> {
     let (sender, gas_object) = context.get_one_gas_object().await.unwrap().unwrap();
     let gas_price = context.get_reference_gas_price().await.unwrap();
@@ -432,5 +475,6 @@ pub async fn bridge_token(
     assert_eq!(bridge_events.len(), 1);
     match bridge_events.remove(0) {
         SuiBridgeEvent::SuiToEthTokenBridgeV1(event) => event,
+        other => panic!("Expected SuiToEthTokenBridgeV1 event, got {:?}", other),
     }
 }
@@ -3,13 +3,29 @@
 
 use anyhow::anyhow;
 use clap::*;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address as EthAddress;
 use fastcrypto::ed25519::Ed25519KeyPair;
+use fastcrypto::encoding::{Encoding, Hex};
 use fastcrypto::secp256k1::Secp256k1KeyPair;
-use fastcrypto::traits::EncodeDecodeBase64;
+use fastcrypto::traits::{EncodeDecodeBase64, KeyPair, ToFromBytes};
 use std::path::PathBuf;
-use sui_bridge::config::BridgeNodeConfig;
+use std::sync::Arc;
+use sui_bridge::client::bridge_authority_aggregator::BridgeAuthorityAggregator;
+use sui_bridge::config::{read_bridge_client_key, BridgeNodeConfig};
 use sui_bridge::crypto::BridgeAuthorityKeyPair;
 use sui_bridge::crypto::BridgeAuthorityPublicKeyBytes;
+use sui_bridge::governance::{
+    certified_stake, collect_governance_action_signatures, is_sui_governance_action,
+    read_governance_action_file, submit_governance_action_to_eth, submit_governance_action_to_sui,
+    write_governance_action_file,
+};
+use sui_bridge::sui_client::SuiClient;
+use sui_bridge::types::{
+    AssetPriceUpdateAction, BlocklistCommitteeAction, BlocklistType, BridgeAction, BridgeChainId,
+    EmergencyAction, EmergencyActionType, EvmContractUpgradeAction, LimitUpdateAction, TokenId,
+};
 use sui_config::Config;
 use sui_types::base_types::ObjectID;
 use sui_types::base_types::SuiAddress;
@@ -40,6 +56,101 @@ pub enum BridgeValidatorCommand {
         #[clap(name = "run-client", long)]
         run_client: bool,
     },
+    /// Write a governance action to a file. Committee members add its content
+    /// to `approved_governance_actions` in their bridge node config.
+    #[clap(name = "create-governance-action")]
+    CreateGovernanceAction {
+        path: PathBuf,
+        #[clap(subcommand)]
+        action: GovernanceActionCommand,
+    },
+    /// Collect committee signatures for a governance action file and submit
+    /// the certified action to the chain that executes it.
+    #[clap(name = "execute-governance-action")]
+    ExecuteGovernanceAction {
+        path: PathBuf,
+        #[clap(name = "sui-rpc-url", long)]
+        sui_rpc_url: String,
+        /// Only collect signatures and report the stake, do not submit
+        #[clap(name = "dry-run", long)]
+        dry_run: bool,
+        /// Key to sign the Sui transaction with, required for actions on Sui
+        #[clap(name = "sui-key-path", long)]
+        sui_key_path: Option<PathBuf>,
+        #[clap(name = "sui-gas-object", long)]
+        sui_gas_object: Option<ObjectID>,
+        #[clap(name = "eth-rpc-url", long)]
+        eth_rpc_url: Option<String>,
+        /// Secp256k1 key to sign the Ethereum transaction with, required for actions on Ethereum
+        #[clap(name = "eth-key-path", long)]
+        eth_key_path: Option<PathBuf>,
+        #[clap(name = "eth-bridge-proxy-address", long)]
+        eth_bridge_proxy_address: Option<EthAddress>,
+    },
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum GovernanceActionCommand {
+    #[clap(name = "emergency-button")]
+    EmergencyButton {
+        #[clap(name = "chain-id", long)]
+        chain_id: u8,
+        #[clap(name = "nonce", long)]
+        nonce: u64,
+        /// Unpause the bridge instead of pausing it
+        #[clap(name = "unpause", long)]
+        unpause: bool,
+    },
+    #[clap(name = "update-committee-blocklist")]
+    UpdateCommitteeBlocklist {
+        #[clap(name = "chain-id", long)]
+        chain_id: u8,
+        #[clap(name = "nonce", long)]
+        nonce: u64,
+        /// Remove the members from the blocklist instead of adding them
+        #[clap(name = "unblocklist", long)]
+        unblocklist: bool,
+        /// Hex encoded bridge authority public keys
+        #[clap(name = "pubkey", long)]
+        pubkeys: Vec<String>,
+    },
+    #[clap(name = "update-limit")]
+    UpdateLimit {
+        #[clap(name = "chain-id", long)]
+        chain_id: u8,
+        #[clap(name = "nonce", long)]
+        nonce: u64,
+        #[clap(name = "sending-chain-id", long)]
+        sending_chain_id: u8,
+        #[clap(name = "new-usd-limit", long)]
+        new_usd_limit: u64,
+    },
+    #[clap(name = "update-asset-price")]
+    UpdateAssetPrice {
+        #[clap(name = "chain-id", long)]
+        chain_id: u8,
+        #[clap(name = "nonce", long)]
+        nonce: u64,
+        #[clap(name = "token-id", long)]
+        token_id: u8,
+        #[clap(name = "new-usd-price", long)]
+        new_usd_price: u64,
+    },
+    #[clap(name = "upgrade-evm-contract")]
+    UpgradeEvmContract {
+        #[clap(name = "chain-id", long)]
+        chain_id: u8,
+        #[clap(name = "nonce", long)]
+        nonce: u64,
+        #[clap(name = "proxy-address", long)]
+        proxy_address: EthAddress,
+        #[clap(name = "new-impl-address", long)]
+        new_impl_address: EthAddress,
+        /// Hex encoded call data for the new implementation
+        #[clap(name = "call-data", long)]
+        call_data: Option<String>,
+    },
 }
 
 #[tokio::main]
@@ -61,6 +172,94 @@ async fn main() -> anyhow::Result<()> {
                 path.display()
             );
         }
+        BridgeValidatorCommand::CreateGovernanceAction { path, action } => {
+            let action = make_governance_action(action)?;
+            write_governance_action_file(&path, &action)?;
+            println!(
+                "Governance action {:?} written to {}",
+                action.digest(),
+                path.display()
+            );
+        }
+        BridgeValidatorCommand::ExecuteGovernanceAction {
+            path,
+            sui_rpc_url,
+            dry_run,
+            sui_key_path,
+            sui_gas_object,
+            eth_rpc_url,
+            eth_key_path,
+            eth_bridge_proxy_address,
+        } => {
+            let action = read_governance_action_file(&path)?;
+            let sui_client = SuiClient::new(&sui_rpc_url).await?;
+            let committee = Arc::new(
+                sui_client
+                    .get_bridge_committee()
+                    .await
+                    .map_err(|e| anyhow!("Failed to get bridge committee: {:?}", e))?,
+            );
+            let auth_agg = BridgeAuthorityAggregator::new(committee.clone());
+            let certified_action = collect_governance_action_signatures(&auth_agg, action.clone())
+                .await
+                .map_err(|e| anyhow!("Failed to collect signatures: {:?}", e))?;
+            println!(
+                "Collected signatures from {} authorities with total stake {} (required: {})",
+                certified_action.auth_sig().signatures.len(),
+                certified_stake(&committee, &certified_action),
+                action.approval_threshold(),
+            );
+            if dry_run {
+                return Ok(());
+            }
+
+            if is_sui_governance_action(&action) {
+                let sui_key_path = sui_key_path
+                    .ok_or_else(|| anyhow!("--sui-key-path is required for actions on Sui"))?;
+                let sui_gas_object = sui_gas_object
+                    .ok_or_else(|| anyhow!("--sui-gas-object is required for actions on Sui"))?;
+                let sui_key = read_bridge_client_key(&sui_key_path)?;
+                let response = submit_governance_action_to_sui(
+                    &sui_client,
+                    &sui_key,
+                    sui_gas_object,
+                    certified_action,
+                )
+                .await?;
+                println!(
+                    "Sui transaction {} executed with status: {:?}",
+                    response.digest,
+                    response.effects.map(|effects| effects.status().clone())
+                );
+            } else {
+                let eth_rpc_url = eth_rpc_url
+                    .ok_or_else(|| anyhow!("--eth-rpc-url is required for actions on Ethereum"))?;
+                let eth_key_path = eth_key_path
+                    .ok_or_else(|| anyhow!("--eth-key-path is required for actions on Ethereum"))?;
+                let eth_bridge_proxy_address = eth_bridge_proxy_address.ok_or_else(|| {
+                    anyhow!("--eth-bridge-proxy-address is required for actions on Ethereum")
+                })?;
+                let SuiKeyPair::Secp256k1(eth_key) = read_bridge_client_key(&eth_key_path)? else {
+                    return Err(anyhow!("--eth-key-path must point to a secp256k1 key"));
+                };
+                let provider = Provider::<Http>::try_from(eth_rpc_url)?;
+                let chain_id = provider.get_chainid().await?;
+                let wallet = LocalWallet::from_bytes(eth_key.copy().private().as_bytes())?
+                    .with_chain_id(chain_id.as_u64());
+                let eth_client =
+                    Arc::new(ethers::middleware::SignerMiddleware::new(provider, wallet));
+                let receipt = submit_governance_action_to_eth(
+                    eth_client,
+                    eth_bridge_proxy_address,
+                    certified_action,
+                )
+                .await?;
+                println!(
+                    "Ethereum transaction {:?} executed with status: {:?}",
+                    receipt.transaction_hash, receipt.status
+                );
+            }
+        }
     }
 
     Ok(())
@@ -138,3 +337,91 @@ fn generate_bridge_node_config_and_write_to_file(
     }
     config.save(path)
 }
+
+fn make_governance_action(command: GovernanceActionCommand) -> anyhow::Result<BridgeAction> {
+    let parse_chain_id = |chain_id: u8| {
+        BridgeChainId::try_from(chain_id).map_err(|e| anyhow!("Invalid chain id: {:?}", e))
+    };
+    let action = match command {
+        GovernanceActionCommand::EmergencyButton {
+            chain_id,
+            nonce,
+            unpause,
+        } => BridgeAction::EmergencyAction(EmergencyAction {
+            nonce,
+            chain_id: parse_chain_id(chain_id)?,
+            action_type: if unpause {
+                EmergencyActionType::Unpause
+            } else {
+                EmergencyActionType::Pause
+            },
+        }),
+        GovernanceActionCommand::UpdateCommitteeBlocklist {
+            chain_id,
+            nonce,
+            unblocklist,
+            pubkeys,
+        } => {
+            let blocklisted_members = pubkeys
+                .iter()
+                .map(|key| {
+                    let bytes = Hex::decode(key).map_err(|e| anyhow!("{:?}", e))?;
+                    BridgeAuthorityPublicKeyBytes::from_bytes(&bytes)
+                        .map_err(|e| anyhow!("Invalid bridge authority key {}: {:?}", key, e))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            BridgeAction::BlocklistCommitteeAction(BlocklistCommitteeAction {
+                nonce,
+                chain_id: parse_chain_id(chain_id)?,
+                blocklist_type: if unblocklist {
+                    BlocklistType::Unblocklist
+                } else {
+                    BlocklistType::Blocklist
+                },
+                blocklisted_members,
+            })
+        }
+        GovernanceActionCommand::UpdateLimit {
+            chain_id,
+            nonce,
+            sending_chain_id,
+            new_usd_limit,
+        } => BridgeAction::LimitUpdateAction(LimitUpdateAction {
+            nonce,
+            chain_id: parse_chain_id(chain_id)?,
+            sending_chain_id: parse_chain_id(sending_chain_id)?,
+            new_usd_limit,
+        }),
+        GovernanceActionCommand::UpdateAssetPrice {
+            chain_id,
+            nonce,
+            token_id,
+            new_usd_price,
+        } => BridgeAction::AssetPriceUpdateAction(AssetPriceUpdateAction {
+            nonce,
+            chain_id: parse_chain_id(chain_id)?,
+            token_id: TokenId::try_from(token_id)
+                .map_err(|e| anyhow!("Invalid token id: {:?}", e))?,
+            new_usd_price,
+        }),
+        GovernanceActionCommand::UpgradeEvmContract {
+            chain_id,
+            nonce,
+            proxy_address,
+            new_impl_address,
+            call_data,
+        } => BridgeAction::EvmContractUpgradeAction(EvmContractUpgradeAction {
+            nonce,
+            chain_id: parse_chain_id(chain_id)?,
+            proxy_address,
+            new_impl_address,
+            call_data: match call_data {
+                Some(call_data) => {
+                    Hex::decode(&call_data).map_err(|e| anyhow!("Invalid call data: {:?}", e))?
+                }
+                None => vec![],
+            },
+        }),
+    };
+    Ok(action)
+}
@@ -0,0 +1,422 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! `BridgeTransferIndexer` keeps an audit record for every token transfer
+//! that goes through the bridge. It ingests Sui bridge events and Ethereum
+//! logs as they are handed to the orchestrator and correlates deposits with
+//! the approvals and claims that follow, keyed by `BridgeActionDigest`.
+//! Unlike the pending actions WAL, records are never removed.
+
+use crate::abi::{EthBridgeEvent, EthSuiBridgeEvents};
+use crate::error::BridgeResult;
+use crate::events::SuiBridgeEvent;
+use crate::storage::BridgeTransferTables;
+use crate::types::{BridgeAction, BridgeActionDigest, BridgeChainId, EthLog, EthTransactionHash};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use sui_json_rpc_types::SuiEvent;
+use sui_types::digests::TransactionDigest;
+use tracing::{error, info};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BridgeTransferStatus {
+    /// Tokens were deposited on the source chain
+    Deposited,
+    /// The committee approved the transfer on Sui
+    Approved,
+    /// Tokens were claimed on the destination chain
+    Claimed,
+}
+
+/// Where a step of a token transfer was observed on chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeTxLocation {
+    Sui {
+        tx_digest: TransactionDigest,
+        event_index: u16,
+        timestamp_ms: Option<u64>,
+    },
+    Eth {
+        tx_hash: EthTransactionHash,
+        log_index: u16,
+        block_number: u64,
+    },
+}
+
+impl BridgeTxLocation {
+    fn from_sui_event(event: &SuiEvent) -> Self {
+        BridgeTxLocation::Sui {
+            tx_digest: event.id.tx_digest,
+            event_index: event.id.event_seq as u16,
+            timestamp_ms: event.timestamp_ms,
+        }
+    }
+
+    fn from_eth_log(log: &EthLog) -> Self {
+        BridgeTxLocation::Eth {
+            tx_hash: log.tx_hash,
+            log_index: log.log_index_in_tx,
+            block_number: log.block_number,
+        }
+    }
+}
+
+/// Approvals and claims seen for a transfer whose deposit is not indexed yet.
+/// This happens when one chain's syncer is behind the other's.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeTransferProgress {
+    pub approval: Option<BridgeTxLocation>,
+    pub claim: Option<BridgeTxLocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeTransferRecord {
+    pub action_digest: BridgeActionDigest,
+    pub action: BridgeAction,
+    pub status: BridgeTransferStatus,
+    pub deposit: BridgeTxLocation,
+    pub approval: Option<BridgeTxLocation>,
+    pub claim: Option<BridgeTxLocation>,
+}
+
+impl BridgeTransferRecord {
+    /// (source chain, nonce) of the transfer, which is how approvals and
+    /// claims refer to it on chain.
+    pub fn transfer_key(&self) -> (BridgeChainId, u64) {
+        (self.action.chain_id(), self.action.seq_number())
+    }
+
+    fn apply_progress(&mut self, progress: BridgeTransferProgress) {
+        if self.approval.is_none() && progress.approval.is_some() {
+            self.approval = progress.approval;
+            self.status = self.status.max(BridgeTransferStatus::Approved);
+        }
+        if self.claim.is_none() && progress.claim.is_some() {
+            self.claim = progress.claim;
+            self.status = self.status.max(BridgeTransferStatus::Claimed);
+        }
+    }
+}
+
+pub struct BridgeTransferIndexer {
+    store: Arc<BridgeTransferTables>,
+}
+
+impl BridgeTransferIndexer {
+    pub fn new(store: Arc<BridgeTransferTables>) -> Self {
+        Self { store }
+    }
+
+    /// Index a Sui bridge event. Events are expected to be replayed after
+    /// restarts, so indexing the same event twice is a no-op.
+    pub fn index_sui_event(
+        &self,
+        sui_event: &SuiEvent,
+        bridge_event: &SuiBridgeEvent,
+    ) -> BridgeResult<()> {
+        let location = BridgeTxLocation::from_sui_event(sui_event);
+        match bridge_event {
+            SuiBridgeEvent::SuiToEthTokenBridgeV1(_) => {
+                match bridge_event
+                    .clone()
+                    .try_into_bridge_action(sui_event.id.tx_digest, sui_event.id.event_seq as u16)
+                {
+                    Some(action) => self.index_deposit(action, location),
+                    None => Ok(()),
+                }
+            }
+            SuiBridgeEvent::TokenTransferApproved(event) => self.index_progress(
+                (event.source_chain, event.nonce),
+                BridgeTransferProgress {
+                    approval: Some(location),
+                    claim: None,
+                },
+            ),
+            SuiBridgeEvent::TokenTransferClaimed(event) => self.index_progress(
+                (event.source_chain, event.nonce),
+                BridgeTransferProgress {
+                    approval: None,
+                    claim: Some(location),
+                },
+            ),
+        }
+    }
+
+    /// Index an Ethereum bridge log. Like `index_sui_event`, this is idempotent.
+    pub fn index_eth_event(&self, log: &EthLog, bridge_event: &EthBridgeEvent) -> BridgeResult<()> {
+        let location = BridgeTxLocation::from_eth_log(log);
+        match bridge_event {
+            EthBridgeEvent::EthSuiBridgeEvents(EthSuiBridgeEvents::TokensClaimedFilter(event)) => {
+                let Ok(source_chain) = BridgeChainId::try_from(event.source_chain_id) else {
+                    // Like unrecognized deposits, this means the contract and the code disagree.
+                    error!(
+                        tx_hash = ?log.tx_hash,
+                        ?event,
+                        "Unknown source chain in TokensClaimed log"
+                    );
+                    return Ok(());
+                };
+                self.index_progress(
+                    (source_chain, event.nonce),
+                    BridgeTransferProgress {
+                        approval: None,
+                        claim: Some(location),
+                    },
+                )
+            }
+            _ => match bridge_event
+                .clone()
+                .try_into_bridge_action(log.tx_hash, log.log_index_in_tx)
+            {
+                Some(action) => self.index_deposit(action, location),
+                None => Ok(()),
+            },
+        }
+    }
+
+    fn index_deposit(&self, action: BridgeAction, deposit: BridgeTxLocation) -> BridgeResult<()> {
+        let action_digest = action.digest();
+        if self.store.get_transfer(&action_digest)?.is_some() {
+            return Ok(());
+        }
+        let mut record = BridgeTransferRecord {
+            action_digest,
+            action,
+            status: BridgeTransferStatus::Deposited,
+            deposit,
+            approval: None,
+            claim: None,
+        };
+        if let Some(progress) = self.store.get_unmatched_progress(record.transfer_key())? {
+            record.apply_progress(progress);
+        }
+        info!(
+            ?action_digest,
+            status = ?record.status,
+            "Indexed bridge token transfer deposit"
+        );
+        self.store.upsert_transfer(&record)
+    }
+
+    fn index_progress(
+        &self,
+        key: (BridgeChainId, u64),
+        progress: BridgeTransferProgress,
+    ) -> BridgeResult<()> {
+        let record = match self.store.get_transfer_digest_by_key(key.0, key.1)? {
+            Some(digest) => self.store.get_transfer(&digest)?,
+            None => None,
+        };
+        match record {
+            Some(mut record) => {
+                record.apply_progress(progress);
+                info!(
+                    action_digest = ?record.action_digest,
+                    status = ?record.status,
+                    "Indexed bridge token transfer progress"
+                );
+                self.store.upsert_transfer(&record)
+            }
+            None => {
+                // The deposit is not indexed yet, keep the progress around until it is.
+                let mut unmatched = self.store.get_unmatched_progress(key)?.unwrap_or_default();
+                if unmatched.approval.is_none() {
+                    unmatched.approval = progress.approval;
+                }
+                if unmatched.claim.is_none() {
+                    unmatched.claim = progress.claim;
+                }
+                self.store.insert_unmatched_progress(key, &unmatched)
+            }
+        }
+    }
+
+    pub fn get_transfer(
+        &self,
+        action_digest: &BridgeActionDigest,
+    ) -> BridgeResult<Option<BridgeTransferRecord>> {
+        self.store.get_transfer(action_digest)
+    }
+
+    pub fn get_transfer_by_sui_deposit(
+        &self,
+        tx_digest: TransactionDigest,
+        event_index: u16,
+    ) -> BridgeResult<Option<BridgeTransferRecord>> {
+        match self
+            .store
+            .get_transfer_digest_by_sui_deposit(tx_digest, event_index)?
+        {
+            Some(digest) => self.store.get_transfer(&digest),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_transfer_by_eth_deposit(
+        &self,
+        tx_hash: EthTransactionHash,
+        log_index: u16,
+    ) -> BridgeResult<Option<BridgeTransferRecord>> {
+        match self
+            .store
+            .get_transfer_digest_by_eth_deposit(tx_hash, log_index)?
+        {
+            Some(digest) => self.store.get_transfer(&digest),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::tests::get_test_sui_event_and_action;
+    use crate::events::{TokenTransferApproved, TokenTransferClaimed};
+    use crate::test_utils::{get_test_eth_claim_log, get_test_log_and_action};
+    use ethers::types::{Address as EthAddress, TxHash};
+    use std::str::FromStr;
+    use sui_types::Identifier;
+
+    // async: existing runtime is required with typed-store
+    #[tokio::test]
+    async fn test_index_sui_to_eth_transfer() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let indexer = BridgeTransferIndexer::new(BridgeTransferTables::new(temp_dir.path()));
+        let identifier = Identifier::from_str("test_index_sui_to_eth_transfer").unwrap();
+        let (sui_event, action) = get_test_sui_event_and_action(identifier);
+        let bridge_event = SuiBridgeEvent::try_from_sui_event(&sui_event)
+            .unwrap()
+            .unwrap();
+        let digest = action.digest();
+
+        assert!(indexer.get_transfer(&digest).unwrap().is_none());
+        indexer.index_sui_event(&sui_event, &bridge_event).unwrap();
+
+        let record = indexer.get_transfer(&digest).unwrap().unwrap();
+        assert_eq!(record.action, action);
+        assert_eq!(record.status, BridgeTransferStatus::Deposited);
+        assert_eq!(record.deposit, BridgeTxLocation::from_sui_event(&sui_event));
+        assert_eq!(
+            indexer
+                .get_transfer_by_sui_deposit(sui_event.id.tx_digest, sui_event.id.event_seq as u16)
+                .unwrap()
+                .unwrap(),
+            record
+        );
+
+        // Committee approves the transfer on Sui
+        let approval_event = test_sui_event();
+        indexer
+            .index_sui_event(
+                &approval_event,
+                &SuiBridgeEvent::TokenTransferApproved(TokenTransferApproved {
+                    nonce: action.seq_number(),
+                    source_chain: action.chain_id(),
+                }),
+            )
+            .unwrap();
+        let record = indexer.get_transfer(&digest).unwrap().unwrap();
+        let approval = BridgeTxLocation::from_sui_event(&approval_event);
+        assert_eq!(record.status, BridgeTransferStatus::Approved);
+        assert_eq!(record.approval, Some(approval.clone()));
+        assert!(record.claim.is_none());
+
+        // Replaying the deposit event does not reset the record
+        indexer.index_sui_event(&sui_event, &bridge_event).unwrap();
+        let record = indexer.get_transfer(&digest).unwrap().unwrap();
+        assert_eq!(record.status, BridgeTransferStatus::Approved);
+        assert_eq!(record.approval, Some(approval.clone()));
+
+        // Tokens are claimed on Ethereum
+        let log = get_test_eth_claim_log(
+            EthAddress::repeat_byte(1),
+            TxHash::random(),
+            action.chain_id(),
+            action.seq_number(),
+            BridgeChainId::EthSepolia,
+        );
+        let eth_log = EthLog {
+            block_number: log.block_number.unwrap().as_u64(),
+            tx_hash: log.transaction_hash.unwrap(),
+            log_index_in_tx: 0,
+            log,
+        };
+        let claim_event = EthBridgeEvent::try_from_eth_log(&eth_log).unwrap();
+        assert!(claim_event
+            .clone()
+            .try_into_bridge_action(eth_log.tx_hash, eth_log.log_index_in_tx)
+            .is_none());
+        indexer.index_eth_event(&eth_log, &claim_event).unwrap();
+        let record = indexer.get_transfer(&digest).unwrap().unwrap();
+        assert_eq!(record.status, BridgeTransferStatus::Claimed);
+        assert_eq!(record.approval, Some(approval));
+        assert_eq!(record.claim, Some(BridgeTxLocation::from_eth_log(&eth_log)));
+    }
+
+    #[tokio::test]
+    async fn test_index_eth_to_sui_transfer_with_out_of_order_events() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let indexer = BridgeTransferIndexer::new(BridgeTransferTables::new(temp_dir.path()));
+        let (log, action) =
+            get_test_log_and_action(EthAddress::repeat_byte(1), TxHash::random(), 3);
+        let eth_log = EthLog {
+            block_number: log.block_number.unwrap().as_u64(),
+            tx_hash: log.transaction_hash.unwrap(),
+            log_index_in_tx: 3,
+            log,
+        };
+        let bridge_event = EthBridgeEvent::try_from_eth_log(&eth_log).unwrap();
+        let key = (action.chain_id(), action.seq_number());
+
+        // Approval and claim on Sui are observed before the Eth deposit
+        let approval_event = test_sui_event();
+        let claim_event = test_sui_event();
+        indexer
+            .index_sui_event(
+                &approval_event,
+                &SuiBridgeEvent::TokenTransferApproved(TokenTransferApproved {
+                    nonce: key.1,
+                    source_chain: key.0,
+                }),
+            )
+            .unwrap();
+        indexer
+            .index_sui_event(
+                &claim_event,
+                &SuiBridgeEvent::TokenTransferClaimed(TokenTransferClaimed {
+                    nonce: key.1,
+                    source_chain: key.0,
+                }),
+            )
+            .unwrap();
+        assert!(indexer.get_transfer(&action.digest()).unwrap().is_none());
+
+        indexer.index_eth_event(&eth_log, &bridge_event).unwrap();
+        let record = indexer
+            .get_transfer_by_eth_deposit(eth_log.tx_hash, eth_log.log_index_in_tx)
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.action_digest, action.digest());
+        assert_eq!(record.status, BridgeTransferStatus::Claimed);
+        assert_eq!(record.deposit, BridgeTxLocation::from_eth_log(&eth_log));
+        assert_eq!(
+            record.approval,
+            Some(BridgeTxLocation::from_sui_event(&approval_event))
+        );
+        assert_eq!(
+            record.claim,
+            Some(BridgeTxLocation::from_sui_event(&claim_event))
+        );
+        // unmatched progress is consumed once the deposit is indexed
+        assert!(indexer.store.get_unmatched_progress(key).unwrap().is_none());
+    }
+
+    // Only the event id and timestamp are used when indexing approvals and claims
+    fn test_sui_event() -> SuiEvent {
+        let identifier = Identifier::from_str("bridge").unwrap();
+        let (mut event, _) = get_test_sui_event_and_action(identifier);
+        event.id.tx_digest = TransactionDigest::random();
+        event.timestamp_ms = Some(1_700_000_000_000);
+        event
+    }
+}
@@ -12,6 +12,7 @@ use ethers::types::Address as EthAddress;
 use ethers::types::Log;
 use ethers::types::H256;
 pub use ethers::types::H256 as EthTransactionHash;
+use fastcrypto::encoding::{Base58, Encoding};
 use fastcrypto::hash::{HashFunction, Keccak256};
 use num_enum::TryFromPrimitive;
 use rand::seq::SliceRandom;
@@ -155,7 +156,7 @@ impl CommitteeTrait<BridgeAuthorityPublicKeyBytes> for BridgeCommittee {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 #[repr(u8)]
 pub enum BridgeActionType {
     TokenTransfer = 0,
@@ -564,6 +565,22 @@ impl BridgeAction {
         }
     }
 
+    /// Stake required to execute this action on chain. This mirrors
+    /// `requiredStake` in the bridge's `BridgeMessage.sol`.
+    pub fn approval_threshold(&self) -> StakeUnit {
+        match self {
+            BridgeAction::SuiToEthBridgeAction(_) | BridgeAction::EthToSuiBridgeAction(_) => 3334,
+            BridgeAction::BlocklistCommitteeAction(_) => 5001,
+            BridgeAction::EmergencyAction(a) => match a.action_type {
+                EmergencyActionType::Pause => 450,
+                EmergencyActionType::Unpause => 5001,
+            },
+            BridgeAction::LimitUpdateAction(_) => 5001,
+            BridgeAction::AssetPriceUpdateAction(_) => 5001,
+            BridgeAction::EvmContractUpgradeAction(_) => 5001,
+        }
+    }
+
     // Also called `nonce`
     pub fn seq_number(&self) -> u64 {
         match self {
@@ -587,6 +604,20 @@ impl BridgeActionDigest {
     }
 }
 
+impl std::str::FromStr for BridgeActionDigest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result = [0; 32];
+        let buffer = Base58::decode(s).map_err(|e| anyhow::anyhow!(e))?;
+        if buffer.len() != 32 {
+            return Err(anyhow::anyhow!("Invalid digest length. Expected 32 bytes"));
+        }
+        result.copy_from_slice(&buffer);
+        Ok(BridgeActionDigest::new(result))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BridgeCommitteeValiditySignInfo {
     pub signatures: BTreeMap<BridgeAuthorityPublicKeyBytes, BridgeAuthorityRecoverableSignature>,
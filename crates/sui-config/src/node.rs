@@ -385,6 +385,11 @@ pub struct ConsensusConfig {
 
     pub narwhal_config: ConsensusParameters,
 
+    /// When set, Mysticeti archives committed sub-dags, including their blocks, into this
+    /// object store before consensus storage garbage collects them.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub commit_archive_store_config: Option<ObjectStoreConfig>,
+
     /// The choice of consensus protocol to run. We default to Narwhal.
     #[serde(skip)]
     #[serde(default = "default_consensus_protocol")]
@@ -182,6 +182,20 @@ pub struct StateSyncConfig {
     /// If unspecified, this will default to no limit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub get_checkpoint_contents_per_checkpoint_limit: Option<usize>,
+
+    /// Number of consecutive failed or timed out requests after which a peer is temporarily
+    /// banned from state sync. Peers which respond with invalid data are banned right away.
+    ///
+    /// If unspecified, this will default to `5`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_ban_failure_threshold: Option<u32>,
+
+    /// How long a misbehaving peer is banned from state sync. The duration doubles every time the
+    /// same peer is banned again, up to 16 times this value.
+    ///
+    /// If unspecified, this will default to `60,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_ban_duration_ms: Option<u64>,
 }
 
 impl StateSyncConfig {
@@ -240,6 +254,19 @@ impl StateSyncConfig {
             .map(Duration::from_millis)
             .unwrap_or(DEFAULT_TIMEOUT)
     }
+
+    pub fn peer_ban_failure_threshold(&self) -> u32 {
+        const PEER_BAN_FAILURE_THRESHOLD: u32 = 5;
+
+        self.peer_ban_failure_threshold
+            .unwrap_or(PEER_BAN_FAILURE_THRESHOLD)
+    }
+
+    pub fn peer_ban_duration(&self) -> Duration {
+        const PEER_BAN_DURATION_MS: u64 = 60_000; // 1 minute
+
+        Duration::from_millis(self.peer_ban_duration_ms.unwrap_or(PEER_BAN_DURATION_MS))
+    }
 }
 
 /// Access Type of a node.
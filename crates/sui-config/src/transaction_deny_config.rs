@@ -118,6 +118,110 @@ impl TransactionDenyConfig {
     pub fn zklogin_disabled_providers(&self) -> &HashSet<String> {
         &self.zklogin_disabled_providers
     }
+
+    pub fn toggle(&self, toggle: TransactionDenyToggle) -> bool {
+        match toggle {
+            TransactionDenyToggle::PackagePublishDisabled => self.package_publish_disabled,
+            TransactionDenyToggle::PackageUpgradeDisabled => self.package_upgrade_disabled,
+            TransactionDenyToggle::SharedObjectDisabled => self.shared_object_disabled,
+            TransactionDenyToggle::UserTransactionDisabled => self.user_transaction_disabled,
+            TransactionDenyToggle::ReceivingObjectsDisabled => self.receiving_objects_disabled,
+            TransactionDenyToggle::ZkloginSigDisabled => self.zklogin_sig_disabled,
+        }
+    }
+
+    /// Sets a toggle, returns whether its value changed.
+    pub fn set_toggle(&mut self, toggle: TransactionDenyToggle, value: bool) -> bool {
+        let field = match toggle {
+            TransactionDenyToggle::PackagePublishDisabled => &mut self.package_publish_disabled,
+            TransactionDenyToggle::PackageUpgradeDisabled => &mut self.package_upgrade_disabled,
+            TransactionDenyToggle::SharedObjectDisabled => &mut self.shared_object_disabled,
+            TransactionDenyToggle::UserTransactionDisabled => &mut self.user_transaction_disabled,
+            TransactionDenyToggle::ReceivingObjectsDisabled => &mut self.receiving_objects_disabled,
+            TransactionDenyToggle::ZkloginSigDisabled => &mut self.zklogin_sig_disabled,
+        };
+        std::mem::replace(field, value) != value
+    }
+
+    /// Adds an object to the deny list, returns false if it was already denied.
+    pub fn deny_object(&mut self, id: ObjectID) -> bool {
+        let added = add_entry(&mut self.object_deny_list, id);
+        if added {
+            self.object_deny_set.take();
+        }
+        added
+    }
+
+    /// Removes an object from the deny list, returns false if it was not denied.
+    pub fn allow_object(&mut self, id: ObjectID) -> bool {
+        let removed = remove_entry(&mut self.object_deny_list, &id);
+        if removed {
+            self.object_deny_set.take();
+        }
+        removed
+    }
+
+    /// Adds a package to the deny list, returns false if it was already denied.
+    pub fn deny_package(&mut self, id: ObjectID) -> bool {
+        let added = add_entry(&mut self.package_deny_list, id);
+        if added {
+            self.package_deny_set.take();
+        }
+        added
+    }
+
+    /// Removes a package from the deny list, returns false if it was not denied.
+    pub fn allow_package(&mut self, id: ObjectID) -> bool {
+        let removed = remove_entry(&mut self.package_deny_list, &id);
+        if removed {
+            self.package_deny_set.take();
+        }
+        removed
+    }
+
+    /// Adds an address to the deny list, returns false if it was already denied.
+    pub fn deny_address(&mut self, address: SuiAddress) -> bool {
+        let added = add_entry(&mut self.address_deny_list, address);
+        if added {
+            self.address_deny_set.take();
+        }
+        added
+    }
+
+    /// Removes an address from the deny list, returns false if it was not denied.
+    pub fn allow_address(&mut self, address: SuiAddress) -> bool {
+        let removed = remove_entry(&mut self.address_deny_list, &address);
+        if removed {
+            self.address_deny_set.take();
+        }
+        removed
+    }
+}
+
+fn add_entry<T: PartialEq>(list: &mut Vec<T>, entry: T) -> bool {
+    if list.contains(&entry) {
+        return false;
+    }
+    list.push(entry);
+    true
+}
+
+fn remove_entry<T: PartialEq>(list: &mut Vec<T>, entry: &T) -> bool {
+    let len = list.len();
+    list.retain(|e| e != entry);
+    list.len() != len
+}
+
+/// The boolean switches of a TransactionDenyConfig, which can be flipped at runtime.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransactionDenyToggle {
+    PackagePublishDisabled,
+    PackageUpgradeDisabled,
+    SharedObjectDisabled,
+    UserTransactionDisabled,
+    ReceivingObjectsDisabled,
+    ZkloginSigDisabled,
 }
 
 #[derive(Default)]
@@ -184,3 +288,43 @@ impl TransactionDenyConfigBuilder {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_deny_lists() {
+        let id = ObjectID::random();
+        let address = SuiAddress::random_for_testing_only();
+        let mut config = TransactionDenyConfigBuilder::new()
+            .add_denied_object(id)
+            .build();
+        assert!(config.get_object_deny_set().contains(&id));
+
+        // the cached sets reflect updates
+        assert!(!config.deny_object(id));
+        assert!(config.allow_object(id));
+        assert!(!config.allow_object(id));
+        assert!(config.get_object_deny_set().is_empty());
+
+        assert!(config.deny_package(id));
+        assert!(config.get_package_deny_set().contains(&id));
+        assert!(config.deny_address(address));
+        assert!(config.get_address_deny_set().contains(&address));
+        assert!(config.allow_address(address));
+        assert!(config.get_address_deny_set().is_empty());
+    }
+
+    #[test]
+    fn update_toggles() {
+        let mut config = TransactionDenyConfig::default();
+        assert!(config.set_toggle(TransactionDenyToggle::PackagePublishDisabled, true));
+        assert!(!config.set_toggle(TransactionDenyToggle::PackagePublishDisabled, true));
+        assert!(config.package_publish_disabled());
+        assert!(config.toggle(TransactionDenyToggle::PackagePublishDisabled));
+        assert!(!config.toggle(TransactionDenyToggle::SharedObjectDisabled));
+        assert!(config.set_toggle(TransactionDenyToggle::PackagePublishDisabled, false));
+        assert!(!config.package_publish_disabled());
+    }
+}
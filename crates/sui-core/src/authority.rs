@@ -128,13 +128,14 @@ use sui_types::{
     SUI_SYSTEM_ADDRESS,
 };
 use sui_types::{is_system_package, TypeTag};
+use typed_store::rocks::ColumnFamilyStats;
 use typed_store::TypedStoreError;
 
 use crate::authority::authority_per_epoch_store::{AuthorityPerEpochStore, CertTxGuard};
 use crate::authority::authority_per_epoch_store_pruner::AuthorityPerEpochStorePruner;
 use crate::authority::authority_store::{ExecutionLockReadGuard, ObjectLockStatus};
 use crate::authority::authority_store_pruner::{
-    AuthorityStorePruner, EPOCH_DURATION_MS_FOR_TESTING,
+    AuthorityStorePruner, AuthorityStorePrunerHandle, EPOCH_DURATION_MS_FOR_TESTING,
 };
 use crate::authority::epoch_start_configuration::EpochStartConfigTrait;
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
@@ -785,7 +786,7 @@ pub struct AuthorityState {
     tx_execution_shutdown: Mutex<Option<oneshot::Sender<()>>>,
 
     pub metrics: Arc<AuthorityMetrics>,
-    _pruner: AuthorityStorePruner,
+    pruner: AuthorityStorePruner,
     _authority_per_epoch_pruner: AuthorityPerEpochStorePruner,
 
     /// Take db checkpoints of different dbs
@@ -794,7 +795,9 @@ pub struct AuthorityState {
     /// Config controlling what kind of expensive safety checks to perform.
     expensive_safety_check_config: ExpensiveSafetyCheckConfig,
 
-    transaction_deny_config: TransactionDenyConfig,
+    /// Config controlling which transactions are denied. Can be updated at runtime through the
+    /// admin interface.
+    transaction_deny_config: ArcSwap<TransactionDenyConfig>,
 
     certificate_deny_config: CertificateDenyConfig,
 
@@ -868,7 +871,7 @@ impl AuthorityState {
             transaction.tx_signatures(),
             &input_object_kinds,
             &receiving_objects_refs,
-            &self.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -1714,7 +1717,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -1933,7 +1936,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -2651,7 +2654,7 @@ impl AuthorityState {
 
         let _authority_per_epoch_pruner =
             AuthorityPerEpochStorePruner::new(epoch_store.get_parent_path(), &pruning_config);
-        let _pruner = AuthorityStorePruner::new(
+        let pruner = AuthorityStorePruner::new(
             store.perpetual_tables.clone(),
             checkpoint_store.clone(),
             store.objects_lock_table.clone(),
@@ -2680,11 +2683,11 @@ impl AuthorityState {
             transaction_manager,
             tx_execution_shutdown: Mutex::new(Some(tx_execution_shutdown)),
             metrics,
-            _pruner,
+            pruner,
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
             expensive_safety_check_config,
-            transaction_deny_config,
+            transaction_deny_config: ArcSwap::from_pointee(transaction_deny_config),
             certificate_deny_config,
             debug_dump_config,
             authority_overload_config: authority_overload_config.clone(),
@@ -4067,6 +4070,26 @@ impl AuthorityState {
             .get_latest_object_ref_or_tombstone(object_id)
     }
 
+    /// Handle to pause, resume or immediately run the pruning of the perpetual store.
+    pub fn pruner_handle(&self) -> AuthorityStorePrunerHandle {
+        self.pruner.handle()
+    }
+
+    /// Size and compaction state of the tables of the perpetual store.
+    pub fn perpetual_db_stats(&self) -> SuiResult<BTreeMap<String, ColumnFamilyStats>> {
+        self.execution_cache.column_family_stats()
+    }
+
+    pub fn transaction_deny_config(&self) -> Arc<TransactionDenyConfig> {
+        self.transaction_deny_config.load_full()
+    }
+
+    /// Replaces the transaction deny config. Transactions checked after this returns are
+    /// checked against the new config.
+    pub fn set_transaction_deny_config(&self, config: TransactionDenyConfig) {
+        self.transaction_deny_config.store(Arc::new(config));
+    }
+
     /// Ordinarily, protocol upgrades occur when 2f + 1 + (f *
     /// ProtocolConfig::buffer_stake_for_protocol_upgrade_bps) vote for the upgrade.
     ///
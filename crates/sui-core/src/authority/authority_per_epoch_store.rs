@@ -41,7 +41,7 @@ use tokio::sync::OnceCell;
 use tracing::{debug, error, info, instrument, trace, warn};
 use typed_store::rocks::{read_size_from_env, ReadWriteOptions};
 use typed_store::{
-    rocks::{default_db_options, DBBatch, DBMap, DBOptions, MetricConf},
+    rocks::{default_db_options, ColumnFamilyStats, DBBatch, DBMap, DBOptions, MetricConf},
     traits::{TableSummary, TypedStoreDebug},
     TypedStoreError,
 };
@@ -963,6 +963,15 @@ impl AuthorityPerEpochStore {
         }
     }
 
+    /// Size and compaction state of the tables of the epoch db.
+    pub fn column_family_stats(&self) -> SuiResult<BTreeMap<String, ColumnFamilyStats>> {
+        // Every table shares the same db, so any table reports all of them
+        self.tables()?
+            .signed_transactions
+            .column_family_stats()
+            .map_err(Into::into)
+    }
+
     // Ideally the epoch tables handle should have the same lifetime as the outer AuthorityPerEpochStore,
     // and this function should be unnecesary. But unfortunately, Arc<AuthorityPerEpochStore> outlives the
     // epoch significantly right now, so we need to manually release the tables to release its memory usage.
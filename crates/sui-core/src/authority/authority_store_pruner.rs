@@ -13,6 +13,7 @@ use prometheus::{
 use rocksdb::LiveFile;
 use std::cmp::{max, min};
 use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{sync::Arc, time::Duration};
@@ -30,6 +31,7 @@ use sui_types::{
     base_types::{ObjectID, VersionNumber},
     storage::ObjectKey,
 };
+use tokio::sync::mpsc;
 use tokio::sync::oneshot::{self, Sender};
 use tokio::time::Instant;
 use tracing::{debug, error, info, warn};
@@ -53,6 +55,45 @@ static PERIODIC_PRUNING_TABLES: Lazy<BTreeSet<String>> = Lazy::new(|| {
 pub const EPOCH_DURATION_MS_FOR_TESTING: u64 = 24 * 60 * 60 * 1000;
 pub struct AuthorityStorePruner {
     _objects_pruner_cancel_handle: oneshot::Sender<()>,
+    handle: AuthorityStorePrunerHandle,
+}
+
+/// Controls the periodic runs of an AuthorityStorePruner: they can be paused and resumed, and
+/// an immediate run can be requested.
+#[derive(Clone)]
+pub struct AuthorityStorePrunerHandle {
+    paused: Arc<AtomicBool>,
+    run_requests: mpsc::Sender<oneshot::Sender<anyhow::Result<()>>>,
+}
+
+impl AuthorityStorePrunerHandle {
+    /// Pauses the periodic pruning runs, returns whether they were already paused.
+    /// A run in progress is not interrupted.
+    pub fn pause(&self) -> bool {
+        self.paused.swap(true, Ordering::Relaxed)
+    }
+
+    /// Resumes the periodic pruning runs, returns whether they were paused.
+    pub fn resume(&self) -> bool {
+        self.paused.swap(false, Ordering::Relaxed)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Prunes objects and checkpoints of the epochs eligible for pruning now, even when periodic
+    /// runs are paused, and waits for the run to complete.
+    pub async fn run_now(&self) -> anyhow::Result<()> {
+        let (sender, receiver) = oneshot::channel();
+        self.run_requests
+            .send(sender)
+            .await
+            .map_err(|_| anyhow!("pruner is not running"))?;
+        receiver
+            .await
+            .map_err(|_| anyhow!("pruner stopped before completing the run"))?
+    }
 }
 
 pub struct AuthorityStorePruningMetrics {
@@ -587,6 +628,8 @@ impl AuthorityStorePruner {
         metrics: Arc<AuthorityStorePruningMetrics>,
         indirect_objects_threshold: usize,
         archive_readers: ArchiveReaderBalancer,
+        paused: Arc<AtomicBool>,
+        mut run_requests: mpsc::Receiver<oneshot::Sender<anyhow::Result<()>>>,
     ) -> Sender<()> {
         let (sender, mut recv) = tokio::sync::oneshot::channel();
         debug!(
@@ -640,19 +683,44 @@ impl AuthorityStorePruner {
                 .unwrap_or_default() as i64,
         );
 
+        let objects_pruning_enabled = config.num_epochs_to_retain != u64::MAX;
+        let checkpoints_pruning_enabled = !matches!(
+            config.num_epochs_to_retain_for_checkpoints(),
+            None | Some(u64::MAX) | Some(0)
+        );
         tokio::task::spawn(async move {
             loop {
                 tokio::select! {
-                    _ = objects_prune_interval.tick(), if config.num_epochs_to_retain != u64::MAX => {
+                    _ = objects_prune_interval.tick(), if objects_pruning_enabled => {
+                        if paused.load(Ordering::Relaxed) {
+                            continue;
+                        }
                         if let Err(err) = Self::prune_objects_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config, metrics.clone(), indirect_objects_threshold, epoch_duration_ms).await {
                             error!("Failed to prune objects: {:?}", err);
                         }
                     },
-                    _ = checkpoints_prune_interval.tick(), if !matches!(config.num_epochs_to_retain_for_checkpoints(), None | Some(u64::MAX) | Some(0)) => {
+                    _ = checkpoints_prune_interval.tick(), if checkpoints_pruning_enabled => {
+                        if paused.load(Ordering::Relaxed) {
+                            continue;
+                        }
                         if let Err(err) = Self::prune_checkpoints_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config, metrics.clone(), indirect_objects_threshold, archive_readers.clone(), epoch_duration_ms).await {
                             error!("Failed to prune checkpoints: {:?}", err);
                         }
                     },
+                    Some(response) = run_requests.recv() => {
+                        info!("Running requested pruning");
+                        let mut result = Ok(());
+                        if objects_pruning_enabled {
+                            result = Self::prune_objects_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config, metrics.clone(), indirect_objects_threshold, epoch_duration_ms).await;
+                        }
+                        if result.is_ok() && checkpoints_pruning_enabled {
+                            result = Self::prune_checkpoints_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config, metrics.clone(), indirect_objects_threshold, archive_readers.clone(), epoch_duration_ms).await;
+                        }
+                        if let Err(err) = &result {
+                            error!("Failed to run requested pruning: {:?}", err);
+                        }
+                        let _ = response.send(result);
+                    },
                     _ = &mut recv => break,
                 }
             }
@@ -681,6 +749,8 @@ impl AuthorityStorePruner {
                 warn!("Consider using an aggressive pruner (num_epochs_to_retain = 0)");
             }
         }
+        let paused = Arc::new(AtomicBool::new(false));
+        let (run_requests, run_requests_receiver) = mpsc::channel(1);
         AuthorityStorePruner {
             _objects_pruner_cancel_handle: Self::setup_pruning(
                 pruning_config,
@@ -691,10 +761,20 @@ impl AuthorityStorePruner {
                 AuthorityStorePruningMetrics::new(registry),
                 indirect_objects_threshold,
                 archive_readers,
+                paused.clone(),
+                run_requests_receiver,
             ),
+            handle: AuthorityStorePrunerHandle {
+                paused,
+                run_requests,
+            },
         }
     }
 
+    pub fn handle(&self) -> AuthorityStorePrunerHandle {
+        self.handle.clone()
+    }
+
     pub fn compact(perpetual_db: &Arc<AuthorityPerpetualTables>) -> Result<(), TypedStoreError> {
         perpetual_db.objects.compact_range(
             &ObjectKey(ObjectID::ZERO, SequenceNumber::MIN),
@@ -945,6 +1025,35 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_pruner_handle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path();
+        let perpetual_db = Arc::new(AuthorityPerpetualTables::open(
+            &path.join("perpetual"),
+            None,
+        ));
+        let pruner = super::AuthorityStorePruner::new(
+            perpetual_db,
+            crate::checkpoints::CheckpointStore::new(&path.join("checkpoints")),
+            lock_table(),
+            sui_config::node::AuthorityStorePruningConfig::default(),
+            false,
+            super::EPOCH_DURATION_MS_FOR_TESTING,
+            &Registry::default(),
+            0,
+            sui_archival::reader::ArchiveReaderBalancer::default(),
+        );
+        let handle = pruner.handle();
+        assert!(!handle.is_paused());
+        assert!(!handle.pause());
+        assert!(handle.is_paused());
+        // requested runs are not affected by pausing
+        handle.run_now().await.unwrap();
+        assert!(handle.resume());
+        assert!(!handle.is_paused());
+    }
+
     #[cfg(not(target_env = "msvc"))]
     #[tokio::test]
     async fn test_db_size_after_compaction() -> Result<(), anyhow::Error> {
@@ -5,6 +5,7 @@ use super::*;
 use crate::authority::authority_store::LockDetailsWrapperDeprecated;
 use rocksdb::Options;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::SequenceNumber;
@@ -14,7 +15,8 @@ use sui_types::storage::MarkerValue;
 use typed_store::metrics::SamplingInterval;
 use typed_store::rocks::util::{empty_compaction_filter, reference_count_merge_operator};
 use typed_store::rocks::{
-    default_db_options, read_size_from_env, DBBatch, DBMap, DBOptions, MetricConf, ReadWriteOptions,
+    default_db_options, read_size_from_env, ColumnFamilyStats, DBBatch, DBMap, DBOptions,
+    MetricConf, ReadWriteOptions,
 };
 use typed_store::traits::{Map, TableSummary, TypedStoreDebug};
 
@@ -385,6 +387,11 @@ impl AuthorityPerpetualTables {
         self.objects.checkpoint_db(path).map_err(Into::into)
     }
 
+    pub fn column_family_stats(&self) -> SuiResult<BTreeMap<String, ColumnFamilyStats>> {
+        // Every table shares the same db, so the objects table reports all of them
+        self.objects.column_family_stats().map_err(Into::into)
+    }
+
     pub fn reset_db_for_execution_since_genesis(&self) -> SuiResult {
         // TODO: Add new tables that get added to the db automatically
         self.objects.unsafe_clear()?;
@@ -69,7 +69,7 @@ use tracing::{debug, error, info, instrument, warn};
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store::Map;
 use typed_store::{
-    rocks::{DBMap, MetricConf},
+    rocks::{ColumnFamilyStats, DBMap, MetricConf},
     TypedStoreError,
 };
 use typed_store_derive::DBMapUtils;
@@ -723,6 +723,12 @@ impl CheckpointStore {
             .map_err(Into::into)
     }
 
+    pub fn column_family_stats(&self) -> SuiResult<BTreeMap<String, ColumnFamilyStats>> {
+        self.checkpoint_content
+            .column_family_stats()
+            .map_err(Into::into)
+    }
+
     pub fn delete_highest_executed_checkpoint_test_only(&self) -> Result<(), TypedStoreError> {
         let mut wb = self.watermarks.batch();
         wb.delete_batch(
@@ -94,7 +94,7 @@ impl MysticetiManager {
 impl ConsensusManagerTrait for MysticetiManager {
     async fn start(
         &self,
-        _config: &NodeConfig,
+        config: &NodeConfig,
         epoch_store: Arc<AuthorityPerEpochStore>,
         consensus_handler_initializer: ConsensusHandlerInitializer,
         tx_validator: SuiTxValidator,
@@ -136,12 +136,22 @@ impl ConsensusManagerTrait for MysticetiManager {
         let (commit_sender, commit_receiver) = unbounded_channel();
 
         let consensus_handler = consensus_handler_initializer.new_consensus_handler();
-        let consumer = CommitConsumer::new(
+        let mut consumer = CommitConsumer::new(
             commit_sender,
             // TODO(mysticeti): remove dependency on narwhal executor
             consensus_handler.last_executed_sub_dag_round() as Round,
             consensus_handler.last_executed_sub_dag_index() as CommitIndex,
         );
+        if let Some(archive_store_config) = config
+            .consensus_config()
+            .and_then(|c| c.commit_archive_store_config.as_ref())
+        {
+            // The config is validated when the node starts.
+            let archive_store = archive_store_config
+                .make()
+                .expect("Consensus commit archive store config should be valid");
+            consumer = consumer.with_archive_store(archive_store);
+        }
 
         // TODO(mysticeti): Investigate if we need to return potential errors from
         // AuthorityNode and add retries here?
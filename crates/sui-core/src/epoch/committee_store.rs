@@ -3,13 +3,13 @@
 
 use parking_lot::RwLock;
 use rocksdb::Options;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use sui_types::base_types::ObjectID;
 use sui_types::committee::{Committee, EpochId};
 use sui_types::error::{SuiError, SuiResult};
-use typed_store::rocks::{default_db_options, DBMap, DBOptions, MetricConf};
+use typed_store::rocks::{default_db_options, ColumnFamilyStats, DBMap, DBOptions, MetricConf};
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 
 use typed_store::Map;
@@ -125,6 +125,13 @@ impl CommitteeStore {
             .map_err(Into::into)
     }
 
+    pub fn column_family_stats(&self) -> SuiResult<BTreeMap<String, ColumnFamilyStats>> {
+        self.tables
+            .committee_map
+            .column_family_stats()
+            .map_err(Into::into)
+    }
+
     fn database_is_empty(&self) -> bool {
         self.tables.committee_map.unbounded_iter().next().is_none()
     }
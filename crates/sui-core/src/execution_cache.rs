@@ -12,7 +12,7 @@ use async_trait::async_trait;
 
 use futures::{future::BoxFuture, FutureExt};
 use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 use sui_protocol_config::ProtocolVersion;
@@ -35,6 +35,7 @@ use sui_types::{
     storage::InputKey,
 };
 use tracing::instrument;
+use typed_store::rocks::ColumnFamilyStats;
 
 pub(crate) mod cache_types;
 mod object_locks;
@@ -563,6 +564,9 @@ pub trait ExecutionCacheReconfigAPI: Send + Sync {
 
     fn checkpoint_db(&self, path: &Path) -> SuiResult;
 
+    /// Size and compaction state of the tables of the perpetual store.
+    fn column_family_stats(&self) -> SuiResult<BTreeMap<String, ColumnFamilyStats>>;
+
     /// This is a temporary method to be used when we enable simplified_unwrap_then_delete.
     /// It re-accumulates state hash for the new epoch if simplified_unwrap_then_delete is enabled.
     fn maybe_reaccumulate_state_hash(
@@ -788,6 +792,14 @@ macro_rules! implement_passthrough_traits {
                 self.store.perpetual_tables.checkpoint_db(path)
             }
 
+            fn column_family_stats(
+                &self,
+            ) -> SuiResult<
+                std::collections::BTreeMap<String, typed_store::rocks::ColumnFamilyStats>,
+            > {
+                self.store.perpetual_tables.column_family_stats()
+            }
+
             fn maybe_reaccumulate_state_hash(
                 &self,
                 cur_epoch_store: &AuthorityPerEpochStore,
@@ -240,6 +240,26 @@ async fn test_signer_denied() {
     assert_denied(&transfer_with_account(&accounts[2], &accounts[1], &state).await);
 }
 
+#[tokio::test]
+async fn test_deny_config_updated_at_runtime() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+
+    let mut config = (*state.transaction_deny_config()).clone();
+    assert!(config.deny_address(accounts[0].0));
+    state.set_transaction_deny_config(config.clone());
+    assert_denied(&transfer_with_account(&accounts[0], &accounts[0], &state).await);
+    assert!(transfer_with_account(&accounts[1], &accounts[1], &state)
+        .await
+        .is_ok());
+
+    assert!(config.allow_address(accounts[0].0));
+    state.set_transaction_deny_config(config);
+    assert!(transfer_with_account(&accounts[0], &accounts[0], &state)
+        .await
+        .is_ok());
+}
+
 #[tokio::test]
 async fn test_shared_object_transaction_disabled() {
     let (network_config, state) = setup_test(
@@ -6,8 +6,9 @@ use crate::error::Error;
 
 use super::checkpoint::{Checkpoint, CheckpointId};
 use async_graphql::*;
+use diesel::dsl::max;
 use diesel::{CombineDsl, ExpressionMethods, QueryDsl, QueryResult};
-use sui_indexer::schema::{checkpoints, objects_snapshot};
+use sui_indexer::schema::{checkpoints, objects_snapshot, pruner_watermarks};
 
 #[derive(Clone, Debug, PartialEq, Eq, Copy)]
 pub(crate) struct AvailableRange {
@@ -57,6 +58,7 @@ impl AvailableRange {
     pub(crate) fn result(conn: &mut Conn, checkpoint_viewed_at: u64) -> QueryResult<Option<Self>> {
         use checkpoints::dsl as checkpoints;
         use objects_snapshot::dsl as snapshots;
+        use pruner_watermarks::dsl as watermarks;
 
         let checkpoint_range: Vec<i64> = conn.results(move || {
             let rhs = checkpoints::checkpoints
@@ -72,7 +74,7 @@ impl AvailableRange {
             lhs.union(rhs)
         })?;
 
-        let (first, mut last) = match checkpoint_range.as_slice() {
+        let (mut first, mut last) = match checkpoint_range.as_slice() {
             [] => (0, 0),
             [single_value] => (0, *single_value as u64),
             values => {
@@ -82,6 +84,13 @@ impl AvailableRange {
             }
         };
 
+        // Checkpoints before the watermark of any pruned table are missing some of their data.
+        let pruned_before: Option<i64> = conn
+            .result(move || watermarks::pruner_watermarks.select(max(watermarks::checkpoint_lo)))?;
+        if let Some(pruned_before) = pruned_before {
+            first = first.max(pruned_before as u64);
+        }
+
         if checkpoint_viewed_at < first || last < checkpoint_viewed_at {
             return Ok(None);
         }
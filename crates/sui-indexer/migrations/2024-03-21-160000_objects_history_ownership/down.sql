@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS objects_history_object_id;
+DROP INDEX IF EXISTS objects_history_owner_object_id;
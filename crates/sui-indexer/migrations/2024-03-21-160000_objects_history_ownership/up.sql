@@ -0,0 +1,4 @@
+-- the primary key and the owner index are led by the checkpoint sequence number, these serve
+-- the ownership history of an object and the objects an address owned at a checkpoint
+CREATE INDEX objects_history_object_id ON objects_history (object_id, object_version);
+CREATE INDEX objects_history_owner_object_id ON objects_history (owner_type, owner_id, object_id) WHERE owner_type BETWEEN 1 AND 2 AND owner_id IS NOT NULL;
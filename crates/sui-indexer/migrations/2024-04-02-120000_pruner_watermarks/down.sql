@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS pruner_watermarks;
+DROP INDEX IF EXISTS tx_input_objects_tx_sequence_number_index;
+DROP INDEX IF EXISTS tx_changed_objects_tx_sequence_number_index;
@@ -0,0 +1,14 @@
+-- The lowest epoch, checkpoint and transaction sequence number still available in each table, or
+-- group of tables, that the pruner removes old data from. Readers must not serve data below them.
+-- `pruned_epoch_lo` is the first epoch whose data the pruner has not finished removing yet, which
+-- lags behind `epoch_lo` while an epoch is being pruned.
+CREATE TABLE pruner_watermarks (
+    entity                      TEXT         PRIMARY KEY,
+    epoch_lo                    BIGINT       NOT NULL,
+    checkpoint_lo               BIGINT       NOT NULL,
+    tx_lo                       BIGINT       NOT NULL,
+    pruned_epoch_lo             BIGINT       NOT NULL
+);
+-- rows of these tables are pruned by transaction sequence number
+CREATE INDEX tx_input_objects_tx_sequence_number_index ON tx_input_objects (tx_sequence_number ASC);
+CREATE INDEX tx_changed_objects_tx_sequence_number_index ON tx_changed_objects (tx_sequence_number ASC);
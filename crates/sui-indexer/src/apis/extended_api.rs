@@ -1,15 +1,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::apis::indexer_api::objects_page;
 use crate::indexer_reader::IndexerReader;
 use diesel::r2d2::R2D2Connection;
 use jsonrpsee::{core::RpcResult, RpcModule};
 use sui_json_rpc::SuiRpcModule;
-use sui_json_rpc_api::{validate_limit, ExtendedApiServer, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS};
+use sui_json_rpc_api::{
+    cap_page_limit, validate_limit, ExtendedApiServer, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
+};
 use sui_json_rpc_types::{
-    CheckpointedObjectID, EpochInfo, EpochPage, Page, QueryObjectsPage, SuiObjectResponseQuery,
+    CheckpointedObjectID, EpochInfo, EpochPage, IndexerAvailableRange, ObjectOwnershipHistoryPage,
+    ObjectsPage, Page, QueryObjectsPage, SuiObjectDataOptions, SuiObjectResponseQuery,
 };
 use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::sui_serde::BigInt;
 
 pub(crate) struct ExtendedApi<T: R2D2Connection + 'static> {
@@ -72,6 +77,54 @@ impl<T: R2D2Connection + 'static> ExtendedApiServer for ExtendedApi<T> {
         .into())
     }
 
+    async fn get_object_ownership_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<ObjectOwnershipHistoryPage> {
+        let limit = cap_page_limit(limit);
+        let mut changes = self
+            .inner
+            .spawn_blocking(move |this| {
+                this.get_object_ownership_history(
+                    object_id,
+                    cursor.map(|x| *x),
+                    limit + 1,
+                    descending_order.unwrap_or(false),
+                )
+            })
+            .await?;
+
+        let has_next_page = changes.len() > limit;
+        changes.truncate(limit);
+        let next_cursor = changes.last().map(|c| c.version.value());
+        Ok(Page {
+            data: changes,
+            next_cursor: next_cursor.map(|version| version.into()),
+            has_next_page,
+        })
+    }
+
+    async fn get_owned_objects_at_checkpoint(
+        &self,
+        address: SuiAddress,
+        checkpoint: BigInt<u64>,
+        options: Option<SuiObjectDataOptions>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        let limit = cap_page_limit(limit);
+        let objects = self
+            .inner
+            .spawn_blocking(move |this| {
+                this.get_owned_objects_at_checkpoint(address, *checkpoint, cursor, limit + 1)
+            })
+            .await?;
+        objects_page(&self.inner, objects, options.unwrap_or_default(), limit).await
+    }
+
     async fn get_total_transactions(&self) -> RpcResult<BigInt<u64>> {
         let latest_checkpoint = self
             .inner
@@ -79,6 +132,13 @@ impl<T: R2D2Connection + 'static> ExtendedApiServer for ExtendedApi<T> {
             .await?;
         Ok(latest_checkpoint.network_total_transactions.into())
     }
+
+    async fn get_available_range(&self) -> RpcResult<IndexerAvailableRange> {
+        Ok(self
+            .inner
+            .spawn_blocking(|this| this.get_available_range())
+            .await?)
+    }
 }
 
 impl<T: R2D2Connection> SuiRpcModule for ExtendedApi<T> {
@@ -13,9 +13,9 @@ use sui_json_rpc::name_service::{Domain, NameRecord, NameServiceConfig};
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_api::{cap_page_limit, IndexerApiServer};
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
-    TransactionFilter,
+    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectDataOptions,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockResponseQuery,
+    TransactionBlocksPage, TransactionFilter,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SuiAddress};
@@ -27,6 +27,7 @@ use sui_types::object::ObjectRead;
 use sui_types::TypeTag;
 
 use crate::indexer_reader::IndexerReader;
+use crate::models::objects::StoredObject;
 use crate::IndexerError;
 
 pub(crate) struct IndexerApi<T: R2D2Connection + 'static> {
@@ -56,81 +57,91 @@ impl<T: R2D2Connection + 'static> IndexerApi<T> {
             .inner
             .get_owned_objects_in_blocking_task(address, filter, cursor, limit + 1)
             .await?;
+        objects_page(&self.inner, objects, options, limit).await
+    }
+}
 
-        let mut object_futures = vec![];
-        for object in objects {
-            object_futures.push(tokio::task::spawn(
-                object.try_into_object_read(self.inner.package_resolver()),
-            ));
-        }
-        let mut objects = futures::future::join_all(object_futures)
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| {
-                tracing::error!("Error joining object read futures.");
-                jsonrpsee::core::Error::Custom(format!("Error joining object read futures. {}", e))
-            })?
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()
-            .tap_err(|e| tracing::error!("Error converting object to object read: {}", e))?;
-        let has_next_page = objects.len() > limit;
-        objects.truncate(limit);
-
-        let next_cursor = objects.last().map(|o_read| o_read.object_id());
-        let mut parallel_tasks = vec![];
-        for o in objects {
-            let inner_clone = self.inner.clone();
-            let options = options.clone();
-            parallel_tasks.push(tokio::task::spawn(async move {
-                match o {
-                    ObjectRead::NotExists(id) => Ok(SuiObjectResponse::new_with_error(
-                        SuiObjectResponseError::NotExists { object_id: id },
-                    )),
-                    ObjectRead::Exists(object_ref, o, layout) => {
-                        if options.show_display {
-                            match inner_clone.get_display_fields(&o, &layout).await {
-                                Ok(rendered_fields) => Ok(SuiObjectResponse::new_with_data(
-                                    (object_ref, o, layout, options, Some(rendered_fields))
-                                        .try_into()?,
-                                )),
-                                Err(e) => Ok(SuiObjectResponse::new(
-                                    Some((object_ref, o, layout, options, None).try_into()?),
-                                    Some(SuiObjectResponseError::DisplayError {
-                                        error: e.to_string(),
-                                    }),
-                                )),
-                            }
-                        } else {
-                            Ok(SuiObjectResponse::new_with_data(
-                                (object_ref, o, layout, options, None).try_into()?,
-                            ))
+/// Converts the `objects` of a page of at most `limit` objects, fetched with one extra object to
+/// tell whether there is a next page, into a page of object responses.
+pub(crate) async fn objects_page<T: R2D2Connection + 'static>(
+    inner: &IndexerReader<T>,
+    objects: Vec<StoredObject>,
+    options: SuiObjectDataOptions,
+    limit: usize,
+) -> RpcResult<ObjectsPage> {
+    let mut object_futures = vec![];
+    for object in objects {
+        object_futures.push(tokio::task::spawn(
+            object.try_into_object_read(inner.package_resolver()),
+        ));
+    }
+    let mut objects = futures::future::join_all(object_futures)
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| {
+            tracing::error!("Error joining object read futures.");
+            jsonrpsee::core::Error::Custom(format!("Error joining object read futures. {}", e))
+        })?
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .tap_err(|e| tracing::error!("Error converting object to object read: {}", e))?;
+    let has_next_page = objects.len() > limit;
+    objects.truncate(limit);
+
+    let next_cursor = objects.last().map(|o_read| o_read.object_id());
+    let mut parallel_tasks = vec![];
+    for o in objects {
+        let inner_clone = inner.clone();
+        let options = options.clone();
+        parallel_tasks.push(tokio::task::spawn(async move {
+            match o {
+                ObjectRead::NotExists(id) => Ok(SuiObjectResponse::new_with_error(
+                    SuiObjectResponseError::NotExists { object_id: id },
+                )),
+                ObjectRead::Exists(object_ref, o, layout) => {
+                    if options.show_display {
+                        match inner_clone.get_display_fields(&o, &layout).await {
+                            Ok(rendered_fields) => Ok(SuiObjectResponse::new_with_data(
+                                (object_ref, o, layout, options, Some(rendered_fields))
+                                    .try_into()?,
+                            )),
+                            Err(e) => Ok(SuiObjectResponse::new(
+                                Some((object_ref, o, layout, options, None).try_into()?),
+                                Some(SuiObjectResponseError::DisplayError {
+                                    error: e.to_string(),
+                                }),
+                            )),
                         }
+                    } else {
+                        Ok(SuiObjectResponse::new_with_data(
+                            (object_ref, o, layout, options, None).try_into()?,
+                        ))
                     }
-                    ObjectRead::Deleted((object_id, version, digest)) => Ok(
-                        SuiObjectResponse::new_with_error(SuiObjectResponseError::Deleted {
-                            object_id,
-                            version,
-                            digest,
-                        }),
-                    ),
                 }
-            }));
-        }
-        let data = futures::future::join_all(parallel_tasks)
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e: tokio::task::JoinError| anyhow::anyhow!(e))?
-            .into_iter()
-            .collect::<Result<Vec<_>, anyhow::Error>>()?;
-
-        Ok(Page {
-            data,
-            next_cursor,
-            has_next_page,
-        })
+                ObjectRead::Deleted((object_id, version, digest)) => Ok(
+                    SuiObjectResponse::new_with_error(SuiObjectResponseError::Deleted {
+                        object_id,
+                        version,
+                        digest,
+                    }),
+                ),
+            }
+        }));
     }
+    let data = futures::future::join_all(parallel_tasks)
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e: tokio::task::JoinError| anyhow::anyhow!(e))?
+        .into_iter()
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    Ok(Page {
+        data,
+        next_cursor,
+        has_next_page,
+    })
 }
 
 #[async_trait]
@@ -310,7 +310,15 @@ pub mod setup_postgres {
                 tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
             }
         });
-        if indexer_config.fullnode_sync_worker {
+        if indexer_config.backfill_config.is_enabled() {
+            let store = PgIndexerStore::<PgConnection>::new(blocking_cp, indexer_metrics.clone());
+            return Indexer::start_backfill::<PgIndexerStore<PgConnection>, PgConnection>(
+                &indexer_config,
+                store,
+                indexer_metrics,
+            )
+            .await;
+        } else if indexer_config.fullnode_sync_worker {
             let store = PgIndexerStore::<PgConnection>::new(blocking_cp, indexer_metrics.clone());
             return Indexer::start_writer::<PgIndexerStore<PgConnection>, PgConnection>(
                 &indexer_config,
@@ -0,0 +1,321 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use clap::{Args, ValueEnum};
+use diesel::r2d2::R2D2Connection;
+use tokio::sync::oneshot;
+use tracing::info;
+
+use sui_data_ingestion_core::{ProgressStore, Worker};
+use sui_package_resolver::Resolver;
+use sui_rest_api::CheckpointData;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::errors::IndexerError;
+use crate::handlers::checkpoint_handler::CheckpointHandler;
+use crate::metrics::IndexerMetrics;
+use crate::store::package_resolver::IndexerStorePackageResolver;
+use crate::store::IndexerStore;
+use crate::types::{IndexerResult, PrunableTable};
+
+const BACKFILL_CONCURRENCY: usize = 10;
+
+/// Tables that can be rebuilt from checkpoint data alone, independently of the rest of the
+/// database.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackfillTable {
+    Transactions,
+    /// `tx_senders`, `tx_recipients`, `tx_input_objects`, `tx_changed_objects` and `tx_calls`
+    TxIndices,
+    Events,
+    ObjectsHistory,
+}
+
+impl BackfillTable {
+    /// The table whose pruner watermark bounds the checkpoints that can be backfilled.
+    fn pruned_with(&self) -> PrunableTable {
+        match self {
+            BackfillTable::Transactions | BackfillTable::TxIndices => PrunableTable::Transactions,
+            BackfillTable::Events => PrunableTable::Events,
+            BackfillTable::ObjectsHistory => PrunableTable::ObjectsHistory,
+        }
+    }
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct BackfillConfig {
+    /// Tables to reindex. When set, the indexer backfills these tables for the given checkpoint
+    /// range and exits, instead of running as a writer or reader.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    pub backfill_tables: Vec<BackfillTable>,
+    /// First checkpoint to backfill, defaults to the first checkpoint not pruned from any of the
+    /// tables.
+    #[clap(long)]
+    pub backfill_start_checkpoint: Option<u64>,
+    /// Last checkpoint to backfill, defaults to the latest checkpoint committed by the writer.
+    #[clap(long)]
+    pub backfill_end_checkpoint: Option<u64>,
+    /// Number of checkpoints indexed in parallel.
+    #[clap(long, default_value_t = BACKFILL_CONCURRENCY)]
+    pub backfill_concurrency: usize,
+    /// Replace the rows already indexed for the backfilled checkpoints, to rebuild tables whose
+    /// content changed. Otherwise only missing rows are written.
+    #[clap(long)]
+    pub backfill_overwrite: bool,
+}
+
+impl BackfillConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.backfill_tables.is_empty()
+    }
+
+    /// Returns the range of checkpoints to backfill, which must be committed by the writer and
+    /// not pruned from any of the tables to backfill.
+    pub async fn checkpoint_range<S: IndexerStore>(
+        &self,
+        store: &S,
+    ) -> IndexerResult<(CheckpointSequenceNumber, CheckpointSequenceNumber)> {
+        let Some(latest_checkpoint) = store.get_latest_checkpoint_sequence_number().await? else {
+            return Err(IndexerError::InvalidArgumentError(
+                "Cannot backfill before the writer has committed any checkpoint".to_string(),
+            ));
+        };
+        let end = self.backfill_end_checkpoint.unwrap_or(latest_checkpoint);
+        if end > latest_checkpoint {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Cannot backfill up to checkpoint {end}, the writer has only committed up to \
+                 checkpoint {latest_checkpoint}"
+            )));
+        }
+
+        let mut first_available = 0;
+        for table in &self.backfill_tables {
+            if let Some(watermark) = store.get_pruner_watermark(table.pruned_with()).await? {
+                first_available = first_available.max(watermark.checkpoint_lo as u64);
+            }
+        }
+        let start = self.backfill_start_checkpoint.unwrap_or(first_available);
+        if start < first_available {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Cannot backfill from checkpoint {start}, checkpoints before {first_available} \
+                 are pruned"
+            )));
+        }
+        if start > end {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Backfill start checkpoint {start} is after end checkpoint {end}"
+            )));
+        }
+        Ok((start, end))
+    }
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            backfill_tables: vec![],
+            backfill_start_checkpoint: None,
+            backfill_end_checkpoint: None,
+            backfill_concurrency: BACKFILL_CONCURRENCY,
+            backfill_overwrite: false,
+        }
+    }
+}
+
+/// Reindexes the selected tables of each checkpoint it is given. Rows are written with the same
+/// idempotent inserts as the writer, and the `checkpoints`, `epochs` and `objects` tables the
+/// committer tracks its progress and the live object set with are never written to, so a backfill
+/// can run alongside the writer. When overwriting, the rows of a checkpoint are deleted before
+/// they are written again, so readers may briefly miss them.
+pub struct BackfillHandler<S, T: R2D2Connection + 'static> {
+    store: S,
+    metrics: IndexerMetrics,
+    tables: Vec<BackfillTable>,
+    end_checkpoint: CheckpointSequenceNumber,
+    overwrite: bool,
+    package_resolver: Arc<Resolver<IndexerStorePackageResolver<T>>>,
+}
+
+impl<S, T> BackfillHandler<S, T>
+where
+    S: IndexerStore + Clone + Sync + Send + 'static,
+    T: R2D2Connection + 'static,
+{
+    pub fn new(
+        store: S,
+        metrics: IndexerMetrics,
+        tables: Vec<BackfillTable>,
+        end_checkpoint: CheckpointSequenceNumber,
+        overwrite: bool,
+    ) -> Result<Self, IndexerError> {
+        let pg_blocking_cp = CheckpointHandler::<S, T>::pg_blocking_cp(store.clone())?;
+        // The packages of the checkpoints to backfill are committed by the writer already.
+        let package_resolver = Arc::new(Resolver::new(IndexerStorePackageResolver::new(
+            pg_blocking_cp,
+        )));
+        Ok(Self {
+            store,
+            metrics,
+            tables,
+            end_checkpoint,
+            overwrite,
+            package_resolver,
+        })
+    }
+
+    fn backfills(&self, table: BackfillTable) -> bool {
+        self.tables.contains(&table)
+    }
+}
+
+#[async_trait]
+impl<S, T> Worker for BackfillHandler<S, T>
+where
+    S: IndexerStore + Clone + Sync + Send + 'static,
+    T: R2D2Connection + 'static,
+{
+    async fn process_checkpoint(&self, checkpoint: CheckpointData) -> anyhow::Result<()> {
+        let checkpoint_seq = checkpoint.checkpoint_summary.sequence_number;
+        // The reader keeps fetching checkpoints until the executor exits.
+        if checkpoint_seq > self.end_checkpoint {
+            return Ok(());
+        }
+
+        if self.overwrite {
+            let tx_hi = checkpoint.checkpoint_summary.network_total_transactions;
+            let tx_lo = tx_hi - checkpoint.transactions.len() as u64;
+            futures::future::try_join_all(self.tables.iter().map(|table| {
+                self.store
+                    .delete_checkpoint_rows(*table, checkpoint_seq, tx_lo, tx_hi)
+            }))
+            .await?;
+        }
+
+        let mut persist_tasks = vec![];
+        if self.backfills(BackfillTable::ObjectsHistory) {
+            let object_history_changes = CheckpointHandler::<S, T>::index_objects_history(
+                checkpoint.clone(),
+                self.package_resolver.clone(),
+            )
+            .await?;
+            persist_tasks.push(
+                self.store
+                    .persist_object_history(vec![object_history_changes]),
+            );
+        }
+        if self.backfills(BackfillTable::Transactions)
+            || self.backfills(BackfillTable::TxIndices)
+            || self.backfills(BackfillTable::Events)
+        {
+            let CheckpointData {
+                transactions,
+                checkpoint_summary,
+                checkpoint_contents,
+            } = checkpoint;
+            let (db_transactions, db_events, db_indices, _) =
+                CheckpointHandler::<S, T>::index_transactions(
+                    transactions,
+                    &checkpoint_summary,
+                    &checkpoint_contents,
+                    &self.metrics,
+                )
+                .await?;
+            if self.backfills(BackfillTable::Transactions) {
+                persist_tasks.push(self.store.persist_transactions(db_transactions));
+            }
+            if self.backfills(BackfillTable::TxIndices) {
+                persist_tasks.push(self.store.persist_tx_indices(db_indices));
+            }
+            if self.backfills(BackfillTable::Events) {
+                persist_tasks.push(self.store.persist_events(db_events));
+            }
+        }
+        futures::future::try_join_all(persist_tasks).await?;
+        info!(checkpoint_seq, "Backfilled checkpoint");
+        Ok(())
+    }
+}
+
+/// Starts the executor at the first checkpoint to backfill, and stops it once every checkpoint up
+/// to the last one is processed.
+pub struct BackfillProgressStore {
+    start_checkpoint: CheckpointSequenceNumber,
+    end_checkpoint: CheckpointSequenceNumber,
+    exit_sender: Option<oneshot::Sender<()>>,
+}
+
+impl BackfillProgressStore {
+    pub fn new(
+        start_checkpoint: CheckpointSequenceNumber,
+        end_checkpoint: CheckpointSequenceNumber,
+        exit_sender: oneshot::Sender<()>,
+    ) -> Self {
+        Self {
+            start_checkpoint,
+            end_checkpoint,
+            exit_sender: Some(exit_sender),
+        }
+    }
+}
+
+#[async_trait]
+impl ProgressStore for BackfillProgressStore {
+    async fn load(&mut self, _: String) -> anyhow::Result<CheckpointSequenceNumber> {
+        Ok(self.start_checkpoint)
+    }
+
+    // `checkpoint_number` is the next checkpoint to process, all the checkpoints before it are
+    // processed.
+    async fn save(
+        &mut self,
+        _: String,
+        checkpoint_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<()> {
+        if checkpoint_number > self.end_checkpoint {
+            if let Some(exit_sender) = self.exit_sender.take() {
+                info!(
+                    "Backfilled checkpoints {} to {}",
+                    self.start_checkpoint, self.end_checkpoint
+                );
+                let _ = exit_sender.send(());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_backfill_progress_store_exits_after_end_checkpoint() {
+        let (exit_sender, mut exit_receiver) = oneshot::channel();
+        let mut progress_store = BackfillProgressStore::new(10, 20, exit_sender);
+        assert_eq!(
+            progress_store.load("backfill".to_string()).await.unwrap(),
+            10
+        );
+
+        progress_store
+            .save("backfill".to_string(), 20)
+            .await
+            .unwrap();
+        assert!(exit_receiver.try_recv().is_err());
+
+        progress_store
+            .save("backfill".to_string(), 21)
+            .await
+            .unwrap();
+        assert!(exit_receiver.try_recv().is_ok());
+
+        // Later progress does not need to signal again.
+        progress_store
+            .save("backfill".to_string(), 22)
+            .await
+            .unwrap();
+    }
+}
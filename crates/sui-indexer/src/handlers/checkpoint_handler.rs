@@ -316,7 +316,7 @@ where
         })
     }
 
-    async fn index_transactions(
+    pub(crate) async fn index_transactions(
         transactions: Vec<CheckpointTransaction>,
         checkpoint_summary: &CertifiedCheckpointSummary,
         checkpoint_contents: &CheckpointContents,
@@ -553,7 +553,7 @@ where
     }
 
     // similar to index_objects, but objects_history keeps all versions of objects
-    async fn index_objects_history(
+    pub(crate) async fn index_objects_history(
         data: CheckpointData,
         package_resolver: Arc<Resolver<impl PackageStore>>,
     ) -> Result<TransactionObjectChangesToCommit, IndexerError> {
@@ -674,7 +674,7 @@ where
             .collect()
     }
 
-    fn pg_blocking_cp(state: S) -> Result<ConnectionPool<T>, IndexerError> {
+    pub(crate) fn pg_blocking_cp(state: S) -> Result<ConnectionPool<T>, IndexerError> {
         let state_as_any = state.as_any();
         if let Some(pg_state) = state_as_any.downcast_ref::<PgIndexerStore<T>>() {
             return Ok(pg_state.blocking_cp());
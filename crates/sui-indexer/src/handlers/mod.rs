@@ -11,9 +11,11 @@ use crate::{
     },
 };
 
+pub mod backfill;
 pub mod checkpoint_handler;
 pub mod committer;
 pub mod objects_snapshot_processor;
+pub mod pruner;
 pub mod tx_processor;
 
 #[derive(Debug)]
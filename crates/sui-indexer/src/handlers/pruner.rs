@@ -0,0 +1,182 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+use std::time::Duration;
+
+use clap::Args;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use crate::store::IndexerStore;
+use crate::types::{IndexerResult, PrunableTable};
+
+const PRUNER_SLEEP_DURATION: Duration = Duration::from_secs(60);
+
+/// How many epochs of data to keep in each prunable table, including the current epoch. Tables
+/// without a retention are never pruned.
+#[derive(Args, Clone, Debug, Default)]
+pub struct RetentionConfig {
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub transactions_retention_epochs: Option<u64>,
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub events_retention_epochs: Option<u64>,
+    /// Object history is never pruned past the latest object snapshot, from which the objects
+    /// owned at a past checkpoint are rebuilt.
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub objects_history_retention_epochs: Option<u64>,
+}
+
+impl RetentionConfig {
+    pub fn retention_epochs(&self, table: PrunableTable) -> Option<u64> {
+        match table {
+            PrunableTable::Transactions => self.transactions_retention_epochs,
+            PrunableTable::Events => self.events_retention_epochs,
+            PrunableTable::ObjectsHistory => self.objects_history_retention_epochs,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        PrunableTable::ALL
+            .iter()
+            .any(|table| self.retention_epochs(*table).is_some())
+    }
+}
+
+pub struct Pruner<S> {
+    store: S,
+    config: RetentionConfig,
+    cancel: CancellationToken,
+}
+
+impl<S> Pruner<S>
+where
+    S: IndexerStore + Clone + Sync + Send + 'static,
+{
+    pub fn new(store: S, config: RetentionConfig, cancel: CancellationToken) -> Self {
+        Self {
+            store,
+            config,
+            cancel,
+        }
+    }
+
+    // Every table with a retention of N epochs keeps the current epoch and the N - 1 epochs
+    // before it. Older epochs are pruned one at a time, oldest first. The pruner watermark of
+    // the table records the first epoch, checkpoint and transaction still available to readers,
+    // and the first epoch not completely removed yet, from which pruning resumes after a failure
+    // or a restart.
+    pub async fn start(&self) -> IndexerResult<()> {
+        info!("Starting pruner with {:?}", self.config);
+        loop {
+            if let Err(e) = self.prune().await {
+                error!("Failed to prune: {e}");
+            }
+            tokio::select! {
+                _ = self.cancel.cancelled() => {
+                    info!("Shutdown signal received, terminating pruner");
+                    return Ok(());
+                }
+                _ = tokio::time::sleep(PRUNER_SLEEP_DURATION) => {}
+            }
+        }
+    }
+
+    async fn prune(&self) -> IndexerResult<()> {
+        let Some((first_epoch, current_epoch)) = self.store.get_epoch_range().await? else {
+            return Ok(());
+        };
+        for table in PrunableTable::ALL {
+            let Some(retention) = self.config.retention_epochs(table) else {
+                continue;
+            };
+            let pruned_epoch_lo = self
+                .store
+                .get_pruner_watermark(table)
+                .await?
+                .map(|watermark| watermark.pruned_epoch_lo as u64);
+            let epoch_cap = match table {
+                PrunableTable::ObjectsHistory => Some(self.snapshot_epoch().await?),
+                _ => None,
+            };
+            let epochs = epochs_to_prune(
+                first_epoch,
+                current_epoch,
+                pruned_epoch_lo,
+                retention,
+                epoch_cap,
+            );
+            for epoch in epochs {
+                if self.cancel.is_cancelled() {
+                    return Ok(());
+                }
+                self.store.prune_epoch(table, epoch).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the epoch of the latest object snapshot checkpoint, or 0 without a snapshot.
+    async fn snapshot_epoch(&self) -> IndexerResult<u64> {
+        let Some(checkpoint) = self
+            .store
+            .get_latest_object_snapshot_checkpoint_sequence_number()
+            .await?
+        else {
+            return Ok(0);
+        };
+        Ok(self
+            .store
+            .get_checkpoint_epoch(checkpoint)
+            .await?
+            .unwrap_or_default())
+    }
+}
+
+/// Returns the epochs to prune from a table keeping `retention` epochs, where `first_epoch` and
+/// `current_epoch` are the first and last epochs indexed, and `pruned_epoch_lo` is the first epoch
+/// not completely pruned yet, if the table was ever pruned. Epochs from `epoch_cap` on are kept
+/// regardless of the retention.
+fn epochs_to_prune(
+    first_epoch: u64,
+    current_epoch: u64,
+    pruned_epoch_lo: Option<u64>,
+    retention: u64,
+    epoch_cap: Option<u64>,
+) -> Range<u64> {
+    let prune_lo = pruned_epoch_lo.unwrap_or(first_epoch);
+    let mut prune_hi = (current_epoch + 1).saturating_sub(retention);
+    if let Some(epoch_cap) = epoch_cap {
+        prune_hi = prune_hi.min(epoch_cap);
+    }
+    prune_lo..prune_hi.max(prune_lo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epochs_to_prune() {
+        // Keeping 3 epochs at epoch 10 keeps epochs 8, 9 and 10.
+        assert_eq!(epochs_to_prune(0, 10, None, 3, None), 0..8);
+        // Pruning resumes from the first epoch not completely pruned.
+        assert_eq!(epochs_to_prune(0, 10, Some(5), 3, None), 5..8);
+        assert_eq!(epochs_to_prune(0, 10, Some(8), 3, None), 8..8);
+        // The indexer may start after genesis.
+        assert_eq!(epochs_to_prune(4, 10, None, 3, None), 4..8);
+        // Keeping a single epoch keeps the current one only.
+        assert_eq!(epochs_to_prune(0, 10, None, 1, None), 0..10);
+        // Nothing to prune while fewer epochs than the retention are indexed.
+        assert!(epochs_to_prune(0, 1, None, 3, None).is_empty());
+        assert!(epochs_to_prune(0, 10, None, 20, None).is_empty());
+        assert!(epochs_to_prune(4, 5, None, 3, None).is_empty());
+        // Object history is kept from the epoch of the latest object snapshot on.
+        assert_eq!(epochs_to_prune(0, 10, None, 3, Some(5)), 0..5);
+        assert_eq!(epochs_to_prune(0, 10, Some(2), 3, Some(5)), 2..5);
+        assert_eq!(epochs_to_prune(0, 10, None, 3, Some(9)), 0..8);
+        // Nothing is pruned before the snapshot passes the first epoch not pruned yet.
+        assert!(epochs_to_prune(0, 10, None, 3, Some(0)).is_empty());
+        assert!(epochs_to_prune(0, 10, Some(5), 3, Some(4)).is_empty());
+    }
+}
@@ -17,8 +17,10 @@ use sui_data_ingestion_core::{
 
 use crate::build_json_rpc_server;
 use crate::errors::IndexerError;
+use crate::handlers::backfill::{BackfillHandler, BackfillProgressStore};
 use crate::handlers::checkpoint_handler::new_handlers;
 use crate::handlers::objects_snapshot_processor::{ObjectsSnapshotProcessor, SnapshotLagConfig};
+use crate::handlers::pruner::Pruner;
 use crate::indexer_reader::IndexerReader;
 use crate::metrics::IndexerMetrics;
 use crate::store::IndexerStore;
@@ -98,6 +100,15 @@ impl Indexer {
         );
         spawn_monitored_task!(objects_snapshot_processor.start());
 
+        if config.retention_config.is_enabled() {
+            let pruner = Pruner::new(
+                store.clone(),
+                config.retention_config.clone(),
+                cancel.clone(),
+            );
+            spawn_monitored_task!(pruner.start());
+        }
+
         let cancel_clone = cancel.clone();
         let (exit_sender, exit_receiver) = oneshot::channel();
         // Spawn a task that links the cancellation token to the exit sender
@@ -136,6 +147,58 @@ impl Indexer {
         Ok(())
     }
 
+    pub async fn start_backfill<
+        S: IndexerStore + Sync + Send + Clone + 'static,
+        T: R2D2Connection + 'static,
+    >(
+        config: &IndexerConfig,
+        store: S,
+        metrics: IndexerMetrics,
+    ) -> Result<(), IndexerError> {
+        let backfill_config = &config.backfill_config;
+        let (start, end) = backfill_config.checkpoint_range(&store).await?;
+        info!(
+            "Sui Indexer Backfill (version {:?}) started for {:?}, checkpoints {} to {}",
+            env!("CARGO_PKG_VERSION"),
+            backfill_config.backfill_tables,
+            start,
+            end
+        );
+
+        let (exit_sender, exit_receiver) = oneshot::channel();
+        let mut executor = IndexerExecutor::new(
+            BackfillProgressStore::new(start, end, exit_sender),
+            1,
+            DataIngestionMetrics::new(&Registry::new()),
+        );
+        let worker = BackfillHandler::<S, T>::new(
+            store,
+            metrics,
+            backfill_config.backfill_tables.clone(),
+            end,
+            backfill_config.backfill_overwrite,
+        )?;
+        let worker_pool = WorkerPool::new(
+            worker,
+            "backfill".to_string(),
+            backfill_config.backfill_concurrency,
+        );
+        executor.register(worker_pool).await?;
+        executor
+            .run(
+                config
+                    .data_ingestion_path
+                    .clone()
+                    .unwrap_or(tempfile::tempdir().unwrap().into_path()),
+                config.remote_store_url.clone(),
+                vec![],
+                ReaderOptions::default(),
+                exit_receiver,
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn start_reader<T: R2D2Connection + 'static>(
         config: &IndexerConfig,
         registry: &Registry,
@@ -24,8 +24,8 @@ use sui_json_rpc_types::{
     SuiTransactionBlockEffectsAPI,
 };
 use sui_json_rpc_types::{
-    CheckpointId, EpochInfo, EventFilter, SuiEvent, SuiObjectDataFilter,
-    SuiTransactionBlockResponse, TransactionFilter,
+    CheckpointId, EpochInfo, EventFilter, IndexerAvailableRange, ObjectOwnerChange, SuiEvent,
+    SuiObjectDataFilter, SuiTransactionBlockResponse, TransactionFilter,
 };
 use sui_package_resolver::Package;
 use sui_package_resolver::PackageStore;
@@ -52,19 +52,28 @@ use crate::{
         display::StoredDisplay,
         epoch::StoredEpochInfo,
         events::StoredEvent,
-        objects::{CoinBalance, ObjectRefColumn, StoredObject},
+        objects::{CoinBalance, ObjectRefColumn, StoredHistoryObject, StoredObject},
+        pruner_watermarks::StoredPrunerWatermark,
         transactions::{tx_events_to_sui_tx_events, StoredTransaction},
-        tx_indices::TxSequenceNumber,
+        tx_indices::{TxDigest, TxSequenceNumber},
+    },
+    schema::{
+        checkpoints, display, epochs, events, objects, objects_snapshot, pruner_watermarks,
+        transactions,
     },
-    schema::{checkpoints, display, epochs, events, objects, objects_snapshot, transactions},
     store::package_resolver::IndexerStorePackageResolver,
-    types::{IndexerResult, OwnerType},
+    types::{IndexerResult, ObjectStatus, OwnerType, PrunableTable},
 };
 
 pub const TX_SEQUENCE_NUMBER_STR: &str = "tx_sequence_number";
 pub const TRANSACTION_DIGEST_STR: &str = "transaction_digest";
 pub const EVENT_SEQUENCE_NUMBER_STR: &str = "event_sequence_number";
 
+// Columns shared by `objects_snapshot` and `objects_history`, in the order of `objects_history`.
+const OBJECT_COLUMNS: &str = "object_id, object_version, object_status, object_digest, \
+    checkpoint_sequence_number, owner_type, owner_id, object_type, serialized_object, coin_type, \
+    coin_balance, df_kind, df_name, df_object_type, df_object_id";
+
 pub struct IndexerReader<T>
 where
     T: R2D2Connection + 'static,
@@ -386,6 +395,40 @@ impl<U: R2D2Connection> IndexerReader<U> {
         Ok(stored_checkpoint)
     }
 
+    pub fn get_pruner_watermark(
+        &self,
+        table: PrunableTable,
+    ) -> Result<Option<StoredPrunerWatermark>, IndexerError> {
+        run_query!(&self.pool, |conn| {
+            pruner_watermarks::table
+                .filter(pruner_watermarks::entity.eq(table.name()))
+                .first::<StoredPrunerWatermark>(conn)
+                .optional()
+        })
+    }
+
+    /// Returns the first checkpoint with data in `table`, which is genesis until the table is
+    /// pruned.
+    pub fn get_first_available_checkpoint(
+        &self,
+        table: PrunableTable,
+    ) -> Result<u64, IndexerError> {
+        Ok(self
+            .get_pruner_watermark(table)?
+            .map_or(0, |watermark| watermark.checkpoint_lo as u64))
+    }
+
+    pub fn get_available_range(&self) -> Result<IndexerAvailableRange, IndexerError> {
+        Ok(IndexerAvailableRange {
+            transactions_first_checkpoint: self
+                .get_first_available_checkpoint(PrunableTable::Transactions)?,
+            events_first_checkpoint: self.get_first_available_checkpoint(PrunableTable::Events)?,
+            objects_history_first_checkpoint: self
+                .get_first_available_checkpoint(PrunableTable::ObjectsHistory)?,
+            last_checkpoint: self.get_latest_checkpoint_from_db()?.sequence_number as u64,
+        })
+    }
+
     pub fn get_checkpoint(
         &self,
         checkpoint_id: CheckpointId,
@@ -1587,6 +1630,207 @@ impl<U: R2D2Connection> IndexerReader<U> {
         ))
     }
 
+    /// Returns the versions of `object_id` at which its owner changed, or at which it was
+    /// created, wrapped or deleted, after (or before, in descending order) the version `cursor`.
+    pub fn get_object_ownership_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<u64>,
+        limit: usize,
+        descending_order: bool,
+    ) -> Result<Vec<ObjectOwnerChange>, IndexerError> {
+        let (cursor_op, order) = if descending_order {
+            ("<", "DESC")
+        } else {
+            (">", "ASC")
+        };
+        let cursor_clause = cursor
+            .map(|version| format!("AND object_version {cursor_op} {version}"))
+            .unwrap_or_default();
+        // Every version is compared with the previous one, the first version has none.
+        let query = format!(
+            "
+            SELECT * FROM ( \
+                SELECT *, \
+                LAG(object_version) OVER w AS previous_version, \
+                LAG(object_status) OVER w AS previous_status, \
+                LAG(owner_type) OVER w AS previous_owner_type, \
+                LAG(owner_id) OVER w AS previous_owner_id \
+                FROM objects_history \
+                WHERE object_id = '\\x{}'::BYTEA \
+                WINDOW w AS (ORDER BY object_version) \
+            ) h \
+            WHERE (previous_version IS NULL \
+                OR object_status IS DISTINCT FROM previous_status \
+                OR owner_type IS DISTINCT FROM previous_owner_type \
+                OR owner_id IS DISTINCT FROM previous_owner_id) \
+            {cursor_clause} \
+            ORDER BY object_version {order} \
+            LIMIT {limit}
+        ",
+            Hex::encode(object_id.to_vec()),
+        );
+
+        tracing::debug!("get object ownership history query: {query}");
+        let history = run_query!(&self.pool, |conn| diesel::sql_query(query)
+            .load::<StoredHistoryObject>(conn))?;
+
+        let mut changes = Vec::with_capacity(history.len());
+        for stored in history {
+            let version = SequenceNumber::from_u64(stored.object_version as u64);
+            let checkpoint = stored.checkpoint_sequence_number as u64;
+            let (owner, transaction_digest) = if stored.object_status == ObjectStatus::Active as i16
+            {
+                let object: Object = StoredObject::try_from(stored)?.try_into()?;
+                (Some(object.owner), Some(object.previous_transaction))
+            } else {
+                let digest = self.get_wrapping_or_deleting_transaction(object_id, checkpoint)?;
+                (None, digest)
+            };
+            changes.push(ObjectOwnerChange {
+                object_id,
+                version,
+                checkpoint,
+                owner,
+                transaction_digest,
+            });
+        }
+        Ok(changes)
+    }
+
+    /// Returns the last transaction of `checkpoint` which took `object_id` as an input, which is
+    /// the transaction that wrapped or deleted the object in this checkpoint. Objects wrapped or
+    /// deleted without being an input, like dynamic fields, have no such transaction.
+    fn get_wrapping_or_deleting_transaction(
+        &self,
+        object_id: ObjectID,
+        checkpoint: u64,
+    ) -> Result<Option<TransactionDigest>, IndexerError> {
+        let query = format!(
+            "
+            SELECT t.transaction_digest \
+            FROM tx_input_objects i \
+            JOIN transactions t ON t.tx_sequence_number = i.tx_sequence_number \
+            WHERE i.object_id = '\\x{}'::BYTEA \
+            AND t.checkpoint_sequence_number = {checkpoint} \
+            ORDER BY i.tx_sequence_number DESC \
+            LIMIT 1
+        ",
+            Hex::encode(object_id.to_vec()),
+        );
+        let digest = run_query!(&self.pool, |conn| diesel::sql_query(query)
+            .get_result::<TxDigest>(conn)
+            .optional())?;
+        digest
+            .map(|d| {
+                TransactionDigest::try_from(d.transaction_digest.as_slice()).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Can't convert {:?} to a transaction digest: {e}",
+                        d.transaction_digest
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// Returns the objects `address` owned as of `checkpoint`, at their latest version at that
+    /// checkpoint, ordered by ID after `cursor`.
+    ///
+    /// `objects_history` only has a row when an object changes, so once it is pruned, objects
+    /// which did not change since the pruned epochs are only found in `objects_snapshot`. Owned
+    /// objects are then rebuilt from the snapshot and the history after it, which is only
+    /// possible at or after the snapshot checkpoint.
+    pub fn get_owned_objects_at_checkpoint(
+        &self,
+        address: SuiAddress,
+        checkpoint: u64,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<StoredObject>, IndexerError> {
+        let latest_checkpoint = self.get_latest_checkpoint_from_db()?.sequence_number as u64;
+        if checkpoint > latest_checkpoint {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Checkpoint {checkpoint} is not indexed yet, the latest indexed checkpoint is \
+                 {latest_checkpoint}"
+            )));
+        }
+        let snapshot_checkpoint = if self
+            .get_pruner_watermark(PrunableTable::ObjectsHistory)?
+            .is_some()
+        {
+            // The pruner never removes history past the snapshot, so the snapshot exists.
+            let (snapshot_checkpoint, _) = self.get_consistent_read_range()?;
+            if checkpoint < snapshot_checkpoint as u64 {
+                return Err(IndexerError::InvalidArgumentError(format!(
+                    "Owned objects at checkpoint {checkpoint} are unavailable, object history is \
+                     pruned and owned objects are only available from checkpoint \
+                     {snapshot_checkpoint}"
+                )));
+            }
+            Some(snapshot_checkpoint)
+        } else {
+            None
+        };
+
+        let owner = Hex::encode(address.to_vec());
+        let cursor_clause = cursor
+            .map(|id| format!("AND object_id > '\\x{}'::BYTEA", Hex::encode(id.to_vec())))
+            .unwrap_or_default();
+        let owner_clause = format!(
+            "owner_type = {} AND owner_id = '\\x{owner}'::BYTEA",
+            OwnerType::Address as i16
+        );
+        // The history after the snapshot, or all of it while nothing is pruned.
+        let history_clause = format!(
+            "checkpoint_sequence_number > {} AND checkpoint_sequence_number <= {checkpoint}",
+            snapshot_checkpoint.unwrap_or(-1),
+        );
+        let (snapshot_candidates, snapshot_rows) = if snapshot_checkpoint.is_some() {
+            (
+                format!(
+                    "SELECT object_id FROM objects_snapshot \
+                     WHERE {owner_clause} {cursor_clause} UNION "
+                ),
+                format!(
+                    "SELECT {OBJECT_COLUMNS} FROM objects_snapshot \
+                     WHERE object_id IN (SELECT object_id FROM candidates) UNION ALL "
+                ),
+            )
+        } else {
+            (String::new(), String::new())
+        };
+        // Only objects the address owned at the snapshot or since are candidates, the latest
+        // version of each at the checkpoint tells whether the address still owned it.
+        let query = format!(
+            "
+            WITH candidates AS ( \
+                {snapshot_candidates} \
+                SELECT object_id FROM objects_history \
+                WHERE {owner_clause} AND {history_clause} {cursor_clause} \
+            ) \
+            SELECT * FROM ( \
+                SELECT DISTINCT ON (object_id) * FROM ( \
+                    {snapshot_rows} \
+                    SELECT {OBJECT_COLUMNS} FROM objects_history \
+                    WHERE {history_clause} \
+                    AND object_id IN (SELECT object_id FROM candidates) \
+                ) r \
+                ORDER BY object_id, object_version DESC \
+            ) o \
+            WHERE o.object_status = {active} \
+            AND {owner_clause} \
+            ORDER BY o.object_id ASC \
+            LIMIT {limit}
+        ",
+            active = ObjectStatus::Active as i16,
+        );
+
+        tracing::debug!("get owned objects at checkpoint query: {query}");
+        let objects = run_query!(&self.pool, |conn| diesel::sql_query(query)
+            .load::<StoredHistoryObject>(conn))?;
+        objects.into_iter().map(StoredObject::try_from).collect()
+    }
+
     pub fn package_resolver(&self) -> PackageResolver<U> {
         self.package_resolver.clone()
     }
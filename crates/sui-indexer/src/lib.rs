@@ -28,6 +28,8 @@ use crate::apis::{
     CoinReadApi, ExtendedApi, GovernanceReadApi, IndexerApi, MoveUtilsApi, ReadApi,
     TransactionBuilderApi, WriteApi,
 };
+use crate::handlers::backfill::BackfillConfig;
+use crate::handlers::pruner::RetentionConfig;
 use crate::indexer_reader::IndexerReader;
 use errors::IndexerError;
 
@@ -85,6 +87,10 @@ pub struct IndexerConfig {
     pub rpc_server_worker: bool,
     #[clap(long)]
     pub data_ingestion_path: Option<PathBuf>,
+    #[clap(flatten)]
+    pub retention_config: RetentionConfig,
+    #[clap(flatten)]
+    pub backfill_config: BackfillConfig,
 }
 
 impl IndexerConfig {
@@ -138,6 +144,8 @@ impl Default for IndexerConfig {
             fullnode_sync_worker: true,
             rpc_server_worker: true,
             data_ingestion_path: None,
+            retention_config: RetentionConfig::default(),
+            backfill_config: BackfillConfig::default(),
         }
     }
 }
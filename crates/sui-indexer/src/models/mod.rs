@@ -7,5 +7,6 @@ pub mod epoch;
 pub mod events;
 pub mod objects;
 pub mod packages;
+pub mod pruner_watermarks;
 pub mod transactions;
 pub mod tx_indices;
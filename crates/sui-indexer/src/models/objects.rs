@@ -169,6 +169,43 @@ impl From<StoredObject> for StoredHistoryObject {
     }
 }
 
+impl TryFrom<StoredHistoryObject> for StoredObject {
+    type Error = IndexerError;
+
+    fn try_from(o: StoredHistoryObject) -> Result<Self, Self::Error> {
+        let (object_id, object_version) = (o.object_id.clone(), o.object_version);
+        let missing = |column: &str| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "history of object {object_id:?} at version {object_version} is missing {column}"
+            ))
+        };
+        if o.object_status != ObjectStatus::Active as i16 {
+            return Err(IndexerError::PersistentStorageDataCorruptionError(format!(
+                "object {:?} at version {} is wrapped or deleted",
+                o.object_id, o.object_version
+            )));
+        }
+        Ok(Self {
+            object_digest: o.object_digest.ok_or_else(|| missing("object_digest"))?,
+            owner_type: o.owner_type.ok_or_else(|| missing("owner_type"))?,
+            serialized_object: o
+                .serialized_object
+                .ok_or_else(|| missing("serialized_object"))?,
+            object_id: o.object_id,
+            object_version: o.object_version,
+            checkpoint_sequence_number: o.checkpoint_sequence_number,
+            owner_id: o.owner_id,
+            object_type: o.object_type,
+            coin_type: o.coin_type,
+            coin_balance: o.coin_balance,
+            df_kind: o.df_kind,
+            df_name: o.df_name,
+            df_object_type: o.df_object_type,
+            df_object_id: o.df_object_id,
+        })
+    }
+}
+
 #[derive(Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName)]
 #[diesel(table_name = objects, primary_key(object_id))]
 pub struct StoredDeletedObject {
@@ -549,6 +586,28 @@ mod tests {
         assert_eq!(sui_coin.coin_type, "0x2::sui::SUI");
     }
 
+    #[test]
+    fn test_history_object_round_trip() {
+        let test_obj = Object::new_gas_for_testing();
+        let indexed_obj = IndexedObject::from_object(1, test_obj, None);
+
+        let stored_obj = StoredObject::from(indexed_obj);
+        let history_obj = StoredHistoryObject::from(stored_obj.clone());
+        let round_trip = StoredObject::try_from(history_obj).unwrap();
+        assert_eq!(round_trip.serialized_object, stored_obj.serialized_object);
+        assert_eq!(round_trip.owner_type, stored_obj.owner_type);
+
+        let deleted_history_obj = StoredHistoryObject {
+            object_version: stored_obj.object_version + 1,
+            object_status: ObjectStatus::WrappedOrDeleted as i16,
+            object_digest: None,
+            owner_type: None,
+            serialized_object: None,
+            ..StoredHistoryObject::from(stored_obj)
+        };
+        assert!(StoredObject::try_from(deleted_history_obj).is_err());
+    }
+
     #[test]
     fn test_output_format_coin_balance() {
         let test_obj = Object::new_gas_for_testing();
@@ -0,0 +1,20 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema::pruner_watermarks;
+
+/// The lowest epoch, checkpoint and transaction sequence number which have not been pruned from
+/// a table.
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = pruner_watermarks, primary_key(entity))]
+pub struct StoredPrunerWatermark {
+    pub entity: String,
+    pub epoch_lo: i64,
+    pub checkpoint_lo: i64,
+    pub tx_lo: i64,
+    /// The first epoch whose data is not completely removed yet. Readers stop serving an epoch
+    /// before its data is removed, so this is at most `epoch_lo`, and pruning resumes from it.
+    pub pruned_epoch_lo: i64,
+}
@@ -157,6 +157,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    pruner_watermarks (entity) {
+        entity -> Text,
+        epoch_lo -> Int8,
+        checkpoint_lo -> Int8,
+        tx_lo -> Int8,
+        pruned_epoch_lo -> Int8,
+    }
+}
+
 diesel::table! {
     transactions (tx_sequence_number, checkpoint_sequence_number) {
         tx_sequence_number -> Int8,
@@ -236,6 +246,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     objects_history_partition_0,
     objects_snapshot,
     packages,
+    pruner_watermarks,
     transactions,
     transactions_partition_0,
     tx_calls,
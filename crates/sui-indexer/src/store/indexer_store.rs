@@ -7,10 +7,14 @@ use std::collections::BTreeMap;
 use async_trait::async_trait;
 
 use crate::errors::IndexerError;
+use crate::handlers::backfill::BackfillTable;
 use crate::handlers::{EpochToCommit, TransactionObjectChangesToCommit};
 use crate::models::display::StoredDisplay;
 use crate::models::objects::{StoredDeletedObject, StoredObject};
-use crate::types::{IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex};
+use crate::models::pruner_watermarks::StoredPrunerWatermark;
+use crate::types::{
+    IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, PrunableTable, TxIndex,
+};
 
 #[allow(clippy::large_enum_variant)]
 pub enum ObjectChangeToCommit {
@@ -75,5 +79,29 @@ pub trait IndexerStore: Any + Clone + Sync + Send + 'static {
         epoch: u64,
     ) -> Result<u64, IndexerError>;
 
+    // first and last epochs in the DB
+    async fn get_epoch_range(&self) -> Result<Option<(u64, u64)>, IndexerError>;
+
+    // epoch of an indexed checkpoint
+    async fn get_checkpoint_epoch(&self, checkpoint: u64) -> Result<Option<u64>, IndexerError>;
+
+    async fn get_pruner_watermark(
+        &self,
+        table: PrunableTable,
+    ) -> Result<Option<StoredPrunerWatermark>, IndexerError>;
+
+    // remove the data of an ended epoch from a table, moving its pruner watermark past the epoch
+    async fn prune_epoch(&self, table: PrunableTable, epoch: u64) -> Result<(), IndexerError>;
+
+    // remove the rows of a checkpoint from a table before it is backfilled again, where
+    // `[tx_lo, tx_hi)` are the transactions of the checkpoint
+    async fn delete_checkpoint_rows(
+        &self,
+        table: BackfillTable,
+        checkpoint: u64,
+        tx_lo: u64,
+        tx_hi: u64,
+    ) -> Result<(), IndexerError>;
+
     fn as_any(&self) -> &dyn Any;
 }
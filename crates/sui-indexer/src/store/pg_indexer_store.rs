@@ -5,12 +5,13 @@ use std::any::Any as StdAny;
 use std::collections::hash_map::Entry;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::num::NonZeroU64;
 use std::time::Duration;
 use std::time::Instant;
 
 use async_trait::async_trait;
 use core::result::Result::Ok;
-use diesel::dsl::max;
+use diesel::dsl::{max, min};
 use diesel::r2d2::R2D2Connection;
 use diesel::upsert::excluded;
 use diesel::ExpressionMethods;
@@ -25,6 +26,7 @@ use sui_types::base_types::ObjectID;
 
 use crate::db::ConnectionPool;
 use crate::errors::{Context, IndexerError};
+use crate::handlers::backfill::BackfillTable;
 use crate::handlers::EpochToCommit;
 use crate::handlers::TransactionObjectChangesToCommit;
 use crate::metrics::IndexerMetrics;
@@ -37,12 +39,16 @@ use crate::models::objects::{
     StoredObjectSnapshot,
 };
 use crate::models::packages::StoredPackage;
+use crate::models::pruner_watermarks::StoredPrunerWatermark;
 use crate::models::transactions::StoredTransaction;
 use crate::schema::{
     checkpoints, display, epochs, events, objects, objects_history, objects_snapshot, packages,
-    transactions, tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
+    pruner_watermarks, transactions, tx_calls, tx_changed_objects, tx_input_objects, tx_recipients,
+    tx_senders,
+};
+use crate::types::{
+    IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, PrunableTable, TxIndex,
 };
-use crate::types::{IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex};
 use crate::{read_only_blocking, transactional_blocking_with_retry};
 
 use super::pg_partition_manager::{EpochPartitionData, PgPartitionManager};
@@ -73,6 +79,16 @@ const PG_COMMIT_PARALLEL_CHUNK_SIZE: usize = 100;
 // optimistic locking.
 const PG_COMMIT_OBJECTS_PARALLEL_CHUNK_SIZE: usize = 500;
 const PG_DB_COMMIT_SLEEP_DURATION: Duration = Duration::from_secs(3600);
+// The amount of transactions whose rows are deleted in one DB transaction when pruning
+const PG_PRUNING_BATCH_SIZE: u64 = 10000;
+// Tables indexing transactions, pruned along with the `transactions` table
+const TX_INDICES_TABLES: [&str; 5] = [
+    "tx_senders",
+    "tx_recipients",
+    "tx_input_objects",
+    "tx_changed_objects",
+    "tx_calls",
+];
 
 // with rn = 1, we only select the latest version of each object,
 // so that we don't have to update the same object multiple times.
@@ -108,6 +124,7 @@ pub struct PgIndexerStore<T: R2D2Connection + 'static> {
     metrics: IndexerMetrics,
     parallel_chunk_size: usize,
     parallel_objects_chunk_size: usize,
+    pruning_batch_size: u64,
     partition_manager: PgPartitionManager<T>,
 }
 
@@ -118,6 +135,7 @@ impl<T: R2D2Connection> Clone for PgIndexerStore<T> {
             metrics: self.metrics.clone(),
             parallel_chunk_size: self.parallel_chunk_size,
             parallel_objects_chunk_size: self.parallel_objects_chunk_size,
+            pruning_batch_size: self.pruning_batch_size,
             partition_manager: self.partition_manager.clone(),
         }
     }
@@ -133,6 +151,11 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
             .unwrap_or_else(|_e| PG_COMMIT_OBJECTS_PARALLEL_CHUNK_SIZE.to_string())
             .parse::<usize>()
             .unwrap();
+        let pruning_batch_size = std::env::var("PG_PRUNING_BATCH_SIZE")
+            .unwrap_or_else(|_e| PG_PRUNING_BATCH_SIZE.to_string())
+            .parse::<NonZeroU64>()
+            .expect("PG_PRUNING_BATCH_SIZE should be a positive integer")
+            .get();
         let partition_manager = PgPartitionManager::new(blocking_cp.clone())
             .expect("Failed to initialize partition manager");
 
@@ -141,6 +164,7 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
             metrics,
             parallel_chunk_size,
             parallel_objects_chunk_size,
+            pruning_batch_size,
             partition_manager,
         }
     }
@@ -910,6 +934,195 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
         .map(|v| v as u64)
     }
 
+    fn get_epoch_range(&self) -> Result<Option<(u64, u64)>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::table
+                .select((min(epochs::epoch), max(epochs::epoch)))
+                .first::<(Option<i64>, Option<i64>)>(conn)
+        })
+        .context("Failed to read the epoch range from PostgresDB")
+        .map(|range| match range {
+            (Some(first), Some(last)) => Some((first as u64, last as u64)),
+            _ => None,
+        })
+    }
+
+    fn get_checkpoint_epoch(&self, checkpoint: u64) -> Result<Option<u64>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::table
+                .filter(checkpoints::sequence_number.eq(checkpoint as i64))
+                .select(checkpoints::epoch)
+                .first::<i64>(conn)
+                .optional()
+        })
+        .context("Failed to read the epoch of a checkpoint from PostgresDB")
+        .map(|epoch| epoch.map(|epoch| epoch as u64))
+    }
+
+    fn get_pruner_watermark(
+        &self,
+        table: PrunableTable,
+    ) -> Result<Option<StoredPrunerWatermark>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            pruner_watermarks::table
+                .filter(pruner_watermarks::entity.eq(table.name()))
+                .first::<StoredPrunerWatermark>(conn)
+                .optional()
+        })
+        .context("Failed to read pruner watermark from PostgresDB")
+    }
+
+    fn prune_epoch(&self, table: PrunableTable, epoch: u64) -> Result<(), IndexerError> {
+        let last_checkpoint: Option<i64> = read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::table
+                .filter(epochs::epoch.eq(epoch as i64))
+                .select(epochs::last_checkpoint_id)
+                .first::<Option<i64>>(conn)
+        })
+        .context("Failed to read epoch to prune from PostgresDB")?;
+        let Some(last_checkpoint) = last_checkpoint else {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Cannot prune epoch {epoch}, which has not ended yet"
+            )));
+        };
+        let tx_lo = if epoch == 0 {
+            0
+        } else {
+            self.get_network_total_transactions_by_end_of_epoch(epoch - 1)?
+        };
+        let tx_hi = self.get_network_total_transactions_by_end_of_epoch(epoch)?;
+        if table == PrunableTable::ObjectsHistory {
+            // Owned objects at a checkpoint are rebuilt from the object snapshot and the history
+            // after it, so only history already folded into the snapshot can be removed.
+            let snapshot_checkpoint =
+                self.get_latest_object_snapshot_checkpoint_sequence_number()?;
+            if snapshot_checkpoint.map_or(true, |snapshot| last_checkpoint as u64 >= snapshot) {
+                return Err(IndexerError::InvalidArgumentError(format!(
+                    "Cannot prune epoch {epoch} from {}, the object snapshot is at checkpoint \
+                     {snapshot_checkpoint:?}",
+                    table.name()
+                )));
+            }
+        }
+
+        // Move the watermark first, so that readers stop serving the epoch before it is removed.
+        // `pruned_epoch_lo` only moves once the epoch is removed, so that a failed attempt is
+        // retried.
+        let watermark = StoredPrunerWatermark {
+            entity: table.name().to_string(),
+            epoch_lo: epoch as i64 + 1,
+            checkpoint_lo: last_checkpoint + 1,
+            tx_lo: tx_hi as i64,
+            pruned_epoch_lo: epoch as i64,
+        };
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(pruner_watermarks::table)
+                    .values(watermark.clone())
+                    .on_conflict(pruner_watermarks::entity)
+                    .do_update()
+                    .set((
+                        pruner_watermarks::epoch_lo.eq(excluded(pruner_watermarks::epoch_lo)),
+                        pruner_watermarks::checkpoint_lo
+                            .eq(excluded(pruner_watermarks::checkpoint_lo)),
+                        pruner_watermarks::tx_lo.eq(excluded(pruner_watermarks::tx_lo)),
+                    ))
+                    .execute(conn)
+            },
+            PG_DB_COMMIT_SLEEP_DURATION
+        )
+        .context("Failed to update pruner watermark in PostgresDB")?;
+
+        match table {
+            PrunableTable::Transactions => {
+                self.partition_manager
+                    .drop_table_partition(table.name().to_string(), epoch)?;
+                for tx_indices_table in TX_INDICES_TABLES {
+                    self.prune_tx_range(tx_indices_table, tx_lo, tx_hi)?;
+                }
+            }
+            PrunableTable::Events => self.prune_tx_range(table.name(), tx_lo, tx_hi)?,
+            PrunableTable::ObjectsHistory => self
+                .partition_manager
+                .drop_table_partition(table.name().to_string(), epoch)?,
+        }
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::update(pruner_watermarks::table)
+                    .filter(pruner_watermarks::entity.eq(table.name()))
+                    .set(pruner_watermarks::pruned_epoch_lo.eq(epoch as i64 + 1))
+                    .execute(conn)
+            },
+            PG_DB_COMMIT_SLEEP_DURATION
+        )
+        .context("Failed to update pruner watermark in PostgresDB")?;
+        info!(epoch, "Pruned epoch from {}", table.name());
+        Ok(())
+    }
+
+    fn delete_checkpoint_rows(
+        &self,
+        table: BackfillTable,
+        checkpoint: u64,
+        tx_lo: u64,
+        tx_hi: u64,
+    ) -> Result<(), IndexerError> {
+        match table {
+            BackfillTable::Transactions => {
+                self.prune_tx_range(PrunableTable::Transactions.name(), tx_lo, tx_hi)?
+            }
+            BackfillTable::TxIndices => {
+                for tx_indices_table in TX_INDICES_TABLES {
+                    self.prune_tx_range(tx_indices_table, tx_lo, tx_hi)?;
+                }
+            }
+            BackfillTable::Events => {
+                self.prune_tx_range(PrunableTable::Events.name(), tx_lo, tx_hi)?
+            }
+            BackfillTable::ObjectsHistory => {
+                transactional_blocking_with_retry!(
+                    &self.blocking_cp,
+                    |conn| {
+                        diesel::delete(objects_history::table.filter(
+                            objects_history::checkpoint_sequence_number.eq(checkpoint as i64),
+                        ))
+                        .execute(conn)
+                    },
+                    PG_DB_COMMIT_SLEEP_DURATION
+                )
+                .context("Failed to delete objects history from PostgresDB")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the rows of `table` with a transaction sequence number in `[tx_lo, tx_hi)`, in
+    /// batches so that each database transaction stays small.
+    fn prune_tx_range(&self, table: &str, tx_lo: u64, tx_hi: u64) -> Result<(), IndexerError> {
+        for (batch_lo, batch_hi) in tx_range_batches(tx_lo, tx_hi, self.pruning_batch_size) {
+            transactional_blocking_with_retry!(
+                &self.blocking_cp,
+                |conn| {
+                    RunQueryDsl::execute(
+                        diesel::sql_query(format!(
+                            "DELETE FROM {table} \
+                             WHERE tx_sequence_number >= $1 AND tx_sequence_number < $2"
+                        ))
+                        .bind::<diesel::sql_types::BigInt, _>(batch_lo as i64)
+                        .bind::<diesel::sql_types::BigInt, _>(batch_hi as i64),
+                        conn,
+                    )
+                },
+                PG_DB_COMMIT_SLEEP_DURATION
+            )
+            .context(&format!("Failed to delete from {table} in PostgresDB"))?;
+        }
+        Ok(())
+    }
+
     async fn execute_in_blocking_worker<F, R>(&self, f: F) -> Result<R, IndexerError>
     where
         F: FnOnce(Self) -> Result<R, IndexerError> + Send + 'static,
@@ -1347,6 +1560,42 @@ impl<T: R2D2Connection> IndexerStore for PgIndexerStore<T> {
         .await
     }
 
+    async fn get_epoch_range(&self) -> Result<Option<(u64, u64)>, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_epoch_range())
+            .await
+    }
+
+    async fn get_checkpoint_epoch(&self, checkpoint: u64) -> Result<Option<u64>, IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.get_checkpoint_epoch(checkpoint))
+            .await
+    }
+
+    async fn get_pruner_watermark(
+        &self,
+        table: PrunableTable,
+    ) -> Result<Option<StoredPrunerWatermark>, IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.get_pruner_watermark(table))
+            .await
+    }
+
+    async fn prune_epoch(&self, table: PrunableTable, epoch: u64) -> Result<(), IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.prune_epoch(table, epoch))
+            .await
+    }
+
+    async fn delete_checkpoint_rows(
+        &self,
+        table: BackfillTable,
+        checkpoint: u64,
+        tx_lo: u64,
+        tx_hi: u64,
+    ) -> Result<(), IndexerError> {
+        self.execute_in_blocking_worker(move |this| {
+            this.delete_checkpoint_rows(table, checkpoint, tx_lo, tx_hi)
+        })
+        .await
+    }
+
     fn as_any(&self) -> &dyn StdAny {
         self
     }
@@ -1420,3 +1669,31 @@ fn make_objects_history_to_commit(
         )
         .collect()
 }
+
+/// Splits `[tx_lo, tx_hi)` into consecutive ranges of at most `batch_size` transactions.
+fn tx_range_batches(tx_lo: u64, tx_hi: u64, batch_size: u64) -> impl Iterator<Item = (u64, u64)> {
+    (tx_lo..tx_hi)
+        .step_by(batch_size as usize)
+        .map(move |batch_lo| (batch_lo, tx_hi.min(batch_lo + batch_size)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tx_range_batches() {
+        assert_eq!(
+            tx_range_batches(10, 35, 10).collect::<Vec<_>>(),
+            vec![(10, 20), (20, 30), (30, 35)]
+        );
+        // The last batch is full when the range is a multiple of the batch size.
+        assert_eq!(
+            tx_range_batches(0, 20, 10).collect::<Vec<_>>(),
+            vec![(0, 10), (10, 20)]
+        );
+        assert_eq!(tx_range_batches(5, 6, 10).collect::<Vec<_>>(), vec![(5, 6)]);
+        // Epochs without transactions have nothing to delete.
+        assert_eq!(tx_range_batches(7, 7, 10).count(), 0);
+    }
+}
@@ -134,4 +134,55 @@ impl<T: R2D2Connection> PgPartitionManager<T> {
         }
         Ok(())
     }
+
+    /// Drops the partition of `table` holding the checkpoints of the epoch `partition`. The last
+    /// partition, which new checkpoints are written to, cannot be dropped.
+    pub fn drop_table_partition(&self, table: String, partition: u64) -> Result<(), IndexerError> {
+        let last_partition = self.get_table_partitions()?.get(&table).copied();
+        check_droppable_partition(&table, partition, last_partition)?;
+        transactional_blocking_with_retry!(
+            &self.cp,
+            |conn| {
+                RunQueryDsl::execute(
+                    diesel::sql_query(format!(
+                        "DROP TABLE IF EXISTS {table}_partition_{partition}"
+                    )),
+                    conn,
+                )
+            },
+            Duration::from_secs(10)
+        )?;
+        info!("Dropped partition {} of table {}", partition, table);
+        Ok(())
+    }
+}
+
+fn check_droppable_partition(
+    table: &str,
+    partition: u64,
+    last_partition: Option<u64>,
+) -> Result<(), IndexerError> {
+    if last_partition.map_or(true, |last| partition >= last) {
+        return Err(IndexerError::InvalidArgumentError(format!(
+            "Cannot drop partition {partition} of table {table}, its last partition is \
+             {last_partition:?}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_droppable_partition() {
+        assert!(check_droppable_partition("transactions", 3, Some(5)).is_ok());
+        assert!(check_droppable_partition("transactions", 4, Some(5)).is_ok());
+        // The last partition receives new checkpoints.
+        assert!(check_droppable_partition("transactions", 5, Some(5)).is_err());
+        assert!(check_droppable_partition("transactions", 6, Some(5)).is_err());
+        // Tables which are not partitioned have no partition to drop.
+        assert!(check_droppable_partition("events", 0, None).is_err());
+    }
 }
@@ -250,6 +250,34 @@ impl TryFrom<i16> for OwnerType {
     }
 }
 
+/// Data which the pruner removes once it falls out of its retention period. Checkpoints and
+/// epochs are never pruned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PrunableTable {
+    /// The `transactions` table, and the transaction indices (`tx_senders`, `tx_recipients`,
+    /// `tx_input_objects`, `tx_changed_objects` and `tx_calls`).
+    Transactions,
+    Events,
+    ObjectsHistory,
+}
+
+impl PrunableTable {
+    pub const ALL: [PrunableTable; 3] = [
+        PrunableTable::Transactions,
+        PrunableTable::Events,
+        PrunableTable::ObjectsHistory,
+    ];
+
+    /// Name of the table, which also identifies its watermark in `pruner_watermarks`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrunableTable::Transactions => "transactions",
+            PrunableTable::Events => "events",
+            PrunableTable::ObjectsHistory => "objects_history",
+        }
+    }
+}
+
 // Returns owner_type, owner_address
 pub fn owner_to_owner_info(owner: &Owner) -> (OwnerType, Option<SuiAddress>) {
     match owner {
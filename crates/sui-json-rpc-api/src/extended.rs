@@ -5,9 +5,11 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
 use sui_json_rpc_types::{
-    CheckpointedObjectID, EpochInfo, EpochPage, QueryObjectsPage, SuiObjectResponseQuery,
+    CheckpointedObjectID, EpochInfo, EpochPage, IndexerAvailableRange, ObjectOwnershipHistoryPage,
+    ObjectsPage, QueryObjectsPage, SuiObjectDataOptions, SuiObjectResponseQuery,
 };
 use sui_open_rpc_macros::open_rpc;
+use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::sui_serde::BigInt;
 
 #[open_rpc(namespace = "suix", tag = "Extended API")]
@@ -41,6 +43,44 @@ pub trait ExtendedApi {
         limit: Option<usize>,
     ) -> RpcResult<QueryObjectsPage>;
 
+    /// Return the versions of an object at which its owner changed, including its creation and
+    /// the versions at which it was wrapped or deleted, with the transactions which made the
+    /// changes.
+    #[method(name = "getObjectOwnershipHistory")]
+    async fn get_object_ownership_history(
+        &self,
+        /// the ID of the object
+        object_id: ObjectID,
+        /// optional paging cursor, the object version of the last owner change of the previous page
+        cursor: Option<BigInt<u64>>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+        /// flag to return results in descending order
+        descending_order: Option<bool>,
+    ) -> RpcResult<ObjectOwnershipHistoryPage>;
+
+    /// Return the objects owned by an address as of a checkpoint, at their versions at that
+    /// checkpoint.
+    #[method(name = "getOwnedObjectsAtCheckpoint")]
+    async fn get_owned_objects_at_checkpoint(
+        &self,
+        /// the owner's Sui address
+        address: SuiAddress,
+        /// the checkpoint at which to list the owned objects
+        checkpoint: BigInt<u64>,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+        /// An optional paging cursor. If provided, the query will start from the next item after the specified cursor. Default to start from the first item if not specified.
+        cursor: Option<ObjectID>,
+        /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage>;
+
     #[method(name = "getTotalTransactions")]
     async fn get_total_transactions(&self) -> RpcResult<BigInt<u64>>;
+
+    /// Return the range of checkpoints the indexer has transactions, events and object versions
+    /// for, which starts after genesis if older data was pruned.
+    #[method(name = "getAvailableRange")]
+    async fn get_available_range(&self) -> RpcResult<IndexerAvailableRange>;
 }
@@ -12,15 +12,20 @@ use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 
 use sui_types::base_types::AuthorityName;
-use sui_types::base_types::{EpochId, ObjectID};
+use sui_types::base_types::{EpochId, ObjectID, SequenceNumber};
 use sui_types::committee::Committee;
+use sui_types::digests::TransactionDigest;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::Owner;
 use sui_types::sui_serde::BigInt;
+use sui_types::sui_serde::SequenceNumber as AsSequenceNumber;
 use sui_types::sui_system_state::sui_system_state_summary::SuiValidatorSummary;
 
 use crate::Page;
 
 pub type EpochPage = Page<EpochInfo, BigInt<u64>>;
+/// `next_cursor` points to the object version of the last owner change in the page.
+pub type ObjectOwnershipHistoryPage = Page<ObjectOwnerChange, BigInt<u64>>;
 
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -58,6 +63,49 @@ impl EpochInfo {
     }
 }
 
+/// A version of an object at which its owner changed, or at which the object was created,
+/// wrapped or deleted.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectOwnerChange {
+    pub object_id: ObjectID,
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub version: SequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+    /// The owner of the object from this version on, none if the object was wrapped or deleted
+    pub owner: Option<Owner>,
+    /// The transaction which produced this version, none if it is no longer indexed
+    pub transaction_digest: Option<TransactionDigest>,
+}
+
+/// The checkpoints the indexer has data for. Checkpoints are kept from genesis on, but older
+/// transactions, events and object versions may have been pruned.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexerAvailableRange {
+    /// first checkpoint whose transactions are available
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub transactions_first_checkpoint: CheckpointSequenceNumber,
+    /// first checkpoint whose events are available
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub events_first_checkpoint: CheckpointSequenceNumber,
+    /// first checkpoint at which object versions are available
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub objects_history_first_checkpoint: CheckpointSequenceNumber,
+    /// latest indexed checkpoint
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub last_checkpoint: CheckpointSequenceNumber,
+}
+
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -0,0 +1,459 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that the dependencies of a package, as built from their local sources, match the
+//! bytecode they are published with on chain.
+//!
+//! Verification happens in three steps, so that this crate does not need to talk to a network:
+//! [published_dependencies] lists the dependencies with a published address, the caller fetches
+//! the modules of the packages at these addresses, and
+//! [CompiledPackage::verify_published_dependencies] compares them with the local build.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::Context;
+use move_binary_format::{normalized, CompiledModule};
+use move_core_types::account_address::AccountAddress;
+use move_package::lock_file::schema::ManagedPackage;
+use move_package::resolution::resolution_graph::ResolvedGraph;
+use move_package::source_package::layout::SourcePackageLayout;
+use move_symbol_pool::Symbol;
+use sui_types::base_types::ObjectID;
+use sui_types::is_system_package;
+
+use crate::{published_at_property, CompiledPackage};
+
+/// A dependency of the package being built which is published on chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishedDependency {
+    pub name: Symbol,
+    /// ID of the package version the local sources of the dependency should match.
+    pub storage_id: ObjectID,
+    /// ID the modules of the dependency run under on chain, if the dependency is built with `0x0`
+    /// as its address because it is managed through its `Move.lock`. Dependencies with a
+    /// `published-at` address in their manifest are built with their original ID instead.
+    pub original_id: Option<ObjectID>,
+}
+
+/// Modules of an on-chain package, by name.
+pub type OnChainModules = BTreeMap<String, Vec<u8>>;
+
+/// Returns the dependencies in `resolution_graph` which are published on the chain `chain_id`,
+/// according to the managed addresses in their `Move.lock`, or to the `published-at` address in
+/// their manifest.
+///
+/// System packages are skipped: the framework sources a package depends on rarely match the
+/// framework version the network runs exactly, and upgrades are checked against the framework on
+/// chain anyway.
+pub fn published_dependencies(
+    resolution_graph: &ResolvedGraph,
+    chain_id: &str,
+) -> anyhow::Result<Vec<PublishedDependency>> {
+    let root = resolution_graph.root_package();
+    let mut dependencies = vec![];
+    for (name, package) in &resolution_graph.package_table {
+        if *name == root {
+            continue;
+        }
+
+        let lock_file = package.package_path.join(SourcePackageLayout::Lock.path());
+        let managed = managed_package(&lock_file, chain_id)?;
+        let dependency = if let Some(managed) = managed {
+            PublishedDependency {
+                name: *name,
+                storage_id: ObjectID::from_str(&managed.latest_published_id)?,
+                original_id: Some(ObjectID::from_str(&managed.original_published_id)?),
+            }
+        } else if let Ok(published_at) = published_at_property(package) {
+            PublishedDependency {
+                name: *name,
+                storage_id: published_at,
+                original_id: None,
+            }
+        } else {
+            continue;
+        };
+
+        if !is_system_package(dependency.storage_id) {
+            dependencies.push(dependency);
+        }
+    }
+    Ok(dependencies)
+}
+
+/// Returns the managed addresses of a package on the chain `chain_id`, according to its
+/// `lock_file`.
+fn managed_package(lock_file: &Path, chain_id: &str) -> anyhow::Result<Option<ManagedPackage>> {
+    let contents = match std::fs::read_to_string(lock_file) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to read {}", lock_file.display()))
+        }
+    };
+    let context = || format!("Failed to parse {}", lock_file.display());
+    // Lock files without an `env` table don't have any managed address.
+    let lock: toml::Value = toml::from_str(&contents).with_context(context)?;
+    if lock.get("env").is_none() {
+        return Ok(None);
+    }
+    let envs = ManagedPackage::read(&mut contents.as_bytes()).with_context(context)?;
+    Ok(envs.into_values().find(|env| env.chain_id == chain_id))
+}
+
+/// Dependencies whose local build does not match their on-chain bytecode.
+#[derive(Debug)]
+pub struct DependencyVerificationError {
+    pub mismatches: Vec<DependencyMismatch>,
+}
+
+#[derive(Debug)]
+pub struct DependencyMismatch {
+    pub name: Symbol,
+    pub storage_id: ObjectID,
+    pub modules: Vec<ModuleMismatch>,
+}
+
+#[derive(Debug)]
+pub enum ModuleMismatch {
+    /// The package on chain could not be fetched.
+    PackageNotFound,
+    MissingOnChain(String),
+    MissingLocally(String),
+    /// The module exists on chain and locally, with the listed differences.
+    Differs {
+        module: String,
+        differences: Vec<String>,
+    },
+}
+
+impl CompiledPackage {
+    /// Compares the modules of `dependencies` in this package with the modules of their packages
+    /// on chain, where `on_chain` holds the modules of the package at each storage ID.
+    pub fn verify_published_dependencies(
+        &self,
+        dependencies: &[PublishedDependency],
+        on_chain: &BTreeMap<ObjectID, OnChainModules>,
+    ) -> Result<(), DependencyVerificationError> {
+        let mut mismatches = vec![];
+        for dependency in dependencies {
+            let modules = match on_chain.get(&dependency.storage_id) {
+                Some(on_chain) => self.dependency_mismatches(dependency, on_chain),
+                None => vec![ModuleMismatch::PackageNotFound],
+            };
+            if !modules.is_empty() {
+                mismatches.push(DependencyMismatch {
+                    name: dependency.name,
+                    storage_id: dependency.storage_id,
+                    modules,
+                });
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(DependencyVerificationError { mismatches })
+        }
+    }
+
+    fn dependency_mismatches(
+        &self,
+        dependency: &PublishedDependency,
+        on_chain: &OnChainModules,
+    ) -> Vec<ModuleMismatch> {
+        let local: BTreeMap<_, _> = self
+            .package
+            .deps_compiled_units
+            .iter()
+            .filter(|(package, _)| *package == dependency.name)
+            .map(|(_, unit)| {
+                let mut module = unit.unit.module.clone();
+                // Managed dependencies are built at `0x0`, their modules run under their original
+                // ID on chain.
+                if let Some(original_id) = dependency.original_id {
+                    let self_address = module.self_handle().address.0 as usize;
+                    if module.address_identifiers[self_address] == AccountAddress::ZERO {
+                        module.address_identifiers[self_address] = original_id.into();
+                    }
+                }
+                (module.self_id().name().to_string(), module)
+            })
+            .collect();
+
+        let mut mismatches = vec![];
+        for (name, bytes) in on_chain {
+            let Some(local) = local.get(name) else {
+                mismatches.push(ModuleMismatch::MissingLocally(name.clone()));
+                continue;
+            };
+            let differences = match CompiledModule::deserialize_with_defaults(bytes) {
+                Ok(on_chain) if on_chain == *local => continue,
+                Ok(on_chain) => module_differences(local, &on_chain),
+                Err(e) => vec![format!("cannot deserialize the on-chain module: {e}")],
+            };
+            mismatches.push(ModuleMismatch::Differs {
+                module: name.clone(),
+                differences,
+            });
+        }
+        for name in local.keys() {
+            if !on_chain.contains_key(name) {
+                mismatches.push(ModuleMismatch::MissingOnChain(name.clone()));
+            }
+        }
+        mismatches
+    }
+}
+
+/// Describes how the `local` build of a module differs from the module `on_chain`, in terms of
+/// the declarations of the module.
+fn module_differences(local: &CompiledModule, on_chain: &CompiledModule) -> Vec<String> {
+    let local = normalized::Module::new(local);
+    let on_chain = normalized::Module::new(on_chain);
+    let mut differences = vec![];
+
+    if local.address != on_chain.address {
+        differences.push(format!(
+            "module address is {} locally and {} on chain",
+            local.address.to_canonical_display(/* with_prefix */ true),
+            on_chain
+                .address
+                .to_canonical_display(/* with_prefix */ true),
+        ));
+    }
+    if local.file_format_version != on_chain.file_format_version {
+        differences.push(format!(
+            "bytecode version is {} locally and {} on chain",
+            local.file_format_version, on_chain.file_format_version
+        ));
+    }
+    if local.dependencies != on_chain.dependencies {
+        differences.push("module dependencies differ".to_string());
+    }
+    if local.friends != on_chain.friends {
+        differences.push("friends differ".to_string());
+    }
+    diff_declarations(
+        "struct",
+        &local.structs,
+        &on_chain.structs,
+        &mut differences,
+    );
+    diff_declarations(
+        "function",
+        &local.functions,
+        &on_chain.functions,
+        &mut differences,
+    );
+    if local.constants != on_chain.constants {
+        differences.push("constants differ".to_string());
+    }
+
+    if differences.is_empty() {
+        differences
+            .push("declarations match, but the bytecode is laid out differently".to_string());
+    }
+    differences
+}
+
+fn diff_declarations<K: fmt::Display + Ord, V: PartialEq>(
+    kind: &str,
+    local: &BTreeMap<K, V>,
+    on_chain: &BTreeMap<K, V>,
+    differences: &mut Vec<String>,
+) {
+    for (name, declaration) in local {
+        match on_chain.get(name) {
+            None => differences.push(format!("{kind} {name} is not on chain")),
+            Some(on_chain) if on_chain != declaration => {
+                differences.push(format!("{kind} {name} differs"))
+            }
+            Some(_) => (),
+        }
+    }
+    for name in on_chain.keys() {
+        if !local.contains_key(name) {
+            differences.push(format!("{kind} {name} is only on chain"));
+        }
+    }
+}
+
+impl fmt::Display for DependencyVerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Local dependency sources do not match their on-chain bytecode:"
+        )?;
+        for mismatch in &self.mismatches {
+            writeln!(f, "- {} ({}):", mismatch.name, mismatch.storage_id)?;
+            for module in &mismatch.modules {
+                match module {
+                    ModuleMismatch::PackageNotFound => {
+                        writeln!(f, "    package not found on chain")?
+                    }
+                    ModuleMismatch::MissingOnChain(name) => {
+                        writeln!(f, "    module {name} is not on chain")?
+                    }
+                    ModuleMismatch::MissingLocally(name) => {
+                        writeln!(f, "    module {name} is only on chain")?
+                    }
+                    ModuleMismatch::Differs {
+                        module,
+                        differences,
+                    } => {
+                        writeln!(f, "    module {module}:")?;
+                        for difference in differences {
+                            writeln!(f, "      {difference}")?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DependencyVerificationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_from_resolution_graph, BuildConfig};
+    use move_binary_format::file_format::{empty_module, Constant, SignatureToken};
+    use std::path::Path;
+
+    #[test]
+    fn test_module_differences() {
+        let local = empty_module();
+        let mut on_chain = local.clone();
+        on_chain.constant_pool.push(Constant {
+            type_: SignatureToken::U8,
+            data: vec![1],
+        });
+        assert_eq!(
+            module_differences(&local, &on_chain),
+            vec!["constants differ".to_string()]
+        );
+
+        on_chain.address_identifiers[0] = AccountAddress::ONE;
+        let differences = module_differences(&local, &on_chain);
+        assert_eq!(differences.len(), 2);
+        assert!(differences[0].starts_with("module address is 0x0"));
+    }
+
+    #[test]
+    fn test_managed_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_file = dir.path().join("Move.lock");
+
+        // Packages without a lock file or without managed addresses were never published
+        assert!(managed_package(&lock_file, "4c78adac").unwrap().is_none());
+        std::fs::write(&lock_file, "[move]\nversion = 1\n").unwrap();
+        assert!(managed_package(&lock_file, "4c78adac").unwrap().is_none());
+
+        std::fs::write(
+            &lock_file,
+            "[move]\nversion = 1\n\n[env.testnet]\nchain-id = \"4c78adac\"\n\
+             original-published-id = \"0x1234\"\nlatest-published-id = \"0x5678\"\n\
+             published-version = \"2\"\n",
+        )
+        .unwrap();
+        let managed = managed_package(&lock_file, "4c78adac").unwrap().unwrap();
+        assert_eq!(managed.latest_published_id, "0x5678");
+        assert!(managed_package(&lock_file, "35834a8a").unwrap().is_none());
+
+        // Corrupt lock files are reported rather than treated as unpublished
+        std::fs::write(&lock_file, "[move\nversion = 1\n").unwrap();
+        let err = managed_package(&lock_file, "4c78adac").unwrap_err();
+        assert!(format!("{err:#}").contains(&lock_file.display().to_string()));
+        std::fs::write(
+            &lock_file,
+            "[move]\nversion = 1\n\n[env.testnet]\nchain-id = 1\n",
+        )
+        .unwrap();
+        managed_package(&lock_file, "4c78adac").unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_published_dependencies() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("unit_tests")
+            .join("data")
+            .join("verify_deps")
+            .join("root");
+        let resolution_graph = BuildConfig::new_for_testing()
+            .resolution_graph(&path)
+            .unwrap();
+        let dependencies = published_dependencies(&resolution_graph, "4c78adac").unwrap();
+        let storage_id = ObjectID::from_hex_literal("0xabc").unwrap();
+        assert_eq!(
+            dependencies,
+            vec![PublishedDependency {
+                name: Symbol::from("dep"),
+                storage_id,
+                original_id: None,
+            }]
+        );
+        let compiled = build_from_resolution_graph(
+            path,
+            resolution_graph,
+            /* run_bytecode_verifier */ true,
+            /* print_diags_to_stderr */ false,
+        )
+        .unwrap();
+
+        let published: OnChainModules = compiled
+            .package
+            .deps_compiled_units
+            .iter()
+            .map(|(_, unit)| {
+                let mut bytes = vec![];
+                unit.unit.module.serialize(&mut bytes).unwrap();
+                (unit.unit.module.self_id().name().to_string(), bytes)
+            })
+            .collect();
+        let verify = |modules: OnChainModules| {
+            compiled.verify_published_dependencies(
+                &dependencies,
+                &BTreeMap::from([(storage_id, modules)]),
+            )
+        };
+        verify(published.clone()).unwrap();
+
+        // A module which is only published, and one which is only local
+        let mut modules = published.clone();
+        let b = modules.remove("b").unwrap();
+        modules.insert("c".to_string(), b.clone());
+        let err = verify(modules).unwrap_err();
+        assert_eq!(err.mismatches.len(), 1);
+        let mismatches = &err.mismatches[0].modules;
+        assert_eq!(mismatches.len(), 2);
+        assert!(matches!(&mismatches[0], ModuleMismatch::MissingLocally(name) if name == "c"));
+        assert!(matches!(&mismatches[1], ModuleMismatch::MissingOnChain(name) if name == "b"));
+
+        // A module published with different bytecode
+        let mut modules = published;
+        modules.insert("a".to_string(), b);
+        let err = verify(modules).unwrap_err();
+        assert!(matches!(
+            &err.mismatches[0].modules[..],
+            [ModuleMismatch::Differs { module, .. }] if module == "a"
+        ));
+
+        // A dependency which is not on chain
+        let err = compiled
+            .verify_published_dependencies(&dependencies, &BTreeMap::new())
+            .unwrap_err();
+        assert!(matches!(
+            &err.mismatches[0].modules[..],
+            [ModuleMismatch::PackageNotFound]
+        ));
+        assert!(err.to_string().contains("package not found on chain"));
+    }
+}
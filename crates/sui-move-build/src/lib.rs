@@ -50,6 +50,8 @@ use sui_types::{
 };
 use sui_verifier::verifier as sui_bytecode_verifier;
 
+pub mod dependency_verification;
+
 #[cfg(test)]
 #[path = "unit_tests/build_tests.rs"]
 mod build_tests;
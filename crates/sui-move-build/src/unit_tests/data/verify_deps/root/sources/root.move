@@ -0,0 +1,6 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module root::root {
+    public fun three(): u64 { dep::a::one() + dep::b::two() }
+}
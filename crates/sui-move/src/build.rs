@@ -6,8 +6,13 @@ use move_cli::base;
 use move_package::source_package::layout::SourcePackageLayout;
 use move_package::BuildConfig as MoveBuildConfig;
 use serde_json::json;
-use std::{fs, path::PathBuf};
-use sui_move_build::{check_invalid_dependencies, check_unpublished_dependencies, BuildConfig};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use sui_move_build::{
+    check_invalid_dependencies, check_unpublished_dependencies, BuildConfig, CompiledPackage,
+};
 
 const LAYOUTS_DIR: &str = "layouts";
 const STRUCT_LAYOUTS_FILENAME: &str = "struct_layouts.yaml";
@@ -29,6 +34,11 @@ pub struct Build {
     /// and events.
     #[clap(long, global = true)]
     pub generate_struct_layouts: bool,
+    /// Check that the dependencies with a published address match their bytecode on the network
+    /// of the active client environment, and fail the build otherwise. Only supported by
+    /// `sui move build`, which has access to the client configuration.
+    #[clap(long, global = true)]
+    pub verify_deps_on_chain: bool,
 }
 
 impl Build {
@@ -37,6 +47,11 @@ impl Build {
         path: Option<PathBuf>,
         build_config: MoveBuildConfig,
     ) -> anyhow::Result<()> {
+        if self.verify_deps_on_chain {
+            anyhow::bail!(
+                "--verify-deps-on-chain needs a client configuration, use `sui move build` instead"
+            );
+        }
         let rerooted_path = base::reroot_path(path)?;
         let build_config = resolve_lock_file_path(build_config, Some(rerooted_path.clone()))?;
         Self::execute_internal(
@@ -61,6 +76,24 @@ impl Build {
             print_diags_to_stderr: true,
         }
         .build(rerooted_path.clone())?;
+        Self::output_package(
+            pkg,
+            &rerooted_path,
+            with_unpublished_deps,
+            dump_bytecode_as_base64,
+            generate_struct_layouts,
+        )
+    }
+
+    /// Outputs the bytecode and struct layouts of a package built from `rerooted_path` if asked
+    /// to, and records the toolchain version it was built with in its `Move.lock`.
+    pub fn output_package(
+        pkg: CompiledPackage,
+        rerooted_path: &Path,
+        with_unpublished_deps: bool,
+        dump_bytecode_as_base64: bool,
+        generate_struct_layouts: bool,
+    ) -> anyhow::Result<()> {
         if dump_bytecode_as_base64 {
             check_invalid_dependencies(&pkg.dependency_ids.invalid)?;
             if !with_unpublished_deps {
@@ -92,7 +125,7 @@ impl Build {
         pkg.package
             .compiled_package_info
             .build_flags
-            .update_lock_file_toolchain_version(&rerooted_path, env!("CARGO_PKG_VERSION").into())?;
+            .update_lock_file_toolchain_version(rerooted_path, env!("CARGO_PKG_VERSION").into())?;
 
         Ok(())
     }
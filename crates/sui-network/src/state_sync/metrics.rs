@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use mysten_metrics::histogram::Histogram;
-use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_with_registry, IntCounter, IntCounterVec, IntGauge, Registry,
+};
 use std::sync::Arc;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use tap::Pipe;
@@ -45,6 +48,27 @@ impl Metrics {
         }
     }
 
+    pub fn inc_peer_request_failures(&self, reason: &str) {
+        if let Some(inner) = &self.0 {
+            inner
+                .peer_request_failures
+                .with_label_values(&[reason])
+                .inc();
+        }
+    }
+
+    pub fn inc_peer_bans(&self) {
+        if let Some(inner) = &self.0 {
+            inner.peer_bans.inc();
+        }
+    }
+
+    pub fn set_banned_peers(&self, num_banned_peers: usize) {
+        if let Some(inner) = &self.0 {
+            inner.banned_peers.set(num_banned_peers as i64);
+        }
+    }
+
     pub fn checkpoint_summary_age_metric(&self) -> Option<&Histogram> {
         if let Some(inner) = &self.0 {
             return Some(&inner.checkpoint_summary_age_ms);
@@ -58,6 +82,9 @@ struct Inner {
     highest_verified_checkpoint: IntGauge,
     highest_synced_checkpoint: IntGauge,
     checkpoint_summary_age_ms: Histogram,
+    peer_request_failures: IntCounterVec,
+    peer_bans: IntCounter,
+    banned_peers: IntGauge,
 }
 
 impl Inner {
@@ -89,6 +116,28 @@ impl Inner {
                 "Age of checkpoints summaries when they arrive and are verified.",
                 registry,
             ),
+
+            peer_request_failures: register_int_counter_vec_with_registry!(
+                "state_sync_peer_request_failures",
+                "Number of failed state sync requests to peers, by reason",
+                &["reason"],
+                registry
+            )
+            .unwrap(),
+
+            peer_bans: register_int_counter_with_registry!(
+                "state_sync_peer_bans",
+                "Number of times a peer was temporarily banned from state sync",
+                registry
+            )
+            .unwrap(),
+
+            banned_peers: register_int_gauge_with_registry!(
+                "state_sync_banned_peers",
+                "Number of peers currently banned from state sync",
+                registry
+            )
+            .unwrap(),
         }
         .pipe(Arc::new)
     }
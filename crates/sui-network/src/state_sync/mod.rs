@@ -27,7 +27,9 @@
 //!   our latest checkpoint, and is intended to be used as a guarantee of data availability.
 //!
 //! The `PeerHeights` struct is used to track the highest_synced_checkpoint watermark for all of
-//! our peers.
+//! our peers, along with their `PeerScores`, which track the latency and failures of the requests
+//! we make to them. Peers are queried in order of their scores, and peers which keep failing
+//! requests or which serve invalid data are temporarily banned.
 //!
 //! When a new checkpoint is discovered, and we've determined that it is higher than our
 //! highest_verified_checkpoint, then StateSync will kick off a task to synchronize and verify all
@@ -66,8 +68,9 @@ use sui_types::{
     },
     storage::WriteStore,
 };
-use tap::{Pipe, TapFallible, TapOptional};
+use tap::Pipe;
 use tokio::sync::oneshot;
+use tokio::time::Instant;
 use tokio::{
     sync::{broadcast, mpsc, watch},
     task::{AbortHandle, JoinSet},
@@ -79,6 +82,7 @@ mod generated {
 }
 mod builder;
 mod metrics;
+mod peer_scores;
 mod server;
 #[cfg(test)]
 mod tests;
@@ -88,12 +92,17 @@ pub use generated::{
     state_sync_client::StateSyncClient,
     state_sync_server::{StateSync, StateSyncServer},
 };
+pub use peer_scores::PeerScore;
 pub use server::GetCheckpointAvailabilityResponse;
 pub use server::GetCheckpointSummaryRequest;
 use sui_archival::reader::ArchiveReaderBalancer;
 use sui_storage::verify_checkpoint;
 
-use self::{metrics::Metrics, server::CheckpointContentsDownloadLimitLayer};
+use self::{
+    metrics::Metrics,
+    peer_scores::{PeerScores, RequestFailure},
+    server::CheckpointContentsDownloadLimitLayer,
+};
 
 /// A handle to the StateSync subsystem.
 ///
@@ -103,6 +112,7 @@ use self::{metrics::Metrics, server::CheckpointContentsDownloadLimitLayer};
 pub struct Handle {
     sender: mpsc::Sender<StateSyncMessage>,
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
+    peer_heights: Arc<RwLock<PeerHeights>>,
 }
 
 impl Handle {
@@ -125,8 +135,52 @@ impl Handle {
     pub fn subscribe_to_synced_checkpoints(&self) -> broadcast::Receiver<VerifiedCheckpoint> {
         self.checkpoint_event_sender.subscribe()
     }
+
+    /// Report what StateSync knows of the checkpoints its peers have synchronized.
+    pub fn peer_progress(&self) -> PeerProgress {
+        let peer_heights = self.peer_heights.read().unwrap();
+        let mut peers: Vec<_> = peer_heights
+            .peers_on_same_chain()
+            .map(|(peer_id, info)| (*peer_id, info.height))
+            .collect();
+        peers.sort_by_key(|(_, height)| std::cmp::Reverse(*height));
+        let mut banned_peers: Vec<_> = peer_heights
+            .scores
+            .scores()
+            .keys()
+            .filter(|peer_id| peer_heights.scores.is_banned(peer_id))
+            .copied()
+            .collect();
+        banned_peers.sort();
+        PeerProgress {
+            highest_known_checkpoint: peer_heights.highest_known_checkpoint_sequence_number(),
+            num_peers_on_other_chains: peer_heights.peers.len() - peers.len(),
+            peers,
+            num_unprocessed_checkpoints: peer_heights.unprocessed_checkpoints.len(),
+            banned_peers,
+            peer_scores: peer_heights.scores.scores().clone(),
+        }
+    }
 }
 
+/// A snapshot of the synchronization progress of our peers, as known by StateSync.
+#[derive(Clone, Debug)]
+pub struct PeerProgress {
+    /// Highest checkpoint synchronized by a peer on the same chain as us.
+    pub highest_known_checkpoint: Option<CheckpointSequenceNumber>,
+    /// Peers on the same chain as us with their highest synchronized checkpoint, highest first.
+    pub peers: Vec<(PeerId, CheckpointSequenceNumber)>,
+    /// Number of peers which are not on the same chain as us.
+    pub num_peers_on_other_chains: usize,
+    /// Number of checkpoints announced by peers which haven't been verified yet.
+    pub num_unprocessed_checkpoints: usize,
+    /// Peers which are currently banned from state sync.
+    pub banned_peers: Vec<PeerId>,
+    /// How well each peer has served our requests so far.
+    pub peer_scores: HashMap<PeerId, PeerScore>,
+}
+
+#[derive(Debug)]
 struct PeerHeights {
     /// Table used to track the highest checkpoint for each of our peers.
     peers: HashMap<PeerId, PeerStateSyncInfo>,
@@ -135,6 +189,9 @@ struct PeerHeights {
 
     // The amount of time to wait before retry if there are no peers to sync content from.
     wait_interval_when_no_peer_to_sync_content: Duration,
+
+    /// Reliability of our peers, used to pick which peers to request data from.
+    scores: PeerScores,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -266,7 +323,7 @@ impl PeerHeights {
     }
 }
 
-// PeerBalancer is an Iterator that selects peers based on RTT with some added randomness.
+// PeerBalancer is an Iterator that selects peers based on their scores with some added randomness.
 #[derive(Clone)]
 struct PeerBalancer {
     peers: VecDeque<(anemo::Peer, PeerStateSyncInfo)>,
@@ -286,15 +343,17 @@ impl PeerBalancer {
         peer_heights: Arc<RwLock<PeerHeights>>,
         request_type: PeerCheckpointRequestType,
     ) -> Self {
+        let peer_heights = peer_heights.read().unwrap();
         let mut peers: Vec<_> = peer_heights
-            .read()
-            .unwrap()
             .peers_on_same_chain()
-            // Filter out any peers who we aren't connected with.
+            // Filter out any peers who are banned or who we aren't connected with.
+            .filter(|(peer_id, _info)| !peer_heights.scores.is_banned(peer_id))
             .filter_map(|(peer_id, info)| network.peer(*peer_id).map(|peer| (peer, *info)))
             .collect();
-        peers.sort_by(|(peer_a, _), (peer_b, _)| {
-            peer_a.connection_rtt().cmp(&peer_b.connection_rtt())
+        peers.sort_by_cached_key(|(peer, _)| {
+            peer_heights
+                .scores
+                .selection_cost(&peer.peer_id(), peer.connection_rtt())
         });
         Self {
             peers: peers.into(),
@@ -388,6 +447,7 @@ where
         let mut peer_events = {
             let (subscriber, peers) = self.network.subscribe().unwrap();
             for peer_id in peers {
+                self.peer_heights.write().unwrap().scores.add_peer(&peer_id);
                 self.spawn_get_latest_from_peer(peer_id);
             }
             subscriber
@@ -603,10 +663,13 @@ where
 
         match peer_event {
             Ok(PeerEvent::NewPeer(peer_id)) => {
+                self.peer_heights.write().unwrap().scores.add_peer(&peer_id);
                 self.spawn_get_latest_from_peer(peer_id);
             }
             Ok(PeerEvent::LostPeer(peer_id, _)) => {
-                self.peer_heights.write().unwrap().peers.remove(&peer_id);
+                let mut peer_heights = self.peer_heights.write().unwrap();
+                peer_heights.peers.remove(&peer_id);
+                peer_heights.scores.remove_peer(&peer_id);
             }
 
             Err(RecvError::Closed) => {
@@ -649,6 +712,8 @@ where
         if let Some(layer) = self.download_limit_layer.as_ref() {
             layer.maybe_prune_map();
         }
+
+        self.peer_heights.write().unwrap().scores.expire_bans();
     }
 
     fn maybe_start_checkpoint_summary_sync_task(&mut self) {
@@ -788,10 +853,23 @@ async fn get_latest_from_peer(
             // as us
             let request = Request::new(GetCheckpointSummaryRequest::BySequenceNumber(0))
                 .with_timeout(timeout);
+            let start = Instant::now();
             let response = client
                 .get_checkpoint_summary(request)
                 .await
                 .map(Response::into_inner);
+            match &response {
+                Ok(_) => peer_heights.write().unwrap().scores.record_success(
+                    peer_id,
+                    start.elapsed(),
+                    None,
+                ),
+                Err(status) => peer_heights
+                    .write()
+                    .unwrap()
+                    .scores
+                    .record_failure(peer_id, RequestFailure::from_status(status)),
+            }
 
             let info = match response {
                 Ok(Some(checkpoint)) => {
@@ -827,7 +905,7 @@ async fn get_latest_from_peer(
         return;
     }
     let Some((highest_checkpoint, low_watermark)) =
-        query_peer_for_latest_info(&mut client, timeout).await
+        query_peer_for_latest_info(&mut client, &peer_heights, timeout).await
     else {
         return;
     };
@@ -840,9 +918,12 @@ async fn get_latest_from_peer(
 /// Queries a peer for their highest_synced_checkpoint and low checkpoint watermark
 async fn query_peer_for_latest_info(
     client: &mut StateSyncClient<anemo::Peer>,
+    peer_heights: &RwLock<PeerHeights>,
     timeout: Duration,
 ) -> Option<(Checkpoint, Option<CheckpointSequenceNumber>)> {
+    let peer_id = client.inner().peer_id();
     let request = Request::new(()).with_timeout(timeout);
+    let start = Instant::now();
     let response = client
         .get_checkpoint_availability(request)
         .await
@@ -852,12 +933,22 @@ async fn query_peer_for_latest_info(
             highest_synced_checkpoint,
             lowest_available_checkpoint,
         }) => {
+            peer_heights
+                .write()
+                .unwrap()
+                .scores
+                .record_success(peer_id, start.elapsed(), None);
             return Some((highest_synced_checkpoint, Some(lowest_available_checkpoint)));
         }
         Err(status) => {
             // If peer hasn't upgraded they would return 404 NotFound error
             if status.status() != anemo::types::response::StatusCode::NotFound {
                 trace!("get_checkpoint_availability request failed: {status:?}");
+                peer_heights
+                    .write()
+                    .unwrap()
+                    .scores
+                    .record_failure(peer_id, RequestFailure::from_status(&status));
                 return None;
             }
         }
@@ -866,15 +957,28 @@ async fn query_peer_for_latest_info(
     // Then we try the old query
     // TODO: remove this once the new feature stabilizes
     let request = Request::new(GetCheckpointSummaryRequest::Latest).with_timeout(timeout);
+    let start = Instant::now();
     let response = client
         .get_checkpoint_summary(request)
         .await
         .map(Response::into_inner);
     match response {
-        Ok(Some(checkpoint)) => Some((checkpoint, None)),
+        Ok(Some(checkpoint)) => {
+            peer_heights
+                .write()
+                .unwrap()
+                .scores
+                .record_success(peer_id, start.elapsed(), None);
+            Some((checkpoint, None))
+        }
         Ok(None) => None,
         Err(status) => {
             trace!("get_checkpoint_summary (latest) request failed: {status:?}");
+            peer_heights
+                .write()
+                .unwrap()
+                .scores
+                .record_failure(peer_id, RequestFailure::from_status(&status));
             None
         }
     }
@@ -887,18 +991,23 @@ async fn query_peers_for_their_latest_checkpoint(
     timeout: Duration,
 ) {
     let peer_heights = &peer_heights;
-    let futs = peer_heights
-        .read()
-        .unwrap()
-        .peers_on_same_chain()
-        // Filter out any peers who we aren't connected with
-        .flat_map(|(peer_id, _info)| network.peer(*peer_id))
+    let peers: Vec<_> = {
+        let peer_heights = peer_heights.read().unwrap();
+        peer_heights
+            .peers_on_same_chain()
+            // Filter out any peers who are banned or who we aren't connected with
+            .filter(|(peer_id, _info)| !peer_heights.scores.is_banned(peer_id))
+            .flat_map(|(peer_id, _info)| network.peer(*peer_id))
+            .collect()
+    };
+    let futs = peers
+        .into_iter()
         .map(|peer| {
             let peer_id = peer.peer_id();
             let mut client = StateSyncClient::new(peer);
 
             async move {
-                let response = query_peer_for_latest_info(&mut client, timeout).await;
+                let response = query_peer_for_latest_info(&mut client, peer_heights, timeout).await;
                 match response {
                     Some((highest_checkpoint, low_watermark)) => peer_heights
                         .write()
@@ -982,48 +1091,73 @@ where
                 // Iterate through peers trying each one in turn until we're able to
                 // successfully get the target checkpoint
                 for mut peer in peers {
+                    let peer_id = peer.inner().peer_id();
                     let request = Request::new(GetCheckpointSummaryRequest::BySequenceNumber(next))
                         .with_timeout(timeout);
-                    if let Some(checkpoint) = peer
-                        .get_checkpoint_summary(request)
-                        .await
-                        .tap_err(|e| trace!("{e:?}"))
-                        .ok()
-                        .and_then(Response::into_inner)
-                        .tap_none(|| trace!("peer unable to help sync"))
-                    {
-                        // peer didn't give us a checkpoint with the height that we requested
-                        if *checkpoint.sequence_number() != next {
-                            tracing::debug!(
-                                "peer returned checkpoint with wrong sequence number: expected {next}, got {}",
-                                checkpoint.sequence_number()
-                            );
+                    let start = Instant::now();
+                    let checkpoint = match peer.get_checkpoint_summary(request).await {
+                        Ok(response) => response.into_inner(),
+                        Err(status) => {
+                            trace!("{status:?}");
+                            peer_heights
+                                .write()
+                                .unwrap()
+                                .scores
+                                .record_failure(peer_id, RequestFailure::from_status(&status));
                             continue;
                         }
+                    };
+                    let latency = start.elapsed();
+                    let Some(checkpoint) = checkpoint else {
+                        trace!("peer unable to help sync");
+                        peer_heights
+                            .write()
+                            .unwrap()
+                            .scores
+                            .record_unavailable(peer_id);
+                        continue;
+                    };
 
-                        // peer gave us a checkpoint whose digest does not match pinned digest
-                        let checkpoint_digest = checkpoint.digest();
-                        if let Ok(pinned_digest_index) = pinned_checkpoints.binary_search_by_key(
-                            checkpoint.sequence_number(),
-                            |(seq_num, _digest)| *seq_num
-                        ) {
-                            if pinned_checkpoints[pinned_digest_index].1 != *checkpoint_digest {
-                                tracing::debug!(
-                                    "peer returned checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
-                                    pinned_checkpoints[pinned_digest_index].1,
-                                    checkpoint_digest
-                                );
-                                continue;
-                            }
-                        }
-
-                        // Insert in our store in the event that things fail and we need to retry
+                    // peer didn't give us a checkpoint with the height that we requested
+                    if *checkpoint.sequence_number() != next {
+                        tracing::debug!(
+                            "peer returned checkpoint with wrong sequence number: expected {next}, got {}",
+                            checkpoint.sequence_number()
+                        );
                         peer_heights
                             .write()
                             .unwrap()
-                            .insert_checkpoint(checkpoint.clone());
-                        return (Some(checkpoint), next, Some(peer.inner().peer_id()));
+                            .scores
+                            .record_failure(peer_id, RequestFailure::InvalidResponse);
+                        continue;
                     }
+
+                    // peer gave us a checkpoint whose digest does not match pinned digest
+                    let checkpoint_digest = checkpoint.digest();
+                    if let Ok(pinned_digest_index) = pinned_checkpoints.binary_search_by_key(
+                        checkpoint.sequence_number(),
+                        |(seq_num, _digest)| *seq_num
+                    ) {
+                        if pinned_checkpoints[pinned_digest_index].1 != *checkpoint_digest {
+                            tracing::debug!(
+                                "peer returned checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
+                                pinned_checkpoints[pinned_digest_index].1,
+                                checkpoint_digest
+                            );
+                            peer_heights
+                                .write()
+                                .unwrap()
+                                .scores
+                                .record_failure(peer_id, RequestFailure::InvalidResponse);
+                            continue;
+                        }
+                    }
+
+                    // Insert in our store in the event that things fail and we need to retry
+                    let mut peer_heights = peer_heights.write().unwrap();
+                    peer_heights.scores.record_success(peer_id, latency, Some(next));
+                    peer_heights.insert_checkpoint(checkpoint.clone());
+                    return (Some(checkpoint), next, Some(peer_id));
                 }
                 (None, next, None)
             }
@@ -1060,9 +1194,13 @@ where
                     // another peer for a different one
                     peer_heights.remove_checkpoint(checkpoint.digest());
 
-                    // Mark peer as not on the same chain as us
+                    // Mark peer as not on the same chain as us, and ban it for sending us a
+                    // checkpoint that doesn't verify
                     if let Some(peer_id) = maybe_peer_id {
                         peer_heights.mark_peer_as_not_on_same_chain(peer_id);
+                        peer_heights
+                            .scores
+                            .record_failure(peer_id, RequestFailure::InvalidResponse);
                     }
 
                     return Err(anyhow::anyhow!(
@@ -1305,7 +1443,8 @@ where
         PeerCheckpointRequestType::Content,
     )
     .with_checkpoint(*checkpoint.sequence_number());
-    let Some(_contents) = get_full_checkpoint_contents(peers, &store, &checkpoint, timeout).await
+    let Some(_contents) =
+        get_full_checkpoint_contents(peers, &store, &peer_heights, &checkpoint, timeout).await
     else {
         // Delay completion in case of error so we don't hammer the network with retries.
         let duration = peer_heights
@@ -1324,6 +1463,7 @@ where
 async fn get_full_checkpoint_contents<S>(
     peers: PeerBalancer,
     store: S,
+    peer_heights: &RwLock<PeerHeights>,
     checkpoint: &VerifiedCheckpoint,
     timeout: Duration,
 ) -> Option<FullCheckpointContents>
@@ -1347,28 +1487,51 @@ where
     // Iterate through our selected peers trying each one in turn until we're able to
     // successfully get the target checkpoint
     for mut peer in peers {
-        debug!(
-            ?timeout,
-            "requesting checkpoint contents from {}",
-            peer.inner().peer_id(),
-        );
+        let peer_id = peer.inner().peer_id();
+        debug!(?timeout, "requesting checkpoint contents from {}", peer_id);
         let request = Request::new(digest).with_timeout(timeout);
-        if let Some(contents) = peer
-            .get_checkpoint_contents(request)
-            .await
-            .tap_err(|e| trace!("{e:?}"))
-            .ok()
-            .and_then(Response::into_inner)
-            .tap_none(|| trace!("peer unable to help sync"))
-        {
-            if contents.verify_digests(digest).is_ok() {
-                let verified_contents = VerifiedCheckpointContents::new_unchecked(contents.clone());
-                store
-                    .insert_checkpoint_contents(checkpoint, verified_contents)
-                    .expect("store operation should not fail");
-                return Some(contents);
+        let start = Instant::now();
+        let contents = match peer.get_checkpoint_contents(request).await {
+            Ok(response) => response.into_inner(),
+            Err(status) => {
+                trace!("{status:?}");
+                peer_heights
+                    .write()
+                    .unwrap()
+                    .scores
+                    .record_failure(peer_id, RequestFailure::from_status(&status));
+                continue;
             }
+        };
+        let latency = start.elapsed();
+        let Some(contents) = contents else {
+            trace!("peer unable to help sync");
+            peer_heights
+                .write()
+                .unwrap()
+                .scores
+                .record_unavailable(peer_id);
+            continue;
+        };
+        if contents.verify_digests(digest).is_err() {
+            debug!("peer {peer_id} returned checkpoint contents which don't match digest {digest}");
+            peer_heights
+                .write()
+                .unwrap()
+                .scores
+                .record_failure(peer_id, RequestFailure::InvalidResponse);
+            continue;
         }
+        peer_heights.write().unwrap().scores.record_success(
+            peer_id,
+            latency,
+            Some(*checkpoint.sequence_number()),
+        );
+        let verified_contents = VerifiedCheckpointContents::new_unchecked(contents.clone());
+        store
+            .insert_checkpoint_contents(checkpoint, verified_contents)
+            .expect("store operation should not fail");
+        return Some(contents);
     }
     debug!("no peers had checkpoint contents");
     None
@@ -0,0 +1,249 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tracking of how well our peers serve state sync requests.
+//!
+//! Every request made to a peer is recorded, either as a success along with how long it took, or
+//! as a failure. Peers are preferred based on the latency of their successful responses, with a
+//! penalty for each failure in a row. Peers which fail too many requests in a row, or which send
+//! back data that fails validation, are banned from state sync for a while. Peers which simply
+//! don't have the data we asked for are only deprioritized, as that is expected of peers which
+//! are still catching up or have pruned it.
+
+use anemo::{types::response::StatusCode, PeerId};
+use std::collections::HashMap;
+use std::time::Duration;
+use sui_config::p2p::StateSyncConfig;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::time::Instant;
+use tracing::info;
+
+use super::metrics::Metrics;
+
+/// Weight given to the latest sample when updating the latency estimate of a peer.
+const LATENCY_EWMA_WEIGHT: f64 = 0.2;
+
+/// Cap on the factor applied to the ban duration of a peer that keeps getting banned.
+const MAX_BAN_DURATION_MULTIPLIER: u32 = 16;
+
+/// The ways a request to a peer can fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum RequestFailure {
+    /// The peer didn't respond before the request timed out.
+    Timeout,
+    /// The request failed.
+    Error,
+    /// The peer responded with data that failed validation.
+    InvalidResponse,
+}
+
+impl RequestFailure {
+    pub fn from_status(status: &anemo::rpc::Status) -> Self {
+        if status.status() == StatusCode::RequestTimeout {
+            Self::Timeout
+        } else {
+            Self::Error
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::Error => "error",
+            Self::InvalidResponse => "invalid_response",
+        }
+    }
+}
+
+/// How well a peer has served our state sync requests.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeerScore {
+    /// Moving average of the latency of the successful requests made to this peer.
+    pub latency: Option<Duration>,
+    /// Number of requests which failed since the last successful one.
+    pub consecutive_failures: u32,
+    /// Number of requests for data the peer didn't have since the last successful one.
+    pub consecutive_unavailable: u32,
+    pub num_timeouts: u64,
+    pub num_invalid_responses: u64,
+    /// Highest checkpoint this peer has served us the summary or contents of.
+    pub highest_served_checkpoint: Option<CheckpointSequenceNumber>,
+    /// Number of times this peer has been banned.
+    pub num_bans: u32,
+    pub banned_until: Option<Instant>,
+    /// Whether we lost our connection to this peer while it was banned.
+    disconnected: bool,
+}
+
+impl PeerScore {
+    fn is_banned(&self, now: Instant) -> bool {
+        self.banned_until.is_some_and(|until| until > now)
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct PeerScores {
+    scores: HashMap<PeerId, PeerScore>,
+    failure_ban_threshold: u32,
+    ban_duration: Duration,
+    metrics: Metrics,
+}
+
+impl PeerScores {
+    pub fn new(config: &StateSyncConfig, metrics: Metrics) -> Self {
+        Self {
+            scores: HashMap::new(),
+            failure_ban_threshold: config.peer_ban_failure_threshold(),
+            ban_duration: config.peer_ban_duration(),
+            metrics,
+        }
+    }
+
+    pub fn scores(&self) -> &HashMap<PeerId, PeerScore> {
+        &self.scores
+    }
+
+    pub fn record_success(
+        &mut self,
+        peer_id: PeerId,
+        latency: Duration,
+        served_checkpoint: Option<CheckpointSequenceNumber>,
+    ) {
+        let Some(score) = self.scores.get_mut(&peer_id) else {
+            return;
+        };
+        score.latency = Some(match score.latency {
+            Some(average) => {
+                average.mul_f64(1.0 - LATENCY_EWMA_WEIGHT) + latency.mul_f64(LATENCY_EWMA_WEIGHT)
+            }
+            None => latency,
+        });
+        score.consecutive_failures = 0;
+        score.consecutive_unavailable = 0;
+        if let Some(checkpoint) = served_checkpoint {
+            score.highest_served_checkpoint =
+                std::cmp::max(score.highest_served_checkpoint, Some(checkpoint));
+        }
+    }
+
+    pub fn record_failure(&mut self, peer_id: PeerId, failure: RequestFailure) {
+        self.metrics.inc_peer_request_failures(failure.as_str());
+        let Some(score) = self.scores.get_mut(&peer_id) else {
+            return;
+        };
+        score.consecutive_failures += 1;
+        let should_ban = match failure {
+            RequestFailure::Timeout => {
+                score.num_timeouts += 1;
+                score.consecutive_failures >= self.failure_ban_threshold
+            }
+            RequestFailure::Error => score.consecutive_failures >= self.failure_ban_threshold,
+            RequestFailure::InvalidResponse => {
+                score.num_invalid_responses += 1;
+                true
+            }
+        };
+        if should_ban {
+            self.ban(peer_id, failure);
+        }
+    }
+
+    /// Records that a peer didn't have the data we asked for. This doesn't count towards banning
+    /// the peer, but makes it less attractive until it serves us again.
+    pub fn record_unavailable(&mut self, peer_id: PeerId) {
+        self.metrics.inc_peer_request_failures("unavailable");
+        let Some(score) = self.scores.get_mut(&peer_id) else {
+            return;
+        };
+        score.consecutive_unavailable = score.consecutive_unavailable.saturating_add(1);
+    }
+
+    fn ban(&mut self, peer_id: PeerId, failure: RequestFailure) {
+        let now = Instant::now();
+        let Some(score) = self.scores.get_mut(&peer_id) else {
+            return;
+        };
+        if score.is_banned(now) {
+            return;
+        }
+        let multiplier = 2u32
+            .saturating_pow(score.num_bans)
+            .min(MAX_BAN_DURATION_MULTIPLIER);
+        let duration = self.ban_duration.saturating_mul(multiplier);
+        score.num_bans += 1;
+        score.consecutive_failures = 0;
+        score.banned_until = Some(now + duration);
+        info!(
+            "banning peer {peer_id} from state sync for {duration:?} after {}",
+            failure.as_str()
+        );
+        self.metrics.inc_peer_bans();
+        self.update_banned_peers_metric(now);
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.scores
+            .get(peer_id)
+            .is_some_and(|score| score.is_banned(Instant::now()))
+    }
+
+    /// Cost of requesting data from a peer, lower is better. Falls back on the round trip time of
+    /// the connection to the peer until we have measured the latency of its responses.
+    pub fn selection_cost(&self, peer_id: &PeerId, rtt: Duration) -> Duration {
+        match self.scores.get(peer_id) {
+            Some(score) => {
+                let penalty = score
+                    .consecutive_failures
+                    .saturating_add(score.consecutive_unavailable);
+                score
+                    .latency
+                    .unwrap_or(rtt)
+                    .saturating_mul(penalty.saturating_add(1))
+            }
+            None => rtt,
+        }
+    }
+
+    /// Starts tracking a peer we connected to. The score of a peer we reconnected to is kept, so
+    /// that its ban isn't lifted. Only connected peers are scored, so that responses still in
+    /// flight when we disconnect from a peer don't bring its score back.
+    pub fn add_peer(&mut self, peer_id: &PeerId) {
+        self.scores.entry(*peer_id).or_default().disconnected = false;
+    }
+
+    /// Forgets about a peer we disconnected from. Banned peers are kept until their ban expires
+    /// so that reconnecting doesn't lift it.
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        let now = Instant::now();
+        match self.scores.get_mut(peer_id) {
+            Some(score) if score.is_banned(now) => score.disconnected = true,
+            Some(_) => {
+                self.scores.remove(peer_id);
+            }
+            None => {}
+        }
+    }
+
+    /// Lifts the bans which have expired, forgetting about the peers we are no longer connected
+    /// to.
+    pub fn expire_bans(&mut self) {
+        let now = Instant::now();
+        self.scores.retain(|_, score| {
+            if score.banned_until.is_some_and(|until| until <= now) {
+                score.banned_until = None;
+                return !score.disconnected;
+            }
+            true
+        });
+        self.update_banned_peers_metric(now);
+    }
+
+    fn update_banned_peers_metric(&self, now: Instant) {
+        let num_banned_peers = self
+            .scores
+            .values()
+            .filter(|score| score.is_banned(now))
+            .count();
+        self.metrics.set_banned_peers(num_banned_peers);
+    }
+}
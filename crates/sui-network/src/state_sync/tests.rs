@@ -3,6 +3,8 @@
 
 use crate::{
     state_sync::{
+        metrics::Metrics,
+        peer_scores::{PeerScores, RequestFailure},
         Builder, GetCheckpointSummaryRequest, PeerStateSyncInfo, StateSync, StateSyncMessage,
         UnstartedStateSync,
     },
@@ -17,6 +19,7 @@ use sui_archival::reader::ArchiveReaderBalancer;
 use sui_archival::writer::ArchiveWriter;
 use sui_config::node::ArchiveReaderConfig;
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_config::p2p::StateSyncConfig;
 use sui_storage::{FileCompression, StorageFormat};
 use sui_swarm_config::test_utils::{empty_contents, CommitteeFixture};
 use sui_types::{
@@ -97,6 +100,158 @@ async fn server_push_checkpoint() {
     ));
 }
 
+#[tokio::test]
+async fn handle_peer_progress() {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (ordered_checkpoints, _, _sequence_number_to_digest, _checkpoints) =
+        committee.make_empty_checkpoints(1, None);
+    let store = SharedInMemoryStore::default();
+    let (
+        UnstartedStateSync {
+            handle,
+            peer_heights,
+            ..
+        },
+        _server,
+    ) = Builder::new().store(store).build_internal();
+
+    let progress = handle.peer_progress();
+    assert_eq!(progress.highest_known_checkpoint, None);
+    assert!(progress.peers.is_empty());
+
+    for (peer_id, on_same_chain_as_us, height) in [
+        (PeerId([1; 32]), true, 5),
+        (PeerId([2; 32]), true, 10),
+        (PeerId([3; 32]), false, 20),
+    ] {
+        peer_heights.write().unwrap().peers.insert(
+            peer_id,
+            PeerStateSyncInfo {
+                genesis_checkpoint_digest: *ordered_checkpoints[0].digest(),
+                on_same_chain_as_us,
+                height,
+                lowest: 0,
+            },
+        );
+    }
+
+    let progress = handle.peer_progress();
+    assert_eq!(progress.highest_known_checkpoint, Some(10));
+    assert_eq!(
+        progress.peers,
+        vec![(PeerId([2; 32]), 10), (PeerId([1; 32]), 5)]
+    );
+    assert_eq!(progress.num_peers_on_other_chains, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn peer_scores_ban_misbehaving_peers() {
+    let config = StateSyncConfig {
+        peer_ban_failure_threshold: Some(2),
+        peer_ban_duration_ms: Some(1_000),
+        ..Default::default()
+    };
+    let mut scores = PeerScores::new(&config, Metrics::disabled());
+    let slow_peer = PeerId([1; 32]);
+    let fast_peer = PeerId([2; 32]);
+    let malicious_peer = PeerId([3; 32]);
+    let rtt = Duration::from_millis(10);
+    for peer in [slow_peer, fast_peer, malicious_peer] {
+        scores.add_peer(&peer);
+    }
+
+    // Peers we haven't heard from yet are ranked by the RTT of their connection
+    assert_eq!(scores.selection_cost(&PeerId([4; 32]), rtt), rtt);
+
+    scores.record_success(slow_peer, Duration::from_millis(500), Some(1));
+    scores.record_success(fast_peer, Duration::from_millis(50), Some(1));
+    assert!(scores.selection_cost(&fast_peer, rtt) < scores.selection_cost(&slow_peer, rtt));
+
+    // Each failure in a row makes a peer less attractive, until it gets banned
+    scores.record_failure(fast_peer, RequestFailure::Timeout);
+    assert_eq!(
+        scores.selection_cost(&fast_peer, rtt),
+        Duration::from_millis(100)
+    );
+    assert!(!scores.is_banned(&fast_peer));
+    scores.record_failure(fast_peer, RequestFailure::Error);
+    assert!(scores.is_banned(&fast_peer));
+
+    // Invalid responses get a peer banned right away, and reconnecting doesn't lift the ban
+    scores.record_failure(malicious_peer, RequestFailure::InvalidResponse);
+    assert!(scores.is_banned(&malicious_peer));
+    scores.remove_peer(&malicious_peer);
+    scores.add_peer(&malicious_peer);
+    assert!(scores.is_banned(&malicious_peer));
+
+    tokio::time::advance(Duration::from_millis(1_001)).await;
+    scores.expire_bans();
+    assert!(!scores.is_banned(&fast_peer));
+    assert!(!scores.is_banned(&malicious_peer));
+
+    // Peers that misbehave again are banned for longer
+    scores.record_failure(malicious_peer, RequestFailure::InvalidResponse);
+    tokio::time::advance(Duration::from_millis(1_001)).await;
+    assert!(scores.is_banned(&malicious_peer));
+    tokio::time::advance(Duration::from_millis(1_000)).await;
+    assert!(!scores.is_banned(&malicious_peer));
+
+    let score = &scores.scores()[&malicious_peer];
+    assert_eq!(score.num_invalid_responses, 2);
+    assert_eq!(score.num_bans, 2);
+    assert_eq!(
+        scores.scores()[&slow_peer].highest_served_checkpoint,
+        Some(1)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn peer_scores_unavailable_data_and_disconnected_peers() {
+    let config = StateSyncConfig {
+        peer_ban_failure_threshold: Some(2),
+        peer_ban_duration_ms: Some(1_000),
+        ..Default::default()
+    };
+    let mut scores = PeerScores::new(&config, Metrics::disabled());
+    let lagging_peer = PeerId([1; 32]);
+    let malicious_peer = PeerId([2; 32]);
+    let rtt = Duration::from_millis(10);
+    scores.add_peer(&lagging_peer);
+    scores.add_peer(&malicious_peer);
+
+    // Peers which don't have the data we asked for are deprioritized but never banned
+    scores.record_success(lagging_peer, Duration::from_millis(50), Some(1));
+    for _ in 0..3 {
+        scores.record_unavailable(lagging_peer);
+    }
+    assert!(!scores.is_banned(&lagging_peer));
+    assert_eq!(
+        scores.selection_cost(&lagging_peer, rtt),
+        Duration::from_millis(200)
+    );
+    scores.record_success(lagging_peer, Duration::from_millis(50), Some(2));
+    assert_eq!(
+        scores.selection_cost(&lagging_peer, rtt),
+        Duration::from_millis(50)
+    );
+
+    // Banned peers we disconnected from are forgotten once their ban expires
+    scores.record_failure(malicious_peer, RequestFailure::InvalidResponse);
+    scores.remove_peer(&malicious_peer);
+    assert!(scores.scores().contains_key(&malicious_peer));
+    tokio::time::advance(Duration::from_millis(1_001)).await;
+    scores.expire_bans();
+    assert!(!scores.scores().contains_key(&malicious_peer));
+    assert!(scores.scores().contains_key(&lagging_peer));
+
+    // Responses still in flight when we disconnect from a peer don't bring its score back
+    scores.remove_peer(&lagging_peer);
+    scores.record_success(lagging_peer, Duration::from_millis(50), Some(3));
+    scores.record_unavailable(lagging_peer);
+    scores.record_failure(malicious_peer, RequestFailure::InvalidResponse);
+    assert!(scores.scores().is_empty());
+}
+
 #[tokio::test]
 async fn server_get_checkpoint() {
     let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
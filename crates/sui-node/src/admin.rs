@@ -2,19 +2,29 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::SuiNode;
+use anyhow::{bail, Context};
 use axum::{
-    extract::{Query, State},
+    extract::{ConnectInfo, Query, State},
     http::StatusCode,
     routing::{get, post},
     Router,
 };
-use humantime::parse_duration;
+use humantime::{format_rfc3339_seconds, parse_duration};
 use serde::Deserialize;
+use std::collections::VecDeque;
+use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::SystemTime;
+use sui_config::transaction_deny_config::{TransactionDenyConfig, TransactionDenyToggle};
+use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::error::SuiError;
 use telemetry_subscribers::TracingHandle;
-use tracing::info;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
 
 // Example commands:
 //
@@ -47,6 +57,49 @@ use tracing::info;
 // Reset tracing to the TRACE_FILTER env var.
 //
 //   $ curl -X POST 'http://127.0.0.1:1337/reset-tracing'
+//
+// View the transaction deny config currently applied:
+//
+//   $ curl 'http://127.0.0.1:1337/transaction-deny-config'
+//
+// Deny transactions using an object, calling into a package or signed by an address, and
+// allow them again. Changes are applied immediately and persisted to the node config file, where
+// only the transaction-deny-config section is rewritten and comments elsewhere are kept:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/transaction-deny-config/deny?object=0x1234'
+//   $ curl -X POST 'http://127.0.0.1:1337/transaction-deny-config/allow?address=0x1234'
+//
+// Disable package publishing (other toggles: package-upgrade-disabled, shared-object-disabled,
+// user-transaction-disabled, receiving-objects-disabled, zklogin-sig-disabled):
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/transaction-deny-config/toggle?toggle=package-publish-disabled&value=true'
+//
+// View the last changes made to the transaction deny config. When the node runs from a config
+// file, changes are also appended to transaction-deny-audit.log next to it, and kept across
+// restarts:
+//
+//   $ curl 'http://127.0.0.1:1337/transaction-deny-config/audit-log'
+//
+// Take a db checkpoint now, optionally including the index db. The checkpoint is written to
+// the db checkpoint directory and is not uploaded:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/db-checkpoint?include_indexes=true'
+//
+// View whether pruning is paused, pause or resume the periodic pruning runs, or prune now:
+//
+//   $ curl 'http://127.0.0.1:1337/pruning'
+//   $ curl -X POST 'http://127.0.0.1:1337/pruning/pause'
+//   $ curl -X POST 'http://127.0.0.1:1337/pruning/resume'
+//   $ curl -X POST 'http://127.0.0.1:1337/pruning/run'
+//
+// View the size and compaction state of every table of the perpetual, checkpoints, committee
+// (epochs) and current epoch dbs:
+//
+//   $ curl 'http://127.0.0.1:1337/db-stats'
+//
+// View the progress of state sync:
+//
+//   $ curl 'http://127.0.0.1:1337/state-sync'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -56,24 +109,71 @@ const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const TRANSACTION_DENY_CONFIG: &str = "/transaction-deny-config";
+const TRANSACTION_DENY_CONFIG_DENY: &str = "/transaction-deny-config/deny";
+const TRANSACTION_DENY_CONFIG_ALLOW: &str = "/transaction-deny-config/allow";
+const TRANSACTION_DENY_CONFIG_TOGGLE: &str = "/transaction-deny-config/toggle";
+const TRANSACTION_DENY_CONFIG_AUDIT_LOG: &str = "/transaction-deny-config/audit-log";
+const DB_CHECKPOINT: &str = "/db-checkpoint";
+const PRUNING: &str = "/pruning";
+const PRUNING_PAUSE: &str = "/pruning/pause";
+const PRUNING_RESUME: &str = "/pruning/resume";
+const PRUNING_RUN: &str = "/pruning/run";
+const DB_STATS: &str = "/db-stats";
+const STATE_SYNC: &str = "/state-sync";
+
+/// Number of transaction deny config changes kept in the audit log.
+const TRANSACTION_DENY_AUDIT_LOG_SIZE: usize = 1000;
+/// File the audit log is appended to, in the directory of the node config file.
+const TRANSACTION_DENY_AUDIT_LOG_FILE: &str = "transaction-deny-audit.log";
+/// Key of the transaction deny config in the node config file.
+const TRANSACTION_DENY_CONFIG_KEY: &str = "transaction-deny-config";
 
 struct AppState {
     node: Arc<SuiNode>,
     tracing_handle: TracingHandle,
+    /// The node config file, transaction deny config changes are persisted to it when set.
+    config_path: Option<PathBuf>,
+    /// Serializes transaction deny config changes, and records the ones applied.
+    transaction_deny_audit_log: Mutex<VecDeque<String>>,
 }
 
-pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: TracingHandle) {
+pub async fn run_admin_server(
+    node: Arc<SuiNode>,
+    port: u16,
+    tracing_handle: TracingHandle,
+    config_path: Option<PathBuf>,
+) {
     let filter = tracing_handle.get_log().unwrap();
 
+    let audit_log = match &config_path {
+        Some(config_path) => read_transaction_deny_audit_log(config_path)
+            .await
+            .unwrap_or_else(|err| {
+                warn!("can't read transaction deny audit log: {err:?}");
+                VecDeque::new()
+            }),
+        None => VecDeque::new(),
+    };
     let app_state = AppState {
         node,
         tracing_handle,
+        config_path,
+        transaction_deny_audit_log: Mutex::new(audit_log),
     };
 
     let app = Router::new()
         .route(LOGGING_ROUTE, get(get_filter))
         .route(CAPABILITIES, get(capabilities))
         .route(NODE_CONFIG, get(node_config))
+        .route(TRANSACTION_DENY_CONFIG, get(transaction_deny_config))
+        .route(
+            TRANSACTION_DENY_CONFIG_AUDIT_LOG,
+            get(transaction_deny_config_audit_log),
+        )
+        .route(PRUNING, get(pruning_status))
+        .route(DB_STATS, get(db_stats))
+        .route(STATE_SYNC, get(state_sync))
         .route(LOGGING_ROUTE, post(set_filter))
         .route(
             SET_BUFFER_STAKE_ROUTE,
@@ -86,6 +186,16 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
         .route(TRACING_ROUTE, post(enable_tracing))
         .route(TRACING_RESET_ROUTE, post(reset_tracing))
+        .route(TRANSACTION_DENY_CONFIG_DENY, post(deny_transaction_entry))
+        .route(TRANSACTION_DENY_CONFIG_ALLOW, post(allow_transaction_entry))
+        .route(
+            TRANSACTION_DENY_CONFIG_TOGGLE,
+            post(set_transaction_deny_toggle),
+        )
+        .route(DB_CHECKPOINT, post(db_checkpoint))
+        .route(PRUNING_PAUSE, post(pause_pruning))
+        .route(PRUNING_RESUME, post(resume_pruning))
+        .route(PRUNING_RUN, post(run_pruning))
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -288,3 +398,553 @@ async fn force_close_epoch(
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
     }
 }
+
+#[derive(Deserialize)]
+struct DbCheckpoint {
+    #[serde(default)]
+    include_indexes: bool,
+}
+
+async fn db_checkpoint(
+    State(state): State<Arc<AppState>>,
+    Query(DbCheckpoint { include_indexes }): Query<DbCheckpoint>,
+) -> (StatusCode, String) {
+    let node = state.node.clone();
+    let result = tokio::task::spawn_blocking(move || node.checkpoint_dbs(include_indexes)).await;
+    match result {
+        Ok(Ok(path)) => {
+            info!(path =% path.display(), "db checkpoint taken");
+            (
+                StatusCode::OK,
+                format!("db checkpoint written to {}\n", path.display()),
+            )
+        }
+        Ok(Err(err)) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn pruning_status(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let paused = state.node.state().pruner_handle().is_paused();
+    (StatusCode::OK, format!("pruning paused: {paused}\n"))
+}
+
+async fn pause_pruning(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let was_paused = state.node.state().pruner_handle().pause();
+    if !was_paused {
+        info!("pruning paused");
+    }
+    (StatusCode::OK, "pruning paused\n".into())
+}
+
+async fn resume_pruning(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let was_paused = state.node.state().pruner_handle().resume();
+    if was_paused {
+        info!("pruning resumed");
+    }
+    (StatusCode::OK, "pruning resumed\n".into())
+}
+
+async fn run_pruning(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match state.node.state().pruner_handle().run_now().await {
+        Ok(()) => (StatusCode::OK, "pruning completed\n".into()),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:?}\n")),
+    }
+}
+
+async fn db_stats(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let node = state.node.clone();
+    let stats = match tokio::task::spawn_blocking(move || node.db_stats()).await {
+        Ok(Ok(stats)) => stats,
+        Ok(Err(err)) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    };
+
+    let mut output = format!(
+        "{:<12} {:<48} {:>16} {:>16} {:>16} {:>14} {:>10} {:>10} {:>18}\n",
+        "db",
+        "table",
+        "sst_bytes",
+        "blob_bytes",
+        "memtable_bytes",
+        "est_keys",
+        "compacting",
+        "pending",
+        "pending_bytes",
+    );
+    for (db, tables) in stats {
+        for (table, stats) in tables {
+            output.push_str(&format!(
+                "{:<12} {:<48} {:>16} {:>16} {:>16} {:>14} {:>10} {:>10} {:>18}\n",
+                db,
+                table,
+                stats.total_sst_files_size,
+                stats.total_blob_files_size,
+                stats.size_all_mem_tables,
+                stats.estimated_num_keys,
+                stats.num_running_compactions,
+                stats.compaction_pending,
+                stats.estimate_pending_compaction_bytes,
+            ));
+        }
+    }
+    (StatusCode::OK, output)
+}
+
+async fn state_sync(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let checkpoint_store = state.node.state().get_checkpoint_store().clone();
+    let watermarks = (|| {
+        let verified = checkpoint_store
+            .get_highest_verified_checkpoint()?
+            .map(|c| *c.sequence_number());
+        let synced = checkpoint_store
+            .get_highest_synced_checkpoint()?
+            .map(|c| *c.sequence_number());
+        let executed = checkpoint_store.get_highest_executed_checkpoint_seq_number()?;
+        Ok::<_, typed_store::TypedStoreError>((verified, synced, executed))
+    })();
+    let (verified, synced, executed) = match watermarks {
+        Ok(watermarks) => watermarks,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    };
+    let progress = state.node.state_sync_handle().peer_progress();
+
+    let mut output = String::new();
+    output.push_str(&format!("highest verified checkpoint: {verified:?}\n"));
+    output.push_str(&format!("highest synced checkpoint: {synced:?}\n"));
+    output.push_str(&format!("highest executed checkpoint: {executed:?}\n"));
+    output.push_str(&format!(
+        "highest checkpoint known from peers: {:?}\n",
+        progress.highest_known_checkpoint
+    ));
+    if let (Some(synced), Some(highest_known)) = (synced, progress.highest_known_checkpoint) {
+        output.push_str(&format!(
+            "checkpoints behind peers: {}\n",
+            highest_known.saturating_sub(synced)
+        ));
+    }
+    output.push_str(&format!(
+        "unprocessed checkpoints from peers: {}\n",
+        progress.num_unprocessed_checkpoints
+    ));
+    output.push_str(&format!(
+        "peers on other chains: {}\n",
+        progress.num_peers_on_other_chains
+    ));
+    output.push_str(&format!(
+        "peers on the same chain: {}\n",
+        progress.peers.len()
+    ));
+    for (peer_id, height) in progress.peers {
+        output.push_str(&format!("  {peer_id}: {height}"));
+        if let Some(score) = progress.peer_scores.get(&peer_id) {
+            output.push_str(&format!(
+                " (latency: {:?}, consecutive failures: {}, timeouts: {}, invalid responses: {})",
+                score.latency,
+                score.consecutive_failures,
+                score.num_timeouts,
+                score.num_invalid_responses
+            ));
+        }
+        output.push('\n');
+    }
+    output.push_str(&format!(
+        "peers banned from state sync: {}\n",
+        progress.banned_peers.len()
+    ));
+    for peer_id in progress.banned_peers {
+        output.push_str(&format!("  {peer_id}\n"));
+    }
+    (StatusCode::OK, output)
+}
+
+async fn transaction_deny_config(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match serde_yaml::to_string(&*state.node.state().transaction_deny_config()) {
+        Ok(config) => (StatusCode::OK, config),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn transaction_deny_config_audit_log(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, String) {
+    let audit_log = state.transaction_deny_audit_log.lock().await;
+    let mut output = String::new();
+    for change in audit_log.iter() {
+        output.push_str(change);
+        output.push('\n');
+    }
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+struct DenyListEntryQuery {
+    object: Option<String>,
+    package: Option<String>,
+    address: Option<String>,
+}
+
+enum DenyListEntry {
+    Object(ObjectID),
+    Package(ObjectID),
+    Address(SuiAddress),
+}
+
+impl TryFrom<DenyListEntryQuery> for DenyListEntry {
+    type Error = String;
+
+    fn try_from(query: DenyListEntryQuery) -> Result<Self, Self::Error> {
+        match (query.object, query.package, query.address) {
+            (Some(id), None, None) => ObjectID::from_str(&id)
+                .map(Self::Object)
+                .map_err(|err| format!("invalid object id {id}: {err}")),
+            (None, Some(id), None) => ObjectID::from_str(&id)
+                .map(Self::Package)
+                .map_err(|err| format!("invalid package id {id}: {err}")),
+            (None, None, Some(address)) => SuiAddress::from_str(&address)
+                .map(Self::Address)
+                .map_err(|err| format!("invalid address {address}: {err}")),
+            _ => Err("exactly one of object, package or address is required".into()),
+        }
+    }
+}
+
+impl fmt::Display for DenyListEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Object(id) => write!(f, "object {id}"),
+            Self::Package(id) => write!(f, "package {id}"),
+            Self::Address(address) => write!(f, "address {address}"),
+        }
+    }
+}
+
+async fn deny_transaction_entry(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    Query(query): Query<DenyListEntryQuery>,
+) -> (StatusCode, String) {
+    let entry = match DenyListEntry::try_from(query) {
+        Ok(entry) => entry,
+        Err(err) => return (StatusCode::BAD_REQUEST, err),
+    };
+    let change = format!("deny {entry}");
+    update_transaction_deny_config(&state, remote, change, |config| match entry {
+        DenyListEntry::Object(id) => config.deny_object(id),
+        DenyListEntry::Package(id) => config.deny_package(id),
+        DenyListEntry::Address(address) => config.deny_address(address),
+    })
+    .await
+}
+
+async fn allow_transaction_entry(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    Query(query): Query<DenyListEntryQuery>,
+) -> (StatusCode, String) {
+    let entry = match DenyListEntry::try_from(query) {
+        Ok(entry) => entry,
+        Err(err) => return (StatusCode::BAD_REQUEST, err),
+    };
+    let change = format!("allow {entry}");
+    update_transaction_deny_config(&state, remote, change, |config| match entry {
+        DenyListEntry::Object(id) => config.allow_object(id),
+        DenyListEntry::Package(id) => config.allow_package(id),
+        DenyListEntry::Address(address) => config.allow_address(address),
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+struct SetToggle {
+    toggle: TransactionDenyToggle,
+    value: bool,
+}
+
+async fn set_transaction_deny_toggle(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    Query(SetToggle { toggle, value }): Query<SetToggle>,
+) -> (StatusCode, String) {
+    let change = format!("set {toggle:?} to {value}");
+    update_transaction_deny_config(&state, remote, change, |config| {
+        config.set_toggle(toggle, value)
+    })
+    .await
+}
+
+/// Applies a change to a copy of the current transaction deny config. The new config is
+/// persisted before it is applied, so a change that can't be persisted is not applied either.
+async fn update_transaction_deny_config(
+    state: &AppState,
+    remote: SocketAddr,
+    change: String,
+    update: impl FnOnce(&mut TransactionDenyConfig) -> bool,
+) -> (StatusCode, String) {
+    let mut audit_log = state.transaction_deny_audit_log.lock().await;
+
+    let mut config = (*state.node.state().transaction_deny_config()).clone();
+    if !update(&mut config) {
+        return (StatusCode::OK, format!("{change}: already applied\n"));
+    }
+    if let Some(config_path) = &state.config_path {
+        if let Err(err) = persist_transaction_deny_config(config_path, &config).await {
+            warn!(%remote, %change, "can't persist transaction deny config: {err:?}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("{change}: can't persist config, change not applied: {err:?}\n"),
+            );
+        }
+    }
+    state.node.set_transaction_deny_config(config);
+
+    info!(%remote, %change, "transaction deny config updated");
+    let entry = format!(
+        "{} {remote} {change}",
+        format_rfc3339_seconds(SystemTime::now())
+    );
+    if let Some(config_path) = &state.config_path {
+        // The change is already applied, a failure here only loses its audit trail.
+        if let Err(err) = append_transaction_deny_audit_log(config_path, &entry).await {
+            warn!(%remote, %change, "can't persist transaction deny audit log: {err:?}");
+        }
+    }
+    if audit_log.len() == TRANSACTION_DENY_AUDIT_LOG_SIZE {
+        audit_log.pop_front();
+    }
+    audit_log.push_back(entry);
+    (StatusCode::OK, format!("{change}: applied\n"))
+}
+
+fn transaction_deny_audit_log_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name(TRANSACTION_DENY_AUDIT_LOG_FILE)
+}
+
+/// Reads the last entries of the audit log persisted next to the node config file.
+async fn read_transaction_deny_audit_log(config_path: &Path) -> anyhow::Result<VecDeque<String>> {
+    let path = transaction_deny_audit_log_path(config_path);
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(VecDeque::new()),
+        Err(err) => return Err(err).with_context(|| format!("unable to read {}", path.display())),
+    };
+    let mut audit_log = VecDeque::new();
+    for line in contents.lines().filter(|line| !line.is_empty()) {
+        if audit_log.len() == TRANSACTION_DENY_AUDIT_LOG_SIZE {
+            audit_log.pop_front();
+        }
+        audit_log.push_back(line.to_string());
+    }
+    Ok(audit_log)
+}
+
+/// Appends an entry to the audit log persisted next to the node config file.
+async fn append_transaction_deny_audit_log(config_path: &Path, entry: &str) -> anyhow::Result<()> {
+    let path = transaction_deny_audit_log_path(config_path);
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .with_context(|| format!("unable to open {}", path.display()))?;
+    file.write_all(format!("{entry}\n").as_bytes())
+        .await
+        .with_context(|| format!("unable to write {}", path.display()))?;
+    file.flush().await?;
+    Ok(())
+}
+
+/// Replaces the transaction deny config in the node config file. Only the lines of the
+/// `transaction-deny-config` key are rewritten, comments and ordering of the rest of the file
+/// are kept.
+async fn persist_transaction_deny_config(
+    config_path: &Path,
+    config: &TransactionDenyConfig,
+) -> anyhow::Result<()> {
+    let contents = tokio::fs::read_to_string(config_path)
+        .await
+        .with_context(|| format!("unable to read {}", config_path.display()))?;
+    let mut node_config: serde_yaml::Value = serde_yaml::from_str(&contents)?;
+    let serde_yaml::Value::Mapping(fields) = &mut node_config else {
+        bail!("{} is not a node config", config_path.display());
+    };
+    fields.insert(
+        TRANSACTION_DENY_CONFIG_KEY.into(),
+        serde_yaml::to_value(config)?,
+    );
+
+    let mut section = serde_yaml::Mapping::new();
+    section.insert(
+        TRANSACTION_DENY_CONFIG_KEY.into(),
+        serde_yaml::to_value(config)?,
+    );
+    let section = serde_yaml::to_string(&section)?;
+    let section = section.strip_prefix("---\n").unwrap_or(&section);
+    let updated = replace_top_level_key(&contents, TRANSACTION_DENY_CONFIG_KEY, section);
+    // The key is found by its lines, make sure nothing else in the file changed.
+    if serde_yaml::from_str::<serde_yaml::Value>(&updated)? != node_config {
+        bail!(
+            "unable to update {} in {}",
+            TRANSACTION_DENY_CONFIG_KEY,
+            config_path.display()
+        );
+    }
+
+    // Write to a temporary file first, so the config file is never left partially written.
+    let tmp_path = config_path.with_extension("tmp");
+    tokio::fs::write(&tmp_path, updated)
+        .await
+        .with_context(|| format!("unable to write {}", tmp_path.display()))?;
+    tokio::fs::rename(&tmp_path, config_path)
+        .await
+        .with_context(|| format!("unable to replace {}", config_path.display()))?;
+    Ok(())
+}
+
+/// Replaces the lines of a top level key of a YAML document with `section`, or appends
+/// `section` if the key is not set. The lines of a key are the line starting with it and the
+/// indented, list item and blank lines following it.
+fn replace_top_level_key(contents: &str, key: &str, section: &str) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let prefix = format!("{key}:");
+    let Some(start) = lines.iter().position(|line| line.starts_with(&prefix)) else {
+        let mut updated = contents.to_string();
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push_str(section);
+        return updated;
+    };
+    let mut end = start + 1;
+    while end < lines.len() && (lines[end].is_empty() || lines[end].starts_with([' ', '\t', '-'])) {
+        end += 1;
+    }
+    // Blank lines before the next key are not part of the section.
+    while end > start + 1 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+
+    let mut updated = String::new();
+    for line in &lines[..start] {
+        updated.push_str(line);
+        updated.push('\n');
+    }
+    updated.push_str(section);
+    if !section.ends_with('\n') {
+        updated.push('\n');
+    }
+    for line in &lines[end..] {
+        updated.push_str(line);
+        updated.push('\n');
+    }
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_config::transaction_deny_config::TransactionDenyConfigBuilder;
+
+    #[tokio::test]
+    async fn test_persist_transaction_deny_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("fullnode.yaml");
+        std::fs::write(
+            &config_path,
+            "# Fullnode config\ndb-path: /opt/sui/db\nmetrics-address: 0.0.0.0:9184\n",
+        )
+        .unwrap();
+
+        let id = ObjectID::random();
+        let config = TransactionDenyConfigBuilder::new()
+            .add_denied_package(id)
+            .disable_package_publish()
+            .build();
+        persist_transaction_deny_config(&config_path, &config)
+            .await
+            .unwrap();
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        struct PartialNodeConfig {
+            db_path: PathBuf,
+            metrics_address: String,
+            transaction_deny_config: TransactionDenyConfig,
+        }
+        let contents = std::fs::read_to_string(&config_path).unwrap();
+        let persisted: PartialNodeConfig = serde_yaml::from_str(&contents).unwrap();
+        assert_eq!(persisted.db_path, PathBuf::from("/opt/sui/db"));
+        assert_eq!(persisted.metrics_address, "0.0.0.0:9184");
+        assert!(persisted
+            .transaction_deny_config
+            .get_package_deny_set()
+            .contains(&id));
+        assert!(persisted.transaction_deny_config.package_publish_disabled());
+        assert!(contents.starts_with("# Fullnode config\ndb-path: /opt/sui/db\n"));
+
+        // Updating the config again only replaces its own lines.
+        std::fs::write(&config_path, format!("{contents}# Trailing comment\n")).unwrap();
+        let config = TransactionDenyConfigBuilder::new().build();
+        persist_transaction_deny_config(&config_path, &config)
+            .await
+            .unwrap();
+        let contents = std::fs::read_to_string(&config_path).unwrap();
+        let persisted: PartialNodeConfig = serde_yaml::from_str(&contents).unwrap();
+        assert!(persisted
+            .transaction_deny_config
+            .get_package_deny_set()
+            .is_empty());
+        assert!(contents.starts_with("# Fullnode config\ndb-path: /opt/sui/db\n"));
+        assert!(contents.ends_with("\n# Trailing comment\n"));
+    }
+
+    #[test]
+    fn test_replace_top_level_key() {
+        let contents = "a: 1\n# deny config\nkey:\n  b: 2\n  c:\n    - 3\n\n# next\nd: 4\n";
+        assert_eq!(
+            replace_top_level_key(contents, "key", "key:\n  e: 5\n"),
+            "a: 1\n# deny config\nkey:\n  e: 5\n\n# next\nd: 4\n"
+        );
+        // Keys which only start with the key, and nested keys, are not replaced.
+        assert_eq!(
+            replace_top_level_key("key-2: 1\nf:\n  key: 2", "key", "key: {}\n"),
+            "key-2: 1\nf:\n  key: 2\nkey: {}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transaction_deny_audit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("fullnode.yaml");
+        assert!(read_transaction_deny_audit_log(&config_path)
+            .await
+            .unwrap()
+            .is_empty());
+
+        append_transaction_deny_audit_log(&config_path, "first")
+            .await
+            .unwrap();
+        append_transaction_deny_audit_log(&config_path, "second")
+            .await
+            .unwrap();
+        // The log is read back after a restart, in order.
+        assert_eq!(
+            read_transaction_deny_audit_log(&config_path).await.unwrap(),
+            VecDeque::from(["first".to_string(), "second".to_string()])
+        );
+
+        // Only the last entries are kept in memory.
+        for i in 0..TRANSACTION_DENY_AUDIT_LOG_SIZE {
+            append_transaction_deny_audit_log(&config_path, &i.to_string())
+                .await
+                .unwrap();
+        }
+        let audit_log = read_transaction_deny_audit_log(&config_path).await.unwrap();
+        assert_eq!(audit_log.len(), TRANSACTION_DENY_AUDIT_LOG_SIZE);
+        assert_eq!(audit_log.front().unwrap(), "0");
+        assert_eq!(
+            audit_log.back().unwrap(),
+            &(TRANSACTION_DENY_AUDIT_LOG_SIZE - 1).to_string()
+        );
+    }
+}
@@ -13,7 +13,7 @@ use fastcrypto_zkp::bn254::zk_login::JwkId;
 use fastcrypto_zkp::bn254::zk_login::OIDCProvider;
 use futures::TryFutureExt;
 use prometheus::Registry;
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::net::SocketAddr;
 use std::path::PathBuf;
@@ -21,7 +21,7 @@ use std::str::FromStr;
 #[cfg(msim)]
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use sui_core::authority::RandomnessRoundReceiver;
 use sui_core::authority::CHAIN_IDENTIFIER;
 use sui_core::consensus_adapter::SubmitToConsensus;
@@ -58,6 +58,7 @@ use sui_archival::writer::ArchiveWriter;
 use sui_config::node::{DBCheckpointConfig, RunWithRange};
 use sui_config::node_config_metrics::NodeConfigMetrics;
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_config::{ConsensusConfig, NodeConfig};
 use sui_core::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
@@ -127,7 +128,7 @@ use sui_types::quorum_driver_types::QuorumDriverEffectsQueueResult;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemState;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use sui_types::sui_system_state::SuiSystemStateTrait;
-use typed_store::rocks::default_db_options;
+use typed_store::rocks::{default_db_options, ColumnFamilyStats};
 use typed_store::DBMetrics;
 
 use crate::metrics::{GrpcMetrics, SuiNodeMetrics};
@@ -218,6 +219,7 @@ pub struct SuiNode {
     transaction_orchestrator: Option<Arc<TransactiondOrchestrator<NetworkAuthorityClient>>>,
     registry_service: RegistryService,
     metrics: Arc<SuiNodeMetrics>,
+    node_config_metrics: Arc<NodeConfigMetrics>,
 
     _discovery: discovery::Handle,
     state_sync_handle: state_sync::Handle,
@@ -404,7 +406,8 @@ impl SuiNode {
         custom_rpc_runtime: Option<Handle>,
         software_version: &'static str,
     ) -> Result<Arc<SuiNode>> {
-        NodeConfigMetrics::new(&registry_service.default_registry()).record_metrics(&config);
+        let node_config_metrics = NodeConfigMetrics::new(&registry_service.default_registry());
+        node_config_metrics.record_metrics(&config);
         let mut config = config.clone();
         if config.supported_protocol_versions.is_none() {
             info!(
@@ -419,6 +422,15 @@ impl SuiNode {
         let is_full_node = !is_validator;
         let prometheus_registry = registry_service.default_registry();
 
+        if let Some(archive_store_config) = config
+            .consensus_config()
+            .and_then(|c| c.commit_archive_store_config.as_ref())
+        {
+            archive_store_config
+                .make()
+                .map_err(|e| anyhow!("Invalid consensus commit archive store config: {e}"))?;
+        }
+
         info!(node =? config.protocol_public_key(),
             "Initializing sui-node listening on {}", config.network_address
         );
@@ -749,6 +761,7 @@ impl SuiNode {
             transaction_orchestrator,
             registry_service,
             metrics: sui_node_metrics,
+            node_config_metrics,
 
             _discovery: discovery_handle,
             state_sync_handle,
@@ -825,6 +838,62 @@ impl SuiNode {
             .set_override_protocol_upgrade_buffer_stake(epoch, buffer_stake_bps)
     }
 
+    /// Applies a new transaction deny config to the transactions processed by this node.
+    pub fn set_transaction_deny_config(&self, config: TransactionDenyConfig) {
+        self.node_config_metrics
+            .record_transaction_deny_metrics(&config);
+        self.state.set_transaction_deny_config(config);
+    }
+
+    /// Checkpoints the node dbs now, rather than at the end of the epoch. These checkpoints are
+    /// not named after an epoch, so the db checkpoint handler leaves them alone. Returns the
+    /// path of the checkpoint.
+    pub fn checkpoint_dbs(&self, checkpoint_indexes: bool) -> SuiResult<PathBuf> {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let checkpoint_path = self
+            .config
+            .db_checkpoint_config
+            .checkpoint_path
+            .clone()
+            .unwrap_or_else(|| self.config.db_checkpoint_path())
+            .join(format!(
+                "manual_epoch_{}_{timestamp_ms}",
+                epoch_store.epoch()
+            ));
+        self.state
+            .checkpoint_all_dbs(&checkpoint_path, &epoch_store, checkpoint_indexes)?;
+        Ok(checkpoint_path)
+    }
+
+    /// Size and compaction state of the tables of the node dbs, keyed by db and table name. The
+    /// epoch db is the one of the current epoch.
+    pub fn db_stats(&self) -> SuiResult<BTreeMap<String, BTreeMap<String, ColumnFamilyStats>>> {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        Ok(BTreeMap::from([
+            ("perpetual".to_string(), self.state.perpetual_db_stats()?),
+            (
+                "checkpoints".to_string(),
+                self.checkpoint_store.column_family_stats()?,
+            ),
+            (
+                "epochs".to_string(),
+                self.state.committee_store().column_family_stats()?,
+            ),
+            (
+                format!("epoch_{}", epoch_store.epoch()),
+                epoch_store.column_family_stats()?,
+            ),
+        ]))
+    }
+
+    pub fn state_sync_handle(&self) -> state_sync::Handle {
+        self.state_sync_handle.clone()
+    }
+
     // Testing-only API to start epoch close process.
     // For production code, please use the non-testing version.
     pub async fn close_epoch_for_testing(&self) -> SuiResult {
@@ -8,6 +8,8 @@ use move_package::lock_file::{self, LockFile};
 use sui_json_rpc_types::{get_new_package_obj_from_response, SuiTransactionBlockResponse};
 use sui_sdk::wallet_context::WalletContext;
 
+pub mod workspace;
+
 pub enum LockCommand {
     Publish,
     Upgrade,
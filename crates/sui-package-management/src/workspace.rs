@@ -0,0 +1,703 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Publishing and upgrading a set of local packages which depend on each other.
+//!
+//! Packages of a [Workspace] are handled in dependency order. A package without a managed address
+//! for the active environment in its `Move.lock` is published, a package with one is upgraded if
+//! its bytecode differs from the bytecode of its latest version on chain, and left alone
+//! otherwise. Packages depending on workspace packages are built against the addresses recorded
+//! in the `Move.lock` of those packages, so that their manifests can keep assigning `0x0` to the
+//! named addresses of their workspace dependencies.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context};
+use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use move_package::lock_file::schema::ManagedPackage;
+use move_package::resolution::resolution_graph::ResolvedGraph;
+use move_package::source_package::layout::SourcePackageLayout;
+use move_package::source_package::manifest_parser::parse_move_manifest_from_file;
+use move_package::source_package::parsed_manifest::{
+    Dependency, DependencyKind, InternalDependency, NamedAddress, PackageName,
+};
+use move_package::BuildConfig as MoveBuildConfig;
+use move_symbol_pool::Symbol;
+use serde::Serialize;
+use sui_json_rpc_types::{
+    get_new_package_obj_from_response, SuiObjectDataFilter, SuiObjectDataOptions,
+    SuiObjectResponseQuery, SuiRawData, SuiTransactionBlockResponse,
+};
+use sui_move_build::{
+    build_from_resolution_graph, check_invalid_dependencies, check_unpublished_dependencies,
+    BuildConfig, CompiledPackage, PUBLISHED_AT_MANIFEST_FIELD,
+};
+use sui_sdk::wallet_context::WalletContext;
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::move_package::UpgradeCap;
+use sui_types::transaction::TransactionKind;
+
+use crate::{update_lock_file, LockCommand};
+
+/// A package of a [Workspace].
+#[derive(Debug, Clone)]
+pub struct WorkspacePackage {
+    pub name: PackageName,
+    /// Canonical path of the package directory.
+    pub path: PathBuf,
+    /// Workspace packages this package depends on through local dependencies.
+    pub dependencies: BTreeSet<PackageName>,
+    /// Named addresses of the modules of this package, which its manifest assigns to `0x0`.
+    pub self_addresses: BTreeSet<NamedAddress>,
+}
+
+/// A set of local packages depending on each other, which are published and upgraded together.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    /// Packages in dependency order, each package coming after the workspace packages it depends
+    /// on.
+    packages: Vec<WorkspacePackage>,
+}
+
+/// What publishing a workspace did to one of its packages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum WorkspaceAction {
+    Published,
+    Upgraded,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspacePackageOutcome {
+    pub name: String,
+    pub action: WorkspaceAction,
+    pub original_id: ObjectID,
+    /// ID of the latest version of the package, after publishing the workspace.
+    pub latest_id: ObjectID,
+    /// Transaction publishing or upgrading the package, if it changed.
+    pub digest: Option<TransactionDigest>,
+}
+
+/// Original and latest IDs of a published workspace package.
+#[derive(Debug, Clone, Copy)]
+struct PublishedIds {
+    original: ObjectID,
+    latest: ObjectID,
+}
+
+impl Workspace {
+    /// Loads the packages at `paths`. Local dependencies between these packages are what
+    /// determines the order they are published in.
+    pub fn load(paths: &[PathBuf]) -> anyhow::Result<Self> {
+        let mut names = BTreeMap::new();
+        let mut manifests = vec![];
+        for path in paths {
+            let path = path
+                .canonicalize()
+                .with_context(|| format!("Cannot find package at {}", path.display()))?;
+            let manifest = parse_move_manifest_from_file(&path)?;
+            if names.values().any(|name| *name == manifest.package.name) {
+                bail!(
+                    "Workspace contains several packages named {}",
+                    manifest.package.name
+                );
+            }
+            if names.insert(path.clone(), manifest.package.name).is_some() {
+                bail!("Package at {} is listed twice", path.display());
+            }
+            manifests.push((path, manifest));
+        }
+
+        let packages = manifests
+            .into_iter()
+            .map(|(path, manifest)| {
+                let dependencies = manifest
+                    .dependencies
+                    .values()
+                    .filter_map(|dep| match dep {
+                        Dependency::Internal(InternalDependency {
+                            kind: DependencyKind::Local(local),
+                            ..
+                        }) => path
+                            .join(local)
+                            .canonicalize()
+                            .ok()
+                            .and_then(|dep_path| names.get(&dep_path).copied()),
+                        _ => None,
+                    })
+                    .collect();
+                let self_addresses = manifest
+                    .addresses
+                    .iter()
+                    .flatten()
+                    .filter(|(_, address)| **address == Some(AccountAddress::ZERO))
+                    .map(|(name, _)| *name)
+                    .collect();
+                WorkspacePackage {
+                    name: manifest.package.name,
+                    path,
+                    dependencies,
+                    self_addresses,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            packages: dependency_order(packages)?,
+        })
+    }
+
+    /// Packages of the workspace, in the order they are published in.
+    pub fn packages(&self) -> &[WorkspacePackage] {
+        &self.packages
+    }
+}
+
+/// Sorts `packages` so that every package comes after the packages it depends on. Packages which
+/// don't depend on each other are sorted by name, to keep the order stable.
+fn dependency_order(packages: Vec<WorkspacePackage>) -> anyhow::Result<Vec<WorkspacePackage>> {
+    let mut remaining: BTreeMap<_, _> = packages.into_iter().map(|p| (p.name, p)).collect();
+    let mut ordered = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let ready: Vec<_> = remaining
+            .values()
+            .filter(|p| p.dependencies.iter().all(|d| !remaining.contains_key(d)))
+            .map(|p| p.name)
+            .collect();
+        if ready.is_empty() {
+            let names: Vec<_> = remaining.keys().map(|name| name.as_str()).collect();
+            bail!(
+                "Workspace packages have cyclic dependencies between {}",
+                names.join(", ")
+            );
+        }
+        for name in ready {
+            ordered.extend(remaining.remove(&name));
+        }
+    }
+    Ok(ordered)
+}
+
+/// Publishes the packages of `workspace` which were never published in the active environment,
+/// and upgrades the ones whose bytecode changed since they were last published, paying at most
+/// `gas_budget` for each transaction. The `Move.lock` of every package published or upgraded is
+/// updated as soon as its transaction succeeds, so publishing a workspace can be resumed after a
+/// failure.
+pub async fn publish_workspace(
+    context: &mut WalletContext,
+    workspace: &Workspace,
+    build_config: MoveBuildConfig,
+    gas_budget: u64,
+) -> anyhow::Result<Vec<WorkspacePackageOutcome>> {
+    let sender = context.active_address()?;
+    let client = context.get_client().await?;
+    let chain_id = client.read_api().get_chain_identifier().await?;
+    let env = context
+        .config
+        .get_active_env()
+        .context("Could not resolve environment from active wallet context")?
+        .alias
+        .clone();
+
+    let mut published = BTreeMap::new();
+    let mut outcomes = vec![];
+    for package in &workspace.packages {
+        if package.self_addresses.is_empty() {
+            bail!(
+                "Package {} must assign its named address to 0x0 to be published as part of a \
+                 workspace",
+                package.name
+            );
+        }
+        let lock_file = package.path.join(SourcePackageLayout::Lock.path());
+        let managed = managed_ids(&lock_file, &env, &chain_id)
+            .with_context(|| format!("Reading the Move.lock of {}", package.name))?;
+        let compiled = build_package(workspace, package, &published, build_config.clone())
+            .with_context(|| format!("Building {}", package.name))?;
+        let modules = compiled.get_package_bytes(false);
+        let dep_ids: Vec<_> = compiled
+            .dependency_ids
+            .published
+            .values()
+            .copied()
+            .collect();
+
+        let (action, kind) = match managed {
+            None => {
+                let kind = client
+                    .transaction_builder()
+                    .publish_tx_kind(sender, modules, dep_ids)
+                    .await?;
+                (WorkspaceAction::Published, Some(kind))
+            }
+            Some(ids) if is_unchanged(&client, &compiled, ids).await? => {
+                (WorkspaceAction::Unchanged, None)
+            }
+            Some(ids) => {
+                let cap = find_upgrade_cap(&client, sender, ids.latest).await?;
+                let kind = client
+                    .transaction_builder()
+                    .upgrade_tx_kind(
+                        ids.latest,
+                        modules,
+                        dep_ids,
+                        cap.id.id.bytes,
+                        cap.policy,
+                        compiled.get_package_digest(false).to_vec(),
+                    )
+                    .await?;
+                (WorkspaceAction::Upgraded, Some(kind))
+            }
+        };
+
+        let (ids, digest) = match kind {
+            None => (managed.unwrap(), None),
+            Some(kind) => {
+                let response = execute(context, &client, sender, kind, gas_budget)
+                    .await
+                    .with_context(|| format!("Publishing {}", package.name))?;
+                let command = if action == WorkspaceAction::Published {
+                    LockCommand::Publish
+                } else {
+                    LockCommand::Upgrade
+                };
+                update_lock_file(
+                    context,
+                    command,
+                    build_config.install_dir.clone(),
+                    Some(lock_file),
+                    &response,
+                )
+                .await?;
+                let (latest, _, _) = get_new_package_obj_from_response(&response)
+                    .ok_or_else(|| anyhow!("No package was published by {}", response.digest))?;
+                let original = managed.map_or(latest, |ids| ids.original);
+                (PublishedIds { original, latest }, Some(response.digest))
+            }
+        };
+
+        published.insert(package.name, ids);
+        outcomes.push(WorkspacePackageOutcome {
+            name: package.name.to_string(),
+            action,
+            original_id: ids.original,
+            latest_id: ids.latest,
+            digest,
+        });
+    }
+    Ok(outcomes)
+}
+
+/// Reads the IDs recorded for `env` in the `Move.lock` at `lock_file`, if the package was
+/// published there.
+fn managed_ids(
+    lock_file: &Path,
+    env: &str,
+    chain_id: &str,
+) -> anyhow::Result<Option<PublishedIds>> {
+    let contents = match std::fs::read_to_string(lock_file) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    // Lock files without an `env` table don't have any managed address.
+    let lock: toml::Value = toml::from_str(&contents)?;
+    if lock.get("env").is_none() {
+        return Ok(None);
+    }
+    let mut envs = ManagedPackage::read(&mut contents.as_bytes())?;
+    let Some(managed) = envs.remove(env) else {
+        return Ok(None);
+    };
+    if managed.chain_id != chain_id {
+        bail!(
+            "The package was published in environment {env} on chain {}, but the chain of {env} \
+             is now {chain_id}. Remove the [env.{env}] table of its Move.lock to publish it again.",
+            managed.chain_id
+        );
+    }
+    Ok(Some(PublishedIds {
+        original: ObjectID::from_str(&managed.original_published_id)?,
+        latest: ObjectID::from_str(&managed.latest_published_id)?,
+    }))
+}
+
+fn build_package(
+    workspace: &Workspace,
+    package: &WorkspacePackage,
+    published: &BTreeMap<PackageName, PublishedIds>,
+    mut config: MoveBuildConfig,
+) -> anyhow::Result<CompiledPackage> {
+    config.lock_file = Some(package.path.join(SourcePackageLayout::Lock.path()));
+    let mut resolution_graph = BuildConfig {
+        config,
+        run_bytecode_verifier: true,
+        print_diags_to_stderr: true,
+    }
+    .resolution_graph(&package.path)?;
+    rewrite_managed_addresses(&mut resolution_graph, workspace, published);
+
+    let compiled = build_from_resolution_graph(
+        package.path.clone(),
+        resolution_graph,
+        /* run_bytecode_verifier */ true,
+        /* print_diags_to_stderr */ true,
+    )?;
+    check_invalid_dependencies(&compiled.dependency_ids.invalid)?;
+    check_unpublished_dependencies(&compiled.dependency_ids.unpublished)?;
+    Ok(compiled)
+}
+
+/// Links the packages of `resolution_graph` against the published workspace packages, as if the
+/// manifests of these packages assigned their original IDs to their named addresses and had their
+/// latest IDs as `published-at`.
+fn rewrite_managed_addresses(
+    resolution_graph: &mut ResolvedGraph,
+    workspace: &Workspace,
+    published: &BTreeMap<PackageName, PublishedIds>,
+) {
+    for package in &workspace.packages {
+        let Some(ids) = published.get(&package.name) else {
+            continue;
+        };
+        let Some(resolved) = resolution_graph.package_table.get_mut(&package.name) else {
+            continue;
+        };
+        resolved.source_package.package.custom_properties.insert(
+            Symbol::from(PUBLISHED_AT_MANIFEST_FIELD),
+            ids.latest.to_string(),
+        );
+
+        for resolved in resolution_graph.package_table.values_mut() {
+            for (name, address) in resolved.resolved_table.iter_mut() {
+                // Dependent packages may refer to the address under another name.
+                let original_name = match resolved.renaming.get(name) {
+                    Some((from_package, from_name)) if *from_package == package.name => *from_name,
+                    _ => *name,
+                };
+                if *address == AccountAddress::ZERO
+                    && package.self_addresses.contains(&original_name)
+                {
+                    *address = ids.original.into();
+                }
+            }
+        }
+    }
+}
+
+/// Whether the modules of `compiled` are the same as the modules of the latest version of the
+/// package on chain.
+async fn is_unchanged(
+    client: &SuiClient,
+    compiled: &CompiledPackage,
+    ids: PublishedIds,
+) -> anyhow::Result<bool> {
+    let on_chain = client
+        .read_api()
+        .get_object_with_options(ids.latest, SuiObjectDataOptions::new().with_bcs())
+        .await?
+        .into_object()?
+        .bcs
+        .ok_or_else(|| anyhow!("Bcs field is not found"))?;
+    let SuiRawData::Package(on_chain) = on_chain else {
+        bail!("Object {} is not a package", ids.latest);
+    };
+    same_modules(compiled.get_modules(), &on_chain.module_map, ids.original)
+}
+
+/// Whether the `local` modules are the modules in `module_map` of a package whose original ID is
+/// `original`.
+fn same_modules<'a>(
+    local: impl Iterator<Item = &'a CompiledModule>,
+    module_map: &BTreeMap<String, Vec<u8>>,
+    original: ObjectID,
+) -> anyhow::Result<bool> {
+    // Modules are compiled with a `0x0` self address, which becomes the original ID on chain.
+    let local: BTreeMap<_, _> = local
+        .map(|module| {
+            let mut module = module.clone();
+            let self_address = module.self_handle().address.0 as usize;
+            module.address_identifiers[self_address] = original.into();
+            (module.self_id().name().to_string(), module)
+        })
+        .collect();
+    if local.len() != module_map.len() {
+        return Ok(false);
+    }
+    for (name, bytes) in module_map {
+        let Some(local) = local.get(name) else {
+            return Ok(false);
+        };
+        let mut module = CompiledModule::deserialize_with_defaults(bytes)?;
+        // The bytecode version depends on the compiler the package was published with, not on
+        // its contents.
+        module.version = local.version;
+        if local != &module {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Finds the `UpgradeCap` of the package whose latest version is `latest`, among the objects
+/// owned by `owner`.
+async fn find_upgrade_cap(
+    client: &SuiClient,
+    owner: SuiAddress,
+    latest: ObjectID,
+) -> anyhow::Result<UpgradeCap> {
+    let query = SuiObjectResponseQuery::new(
+        Some(SuiObjectDataFilter::StructType(UpgradeCap::type_())),
+        Some(SuiObjectDataOptions::new().with_bcs()),
+    );
+    let mut cursor = None;
+    loop {
+        let page = client
+            .read_api()
+            .get_owned_objects(owner, Some(query.clone()), cursor, None)
+            .await?;
+        for object in page.data {
+            let Some(SuiRawData::MoveObject(raw)) = object.into_object()?.bcs else {
+                continue;
+            };
+            let cap: UpgradeCap = raw.deserialize()?;
+            if cap.package.bytes == latest {
+                return Ok(cap);
+            }
+        }
+        if !page.has_next_page {
+            bail!("{owner} does not own the UpgradeCap of package {latest}");
+        }
+        cursor = page.next_cursor;
+    }
+}
+
+async fn execute(
+    context: &WalletContext,
+    client: &SuiClient,
+    sender: SuiAddress,
+    kind: TransactionKind,
+    gas_budget: u64,
+) -> anyhow::Result<SuiTransactionBlockResponse> {
+    let gas_price = context.get_reference_gas_price().await?;
+    let data = client
+        .transaction_builder()
+        .tx_data(sender, kind, gas_budget, gas_price, vec![], None)
+        .await?;
+    let response = context
+        .execute_transaction_may_fail(context.sign_transaction(&data))
+        .await?;
+    if !response.status_ok().unwrap_or(false) {
+        bail!("Transaction {} failed", response.digest);
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::empty_module;
+    use move_binary_format::file_format_common::VERSION_5;
+    use move_core_types::identifier::Identifier;
+
+    fn package(name: &str, dependencies: &[&str]) -> WorkspacePackage {
+        WorkspacePackage {
+            name: Symbol::from(name),
+            path: PathBuf::from(name),
+            dependencies: dependencies.iter().map(|d| Symbol::from(*d)).collect(),
+            self_addresses: BTreeSet::from([Symbol::from(name)]),
+        }
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let ordered = dependency_order(vec![
+            package("app", &["tokens", "math"]),
+            package("tokens", &["math"]),
+            package("math", &[]),
+            package("admin", &[]),
+        ])
+        .unwrap();
+        let names: Vec<_> = ordered.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["admin", "math", "tokens", "app"]);
+
+        let cycle = dependency_order(vec![
+            package("a", &["b"]),
+            package("b", &["a"]),
+            package("c", &[]),
+        ]);
+        assert!(cycle.unwrap_err().to_string().contains("a, b"));
+    }
+
+    /// Writes a package named `name` under `dir`, with local dependencies on `dependencies`.
+    fn write_package(dir: &Path, name: &str, dependencies: &[&str]) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.join("sources")).unwrap();
+        let dependencies: String = dependencies
+            .iter()
+            .map(|dep| format!("{dep} = {{ local = \"../{dep}\" }}\n"))
+            .collect();
+        std::fs::write(
+            path.join("Move.toml"),
+            format!(
+                "[package]\nname = \"{name}\"\n\n[dependencies]\n{dependencies}\n\
+                 [addresses]\n{name} = \"0x0\"\n"
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            path.join("sources").join(format!("{name}.move")),
+            format!("module {name}::{name} {{}}\n"),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_managed_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_file = dir.path().join("Move.lock");
+        let header = "[move]\nversion = 1\n";
+
+        // Packages without a lock file or without managed addresses were never published
+        assert!(managed_ids(&lock_file, "testnet", "4c78adac")
+            .unwrap()
+            .is_none());
+        std::fs::write(&lock_file, header).unwrap();
+        assert!(managed_ids(&lock_file, "testnet", "4c78adac")
+            .unwrap()
+            .is_none());
+
+        std::fs::write(
+            &lock_file,
+            format!(
+                "{header}\n[env.testnet]\nchain-id = \"4c78adac\"\n\
+                 original-published-id = \"0x1\"\nlatest-published-id = \"0x2\"\n\
+                 published-version = \"2\"\n"
+            ),
+        )
+        .unwrap();
+        let ids = managed_ids(&lock_file, "testnet", "4c78adac")
+            .unwrap()
+            .unwrap();
+        assert_eq!(ids.original, ObjectID::from_single_byte(1));
+        assert_eq!(ids.latest, ObjectID::from_single_byte(2));
+        assert!(managed_ids(&lock_file, "devnet", "4c78adac")
+            .unwrap()
+            .is_none());
+        assert!(managed_ids(&lock_file, "testnet", "35834a8a").is_err());
+
+        // A malformed lock file must not make a published package look unpublished
+        std::fs::write(
+            &lock_file,
+            format!("{header}\n[env.testnet]\nchain-id = \"4c78adac\"\n"),
+        )
+        .unwrap();
+        assert!(managed_ids(&lock_file, "testnet", "4c78adac").is_err());
+        std::fs::write(&lock_file, "[env.testnet\n").unwrap();
+        assert!(managed_ids(&lock_file, "testnet", "4c78adac").is_err());
+    }
+
+    #[test]
+    fn test_rewrite_managed_addresses() {
+        let dir = tempfile::tempdir().unwrap();
+        let math = write_package(dir.path(), "math", &[]);
+        let app = write_package(dir.path(), "app", &["math"]);
+        let workspace = Workspace::load(&[math, app.clone()]).unwrap();
+        let ids = PublishedIds {
+            original: ObjectID::from_single_byte(1),
+            latest: ObjectID::from_single_byte(2),
+        };
+        let published = BTreeMap::from([(Symbol::from("math"), ids)]);
+
+        let mut resolution_graph = BuildConfig::new_for_testing()
+            .resolution_graph(&app)
+            .unwrap();
+        rewrite_managed_addresses(&mut resolution_graph, &workspace, &published);
+
+        let math = &resolution_graph.package_table[&Symbol::from("math")];
+        assert_eq!(
+            math.source_package.package.custom_properties
+                [&Symbol::from(PUBLISHED_AT_MANIFEST_FIELD)],
+            ids.latest.to_string()
+        );
+        for package in resolution_graph.package_table.values() {
+            assert_eq!(
+                package.resolved_table[&Symbol::from("math")],
+                AccountAddress::from(ids.original)
+            );
+        }
+        // Packages which are not published yet keep their `0x0` address
+        let app = &resolution_graph.package_table[&Symbol::from("app")];
+        assert_eq!(
+            app.resolved_table[&Symbol::from("app")],
+            AccountAddress::ZERO
+        );
+        assert!(!app
+            .source_package
+            .package
+            .custom_properties
+            .contains_key(&Symbol::from(PUBLISHED_AT_MANIFEST_FIELD)));
+    }
+
+    fn module(name: &str) -> CompiledModule {
+        let mut module = empty_module();
+        module.identifiers[0] = Identifier::new(name).unwrap();
+        module
+    }
+
+    /// Module map of `modules` once published with `original` as their address.
+    fn module_map(modules: &[CompiledModule], original: ObjectID) -> BTreeMap<String, Vec<u8>> {
+        modules
+            .iter()
+            .map(|module| {
+                let mut module = module.clone();
+                module.address_identifiers[0] = original.into();
+                let mut bytes = vec![];
+                module.serialize(&mut bytes).unwrap();
+                (module.self_id().name().to_string(), bytes)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_is_unchanged() {
+        let original = ObjectID::from_single_byte(1);
+        let local = [module("a"), module("b")];
+        let on_chain = module_map(&local, original);
+        assert!(same_modules(local.iter(), &on_chain, original).unwrap());
+
+        // The same modules published at another address
+        let other = ObjectID::from_single_byte(2);
+        assert!(!same_modules(local.iter(), &on_chain, other).unwrap());
+
+        // A module added or changed locally
+        let added = [module("a"), module("b"), module("c")];
+        assert!(!same_modules(added.iter(), &on_chain, original).unwrap());
+        let mut changed = module("b");
+        changed.identifiers.push(Identifier::new("f").unwrap());
+        let changed = [module("a"), changed];
+        assert!(!same_modules(changed.iter(), &on_chain, original).unwrap());
+
+        // The same modules published with an older bytecode version
+        let on_chain_v5: BTreeMap<_, _> = on_chain
+            .iter()
+            .map(|(name, bytes)| {
+                let module = CompiledModule::deserialize_with_defaults(bytes).unwrap();
+                let mut bytes = vec![];
+                module
+                    .serialize_for_version(Some(VERSION_5), &mut bytes)
+                    .unwrap();
+                (name.clone(), bytes)
+            })
+            .collect();
+        assert_ne!(on_chain, on_chain_v5);
+        assert!(same_modules(local.iter(), &on_chain_v5, original).unwrap());
+    }
+}
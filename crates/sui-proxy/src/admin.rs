@@ -1,6 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::config::{DynamicPeerValidationConfig, RemoteWriteConfig, StaticPeerValidationConfig};
+use crate::buffer::DiskBuffer;
+use crate::config::{
+    BufferConfig, DynamicPeerValidationConfig, RemoteWriteConfig, StaticPeerValidationConfig,
+};
+use crate::consumer::replay_buffer;
 use crate::handlers::publish_metrics;
 use crate::histogram_relay::HistogramRelay;
 use crate::ip::{is_private, to_multiaddr};
@@ -8,6 +12,8 @@ use crate::middleware::{
     expect_content_length, expect_mysten_proxy_header, expect_valid_public_key,
 };
 use crate::peers::{SuiNodeProvider, SuiPeer};
+use crate::relabel::Relabeler;
+use crate::tenants::{Tenant, TenantRouter};
 use crate::var;
 use anyhow::Error;
 use anyhow::Result;
@@ -17,7 +23,6 @@ use fastcrypto::traits::{KeyPair, ToFromBytes};
 use std::fs;
 use std::io::BufReader;
 use std::net::{IpAddr, SocketAddr};
-use std::sync::Arc;
 use std::time::Duration;
 use sui_tls::{rustls::ServerConfig, AllowAll, CertVerifier, SelfSignedCertificate, TlsAcceptor};
 use tokio::signal;
@@ -67,6 +72,15 @@ pub async fn shutdown_signal(h: axum_server::Handle) {
 pub struct ReqwestClient {
     pub client: reqwest::Client,
     pub settings: RemoteWriteConfig,
+    /// payloads that cannot be delivered are stored here for retry, if set
+    pub buffer: Option<DiskBuffer>,
+}
+
+impl ReqwestClient {
+    pub fn with_buffer(mut self, buffer: DiskBuffer) -> Self {
+        self.buffer = Some(buffer);
+        self
+    }
 }
 
 pub fn make_reqwest_client(settings: RemoteWriteConfig, user_agent: &str) -> ReqwestClient {
@@ -78,9 +92,35 @@ pub fn make_reqwest_client(settings: RemoteWriteConfig, user_agent: &str) -> Req
             .build()
             .expect("cannot create reqwest client"),
         settings,
+        buffer: None,
     }
 }
 
+/// make the client of a tenant. if buffering is configured, payloads that cannot be delivered
+/// are buffered in a directory named after the tenant and a task is spawned to replay them.
+pub fn make_tenant_client(
+    settings: RemoteWriteConfig,
+    user_agent: &str,
+    tenant: &str,
+    buffer: Option<&BufferConfig>,
+) -> Result<ReqwestClient> {
+    let client = make_reqwest_client(settings, user_agent);
+    let Some(buffer_config) = buffer else {
+        return Ok(client);
+    };
+    let buffer = DiskBuffer::new(
+        tenant.to_owned(),
+        buffer_config.directory.join(tenant),
+        buffer_config.max_bytes,
+    )?;
+    tokio::spawn(replay_buffer(
+        client.clone(),
+        buffer.clone(),
+        buffer_config.retry_interval,
+    ));
+    Ok(client.with_buffer(buffer))
+}
+
 // Labels are adhoc labels we will inject per our config
 #[derive(Clone)]
 pub struct Labels {
@@ -88,13 +128,30 @@ pub struct Labels {
     pub inventory_hostname: String,
 }
 
-/// App will configure our routes. This fn is also used to instrument our tests
+/// App will configure our routes for a single tenant. This fn is also used to instrument our tests
 pub fn app(
     labels: Labels,
     client: ReqwestClient,
     relay: HistogramRelay,
     allower: Option<SuiNodeProvider>,
 ) -> Router {
+    let tenant = Tenant::new(
+        labels.network.clone(),
+        labels,
+        Default::default(),
+        Relabeler::default(),
+        client,
+        allower,
+    );
+    app_with_tenants(
+        TenantRouter::new(vec![tenant]).expect("a single tenant is always valid"),
+        relay,
+    )
+}
+
+/// App with tenants will configure our routes, metrics are routed to the tenant of the calling node
+pub fn app_with_tenants(tenants: TenantRouter, relay: HistogramRelay) -> Router {
+    let validate_peers = tenants.default_tenant().allower.is_some();
     // build our application with a route and our sender mpsc
     let mut router = Router::new()
         .route("/publish/metrics", post(publish_metrics))
@@ -104,15 +161,14 @@ pub fn app(
         )))
         .route_layer(middleware::from_fn(expect_mysten_proxy_header))
         .route_layer(middleware::from_fn(expect_content_length));
-    if let Some(allower) = allower {
-        router = router
-            .route_layer(middleware::from_fn(expect_valid_public_key))
-            .layer(Extension(Arc::new(allower)));
+    if validate_peers {
+        // routes each request to the tenant of the calling node
+        router = router.route_layer(middleware::from_fn(expect_valid_public_key));
     }
     router
         .layer(Extension(relay))
-        .layer(Extension(labels))
-        .layer(Extension(client))
+        .layer(Extension(tenants.default_tenant().clone()))
+        .layer(Extension(tenants))
         .layer(
             ServiceBuilder::new().layer(
                 TraceLayer::new_for_http().on_response(
@@ -251,14 +307,43 @@ pub fn create_server_cert_enforce_peer(
             "missing certs to initialize server".into(),
         ));
     };
-    let static_peers = load_static_peers(static_peers).map_err(|e| {
-        sui_tls::rustls::Error::General(format!("unable to load static pub keys: {}", e))
-    })?;
-    let allower = SuiNodeProvider::new(dynamic_peers.url, dynamic_peers.interval, static_peers);
-    allower.poll_peer_list();
+    let allower = make_allower(dynamic_peers.url, dynamic_peers.interval, static_peers)
+        .map_err(|e| sui_tls::rustls::Error::General(e.to_string()))?;
     let c = CertVerifier::new(allower.clone()).rustls_server_config(
         load_certs(&certificate_path),
         load_private_key(&private_key_path),
     )?;
     Ok((c, Some(allower)))
 }
+
+/// Verify clients against the validators of every tenant's network, clients that are not a
+/// peer of any tenant will be rejected
+pub fn create_server_cert_enforce_tenants(
+    dynamic_peers: DynamicPeerValidationConfig,
+    tenants: TenantRouter,
+) -> Result<ServerConfig, sui_tls::rustls::Error> {
+    let (Some(certificate_path), Some(private_key_path)) =
+        (dynamic_peers.certificate_file, dynamic_peers.private_key)
+    else {
+        return Err(sui_tls::rustls::Error::General(
+            "missing certs to initialize server".into(),
+        ));
+    };
+    CertVerifier::new(tenants).rustls_server_config(
+        load_certs(&certificate_path),
+        load_private_key(&private_key_path),
+    )
+}
+
+/// make_allower loads the static peers and starts polling the validators of a network
+pub fn make_allower(
+    url: String,
+    interval: Duration,
+    static_peers: Option<StaticPeerValidationConfig>,
+) -> Result<SuiNodeProvider> {
+    let static_peers = load_static_peers(static_peers)
+        .map_err(|e| anyhow::anyhow!("unable to load static pub keys: {}", e))?;
+    let allower = SuiNodeProvider::new(url, interval, static_peers);
+    allower.poll_peer_list();
+    Ok(allower)
+}
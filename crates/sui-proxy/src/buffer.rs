@@ -0,0 +1,256 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use prometheus::{register_counter_vec, register_int_gauge_vec, CounterVec, IntGaugeVec};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+static BUFFER_OPS: Lazy<CounterVec> = Lazy::new(|| {
+    register_counter_vec!(
+        "disk_buffer_operations",
+        "Operations counters and status for the on-disk remote_write buffer.",
+        &["tenant", "operation", "status"]
+    )
+    .unwrap()
+});
+static BUFFER_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "disk_buffer_bytes",
+        "The size in bytes of remote_write payloads waiting in the on-disk buffer.",
+        &["tenant"]
+    )
+    .unwrap()
+});
+
+/// suffix of buffered payloads, which are snappy compressed remote_write protobufs
+const PAYLOAD_SUFFIX: &str = "snappy";
+/// suffix of payloads being written, they are renamed once complete
+const TMP_SUFFIX: &str = "tmp";
+
+/// DiskBuffer stores remote_write payloads that could not be delivered upstream, so they can be
+/// retried later. Payloads are files named after the time they were buffered, so listing them
+/// in lexical order yields the oldest first. When the buffer exceeds its maximum size, the
+/// oldest payloads are dropped.
+#[derive(Clone, Debug)]
+pub struct DiskBuffer {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    tenant: String,
+    directory: PathBuf,
+    max_bytes: u64,
+    // disambiguates payloads buffered within the same nanosecond
+    sequence: AtomicU64,
+    // serializes storing and removing payloads, so eviction and size accounting see a
+    // consistent directory. replays read and send payloads without holding it, so a payload
+    // may be evicted while it is being sent, in which case its removal is a no-op
+    index: Mutex<Index>,
+}
+
+/// buffered payloads and their sizes, loaded from the directory once at startup so that
+/// storing and removing payloads does not list the directory
+#[derive(Debug, Default)]
+struct Index {
+    payloads: BTreeMap<PathBuf, u64>,
+    total_bytes: u64,
+}
+
+impl DiskBuffer {
+    pub fn new(tenant: String, directory: PathBuf, max_bytes: u64) -> Result<Self> {
+        std::fs::create_dir_all(&directory)
+            .with_context(|| format!("cannot create buffer directory {:?}", directory))?;
+        let mut index = Index::default();
+        for entry in std::fs::read_dir(&directory)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == TMP_SUFFIX) {
+                // payloads interrupted while being written are incomplete
+                std::fs::remove_file(&path)?;
+            } else if path.extension().is_some_and(|ext| ext == PAYLOAD_SUFFIX) {
+                let size = entry.metadata()?.len();
+                index.payloads.insert(path, size);
+                index.total_bytes += size;
+            }
+        }
+        let total_bytes = index.total_bytes;
+        let buffer = Self {
+            inner: Arc::new(Inner {
+                tenant,
+                directory,
+                max_bytes,
+                sequence: AtomicU64::new(0),
+                index: Mutex::new(index),
+            }),
+        };
+        buffer.set_size(total_bytes);
+        Ok(buffer)
+    }
+
+    pub fn tenant(&self) -> &str {
+        &self.inner.tenant
+    }
+
+    /// store a payload in the buffer, dropping the oldest payloads if the buffer is full
+    pub async fn store(&self, payload: &[u8]) -> Result<()> {
+        let mut index = self.inner.index.lock().await;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let sequence = self.inner.sequence.fetch_add(1, Ordering::Relaxed);
+        let name = format!("{nanos:024}-{sequence:020}");
+        let tmp = self.inner.directory.join(format!("{name}.{TMP_SUFFIX}"));
+        let path = self
+            .inner
+            .directory
+            .join(format!("{name}.{PAYLOAD_SUFFIX}"));
+        let result = async {
+            tokio::fs::write(&tmp, payload).await?;
+            tokio::fs::rename(&tmp, &path).await
+        }
+        .await;
+        if let Err(error) = result {
+            self.observe("store", "failed");
+            return Err(error).context("unable to buffer payload");
+        }
+        self.observe("store", "success");
+        debug!("buffered {} bytes in {:?}", payload.len(), path);
+        index.payloads.insert(path, payload.len() as u64);
+        index.total_bytes += payload.len() as u64;
+        self.enforce_max_bytes(&mut index).await
+    }
+
+    /// returns buffered payloads, oldest first
+    pub async fn pending(&self) -> Result<Vec<PathBuf>> {
+        Ok(self
+            .inner
+            .index
+            .lock()
+            .await
+            .payloads
+            .keys()
+            .cloned()
+            .collect())
+    }
+
+    /// read a buffered payload
+    pub async fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        tokio::fs::read(path)
+            .await
+            .with_context(|| format!("unable to read buffered payload {:?}", path))
+    }
+
+    /// remove a payload once it has been delivered, or will never be
+    pub async fn remove(&self, path: &Path, status: &str) -> Result<()> {
+        let mut index = self.inner.index.lock().await;
+        // dropped concurrently to make room
+        let Some(size) = index.payloads.get(path).copied() else {
+            return Ok(());
+        };
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+        index.payloads.remove(path);
+        index.total_bytes -= size;
+        self.observe("remove", status);
+        self.set_size(index.total_bytes);
+        Ok(())
+    }
+
+    async fn enforce_max_bytes(&self, index: &mut Index) -> Result<()> {
+        while index.total_bytes > self.inner.max_bytes {
+            let Some((path, size)) = index.payloads.pop_first() else {
+                break;
+            };
+            warn!(
+                "{} buffer is full, dropping oldest payload {:?}",
+                self.inner.tenant, path
+            );
+            index.total_bytes -= size;
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => {
+                    self.set_size(index.total_bytes);
+                    return Err(error.into());
+                }
+            }
+            self.observe("evict", "success");
+        }
+        self.set_size(index.total_bytes);
+        Ok(())
+    }
+
+    fn set_size(&self, total: u64) {
+        BUFFER_BYTES
+            .with_label_values(&[&self.inner.tenant])
+            .set(total as i64);
+    }
+
+    fn observe(&self, operation: &str, status: &str) {
+        BUFFER_OPS
+            .with_label_values(&[&self.inner.tenant, operation, status])
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn buffer_store_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = DiskBuffer::new("test".into(), dir.path().join("test"), 1024).unwrap();
+
+        buffer.store(b"first").await.unwrap();
+        buffer.store(b"second").await.unwrap();
+        let pending = buffer.pending().await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(buffer.read(&pending[0]).await.unwrap(), b"first");
+        assert_eq!(buffer.read(&pending[1]).await.unwrap(), b"second");
+
+        buffer.remove(&pending[0], "sent").await.unwrap();
+        let pending = buffer.pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(buffer.read(&pending[0]).await.unwrap(), b"second");
+
+        // payloads survive a restart
+        drop(buffer);
+        let buffer = DiskBuffer::new("test".into(), dir.path().join("test"), 1024).unwrap();
+        assert_eq!(buffer.pending().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn buffer_drops_oldest_when_full() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = DiskBuffer::new("test".into(), dir.path().to_path_buf(), 10).unwrap();
+
+        for payload in [b"aaaa", b"bbbb", b"cccc"] {
+            buffer.store(payload).await.unwrap();
+        }
+        let pending = buffer.pending().await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(buffer.read(&pending[0]).await.unwrap(), b"bbbb");
+        assert_eq!(buffer.read(&pending[1]).await.unwrap(), b"cccc");
+
+        // payloads buffered before a restart count towards the maximum size
+        drop(buffer);
+        let buffer = DiskBuffer::new("test".into(), dir.path().to_path_buf(), 10).unwrap();
+        buffer.store(b"dddd").await.unwrap();
+        let pending = buffer.pending().await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(buffer.read(&pending[0]).await.unwrap(), b"cccc");
+        assert_eq!(buffer.read(&pending[1]).await.unwrap(), b"dddd");
+    }
+}
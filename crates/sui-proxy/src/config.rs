@@ -4,7 +4,9 @@ use anyhow::{Context, Result};
 use core::time::Duration;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::{serde_as, DurationSeconds};
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use tracing::debug;
 
 #[serde_as]
@@ -18,6 +20,107 @@ pub struct ProxyConfig {
     pub static_peers: Option<StaticPeerValidationConfig>,
     pub metrics_address: String,
     pub histogram_address: String,
+    /// extra labels added to all metrics of the default tenant, before relabeling
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// relabel rules applied to all metrics of the default tenant
+    #[serde(default)]
+    pub relabel_rules: Vec<RelabelConfig>,
+    /// additional networks served by this proxy. The top level network, remote-write and peers
+    /// make up the default tenant. Additional tenants require peer validation, since the tenant
+    /// of a request is found from the public key of the calling node.
+    #[serde(default)]
+    pub tenants: Vec<TenantConfig>,
+    /// if set, remote_write payloads that cannot be delivered are buffered on disk and retried
+    pub buffer: Option<BufferConfig>,
+}
+
+/// TenantConfig describes a network whose nodes push metrics through this proxy and where
+/// we forward them to.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TenantConfig {
+    /// unique name of the tenant, used in metrics and for the buffer directory
+    pub name: String,
+    /// value of the network label added to all metrics of this tenant
+    pub network: String,
+    pub remote_write: RemoteWriteConfig,
+    pub dynamic_peers: TenantPeerConfig,
+    pub static_peers: Option<StaticPeerValidationConfig>,
+    /// extra labels added to all metrics of this tenant, before relabeling
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// relabel rules applied to all metrics of this tenant
+    #[serde(default)]
+    pub relabel_rules: Vec<RelabelConfig>,
+}
+
+/// TenantPeerConfig is the json-rpc endpoint used to obtain the validators of a tenant's network.
+/// Tls settings are shared by all tenants and come from the top level dynamic-peers.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TenantPeerConfig {
+    /// url is the json-rpc url we use to obtain valid peers on the blockchain
+    pub url: String,
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub interval: Duration,
+}
+
+/// RelabelConfig follows the semantics of prometheus relabel_config. The metric name is
+/// available as the `__name__` source label but cannot be rewritten.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RelabelConfig {
+    /// labels whose values are concatenated with the separator and matched against regex
+    #[serde(default)]
+    pub source_labels: Vec<String>,
+    #[serde(default = "relabel_separator_default")]
+    pub separator: String,
+    /// anchored regular expression
+    #[serde(default = "relabel_regex_default")]
+    pub regex: String,
+    /// label to write for the replace action
+    pub target_label: Option<String>,
+    /// replacement for the replace action, may reference regex capture groups
+    #[serde(default = "relabel_replacement_default")]
+    pub replacement: String,
+    #[serde(default)]
+    pub action: RelabelAction,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    /// write the regex replacement to target_label if the source labels match
+    #[default]
+    Replace,
+    /// drop metrics whose source labels do not match
+    Keep,
+    /// drop metrics whose source labels match
+    Drop,
+    /// remove labels whose name matches
+    LabelDrop,
+    /// remove labels whose name does not match
+    LabelKeep,
+}
+
+/// BufferConfig controls on-disk buffering of remote_write payloads while the upstream is
+/// unavailable.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BufferConfig {
+    /// each tenant buffers into a sub directory named after the tenant
+    pub directory: PathBuf,
+    /// maximum size of the buffer of each tenant, the oldest payloads are dropped beyond it
+    #[serde(default = "buffer_max_bytes_default")]
+    pub max_bytes: u64,
+    /// how often we retry sending buffered payloads
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "buffer_retry_interval_default")]
+    pub retry_interval: Duration,
 }
 
 #[serde_as]
@@ -95,6 +198,27 @@ fn hostname_default() -> Option<String> {
     Some("localhost".to_string())
 }
 
+fn relabel_separator_default() -> String {
+    ";".to_string()
+}
+
+fn relabel_regex_default() -> String {
+    "(.*)".to_string()
+}
+
+fn relabel_replacement_default() -> String {
+    "$1".to_string()
+}
+
+/// the default size of a tenant's buffer, 1 GiB
+fn buffer_max_bytes_default() -> u64 {
+    1 << 30
+}
+
+fn buffer_retry_interval_default() -> Duration {
+    Duration::from_secs(30)
+}
+
 /// the default remote write url
 fn remote_write_url() -> String {
     "http://metrics-gw.testnet.sui.io/api/v1/push".to_string()
@@ -118,4 +242,22 @@ mod tests {
 
         let _template: ProxyConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
+
+    #[test]
+    fn config_load_tenants() {
+        const TEMPLATE: &str = include_str!("./data/config_tenants.yaml");
+
+        let config: ProxyConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        assert_eq!(config.tenants.len(), 1);
+        let tenant = &config.tenants[0];
+        assert_eq!(tenant.name, "devnet");
+        assert_eq!(tenant.relabel_rules.len(), 2);
+        assert_eq!(tenant.relabel_rules[0].action, RelabelAction::Drop);
+        assert_eq!(tenant.relabel_rules[0].separator, ";");
+        assert_eq!(tenant.relabel_rules[1].action, RelabelAction::Replace);
+        assert_eq!(tenant.relabel_rules[1].replacement, "$1");
+        let buffer = config.buffer.unwrap();
+        assert_eq!(buffer.max_bytes, 1 << 30);
+        assert_eq!(buffer.retry_interval, Duration::from_secs(10));
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::admin::ReqwestClient;
+use crate::buffer::DiskBuffer;
 use crate::prom_to_mimir::Mimir;
 use crate::remote_write::WriteRequest;
 use anyhow::Result;
@@ -17,7 +18,8 @@ use prometheus::{Counter, CounterVec, HistogramVec};
 use prost::Message;
 use protobuf::CodedInputStream;
 use std::io::Read;
-use tracing::{debug, error};
+use std::time::Duration;
+use tracing::{debug, error, info};
 
 static CONSUMER_OPS_SUBMITTED: Lazy<Counter> = Lazy::new(|| {
     register_counter!(
@@ -288,32 +290,28 @@ pub async fn convert_to_remote_write(
 
     // a counter so we don't iterate the node data 2x
     let mut mf_cnt = 0;
+    // number of payloads that were buffered on disk instead of delivered
+    let mut buffered = 0;
     for request in remote_write_protos {
         mf_cnt += 1;
         let compressed = match encode_compress(&request) {
-            Ok(compressed) => compressed,
+            Ok(compressed) => Bytes::from(compressed),
             Err(error) => return error,
         };
 
-        let response = match rc
-            .client
-            .post(rc.settings.url.to_owned())
-            .header(reqwest::header::CONTENT_ENCODING, "snappy")
-            .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
-            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
-            .basic_auth(
-                rc.settings.username.to_owned(),
-                Some(rc.settings.password.to_owned()),
-            )
-            .body(compressed)
-            .send()
-            .await
-        {
+        let response = match post(&rc, compressed.clone()).await {
             Ok(response) => response,
             Err(error) => {
                 CONSUMER_OPS
                     .with_label_values(&["check_response", "INTERNAL_SERVER_ERROR"])
                     .inc();
+                if let Some(buffer) = &rc.buffer {
+                    if store_in_buffer(buffer, &compressed).await {
+                        debug!("buffering metrics due to post error: {error}");
+                        buffered += 1;
+                        continue;
+                    }
+                }
                 error!("DROPPING METRICS due to post error: {error}");
                 timer.stop_and_discard();
                 return (
@@ -323,6 +321,20 @@ pub async fn convert_to_remote_write(
             }
         };
 
+        if let Some(buffer) = &rc.buffer {
+            if is_retriable(response.status()) && store_in_buffer(buffer, &compressed).await {
+                CONSUMER_OPS
+                    .with_label_values(&["check_response", "BUFFERED"])
+                    .inc();
+                debug!(
+                    "buffering metrics due to upstream status {}",
+                    response.status()
+                );
+                buffered += 1;
+                continue;
+            }
+        }
+
         match check_response(request, response).await {
             Ok(_) => (),
             Err(err) => {
@@ -333,9 +345,100 @@ pub async fn convert_to_remote_write(
     }
     CONSUMER_OPS_SUBMITTED.inc_by(mf_cnt as f64);
     timer.observe_duration();
+    if buffered > 0 {
+        return (StatusCode::ACCEPTED, "accepted; buffered for retry");
+    }
     (StatusCode::CREATED, "created")
 }
 
+/// post a snappy compressed remote_write payload to the upstream tsdb
+async fn post(rc: &ReqwestClient, compressed: Bytes) -> reqwest::Result<reqwest::Response> {
+    rc.client
+        .post(rc.settings.url.to_owned())
+        .header(reqwest::header::CONTENT_ENCODING, "snappy")
+        .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+        .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+        .basic_auth(
+            rc.settings.username.to_owned(),
+            Some(rc.settings.password.to_owned()),
+        )
+        .body(compressed)
+        .send()
+        .await
+}
+
+/// upstream responses for which sending the same payload later may succeed
+fn is_retriable(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// store a payload we failed to deliver in the buffer, returns false if that failed too
+async fn store_in_buffer(buffer: &DiskBuffer, compressed: &[u8]) -> bool {
+    match buffer.store(compressed).await {
+        Ok(()) => true,
+        Err(error) => {
+            error!("{} unable to buffer metrics; {error:#}", buffer.tenant());
+            false
+        }
+    }
+}
+
+/// replay_buffer periodically resends buffered payloads, oldest first. A round stops at the
+/// first payload that still cannot be delivered, to retry it on the next one.
+pub async fn replay_buffer(rc: ReqwestClient, buffer: DiskBuffer, retry_interval: Duration) {
+    info!("replaying buffered metrics for {}", buffer.tenant());
+    let mut interval = tokio::time::interval(retry_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        interval.tick().await;
+        let pending = match buffer.pending().await {
+            Ok(pending) => pending,
+            Err(error) => {
+                error!(
+                    "{} unable to list buffered metrics; {error:#}",
+                    buffer.tenant()
+                );
+                continue;
+            }
+        };
+        for path in pending {
+            // payloads may have been dropped to make room since we listed them
+            let Ok(payload) = buffer.read(&path).await else {
+                continue;
+            };
+            let status = match post(&rc, Bytes::from(payload)).await {
+                Ok(response) if response.status() == reqwest::StatusCode::OK => "sent",
+                Ok(response) if is_retriable(response.status()) => {
+                    debug!(
+                        "{} upstream still unavailable; {}",
+                        buffer.tenant(),
+                        response.status()
+                    );
+                    break;
+                }
+                Ok(response) => {
+                    error!(
+                        "{} DROPPING BUFFERED METRICS, upstream rejected them; {}",
+                        buffer.tenant(),
+                        response.status()
+                    );
+                    "rejected"
+                }
+                Err(error) => {
+                    debug!("{} upstream still unavailable; {error}", buffer.tenant());
+                    break;
+                }
+            };
+            if let Err(error) = buffer.remove(&path, status).await {
+                error!(
+                    "{} unable to remove buffered metrics; {error:#}",
+                    buffer.tenant()
+                );
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use prometheus::proto;
@@ -1,10 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::admin::{Labels, ReqwestClient};
-use crate::consumer::{convert_to_remote_write, populate_labels, NodeMetric};
+use crate::consumer::{convert_to_remote_write, NodeMetric};
 use crate::histogram_relay::HistogramRelay;
 use crate::middleware::LenDelimProtobuf;
 use crate::peers::SuiPeer;
+use crate::tenants::Tenant;
 use axum::{
     extract::{ConnectInfo, Extension},
     http::StatusCode,
@@ -40,10 +40,10 @@ static HTTP_HANDLER_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
 /// Publish handler which receives metrics from nodes.  Nodes will call us at this endpoint
 /// and we relay them to the upstream tsdb
 ///
-/// Clients will receive a response after successfully relaying the metrics upstream
+/// Clients will receive a response after successfully relaying the metrics upstream, or
+/// buffering them if the upstream of their tenant is unavailable
 pub async fn publish_metrics(
-    Extension(labels): Extension<Labels>,
-    Extension(client): Extension<ReqwestClient>,
+    Extension(tenant): Extension<Tenant>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Extension(SuiPeer {
         name, public_key, ..
@@ -57,10 +57,10 @@ pub async fn publish_metrics(
     let timer = HTTP_HANDLER_DURATION
         .with_label_values(&["publish_metrics", &name])
         .start_timer();
-    let data = populate_labels(name, labels.network, labels.inventory_hostname, data);
+    let data = tenant.process(name, data);
     relay.submit(data.clone());
     let response = convert_to_remote_write(
-        client.clone(),
+        tenant.client.clone(),
         NodeMetric {
             data,
             peer_addr: Multiaddr::from(addr.ip()),
@@ -1,16 +1,18 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser;
 use std::env;
 use sui_proxy::config::ProxyConfig;
 use sui_proxy::{
     admin::{
-        app, create_server_cert_default_allow, create_server_cert_enforce_peer,
-        make_reqwest_client, server, Labels,
+        app_with_tenants, create_server_cert_default_allow, create_server_cert_enforce_tenants,
+        make_allower, make_tenant_client, server, Labels,
     },
     config::load,
     histogram_relay, metrics,
+    relabel::Relabeler,
+    tenants::{Tenant, TenantRouter},
 };
 use sui_tls::TlsAcceptor;
 use telemetry_subscribers::TelemetryConfig;
@@ -72,23 +74,80 @@ async fn main() -> Result<()> {
 
     let listener = std::net::TcpListener::bind(config.listen_address).unwrap();
 
-    let (tls_config, allower) =
-        // we'll only use the dynamic peers in some cases - it makes little sense to run with the statics
-        // since this first mode allows all.
-        if config.dynamic_peers.certificate_file.is_none() || config.dynamic_peers.private_key.is_none() {
-            (
-                create_server_cert_default_allow(config.dynamic_peers.hostname.unwrap())
-                    .expect("unable to create self-signed server cert"),
-                None,
-            )
-        } else {
-            create_server_cert_enforce_peer(config.dynamic_peers, config.static_peers)
-                .expect("unable to create tls server config")
-        };
+    // we'll only use the dynamic peers in some cases - it makes little sense to run with the statics
+    // since this first mode allows all.
+    let validate_peers = config.dynamic_peers.certificate_file.is_some()
+        && config.dynamic_peers.private_key.is_some();
+    if !validate_peers && !config.tenants.is_empty() {
+        bail!("tenants require peer validation, please set certificate-file and private-key");
+    }
+    let inventory_hostname =
+        env::var("INVENTORY_HOSTNAME").expect("INVENTORY_HOSTNAME not found in environment");
+
+    // the top level network is the default tenant
+    let default_allower = if validate_peers {
+        Some(make_allower(
+            config.dynamic_peers.url.clone(),
+            config.dynamic_peers.interval,
+            config.static_peers,
+        )?)
+    } else {
+        None
+    };
+    let mut tenants = vec![Tenant::new(
+        config.network.clone(),
+        Labels {
+            network: config.network.clone(),
+            inventory_hostname: inventory_hostname.clone(),
+        },
+        config.labels,
+        Relabeler::new(&config.relabel_rules)?,
+        make_tenant_client(
+            config.remote_write,
+            APP_USER_AGENT,
+            &config.network,
+            config.buffer.as_ref(),
+        )?,
+        default_allower,
+    )];
+    for tenant in config.tenants {
+        info!(
+            "tenant {} send to {:?}",
+            tenant.name, tenant.remote_write.url
+        );
+        tenants.push(Tenant::new(
+            tenant.name.clone(),
+            Labels {
+                network: tenant.network,
+                inventory_hostname: inventory_hostname.clone(),
+            },
+            tenant.labels,
+            Relabeler::new(&tenant.relabel_rules)?,
+            make_tenant_client(
+                tenant.remote_write,
+                APP_USER_AGENT,
+                &tenant.name,
+                config.buffer.as_ref(),
+            )?,
+            Some(make_allower(
+                tenant.dynamic_peers.url,
+                tenant.dynamic_peers.interval,
+                tenant.static_peers,
+            )?),
+        ));
+    }
+    let tenants = TenantRouter::new(tenants)?;
+
+    let tls_config = if validate_peers {
+        create_server_cert_enforce_tenants(config.dynamic_peers, tenants.clone())
+            .expect("unable to create tls server config")
+    } else {
+        create_server_cert_default_allow(config.dynamic_peers.hostname.unwrap())
+            .expect("unable to create self-signed server cert")
+    };
     let histogram_listener = std::net::TcpListener::bind(config.histogram_address).unwrap();
     let metrics_listener = std::net::TcpListener::bind(config.metrics_address).unwrap();
     let acceptor = TlsAcceptor::new(tls_config);
-    let client = make_reqwest_client(config.remote_write, APP_USER_AGENT);
     let histogram_relay = histogram_relay::start_prometheus_server(histogram_listener);
     let registry_service = metrics::start_prometheus_server(metrics_listener);
     let prometheus_registry = registry_service.default_registry();
@@ -99,16 +158,7 @@ async fn main() -> Result<()> {
             "unavailable",
         ))
         .unwrap();
-    let app = app(
-        Labels {
-            network: config.network,
-            inventory_hostname: env::var("INVENTORY_HOSTNAME")
-                .expect("INVENTORY_HOSTNAME not found in environment"),
-        },
-        client,
-        histogram_relay,
-        allower,
-    );
+    let app = app_with_tenants(tenants, histogram_relay);
 
     server(listener, app, Some(acceptor)).await.unwrap();
 
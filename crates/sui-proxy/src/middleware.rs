@@ -1,6 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::{consumer::ProtobufDecoder, peers::SuiNodeProvider};
+use crate::{consumer::ProtobufDecoder, tenants::TenantRouter};
 use axum::{
     async_trait,
     body::Bytes,
@@ -15,7 +15,6 @@ use bytes::Buf;
 use hyper::header::CONTENT_ENCODING;
 use once_cell::sync::Lazy;
 use prometheus::{proto::MetricFamily, register_counter_vec, CounterVec};
-use std::sync::Arc;
 use sui_tls::TlsConnectionInfo;
 use tracing::error;
 
@@ -65,10 +64,10 @@ pub async fn expect_mysten_proxy_header<B>(
     }
 }
 
-/// we expect that calling sui-nodes are known on the blockchain and we enforce
-/// their pub key tls creds here
+/// we expect that calling sui-nodes are known on the blockchain of one of our tenants and we
+/// enforce their pub key tls creds here. the request is routed to that tenant.
 pub async fn expect_valid_public_key<B>(
-    Extension(allower): Extension<Arc<SuiNodeProvider>>,
+    Extension(tenants): Extension<TenantRouter>,
     Extension(tls_connect_info): Extension<TlsConnectionInfo>,
     mut request: Request<B>,
     next: Next<B>,
@@ -80,7 +79,7 @@ pub async fn expect_valid_public_key<B>(
             .inc();
         return Err((StatusCode::FORBIDDEN, "unknown clients are not allowed"));
     };
-    let Some(peer) = allower.get(public_key) else {
+    let Some((tenant, peer)) = tenants.route(public_key) else {
         error!("node with unknown pub key tried to connect {}", public_key);
         MIDDLEWARE_OPS
             .with_label_values(&[
@@ -90,6 +89,7 @@ pub async fn expect_valid_public_key<B>(
             .inc();
         return Err((StatusCode::FORBIDDEN, "unknown clients are not allowed"));
     };
+    request.extensions_mut().insert(tenant.clone());
     request.extensions_mut().insert(peer);
     Ok(next.run(request).await)
 }
@@ -0,0 +1,273 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::config::{RelabelAction, RelabelConfig};
+use anyhow::{bail, Context, Result};
+use prometheus::proto::{self, MetricFamily};
+use regex::Regex;
+
+/// the pseudo label holding the metric name
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// RelabelRule is a compiled RelabelConfig
+#[derive(Debug)]
+struct RelabelRule {
+    source_labels: Vec<String>,
+    separator: String,
+    regex: Regex,
+    target_label: Option<String>,
+    replacement: String,
+    action: RelabelAction,
+}
+
+/// Relabeler applies relabel rules, in order, to the metrics of a tenant
+#[derive(Debug, Default)]
+pub struct Relabeler {
+    rules: Vec<RelabelRule>,
+}
+
+impl Relabeler {
+    pub fn new(configs: &[RelabelConfig]) -> Result<Self> {
+        let rules = configs
+            .iter()
+            .map(|config| {
+                // regexes are anchored on both ends, as in prometheus
+                let regex = Regex::new(&format!("^(?:{})$", config.regex))
+                    .with_context(|| format!("invalid relabel regex {}", config.regex))?;
+                match (config.action, config.target_label.as_deref()) {
+                    (RelabelAction::Replace, None) => {
+                        bail!("relabel action replace requires a target-label")
+                    }
+                    (RelabelAction::Replace, Some(METRIC_NAME_LABEL)) => {
+                        bail!("relabel rules cannot rewrite {METRIC_NAME_LABEL}")
+                    }
+                    _ => {}
+                }
+                Ok(RelabelRule {
+                    source_labels: config.source_labels.clone(),
+                    separator: config.separator.clone(),
+                    regex,
+                    target_label: config.target_label.clone(),
+                    replacement: config.replacement.clone(),
+                    action: config.action,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// apply the rules to every metric. metrics that are dropped are removed from their family
+    /// and families left without metrics are removed.
+    pub fn apply(&self, data: Vec<MetricFamily>) -> Vec<MetricFamily> {
+        if self.rules.is_empty() {
+            return data;
+        }
+        data.into_iter()
+            .filter_map(|mut mf| {
+                let name = mf.get_name().to_owned();
+                let metrics = mf
+                    .take_metric()
+                    .into_iter()
+                    .filter_map(|mut m| self.apply_metric(&name, &mut m).then_some(m))
+                    .collect::<Vec<_>>();
+                if metrics.is_empty() {
+                    return None;
+                }
+                mf.set_metric(metrics.into());
+                Some(mf)
+            })
+            .collect()
+    }
+
+    /// relabel a single metric, returns false if the metric is dropped
+    fn apply_metric(&self, name: &str, metric: &mut proto::Metric) -> bool {
+        for rule in &self.rules {
+            match rule.action {
+                RelabelAction::Replace => {
+                    let value = source_value(rule, name, metric);
+                    let Some(captures) = rule.regex.captures(&value) else {
+                        continue;
+                    };
+                    let mut replaced = String::new();
+                    captures.expand(&rule.replacement, &mut replaced);
+                    let target = rule
+                        .target_label
+                        .as_deref()
+                        .expect("validated in Relabeler::new");
+                    set_label(metric, target, replaced);
+                }
+                RelabelAction::Keep => {
+                    if !rule.regex.is_match(&source_value(rule, name, metric)) {
+                        return false;
+                    }
+                }
+                RelabelAction::Drop => {
+                    if rule.regex.is_match(&source_value(rule, name, metric)) {
+                        return false;
+                    }
+                }
+                RelabelAction::LabelDrop => {
+                    retain_labels(metric, |name| !rule.regex.is_match(name))
+                }
+                RelabelAction::LabelKeep => retain_labels(metric, |name| rule.regex.is_match(name)),
+            }
+        }
+        true
+    }
+}
+
+/// concatenate the values of the rule's source labels. missing labels are empty strings.
+fn source_value(rule: &RelabelRule, name: &str, metric: &proto::Metric) -> String {
+    rule.source_labels
+        .iter()
+        .map(|source| {
+            if source == METRIC_NAME_LABEL {
+                return name;
+            }
+            metric
+                .get_label()
+                .iter()
+                .find(|l| l.get_name() == source)
+                .map(|l| l.get_value())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(&rule.separator)
+}
+
+/// keep only the labels whose name satisfies the predicate
+fn retain_labels(metric: &mut proto::Metric, keep: impl Fn(&str) -> bool) {
+    let labels = metric
+        .take_label()
+        .into_iter()
+        .filter(|l| keep(l.get_name()))
+        .collect::<Vec<_>>();
+    metric.set_label(labels.into());
+}
+
+/// set a label on the metric, an empty value removes the label
+fn set_label(metric: &mut proto::Metric, name: &str, value: String) {
+    let labels = metric.mut_label();
+    let existing = labels.iter().position(|l| l.get_name() == name);
+    match (existing, value.is_empty()) {
+        (Some(i), true) => {
+            labels.remove(i);
+        }
+        (Some(i), false) => labels[i].set_value(value),
+        (None, true) => {}
+        (None, false) => {
+            let mut label = proto::LabelPair::default();
+            label.set_name(name.into());
+            label.set_value(value);
+            labels.push(label);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prom_to_mimir::tests::{
+        create_counter, create_labels, create_metric_counter, create_metric_family,
+    };
+    use protobuf::RepeatedField;
+
+    fn rule(
+        action: RelabelAction,
+        source_labels: &[&str],
+        regex: &str,
+        target_label: Option<&str>,
+        replacement: &str,
+    ) -> RelabelConfig {
+        RelabelConfig {
+            source_labels: source_labels.iter().map(|s| s.to_string()).collect(),
+            separator: ";".into(),
+            regex: regex.into(),
+            target_label: target_label.map(|s| s.to_string()),
+            replacement: replacement.into(),
+            action,
+        }
+    }
+
+    fn family(name: &str, labels: Vec<Vec<(&str, &str)>>) -> MetricFamily {
+        create_metric_family(
+            name,
+            "help",
+            None,
+            RepeatedField::from_vec(
+                labels
+                    .into_iter()
+                    .map(|l| {
+                        create_metric_counter(
+                            RepeatedField::from_vec(create_labels(l)),
+                            create_counter(1.0),
+                        )
+                    })
+                    .collect(),
+            ),
+        )
+    }
+
+    #[test]
+    fn relabel_drop_and_keep() {
+        let relabeler = Relabeler::new(&[
+            rule(RelabelAction::Drop, &["__name__"], "go_.*", None, "$1"),
+            rule(RelabelAction::Keep, &["host"], "validator-.*", None, "$1"),
+        ])
+        .unwrap();
+
+        let data = relabeler.apply(vec![
+            family("go_threads", vec![vec![("host", "validator-0")]]),
+            family(
+                "uptime",
+                vec![vec![("host", "validator-0")], vec![("host", "fullnode-0")]],
+            ),
+        ]);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].get_name(), "uptime");
+        assert_eq!(data[0].get_metric().len(), 1);
+        assert_eq!(
+            data[0].get_metric()[0].get_label(),
+            &create_labels(vec![("host", "validator-0")])
+        );
+    }
+
+    #[test]
+    fn relabel_replace_and_labeldrop() {
+        let relabeler = Relabeler::new(&[
+            rule(
+                RelabelAction::Replace,
+                &["network", "host"],
+                "(.*);validator-(.*)",
+                Some("validator"),
+                "$1/$2",
+            ),
+            rule(RelabelAction::LabelDrop, &[], "host", None, "$1"),
+            // an empty replacement removes the label
+            rule(RelabelAction::Replace, &[], "", Some("network"), ""),
+        ])
+        .unwrap();
+
+        let data = relabeler.apply(vec![family(
+            "uptime",
+            vec![vec![("network", "devnet"), ("host", "validator-3")]],
+        )]);
+        assert_eq!(
+            data[0].get_metric()[0].get_label(),
+            &create_labels(vec![("validator", "devnet/3")])
+        );
+    }
+
+    #[test]
+    fn relabel_invalid_rules() {
+        assert!(Relabeler::new(&[rule(RelabelAction::Replace, &[], "(.*)", None, "$1")]).is_err());
+        assert!(Relabeler::new(&[rule(
+            RelabelAction::Replace,
+            &[],
+            "(.*)",
+            Some("__name__"),
+            "$1"
+        )])
+        .is_err());
+        assert!(Relabeler::new(&[rule(RelabelAction::Drop, &["a"], "(", None, "$1")]).is_err());
+    }
+}
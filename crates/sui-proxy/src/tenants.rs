@@ -0,0 +1,221 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::admin::{Labels, ReqwestClient};
+use crate::consumer::populate_labels;
+use crate::peers::{SuiNodeProvider, SuiPeer};
+use crate::relabel::Relabeler;
+use anyhow::{bail, Result};
+use fastcrypto::ed25519::Ed25519PublicKey;
+use prometheus::proto;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use sui_tls::Allower;
+
+/// A Tenant is a network whose nodes push metrics through this proxy. It holds the peers we
+/// accept for the network, how their metrics are labeled and where they are forwarded.
+#[derive(Clone)]
+pub struct Tenant {
+    pub name: String,
+    pub labels: Labels,
+    /// extra labels added to every metric, before relabeling
+    pub extra_labels: Vec<proto::LabelPair>,
+    pub relabeler: Arc<Relabeler>,
+    pub client: ReqwestClient,
+    /// peers of the tenant, None if peers are not validated
+    pub allower: Option<SuiNodeProvider>,
+}
+
+impl Tenant {
+    pub fn new(
+        name: String,
+        labels: Labels,
+        extra_labels: BTreeMap<String, String>,
+        relabeler: Relabeler,
+        client: ReqwestClient,
+        allower: Option<SuiNodeProvider>,
+    ) -> Self {
+        let extra_labels = extra_labels
+            .into_iter()
+            .map(|(name, value)| {
+                let mut label = proto::LabelPair::default();
+                label.set_name(name);
+                label.set_value(value);
+                label
+            })
+            .collect();
+        Self {
+            name,
+            labels,
+            extra_labels,
+            relabeler: Arc::new(relabeler),
+            client,
+            allower,
+        }
+    }
+
+    /// label metrics received from a peer of this tenant and apply the tenant's relabel rules
+    pub fn process(
+        &self,
+        peer_name: String,
+        data: Vec<proto::MetricFamily>,
+    ) -> Vec<proto::MetricFamily> {
+        let mut data = populate_labels(
+            peer_name,
+            self.labels.network.clone(),
+            self.labels.inventory_hostname.clone(),
+            data,
+        );
+        if !self.extra_labels.is_empty() {
+            for mf in data.iter_mut() {
+                for m in mf.mut_metric() {
+                    m.mut_label().extend(self.extra_labels.clone());
+                }
+            }
+        }
+        self.relabeler.apply(data)
+    }
+}
+
+/// TenantRouter finds the tenant a connecting node belongs to from its public key. The first
+/// tenant is the default one, used for all requests when peers are not validated.
+#[derive(Clone)]
+pub struct TenantRouter {
+    tenants: Arc<Vec<Tenant>>,
+}
+
+impl TenantRouter {
+    pub fn new(tenants: Vec<Tenant>) -> Result<Self> {
+        if tenants.is_empty() {
+            bail!("at least one tenant is required");
+        }
+        let mut names = HashSet::new();
+        for tenant in &tenants {
+            if !names.insert(tenant.name.as_str()) {
+                bail!("duplicate tenant name {}", tenant.name);
+            }
+        }
+        if tenants.len() > 1 && tenants.iter().any(|t| t.allower.is_none()) {
+            bail!("multiple tenants require peer validation for every tenant");
+        }
+        Ok(Self {
+            tenants: Arc::new(tenants),
+        })
+    }
+
+    pub fn default_tenant(&self) -> &Tenant {
+        &self.tenants[0]
+    }
+
+    pub fn tenants(&self) -> &[Tenant] {
+        &self.tenants
+    }
+
+    /// route returns the first tenant whose peers include the key, along with the peer
+    pub fn route(&self, key: &Ed25519PublicKey) -> Option<(&Tenant, SuiPeer)> {
+        self.tenants.iter().find_map(|tenant| {
+            let peer = tenant.allower.as_ref()?.get(key)?;
+            Some((tenant, peer))
+        })
+    }
+}
+
+impl Allower for TenantRouter {
+    fn allowed(&self, key: &Ed25519PublicKey) -> bool {
+        self.tenants
+            .iter()
+            .any(|t| t.allower.as_ref().is_some_and(|a| a.allowed(key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::{generate_self_cert, make_reqwest_client, CertKeyPair};
+    use crate::config::RemoteWriteConfig;
+    use crate::prom_to_mimir::tests::{
+        create_counter, create_labels, create_metric_counter, create_metric_family,
+    };
+    use multiaddr::Multiaddr;
+    use protobuf::RepeatedField;
+    use std::time::Duration;
+
+    fn tenant(name: &str, peers: Vec<SuiPeer>) -> Tenant {
+        Tenant::new(
+            name.into(),
+            Labels {
+                network: name.into(),
+                inventory_hostname: "inventory-hostname".into(),
+            },
+            BTreeMap::from([("committee".to_string(), format!("{name}-committee"))]),
+            Relabeler::default(),
+            make_reqwest_client(RemoteWriteConfig::default(), "dummy user agent"),
+            Some(SuiNodeProvider::new(
+                "".into(),
+                Duration::from_secs(30),
+                peers,
+            )),
+        )
+    }
+
+    fn peer(name: &str) -> SuiPeer {
+        let CertKeyPair(_, public_key) = generate_self_cert(name.into());
+        SuiPeer {
+            name: name.into(),
+            p2p_address: Multiaddr::empty(),
+            public_key,
+        }
+    }
+
+    #[test]
+    fn route_peers_to_tenants() {
+        let testnet_peer = peer("testnet-validator");
+        let devnet_peer = peer("devnet-validator");
+        let unknown_peer = peer("unknown");
+        let router = TenantRouter::new(vec![
+            tenant("testnet", vec![testnet_peer.clone()]),
+            tenant("devnet", vec![devnet_peer.clone()]),
+        ])
+        .unwrap();
+
+        let (tenant, routed) = router.route(&devnet_peer.public_key).unwrap();
+        assert_eq!(tenant.name, "devnet");
+        assert_eq!(routed, devnet_peer);
+        let (tenant, _) = router.route(&testnet_peer.public_key).unwrap();
+        assert_eq!(tenant.name, "testnet");
+        assert!(router.route(&unknown_peer.public_key).is_none());
+
+        assert!(router.allowed(&devnet_peer.public_key));
+        assert!(!router.allowed(&unknown_peer.public_key));
+    }
+
+    #[test]
+    fn invalid_tenants() {
+        assert!(TenantRouter::new(vec![]).is_err());
+        assert!(TenantRouter::new(vec![tenant("a", vec![]), tenant("a", vec![])]).is_err());
+        let mut unvalidated = tenant("b", vec![]);
+        unvalidated.allower = None;
+        assert!(TenantRouter::new(vec![tenant("a", vec![]), unvalidated]).is_err());
+    }
+
+    #[test]
+    fn tenant_labels() {
+        let mf = create_metric_family(
+            "uptime",
+            "help",
+            None,
+            RepeatedField::from_vec(vec![create_metric_counter(
+                RepeatedField::from_vec(create_labels(vec![])),
+                create_counter(1.0),
+            )]),
+        );
+        let data = tenant("devnet", vec![]).process("validator-0".into(), vec![mf]);
+        assert_eq!(
+            data[0].get_metric()[0].get_label(),
+            &create_labels(vec![
+                ("network", "devnet"),
+                ("host", "validator-0"),
+                ("committee", "devnet-committee"),
+            ])
+        );
+    }
+}
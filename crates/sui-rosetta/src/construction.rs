@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+
 use axum::extract::State;
 use axum::{Extension, Json};
 use axum_extra::extract::WithRejection;
@@ -19,17 +21,18 @@ use sui_types::crypto::{DefaultHash, SignatureScheme, ToFromBytes};
 use sui_types::error::SuiError;
 use sui_types::signature::{GenericSignature, VerifyParams};
 use sui_types::signature_verification::verify_sender_signed_data_message_signatures;
-use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
+use sui_types::transaction::{Transaction, TransactionDataAPI};
 
 use crate::errors::Error;
+use crate::operations::Operations;
 use crate::types::{
     Amount, ConstructionCombineRequest, ConstructionCombineResponse, ConstructionDeriveRequest,
     ConstructionDeriveResponse, ConstructionHashRequest, ConstructionMetadata,
     ConstructionMetadataRequest, ConstructionMetadataResponse, ConstructionParseRequest,
     ConstructionParseResponse, ConstructionPayloadsRequest, ConstructionPayloadsResponse,
     ConstructionPreprocessRequest, ConstructionPreprocessResponse, ConstructionSubmitRequest,
-    InternalOperation, MetadataOptions, SignatureType, SigningPayload, TransactionIdentifier,
-    TransactionIdentifierResponse,
+    InternalOperation, MetadataOptions, SignatureType, SignedTransaction, SigningPayload,
+    TransactionIdentifier, TransactionIdentifierResponse, UnsignedTransaction,
 };
 use crate::{OnlineServerContext, SuiEnv};
 
@@ -53,6 +56,9 @@ pub async fn derive(
 /// It returns an unsigned transaction blob and a collection of payloads that must be signed by
 /// particular AccountIdentifiers using a certain SignatureType.
 ///
+/// This endpoint does not require network access, the metadata can be fetched by an online server
+/// ahead of time and carried over to an offline (cold wallet) server.
+///
 /// [Rosetta API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionpayloads)
 pub async fn payloads(
     Extension(env): Extension<SuiEnv>,
@@ -62,23 +68,52 @@ pub async fn payloads(
     let metadata = request.metadata.ok_or(Error::MissingMetadata)?;
     let address = metadata.sender;
 
-    let data = request
-        .operations
-        .into_internal()?
-        .try_into_data(metadata)?;
+    let internal_operation = request.operations.into_internal()?;
+    // The metadata might have been produced for a different request, make sure it is usable
+    // before building the transaction as there is no fullnode to catch it when running offline.
+    if internal_operation.sender() != address {
+        return Err(Error::InvalidInput(format!(
+            "Metadata sender [{address}] does not match operation sender [{}]",
+            internal_operation.sender()
+        )));
+    }
+    if metadata.coins.is_empty() {
+        return Err(Error::InvalidInput("Metadata contains no gas coins".into()));
+    }
+
+    // Use the sender's public key to select the signature type if provided, default to Ed25519.
+    let mut signature_type = SignatureType::Ed25519;
+    for public_key in request.public_keys {
+        let curve_type = public_key.curve_type;
+        let key_address: SuiAddress = public_key.try_into()?;
+        if key_address == address {
+            signature_type = curve_type.try_into()?;
+            break;
+        }
+    }
+
+    let currency = match &internal_operation {
+        InternalOperation::PayCoin { currency, .. } => Some(currency.clone()),
+        _ => None,
+    };
+    let data = internal_operation.try_into_data(metadata)?;
     let intent_msg = IntentMessage::new(Intent::sui_transaction(), data);
-    let intent_msg_bytes = bcs::to_bytes(&intent_msg)?;
 
     let mut hasher = DefaultHash::default();
     hasher.update(&bcs::to_bytes(&intent_msg).expect("Message serialization should not fail"));
     let digest = hasher.finalize().digest;
 
+    let unsigned_tx_bytes = bcs::to_bytes(&UnsignedTransaction {
+        intent_message: intent_msg,
+        currency,
+    })?;
+
     Ok(ConstructionPayloadsResponse {
-        unsigned_transaction: Hex::from_bytes(&intent_msg_bytes),
+        unsigned_transaction: Hex::from_bytes(&unsigned_tx_bytes),
         payloads: vec![SigningPayload {
             account_identifier: address.into(),
             hex_bytes: Hex::encode(digest),
-            signature_type: Some(SignatureType::Ed25519),
+            signature_type: Some(signature_type),
         }],
     })
 }
@@ -92,8 +127,7 @@ pub async fn combine(
     WithRejection(Json(request), _): WithRejection<Json<ConstructionCombineRequest>, Error>,
 ) -> Result<ConstructionCombineResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
-    let unsigned_tx = request.unsigned_transaction.to_vec()?;
-    let intent_msg: IntentMessage<TransactionData> = bcs::from_bytes(&unsigned_tx)?;
+    let unsigned_tx = UnsignedTransaction::from_bytes(&request.unsigned_transaction.to_vec()?)?;
     let sig = request
         .signatures
         .first()
@@ -107,7 +141,7 @@ pub async fn combine(
     .flag()];
 
     let signed_tx = Transaction::from_generic_sig_data(
-        intent_msg.value,
+        unsigned_tx.intent_message.value,
         vec![GenericSignature::from_bytes(
             &[&*flag, &*sig_bytes, &*pub_key].concat(),
         )?],
@@ -120,7 +154,10 @@ pub async fn combine(
         place_holder_epoch,
         &VerifyParams::default(),
     )?;
-    let signed_tx_bytes = bcs::to_bytes(&signed_tx)?;
+    let signed_tx_bytes = bcs::to_bytes(&SignedTransaction {
+        transaction: signed_tx,
+        currency: unsigned_tx.currency,
+    })?;
 
     Ok(ConstructionCombineResponse {
         signed_transaction: Hex::from_bytes(&signed_tx_bytes),
@@ -136,7 +173,8 @@ pub async fn submit(
     WithRejection(Json(request), _): WithRejection<Json<ConstructionSubmitRequest>, Error>,
 ) -> Result<TransactionIdentifierResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
-    let signed_tx: Transaction = bcs::from_bytes(&request.signed_transaction.to_vec()?)?;
+    let signed_tx =
+        SignedTransaction::from_bytes(&request.signed_transaction.to_vec()?)?.transaction;
 
     // According to RosettaClient.rosseta_flow() (see tests), this transaction has already passed
     // through a dry_run with a possibly invalid budget (metadata endpoint), but the requirements
@@ -212,7 +250,7 @@ pub async fn hash(
 ) -> Result<TransactionIdentifierResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
     let tx_bytes = request.signed_transaction.to_vec()?;
-    let tx: Transaction = bcs::from_bytes(&tx_bytes)?;
+    let tx = SignedTransaction::from_bytes(&tx_bytes)?.transaction;
 
     Ok(TransactionIdentifierResponse {
         transaction_identifier: TransactionIdentifier { hash: *tx.digest() },
@@ -248,6 +286,25 @@ pub async fn metadata(
             let amount = amounts.iter().sum::<u64>();
             (Some(amount), vec![])
         }
+        InternalOperation::PayCoin {
+            sender,
+            amounts,
+            currency,
+            ..
+        } => {
+            let amount = amounts.iter().sum::<u64>();
+            let coin_type = currency.coin_type()?.to_string();
+            let coin_objs = context
+                .client
+                .coin_read_api()
+                .select_coins(*sender, Some(coin_type), amount.into(), vec![])
+                .await?
+                .into_iter()
+                .map(|coin| coin.object_ref())
+                .collect();
+            // SUI is only needed for gas
+            (Some(0), coin_objs)
+        }
         InternalOperation::Stake { amount, .. } => (*amount, vec![]),
         InternalOperation::WithdrawStake { sender, stake_ids } => {
             let stake_ids = if stake_ids.is_empty() {
@@ -379,20 +436,27 @@ pub async fn parse(
 ) -> Result<ConstructionParseResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
 
-    let data = if request.signed {
-        let tx: Transaction = bcs::from_bytes(&request.transaction.to_vec()?)?;
-        tx.into_data().intent_message().value.clone()
+    let tx_bytes = request.transaction.to_vec()?;
+    let (data, currency) = if request.signed {
+        let tx = SignedTransaction::from_bytes(&tx_bytes)?;
+        let data = tx.transaction.into_data().intent_message().value.clone();
+        (data, tx.currency)
     } else {
-        let intent: IntentMessage<TransactionData> =
-            bcs::from_bytes(&request.transaction.to_vec()?)?;
-        intent.value
+        let tx = UnsignedTransaction::from_bytes(&tx_bytes)?;
+        (tx.intent_message.value, tx.currency)
     };
     let account_identifier_signers = if request.signed {
         vec![data.sender().into()]
     } else {
         vec![]
     };
-    let operations = data.try_into()?;
+    // The transaction only knows the coin type of a PayCoin operation, report it with the
+    // currency the transaction was built with.
+    let mut currencies = HashMap::new();
+    if let Some(currency) = currency {
+        currencies.insert(currency.coin_type()?, currency);
+    }
+    let operations = Operations::try_from(data)?.with_currencies(&currencies);
     Ok(ConstructionParseResponse {
         operations,
         account_identifier_signers,
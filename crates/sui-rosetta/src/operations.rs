@@ -8,10 +8,11 @@ use std::vec;
 
 use anyhow::anyhow;
 use move_core_types::ident_str;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::resolver::ModuleResolver;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 
 use sui_json_rpc_types::SuiProgrammableMoveCall;
 use sui_json_rpc_types::SuiProgrammableTransactionBlock;
@@ -22,18 +23,22 @@ use sui_sdk::rpc_types::{
     SuiTransactionBlockKind, SuiTransactionBlockResponse,
 };
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::coin::{PAY_MODULE_NAME, PAY_SPLIT_AND_TRANSFER_FUNC_NAME};
 use sui_types::gas_coin::{GasCoin, GAS};
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::object::Owner;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::TransactionData;
-use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
+use sui_types::{
+    parse_sui_type_tag, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID,
+};
 
+use crate::state::CoinMetadataCache;
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, Currency,
+    InternalOperation, OperationIdentifier, OperationStatus, OperationType,
 };
-use crate::Error;
+use crate::{Error, SUI};
 
 #[cfg(test)]
 #[path = "unit_tests/operations_tests.rs"]
@@ -90,6 +95,24 @@ impl Operations {
         self
     }
 
+    /// Report amounts with the given currencies instead of the currency derived from the coin
+    /// type alone, amounts of other coin types are left unchanged.
+    pub fn with_currencies(mut self, currencies: &HashMap<TypeTag, Currency>) -> Self {
+        for op in &mut self.0 {
+            if let Some(amount) = &mut op.amount {
+                if let Some(currency) = amount
+                    .currency
+                    .coin_type()
+                    .ok()
+                    .and_then(|coin_type| currencies.get(&coin_type))
+                {
+                    amount.currency = currency.clone();
+                }
+            }
+        }
+        self
+    }
+
     pub fn type_(&self) -> Option<OperationType> {
         self.0.first().map(|op| op.type_)
     }
@@ -101,6 +124,7 @@ impl Operations {
             .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
+            OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
@@ -108,11 +132,57 @@ impl Operations {
     }
 
     fn pay_sui_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops_to_parts()?;
+        if let Some(currency) = currency {
+            if currency.coin_type()? != GAS::type_tag() {
+                return Err(Error::InvalidInput(
+                    "Custom coin types should be paid with PayCoin operations".to_string(),
+                ));
+            }
+        }
+        Ok(InternalOperation::PaySui {
+            sender,
+            recipients,
+            amounts,
+        })
+    }
+
+    fn pay_coin_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops_to_parts()?;
+        let currency = currency.ok_or_else(|| Error::MissingInput("Currency".to_string()))?;
+        // Fail early on malformed coin types rather than at metadata time.
+        if currency.coin_type()? == GAS::type_tag() {
+            return Err(Error::InvalidInput(
+                "SUI should be paid with PaySui operations".to_string(),
+            ));
+        }
+        Ok(InternalOperation::PayCoin {
+            sender,
+            recipients,
+            amounts,
+            currency,
+        })
+    }
+
+    /// Extract sender, recipients, amounts and the shared currency from pay operations.
+    fn pay_ops_to_parts(
+        self,
+    ) -> Result<(SuiAddress, Vec<SuiAddress>, Vec<u64>, Option<Currency>), Error> {
         let mut recipients = vec![];
         let mut amounts = vec![];
         let mut sender = None;
+        let mut currency: Option<Currency> = None;
         for op in self {
             if let (Some(amount), Some(account)) = (op.amount.clone(), op.account.clone()) {
+                match &currency {
+                    Some(currency) if currency != &amount.currency => {
+                        return Err(Error::MalformedOperationError(
+                            "Pay operations should use the same currency.".into(),
+                        ));
+                    }
+                    Some(_) => {}
+                    None => currency = Some(amount.currency.clone()),
+                }
                 if amount.value.is_negative() {
                     sender = Some(account.address)
                 } else {
@@ -128,11 +198,7 @@ impl Operations {
             }
         }
         let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
-        Ok(InternalOperation::PaySui {
-            sender,
-            recipients,
-            amounts,
-        })
+        Ok((sender, recipients, amounts, currency))
     }
 
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
@@ -308,6 +374,38 @@ impl Operations {
             }
             Some(vec![])
         }
+        fn pay_coin_call(
+            inputs: &[SuiCallArg],
+            call: &SuiProgrammableMoveCall,
+        ) -> Option<(TypeTag, SuiAddress, u64)> {
+            let SuiProgrammableMoveCall {
+                type_arguments,
+                arguments,
+                ..
+            } = call;
+            let coin_type = match &type_arguments[..] {
+                [coin_type] => parse_sui_type_tag(coin_type).ok()?,
+                _ => return None,
+            };
+            let (amount, recipient) = match &arguments[..] {
+                [SuiArgument::Input(coin), SuiArgument::Input(amount), SuiArgument::Input(recipient)] =>
+                {
+                    inputs.get(*coin as usize)?.object()?;
+                    (
+                        inputs.get(*amount as usize)?.pure()?,
+                        inputs.get(*recipient as usize)?.pure()?,
+                    )
+                }
+                _ => return None,
+            };
+            // The pure inputs of Move calls are untyped bcs bytes when the transaction is parsed
+            // without the Move modules.
+            let amount = match amount.to_json_value() {
+                Value::String(amount) => u64::from_str(&amount).ok()?,
+                bytes => bcs::from_bytes(&serde_json::from_value::<Vec<u8>>(bytes).ok()?).ok()?,
+            };
+            Some((coin_type, recipient.to_sui_address().ok()?, amount))
+        }
         fn stake_call(
             inputs: &[SuiCallArg],
             known_results: &[Vec<KnownValue>],
@@ -363,6 +461,9 @@ impl Operations {
         let SuiProgrammableTransactionBlock { inputs, commands } = &pt;
         let mut known_results: Vec<Vec<KnownValue>> = vec![];
         let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        let mut coin_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        let mut pay_coin_type = None;
+        let mut merges_input_coins = false;
         let mut needs_generic = false;
         let mut operations = vec![];
         let mut stake_ids = vec![];
@@ -371,6 +472,12 @@ impl Operations {
                 SuiCommand::SplitCoins(coin, amounts) => {
                     split_coins(inputs, &known_results, *coin, amounts)
                 }
+                SuiCommand::MergeCoins(SuiArgument::Input(_), coins)
+                    if coins.iter().all(|c| matches!(c, SuiArgument::Input(_))) =>
+                {
+                    merges_input_coins = true;
+                    Some(vec![])
+                }
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
                     inputs,
@@ -378,6 +485,14 @@ impl Operations {
                     objs,
                     *addr,
                 ),
+                SuiCommand::MoveCall(m) if Self::is_pay_coin_call(m) => pay_coin_call(inputs, m)
+                    .filter(|(coin_type, ..)| {
+                        *pay_coin_type.get_or_insert_with(|| coin_type.clone()) == *coin_type
+                    })
+                    .map(|(_, recipient, amount)| {
+                        *coin_recipients.entry(recipient).or_default() += amount;
+                        vec![]
+                    }),
                 SuiCommand::MoveCall(m) if Self::is_stake_call(m) => {
                     stake_call(inputs, &known_results, m)?.map(|(amount, validator)| {
                         let amount = amount.map(|amount| Amount::new(-(amount as i128)));
@@ -408,6 +523,14 @@ impl Operations {
             }
         }
 
+        // Input coins are only known to be paid with when their type is, and a transaction paying
+        // both SUI and another coin is not a Rosetta operation.
+        if (merges_input_coins && pay_coin_type.is_none())
+            || (!aggregated_recipients.is_empty() && !coin_recipients.is_empty())
+        {
+            needs_generic = true;
+        }
+
         if !needs_generic && !aggregated_recipients.is_empty() {
             let total_paid: u64 = aggregated_recipients.values().copied().sum();
             operations.extend(
@@ -418,6 +541,20 @@ impl Operations {
                     }),
             );
             operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+        } else if let (false, Some(coin_type)) = (needs_generic, pay_coin_type) {
+            // Without the coin metadata, the currency is named after the coin type. Executed
+            // transactions report the on-chain metadata instead.
+            let currency = Currency::from_coin_type(&coin_type);
+            let total_paid: u64 = coin_recipients.values().copied().sum();
+            operations.extend(coin_recipients.into_iter().map(|(recipient, amount)| {
+                Operation::pay_coin(status, recipient, amount.into(), currency.clone())
+            }));
+            operations.push(Operation::pay_coin(
+                status,
+                sender,
+                -(total_paid as i128),
+                currency,
+            ));
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -443,6 +580,12 @@ impl Operations {
         Ok(operations)
     }
 
+    fn is_pay_coin_call(tx: &SuiProgrammableMoveCall) -> bool {
+        tx.package == SUI_FRAMEWORK_PACKAGE_ID
+            && tx.module == PAY_MODULE_NAME.as_str()
+            && tx.function == PAY_SPLIT_AND_TRANSFER_FUNC_NAME.as_str()
+    }
+
     fn is_stake_call(tx: &SuiProgrammableMoveCall) -> bool {
         tx.package == SUI_SYSTEM_PACKAGE_ID
             && tx.module == SUI_SYSTEM_MODULE_NAME.as_str()
@@ -459,27 +602,30 @@ impl Operations {
         gas_owner: SuiAddress,
         gas_used: i128,
         balance_changes: &[BalanceChange],
+        currencies: &HashMap<TypeTag, Currency>,
         status: Option<OperationStatus>,
-        balances: HashMap<SuiAddress, i128>,
+        balances: HashMap<(SuiAddress, Currency), i128>,
     ) -> impl Iterator<Item = Operation> {
         let mut balances = balance_changes
             .iter()
             .fold(balances, |mut balances, balance_change| {
                 // Rosetta only care about address owner
                 if let Owner::AddressOwner(owner) = balance_change.owner {
-                    if balance_change.coin_type == GAS::type_tag() {
-                        *balances.entry(owner).or_default() += balance_change.amount;
+                    if let Some(currency) = currencies.get(&balance_change.coin_type) {
+                        *balances.entry((owner, currency.clone())).or_default() +=
+                            balance_change.amount;
                     }
                 }
                 balances
             });
         // separate gas from balances
-        *balances.entry(gas_owner).or_default() -= gas_used;
+        *balances.entry((gas_owner, SUI.clone())).or_default() -= gas_used;
 
-        let balance_change = balances
-            .into_iter()
-            .filter(|(_, amount)| *amount != 0)
-            .map(move |(addr, amount)| Operation::balance_change(status, addr, amount));
+        let balance_change = balances.into_iter().filter(|(_, amount)| *amount != 0).map(
+            move |((addr, currency), amount)| {
+                Operation::balance_change(status, addr, amount, currency)
+            },
+        );
 
         let gas = if gas_used != 0 {
             vec![Operation::gas(gas_owner, gas_used)]
@@ -503,9 +649,33 @@ impl TryFrom<SuiTransactionBlockData> for Operations {
     }
 }
 
-impl TryFrom<SuiTransactionBlockResponse> for Operations {
-    type Error = Error;
-    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+impl Operations {
+    /// Convert an executed transaction into operations, the balance changes of all coin types are
+    /// reported using the currencies resolved by the [CoinMetadataCache].
+    pub async fn try_from_response(
+        response: SuiTransactionBlockResponse,
+        coin_metadata_cache: &CoinMetadataCache,
+    ) -> Result<Self, Error> {
+        let balance_changes = response
+            .balance_changes
+            .as_ref()
+            .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?;
+        let mut currencies = HashMap::new();
+        for balance_change in balance_changes {
+            if !currencies.contains_key(&balance_change.coin_type) {
+                let currency = coin_metadata_cache
+                    .get_currency(&balance_change.coin_type)
+                    .await?;
+                currencies.insert(balance_change.coin_type.clone(), currency);
+            }
+        }
+        Self::try_from_response_with_currencies(response, &currencies)
+    }
+
+    fn try_from_response_with_currencies(
+        response: SuiTransactionBlockResponse,
+        currencies: &HashMap<TypeTag, Currency>,
+    ) -> Result<Self, Error> {
         let tx = response
             .transaction
             .ok_or_else(|| anyhow!("Response input should not be empty"))?;
@@ -521,21 +691,25 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
 
         let status = Some(effect.into_status().into());
         let ops: Operations = tx.data.try_into()?;
-        let ops = ops.set_status(status).into_iter();
+        // Report PayCoin amounts with the on-chain coin metadata.
+        let ops = ops
+            .set_status(status)
+            .with_currencies(currencies)
+            .into_iter()
+            .collect::<Vec<_>>();
 
         // We will need to subtract the operation amounts from the actual balance
         // change amount extracted from event to prevent double counting.
-        let mut accounted_balances =
-            ops.as_ref()
-                .iter()
-                .fold(HashMap::new(), |mut balances, op| {
-                    if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
-                        (&op.account, &op.amount, &op.status)
-                    {
-                        *balances.entry(acc.address).or_default() -= amount.value;
-                    }
-                    balances
-                });
+        let mut accounted_balances = ops.iter().fold(HashMap::new(), |mut balances, op| {
+            if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
+                (&op.account, &op.amount, &op.status)
+            {
+                *balances
+                    .entry((acc.address, amount.currency.clone()))
+                    .or_default() -= amount.value;
+            }
+            balances
+        });
 
         let mut principal_amounts = 0;
         let mut reward_amounts = 0;
@@ -564,8 +738,8 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             }
         }
         let staking_balance = if principal_amounts != 0 {
-            *accounted_balances.entry(sender).or_default() -= principal_amounts;
-            *accounted_balances.entry(sender).or_default() -= reward_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= principal_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= reward_amounts;
             vec![
                 Operation::stake_principle(status, sender, principal_amounts),
                 Operation::stake_reward(status, sender, reward_amounts),
@@ -581,6 +755,7 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             &response
                 .balance_changes
                 .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
+            currencies,
             status,
             accounted_balances,
         );
@@ -698,13 +873,35 @@ impl Operation {
         }
     }
 
-    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
+    fn pay_coin(
+        status: Option<OperationStatus>,
+        address: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            type_: OperationType::PayCoin,
+            status,
+            account: Some(address.into()),
+            amount: Some(Amount::new_with_currency(amount, currency)),
+            coin_change: None,
+            metadata: None,
+        }
+    }
+
+    fn balance_change(
+        status: Option<OperationStatus>,
+        addr: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
         Self {
             operation_identifier: Default::default(),
             type_: OperationType::SuiBalanceChange,
             status,
             account: Some(addr.into()),
-            amount: Some(Amount::new(amount)),
+            amount: Some(Amount::new_with_currency(amount, currency)),
             coin_change: None,
             metadata: None,
         }
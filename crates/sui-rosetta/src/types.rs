@@ -14,16 +14,21 @@ use serde_json::Value;
 use strum_macros::EnumIter;
 use strum_macros::EnumString;
 
+use move_core_types::language_storage::TypeTag;
+use shared_crypto::intent::IntentMessage;
+
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockKind};
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::coin::{PAY_MODULE_NAME, PAY_SPLIT_AND_TRANSFER_FUNC_NAME};
 use sui_types::crypto::PublicKey as SuiPublicKey;
 use sui_types::crypto::SignatureScheme;
+use sui_types::gas_coin::GAS;
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
-use sui_types::transaction::{Argument, CallArg, Command, ObjectArg, TransactionData};
-use sui_types::SUI_SYSTEM_PACKAGE_ID;
+use sui_types::transaction::{Argument, CallArg, Command, ObjectArg, Transaction, TransactionData};
+use sui_types::{parse_sui_type_tag, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::errors::{Error, ErrorType};
 use crate::operations::Operations;
@@ -95,11 +100,55 @@ impl From<SuiAddress> for AccountIdentifier {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Currency {
     pub symbol: String,
     pub decimals: u64,
+    /// Only SUI may omit the metadata, for compatibility with clients that predate custom
+    /// coin type support.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<CurrencyMetadata>,
 }
+
+impl Currency {
+    pub fn coin_type(&self) -> Result<TypeTag, Error> {
+        let Some(metadata) = &self.metadata else {
+            if self.symbol == SUI.symbol && self.decimals == SUI.decimals {
+                return Ok(GAS::type_tag());
+            }
+            return Err(Error::InvalidInput(format!(
+                "Currency [{}] is missing the coin type metadata",
+                self.symbol
+            )));
+        };
+        parse_sui_type_tag(&metadata.coin_type).map_err(|e| {
+            Error::InvalidInput(format!("Invalid coin type [{}]: {e}", metadata.coin_type))
+        })
+    }
+
+    /// Currency of a coin type whose metadata is unknown, named after the coin struct and
+    /// reporting raw (undivided) values.
+    pub fn from_coin_type(coin_type: &TypeTag) -> Self {
+        let type_str = coin_type.to_string();
+        Currency {
+            symbol: match coin_type {
+                TypeTag::Struct(tag) => tag.name.to_string(),
+                _ => type_str.clone(),
+            },
+            decimals: 0,
+            metadata: Some(CurrencyMetadata {
+                coin_type: type_str,
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CurrencyMetadata {
+    /// Move type of the coin, e.g. `0x2::sui::SUI`.
+    pub coin_type: String,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct AccountBalanceRequest {
     pub network_identifier: NetworkIdentifier,
@@ -153,9 +202,12 @@ pub struct SubBalance {
 
 impl Amount {
     pub fn new(value: i128) -> Self {
+        Self::new_with_currency(value, SUI.clone())
+    }
+    pub fn new_with_currency(value: i128, currency: Currency) -> Self {
         Self {
             value,
-            currency: SUI.clone(),
+            currency,
             metadata: None,
         }
     }
@@ -197,6 +249,8 @@ pub struct AccountCoinsRequest {
     pub network_identifier: NetworkIdentifier,
     pub account_identifier: AccountIdentifier,
     pub include_mempool: bool,
+    #[serde(default)]
+    pub currencies: Vec<Currency>,
 }
 #[derive(Serialize)]
 pub struct AccountCoinsResponse {
@@ -214,8 +268,8 @@ pub struct Coin {
     pub amount: Amount,
 }
 
-impl From<sui_sdk::rpc_types::Coin> for Coin {
-    fn from(coin: sui_sdk::rpc_types::Coin) -> Self {
+impl Coin {
+    pub fn new(coin: sui_sdk::rpc_types::Coin, currency: Currency) -> Self {
         Self {
             coin_identifier: CoinIdentifier {
                 identifier: CoinID {
@@ -223,11 +277,7 @@ impl From<sui_sdk::rpc_types::Coin> for Coin {
                     version: coin.version,
                 },
             },
-            amount: Amount {
-                value: coin.balance as i128,
-                currency: SUI.clone(),
-                metadata: None,
-            },
+            amount: Amount::new_with_currency(coin.balance as i128, currency),
         }
     }
 }
@@ -405,6 +455,7 @@ pub enum OperationType {
     StakePrinciple,
     // sui-rosetta supported operation type
     PaySui,
+    PayCoin,
     Stake,
     WithdrawStake,
     // All other Sui transaction types, readonly
@@ -492,13 +543,27 @@ pub struct SigningPayload {
     pub signature_type: Option<SignatureType>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum SignatureType {
     Ed25519,
     Ecdsa,
 }
 
+impl TryFrom<CurveType> for SignatureType {
+    type Error = Error;
+
+    fn try_from(curve_type: CurveType) -> Result<Self, Self::Error> {
+        match curve_type {
+            CurveType::Edwards25519 => Ok(SignatureType::Ed25519),
+            CurveType::Secp256k1 => Ok(SignatureType::Ecdsa),
+            CurveType::Secp256r1 | CurveType::ZkLogin => Err(Error::InvalidInput(format!(
+                "Unsupported curve type for signing: {curve_type:?}"
+            ))),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct ConstructionCombineRequest {
     pub network_identifier: NetworkIdentifier,
@@ -582,7 +647,7 @@ impl IntoResponse for ConstructionPreprocessResponse {
         Json(self).into_response()
     }
 }
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct ConstructionHashRequest {
     pub network_identifier: NetworkIdentifier,
     pub signed_transaction: Hex,
@@ -620,14 +685,56 @@ impl IntoResponse for ConstructionMetadataResponse {
     }
 }
 
-#[derive(Deserialize)]
+/// Unsigned transaction blob of /construction/payloads. The transaction only records the coin type
+/// of a PayCoin operation, so the currency it was built with is carried alongside for
+/// /construction/parse to return the operations it was built from.
+#[derive(Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    pub intent_message: IntentMessage<TransactionData>,
+    pub currency: Option<Currency>,
+}
+
+impl UnsignedTransaction {
+    /// Blobs holding a bare intent message are accepted as well.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if let Ok(unsigned_tx) = bcs::from_bytes(bytes) {
+            return Ok(unsigned_tx);
+        }
+        Ok(Self {
+            intent_message: bcs::from_bytes(bytes)?,
+            currency: None,
+        })
+    }
+}
+
+/// Signed transaction blob of /construction/combine, see [UnsignedTransaction].
+#[derive(Serialize, Deserialize)]
+pub struct SignedTransaction {
+    pub transaction: Transaction,
+    pub currency: Option<Currency>,
+}
+
+impl SignedTransaction {
+    /// Blobs holding a bare transaction are accepted as well.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if let Ok(signed_tx) = bcs::from_bytes(bytes) {
+            return Ok(signed_tx);
+        }
+        Ok(Self {
+            transaction: bcs::from_bytes(bytes)?,
+            currency: None,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct ConstructionParseRequest {
     pub network_identifier: NetworkIdentifier,
     pub signed: bool,
     pub transaction: Hex,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ConstructionParseResponse {
     pub operations: Operations,
     pub account_identifier_signers: Vec<AccountIdentifier>,
@@ -862,6 +969,12 @@ pub enum InternalOperation {
         recipients: Vec<SuiAddress>,
         amounts: Vec<u64>,
     },
+    PayCoin {
+        sender: SuiAddress,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        currency: Currency,
+    },
     Stake {
         sender: SuiAddress,
         validator: SuiAddress,
@@ -878,6 +991,7 @@ impl InternalOperation {
     pub fn sender(&self) -> SuiAddress {
         match self {
             InternalOperation::PaySui { sender, .. }
+            | InternalOperation::PayCoin { sender, .. }
             | InternalOperation::Stake { sender, .. }
             | InternalOperation::WithdrawStake { sender, .. } => *sender,
         }
@@ -894,6 +1008,40 @@ impl InternalOperation {
                 builder.pay_sui(recipients, amounts)?;
                 builder.finish()
             }
+            Self::PayCoin {
+                recipients,
+                amounts,
+                currency,
+                ..
+            } => {
+                let coin_type = currency.coin_type()?;
+                let mut builder = ProgrammableTransactionBuilder::new();
+                let mut coins = metadata.objects.into_iter();
+                let coin = coins
+                    .next()
+                    .ok_or_else(|| Error::MissingInput("Coins to pay with".to_string()))?;
+                let coin = builder.obj(ObjectArg::ImmOrOwnedObject(coin))?;
+                let coins = coins
+                    .map(|coin| builder.obj(ObjectArg::ImmOrOwnedObject(coin)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if !coins.is_empty() {
+                    builder.command(Command::MergeCoins(coin, coins));
+                }
+                // The coin type is passed as the type argument of the split, so it is checked
+                // against the input coins on chain and can be read back when parsing.
+                for (recipient, amount) in recipients.into_iter().zip(amounts) {
+                    let amount = builder.pure(amount)?;
+                    let recipient = builder.pure(recipient)?;
+                    builder.command(Command::move_call(
+                        SUI_FRAMEWORK_PACKAGE_ID,
+                        PAY_MODULE_NAME.to_owned(),
+                        PAY_SPLIT_AND_TRANSFER_FUNC_NAME.to_owned(),
+                        vec![coin_type.clone()],
+                        vec![coin, amount, recipient],
+                    ));
+                }
+                builder.finish()
+            }
             InternalOperation::Stake {
                 validator, amount, ..
             } => {
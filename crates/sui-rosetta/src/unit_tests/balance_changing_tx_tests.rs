@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::operations::Operations;
+use crate::state::CoinMetadataCache;
 use crate::types::{ConstructionMetadata, OperationStatus, OperationType};
 use anyhow::anyhow;
 use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::TypeTag;
 use rand::seq::{IteratorRandom, SliceRandom};
 use serde_json::json;
 use shared_crypto::intent::Intent;
@@ -25,7 +27,7 @@ use sui_sdk::rpc_types::{
 };
 use sui_sdk::SuiClient;
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
-use sui_types::gas_coin::GasCoin;
+use sui_types::parse_sui_type_tag;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::transaction::{
@@ -206,7 +208,7 @@ async fn test_publish_and_move_call() {
     test_transaction(
         &client,
         keystore,
-        vec![],
+        vec![recipient],
         sender,
         pt,
         vec![],
@@ -710,7 +712,7 @@ async fn test_transaction(
     let mut addr_to_check = addr_to_check;
     addr_to_check.push(sender);
     for addr in addr_to_check {
-        balances.insert(addr, get_balance(client, addr).await);
+        balances.insert(addr, get_balances(client, addr).await);
     }
 
     let response = client
@@ -740,15 +742,25 @@ async fn test_transaction(
         ));
     }
 
-    let ops = response.clone().try_into().unwrap();
+    let coin_cache = CoinMetadataCache::new(client.clone());
+    let ops = Operations::try_from_response(response.clone(), &coin_cache)
+        .await
+        .unwrap();
     let balances_from_ops = extract_balance_changes_from_ops(ops);
 
     // get actual balance changed after transaction
     let mut actual_balance_change = HashMap::new();
     for (addr, balance) in balances {
-        let new_balance = get_balance(client, addr).await as i128;
-        let balance_changed = new_balance - balance as i128;
-        actual_balance_change.insert(addr, balance_changed);
+        let mut balance_changed = get_balances(client, addr).await;
+        for (coin_type, amount) in balance {
+            *balance_changed.entry(coin_type).or_default() -= amount;
+        }
+        actual_balance_change.extend(
+            balance_changed
+                .into_iter()
+                .filter(|(_, amount)| *amount != 0)
+                .map(|(coin_type, amount)| ((addr, coin_type), amount)),
+        );
     }
     assert_eq!(
         actual_balance_change, balances_from_ops,
@@ -758,26 +770,32 @@ async fn test_transaction(
     response
 }
 
-fn extract_balance_changes_from_ops(ops: Operations) -> HashMap<SuiAddress, i128> {
-    ops.into_iter()
-        .fold(HashMap::<SuiAddress, i128>::new(), |mut changes, op| {
+fn extract_balance_changes_from_ops(ops: Operations) -> HashMap<(SuiAddress, TypeTag), i128> {
+    let mut changes = ops.into_iter().fold(
+        HashMap::<(SuiAddress, TypeTag), i128>::new(),
+        |mut changes, op| {
             if let Some(OperationStatus::Success) = op.status {
                 match op.type_ {
                     OperationType::SuiBalanceChange
                     | OperationType::Gas
                     | OperationType::PaySui
+                    | OperationType::PayCoin
                     | OperationType::StakeReward
                     | OperationType::StakePrinciple
                     | OperationType::Stake => {
                         if let (Some(addr), Some(amount)) = (op.account, op.amount) {
-                            *changes.entry(addr.address).or_default() += amount.value
+                            let coin_type = amount.currency.coin_type().unwrap();
+                            *changes.entry((addr.address, coin_type)).or_default() += amount.value
                         }
                     }
                     _ => {}
                 };
             }
             changes
-        })
+        },
+    );
+    changes.retain(|_, amount| *amount != 0);
+    changes
 }
 
 async fn get_random_sui(
@@ -823,44 +841,18 @@ fn get_random_address(addresses: &[SuiAddress], except: Vec<SuiAddress>) -> SuiA
         .unwrap()
 }
 
-async fn get_balance(client: &SuiClient, address: SuiAddress) -> u64 {
-    let coins = client
-        .read_api()
-        .get_owned_objects(
-            address,
-            Some(SuiObjectResponseQuery::new_with_options(
-                SuiObjectDataOptions::new()
-                    .with_type()
-                    .with_owner()
-                    .with_previous_transaction(),
-            )),
-            /* cursor */ None,
-            /* limit */ None,
-        )
+async fn get_balances(client: &SuiClient, address: SuiAddress) -> HashMap<TypeTag, i128> {
+    client
+        .coin_read_api()
+        .get_all_balances(address)
         .await
         .unwrap()
-        .data;
-
-    let mut balance = 0u64;
-    for coin in coins {
-        let obj = coin.into_object().unwrap();
-        if obj.is_gas_coin() {
-            let object = client
-                .read_api()
-                .get_object_with_options(obj.object_id, SuiObjectDataOptions::new().with_bcs())
-                .await
-                .unwrap();
-            let coin: GasCoin = object
-                .into_object()
-                .unwrap()
-                .bcs
-                .unwrap()
-                .try_as_move()
-                .unwrap()
-                .deserialize()
-                .unwrap();
-            balance += coin.value()
-        }
-    }
-    balance
+        .into_iter()
+        .map(|balance| {
+            (
+                parse_sui_type_tag(&balance.coin_type).unwrap(),
+                balance.total_balance as i128,
+            )
+        })
+        .collect()
 }
@@ -1,14 +1,22 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+
 use move_core_types::annotated_value::MoveTypeLayout;
+use shared_crypto::intent::{Intent, IntentMessage};
 use sui_json_rpc_types::SuiCallArg;
 use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::transaction::{CallArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
 
-use crate::operations::Operations;
-use crate::types::ConstructionMetadata;
+use crate::errors::Error;
+use crate::operations::{Operation, Operations};
+use crate::types::{
+    ConstructionMetadata, Currency, CurrencyMetadata, InternalOperation, OperationType,
+    UnsignedTransaction,
+};
+use crate::SUI;
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -50,6 +58,110 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+#[tokio::test]
+async fn test_pay_coin_operation_data_parsing() -> Result<(), anyhow::Error> {
+    let random_ref = || {
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )
+    };
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let currency = Currency {
+        symbol: "USDC".to_string(),
+        decimals: 6,
+        metadata: Some(CurrencyMetadata {
+            coin_type: "0x2::usdc::USDC".to_string(),
+        }),
+    };
+    let gas = random_ref();
+    let coins = vec![random_ref(), random_ref()];
+    let gas_price = 10;
+    let metadata = || ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: coins.clone(),
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+    };
+    let data = InternalOperation::PayCoin {
+        sender,
+        recipients: vec![recipient],
+        amounts: vec![10000],
+        currency: currency.clone(),
+    }
+    .try_into_data(metadata())?;
+
+    // The symbol and decimals are not part of the transaction, they are carried alongside it.
+    let unsigned_tx = UnsignedTransaction::from_bytes(&bcs::to_bytes(&UnsignedTransaction {
+        intent_message: IntentMessage::new(Intent::sui_transaction(), data.clone()),
+        currency: Some(currency.clone()),
+    })?)?;
+    let currencies = HashMap::from([(currency.coin_type()?, unsigned_tx.currency.unwrap())]);
+    let ops = Operations::try_from(unsigned_tx.intent_message.value)?.with_currencies(&currencies);
+    let mut amounts = HashMap::new();
+    for op in ops.clone() {
+        assert_eq!(op.type_, OperationType::PayCoin);
+        let amount = op.amount.unwrap();
+        assert_eq!(amount.currency, currency);
+        amounts.insert(op.account.unwrap().address, amount.value);
+    }
+    assert_eq!(
+        amounts,
+        HashMap::from([(recipient, 10000), (sender, -10000)])
+    );
+
+    let parsed_data = ops.into_internal()?.try_into_data(metadata())?;
+    assert_eq!(data, parsed_data);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pay_coin_with_sui_currency_is_rejected() {
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let ops = Operations::new(vec![
+        Operation::pay_coin(None, recipient, 10000, SUI.clone()),
+        Operation::pay_coin(None, sender, -10000, SUI.clone()),
+    ]);
+    assert!(matches!(ops.into_internal(), Err(Error::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn test_pay_sui_with_custom_currency_is_rejected() {
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let currency = Currency {
+        symbol: "USDC".to_string(),
+        decimals: 6,
+        metadata: Some(CurrencyMetadata {
+            coin_type: "0x2::usdc::USDC".to_string(),
+        }),
+    };
+    let mut ops = vec![
+        Operation::pay_coin(None, recipient, 10000, currency.clone()),
+        Operation::pay_coin(None, sender, -10000, currency),
+    ];
+    for op in &mut ops {
+        op.type_ = OperationType::PaySui;
+    }
+    let ops = Operations::new(ops);
+    assert!(matches!(ops.into_internal(), Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn test_currency_without_metadata() {
+    let sui: Currency = serde_json::from_str(r#"{"symbol": "SUI", "decimals": 9}"#).unwrap();
+    assert_eq!(sui.coin_type().unwrap(), SUI.coin_type().unwrap());
+
+    let usdc: Currency = serde_json::from_str(r#"{"symbol": "USDC", "decimals": 6}"#).unwrap();
+    assert!(matches!(usdc.coin_type(), Err(Error::InvalidInput(_))));
+}
+
 #[tokio::test]
 async fn test_sui_json() {
     let arg1 = CallArg::Pure(bcs::to_bytes(&1000000u64).unwrap());
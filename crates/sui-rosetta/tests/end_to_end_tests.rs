@@ -1,26 +1,38 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::Duration;
 
+use move_core_types::identifier::Identifier;
 use serde_json::json;
 
 use rosetta_client::start_rosetta_test_server;
-use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_json_rpc_types::{ObjectChange, SuiTransactionBlockResponseOptions};
 use sui_keys::keystore::AccountKeystore;
+use sui_move_build::BuildConfig;
 use sui_rosetta::operations::Operations;
 use sui_rosetta::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, NetworkIdentifier,
     SubAccount, SubAccountType, SuiEnv,
 };
+use sui_rosetta::CoinMetadataCache;
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
 use sui_swarm_config::genesis_config::{DEFAULT_GAS_AMOUNT, DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT};
+use sui_types::parse_sui_type_tag;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::transaction::{
+    CallArg, ObjectArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+    TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+};
 use sui_types::utils::to_sender_signed_transaction;
 use test_cluster::TestClusterBuilder;
 
 use crate::rosetta_client::RosettaEndpoint;
 
+#[allow(dead_code)]
 mod rosetta_client;
 
 #[tokio::test]
@@ -174,7 +186,9 @@ async fn test_stake() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -234,7 +248,9 @@ async fn test_stake_all() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -350,7 +366,9 @@ async fn test_withdraw_stake() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -419,7 +437,9 @@ async fn test_pay_sui() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -478,7 +498,9 @@ async fn test_pay_sui_multiple_times() {
             tx.effects.as_ref().unwrap().status()
         );
 
-        let ops2 = Operations::try_from(tx).unwrap();
+        let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+            .await
+            .unwrap();
         assert!(
             ops2.contains(&ops),
             "Operation mismatch. expecting:{}, got:{}",
@@ -487,3 +509,164 @@ async fn test_pay_sui_multiple_times() {
         );
     }
 }
+
+#[tokio::test]
+async fn test_pay_coin() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let sender = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let client = test_cluster.wallet.get_client().await.unwrap();
+    let keystore = &test_cluster.wallet.config.keystore;
+    let rgp = test_cluster.get_reference_gas_price().await;
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+
+    // Publish a coin with 2 decimals
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.extend([
+        "..",
+        "..",
+        "sui_programmability",
+        "examples",
+        "fungible_tokens",
+    ]);
+    let compiled_package = BuildConfig::new_for_testing().build(path).unwrap();
+    let tx = client
+        .transaction_builder()
+        .publish(
+            sender,
+            compiled_package.get_package_bytes(/* with_unpublished_deps */ false),
+            compiled_package.get_dependency_original_package_ids(),
+            None,
+            rgp * TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+        )
+        .await
+        .unwrap();
+    let object_changes = test_cluster
+        .sign_and_execute_transaction(&tx)
+        .await
+        .object_changes
+        .unwrap();
+    let package = object_changes
+        .iter()
+        .find_map(|change| match change {
+            ObjectChange::Published { package_id, .. } => Some(*package_id),
+            _ => None,
+        })
+        .unwrap();
+    let treasury = object_changes
+        .iter()
+        .find(|change| {
+            matches!(change, ObjectChange::Created { object_type, .. }
+                if object_type.to_string().contains("::TreasuryCap"))
+        })
+        .unwrap()
+        .object_ref();
+
+    // Mint two coins to the sender, so the payment has to merge them
+    let mut builder = ProgrammableTransactionBuilder::new();
+    for _ in 0..2 {
+        builder
+            .move_call(
+                package,
+                Identifier::from_str("managed").unwrap(),
+                Identifier::from_str("mint").unwrap(),
+                vec![],
+                vec![
+                    CallArg::Object(ObjectArg::ImmOrOwnedObject(treasury)),
+                    CallArg::Pure(bcs::to_bytes(&50_000u64).unwrap()),
+                    CallArg::Pure(bcs::to_bytes(&sender).unwrap()),
+                ],
+            )
+            .unwrap();
+    }
+    let gas = client
+        .coin_read_api()
+        .get_coins(sender, None, None, None)
+        .await
+        .unwrap()
+        .data[0]
+        .object_ref();
+    let tx = TransactionData::new_programmable(
+        sender,
+        vec![gas],
+        builder.finish(),
+        rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        rgp,
+    );
+    test_cluster.sign_and_execute_transaction(&tx).await;
+
+    let coin_type = parse_sui_type_tag(&format!("{package}::managed::MANAGED")).unwrap();
+    let currency = json!({
+        "symbol": "MANAGED",
+        "decimals": 2,
+        "metadata": { "coin_type": coin_type.to_string() }
+    });
+    let ops = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"PayCoin",
+            "account": { "address" : recipient.to_string() },
+            "amount" : { "value": "70000" , "currency": currency }
+        },{
+            "operation_identifier":{"index":1},
+            "type":"PayCoin",
+            "account": { "address" : sender.to_string() },
+            "amount" : { "value": "-70000" , "currency": currency }
+        }]
+    ))
+    .unwrap();
+
+    let response = rosetta_client.rosetta_flow(&ops, keystore).await;
+
+    let tx = client
+        .read_api()
+        .get_transaction_with_options(
+            response.transaction_identifier.hash,
+            SuiTransactionBlockResponseOptions::new()
+                .with_input()
+                .with_effects()
+                .with_balance_changes()
+                .with_events(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        &SuiExecutionStatus::Success,
+        tx.effects.as_ref().unwrap().status()
+    );
+    println!("Sui TX: {tx:?}");
+
+    // The currency is reported from the coin metadata published on chain
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
+    assert!(
+        ops2.contains(&ops),
+        "Operation mismatch. expecting:{}, got:{}",
+        serde_json::to_string(&ops).unwrap(),
+        serde_json::to_string(&ops2).unwrap()
+    );
+
+    let network_identifier = NetworkIdentifier {
+        blockchain: "sui".to_string(),
+        network: SuiEnv::LocalNet,
+    };
+    for (address, balance) in [(sender, 30_000), (recipient, 70_000)] {
+        let request = AccountBalanceRequest {
+            network_identifier: network_identifier.clone(),
+            account_identifier: AccountIdentifier {
+                address,
+                sub_account: None,
+            },
+            block_identifier: Default::default(),
+            currencies: vec![serde_json::from_value(currency.clone()).unwrap()],
+        };
+        let response: AccountBalanceResponse = rosetta_client
+            .call(RosettaEndpoint::Balance, &request)
+            .await;
+        assert_eq!(1, response.balances.len());
+        assert_eq!(balance, response.balances[0].value);
+    }
+}
@@ -0,0 +1,269 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::encoding::{Encoding, Hex};
+use serde_json::{json, Value};
+
+use rosetta_client::start_rosetta_offline_test_server;
+use sui_keys::keystore::{AccountKeystore, InMemKeystore, Keystore};
+use sui_rosetta::operations::Operations;
+use sui_rosetta::types::{
+    AccountIdentifier, ConstructionCombineRequest, ConstructionCombineResponse,
+    ConstructionHashRequest, ConstructionMetadata, ConstructionParseRequest,
+    ConstructionParseResponse, ConstructionPayloadsRequest, ConstructionPayloadsResponse,
+    ConstructionPreprocessRequest, ConstructionPreprocessResponse, NetworkIdentifier, Signature,
+    SignatureType, SignedTransaction, SuiEnv, TransactionIdentifierResponse,
+};
+use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::crypto::{SignatureScheme, SuiSignature};
+
+use crate::rosetta_client::RosettaEndpoint;
+
+#[allow(dead_code)]
+mod rosetta_client;
+
+fn network_identifier() -> NetworkIdentifier {
+    NetworkIdentifier {
+        blockchain: "sui".to_string(),
+        network: SuiEnv::LocalNet,
+    }
+}
+
+fn pay_sui_operations(sender: SuiAddress, recipient: SuiAddress) -> Operations {
+    serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"PaySui",
+            "account": { "address" : recipient.to_string() },
+            "amount" : { "value": "1000000000" , "currency": { "symbol": "SUI", "decimals": 9}}
+        },{
+            "operation_identifier":{"index":1},
+            "type":"PaySui",
+            "account": { "address" : sender.to_string() },
+            "amount" : { "value": "-1000000000" , "currency": { "symbol": "SUI", "decimals": 9}}
+        }]
+    ))
+    .unwrap()
+}
+
+fn pay_coin_operations(sender: SuiAddress, recipient: SuiAddress) -> Operations {
+    let currency = json!({
+        "symbol": "USDC",
+        "decimals": 6,
+        "metadata": { "coin_type": "0x2::usdc::USDC" }
+    });
+    serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"PayCoin",
+            "account": { "address" : recipient.to_string() },
+            "amount" : { "value": "1000000" , "currency": currency }
+        },{
+            "operation_identifier":{"index":1},
+            "type":"PayCoin",
+            "account": { "address" : sender.to_string() },
+            "amount" : { "value": "-1000000" , "currency": currency }
+        }]
+    ))
+    .unwrap()
+}
+
+/// Metadata as it would have been fetched from an online server and carried over to the offline
+/// server, no fullnode is involved.
+fn offline_metadata(sender: SuiAddress) -> ConstructionMetadata {
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![(
+            ObjectID::random(),
+            SequenceNumber::from_u64(1),
+            ObjectDigest::random(),
+        )],
+        objects: vec![],
+        total_coin_value: 1_000_000_000_000,
+        gas_price: 1000,
+        budget: 10_000_000,
+    };
+    serde_json::from_value(serde_json::to_value(metadata).unwrap()).unwrap()
+}
+
+async fn test_offline_construction_flow(scheme: SignatureScheme, signature_type: SignatureType) {
+    let (rosetta_client, _handle) = start_rosetta_offline_test_server().await;
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(0));
+    let (sender, _, _) = keystore
+        .generate_and_add_new_key(scheme, None, None, None)
+        .unwrap();
+    let public_key = keystore.get_key(&sender).unwrap().public();
+    let ops = pay_sui_operations(sender, SuiAddress::random_for_testing_only());
+
+    // Preprocess
+    let preprocess: ConstructionPreprocessResponse = rosetta_client
+        .call(
+            RosettaEndpoint::Preprocess,
+            &ConstructionPreprocessRequest {
+                network_identifier: network_identifier(),
+                operations: ops.clone(),
+                metadata: None,
+            },
+        )
+        .await;
+    assert_eq!(
+        preprocess.required_public_keys,
+        vec![AccountIdentifier::from(sender)]
+    );
+
+    // Payloads
+    let payloads: ConstructionPayloadsResponse = rosetta_client
+        .call(
+            RosettaEndpoint::Payloads,
+            &ConstructionPayloadsRequest {
+                network_identifier: network_identifier(),
+                operations: ops.clone(),
+                metadata: Some(offline_metadata(sender)),
+                public_keys: vec![public_key.clone().into()],
+            },
+        )
+        .await;
+    let signing_payload = payloads.payloads.first().unwrap();
+    assert_eq!(signing_payload.account_identifier.address, sender);
+    assert_eq!(signing_payload.signature_type, Some(signature_type.clone()));
+
+    // Parse unsigned
+    let parsed: ConstructionParseResponse = rosetta_client
+        .call(
+            RosettaEndpoint::Parse,
+            &ConstructionParseRequest {
+                network_identifier: network_identifier(),
+                signed: false,
+                transaction: payloads.unsigned_transaction.clone(),
+            },
+        )
+        .await;
+    assert!(parsed.operations.contains(&ops));
+    assert!(parsed.account_identifier_signers.is_empty());
+
+    // Combine
+    let bytes = Hex::decode(&signing_payload.hex_bytes).unwrap();
+    let signature = keystore.sign_hashed(&sender, &bytes).unwrap();
+    let combine: ConstructionCombineResponse = rosetta_client
+        .call(
+            RosettaEndpoint::Combine,
+            &ConstructionCombineRequest {
+                network_identifier: network_identifier(),
+                unsigned_transaction: payloads.unsigned_transaction,
+                signatures: vec![Signature {
+                    signing_payload: signing_payload.clone(),
+                    public_key: public_key.into(),
+                    signature_type,
+                    hex_bytes: Hex::from_bytes(SuiSignature::signature_bytes(&signature)),
+                }],
+            },
+        )
+        .await;
+
+    // Parse signed
+    let parsed: ConstructionParseResponse = rosetta_client
+        .call(
+            RosettaEndpoint::Parse,
+            &ConstructionParseRequest {
+                network_identifier: network_identifier(),
+                signed: true,
+                transaction: combine.signed_transaction.clone(),
+            },
+        )
+        .await;
+    assert!(parsed.operations.contains(&ops));
+    assert_eq!(
+        parsed.account_identifier_signers,
+        vec![AccountIdentifier::from(sender)]
+    );
+
+    // Hash
+    let hash: TransactionIdentifierResponse = rosetta_client
+        .call(
+            RosettaEndpoint::Hash,
+            &ConstructionHashRequest {
+                network_identifier: network_identifier(),
+                signed_transaction: combine.signed_transaction.clone(),
+            },
+        )
+        .await;
+    let tx = SignedTransaction::from_bytes(&combine.signed_transaction.to_vec().unwrap())
+        .unwrap()
+        .transaction;
+    assert_eq!(hash.transaction_identifier.hash, *tx.digest());
+}
+
+#[tokio::test]
+async fn test_offline_construction_ed25519() {
+    test_offline_construction_flow(SignatureScheme::ED25519, SignatureType::Ed25519).await;
+}
+
+#[tokio::test]
+async fn test_offline_construction_secp256k1() {
+    test_offline_construction_flow(SignatureScheme::Secp256k1, SignatureType::Ecdsa).await;
+}
+
+#[tokio::test]
+async fn test_offline_payloads_with_mismatched_metadata() {
+    let (rosetta_client, _handle) = start_rosetta_offline_test_server().await;
+    let sender = SuiAddress::random_for_testing_only();
+    let ops = pay_sui_operations(sender, SuiAddress::random_for_testing_only());
+
+    let response: Value = rosetta_client
+        .call(
+            RosettaEndpoint::Payloads,
+            &ConstructionPayloadsRequest {
+                network_identifier: network_identifier(),
+                operations: ops,
+                metadata: Some(offline_metadata(SuiAddress::random_for_testing_only())),
+                public_keys: vec![],
+            },
+        )
+        .await;
+    assert!(
+        response["details"]["error"]
+            .as_str()
+            .unwrap()
+            .contains("does not match operation sender"),
+        "unexpected response: {response:#?}"
+    );
+}
+
+#[tokio::test]
+async fn test_offline_pay_coin_parse() {
+    let (rosetta_client, _handle) = start_rosetta_offline_test_server().await;
+    let sender = SuiAddress::random_for_testing_only();
+    let ops = pay_coin_operations(sender, SuiAddress::random_for_testing_only());
+    let mut metadata = offline_metadata(sender);
+    metadata.objects = vec![(
+        ObjectID::random(),
+        SequenceNumber::from_u64(1),
+        ObjectDigest::random(),
+    )];
+
+    let payloads: ConstructionPayloadsResponse = rosetta_client
+        .call(
+            RosettaEndpoint::Payloads,
+            &ConstructionPayloadsRequest {
+                network_identifier: network_identifier(),
+                operations: ops.clone(),
+                metadata: Some(metadata),
+                public_keys: vec![],
+            },
+        )
+        .await;
+
+    // The currency symbol and decimals are not part of the transaction, the parsed operations
+    // should still match the intent without access to the coin metadata.
+    let parsed: ConstructionParseResponse = rosetta_client
+        .call(
+            RosettaEndpoint::Parse,
+            &ConstructionParseRequest {
+                network_identifier: network_identifier(),
+                signed: false,
+                transaction: payloads.unsigned_transaction,
+            },
+        )
+        .await;
+    assert_eq!(parsed.operations, ops);
+}
@@ -49,6 +49,22 @@ pub async fn start_rosetta_test_server(
     )
 }
 
+/// Start an offline server only, for construction flows that run without a fullnode.
+/// Online endpoints are not available from the returned client.
+pub async fn start_rosetta_offline_test_server() -> (RosettaClient, JoinHandle<hyper::Result<()>>) {
+    let offline_server = RosettaOfflineServer::new(SuiEnv::LocalNet);
+    let local_ip = local_ip_utils::localhost_for_testing();
+    let offline_port = local_ip_utils::get_available_port(&local_ip);
+    let offline_address = format!("{}:{}", local_ip, offline_port);
+    let offline_handle = offline_server.serve(SocketAddr::from_str(&offline_address).unwrap());
+
+    tokio::task::yield_now().await;
+    (
+        RosettaClient::new(offline_port, offline_port),
+        offline_handle,
+    )
+}
+
 pub struct RosettaClient {
     client: Client,
     online_port: u16,
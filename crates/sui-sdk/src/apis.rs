@@ -14,6 +14,7 @@ use sui_json_rpc_types::DevInspectArgs;
 use sui_json_rpc_types::SuiData;
 
 use crate::error::{Error, SuiRpcResult};
+use crate::finality::{FinalityVerifier, VerifiedEffects};
 use crate::RpcClient;
 use sui_json_rpc_api::{
     CoinReadApiClient, GovernanceReadApiClient, IndexerApiClient, MoveUtilsClient, ReadApiClient,
@@ -1166,6 +1167,34 @@ impl QuorumDriverApi {
             start.elapsed().as_secs(),
         ))
     }
+
+    /// Execute a transaction like `execute_transaction_block`, then wait for it to be included
+    /// in a checkpoint and verify its effects against the certified checkpoint with `verifier`,
+    /// instead of trusting the response of the FullNode. If the raw effects are requested in
+    /// `options`, they are also checked to match the verified effects.
+    pub async fn execute_transaction_block_and_verify(
+        &self,
+        tx: Transaction,
+        options: SuiTransactionBlockResponseOptions,
+        request_type: Option<ExecuteTransactionRequestType>,
+        verifier: &FinalityVerifier,
+    ) -> SuiRpcResult<(SuiTransactionBlockResponse, VerifiedEffects)> {
+        let digest = *tx.digest();
+        let response = self
+            .execute_transaction_block(tx, options, request_type)
+            .await?;
+        let verified = verifier.verify_transaction(digest).await?;
+        if !response.raw_effects.is_empty()
+            && response.raw_effects != bcs::to_bytes(&verified.effects)?
+        {
+            return Err(Error::FinalityVerification {
+                digest,
+                reason: "the effects returned by the FullNode do not match the checkpoint"
+                    .to_string(),
+            });
+        }
+        Ok((response, verified))
+    }
 }
 
 /// Governance API provides the staking functionality.
@@ -22,6 +22,11 @@ pub enum Error {
     Subscription(String),
     #[error("Failed to confirm tx status for {0:?} within {1} seconds.")]
     FailToConfirmTransactionStatus(TransactionDigest, u64),
+    #[error("Failed to verify the finality of tx {digest}: {reason}")]
+    FinalityVerification {
+        digest: TransactionDigest,
+        reason: String,
+    },
     #[error("Data error: {0}")]
     DataError(String),
     #[error("Client/Server api version mismatch, client api version : {client_version}, server api version : {server_version}")]
@@ -0,0 +1,765 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An HTTP JSON-RPC client spreading requests over several fullnodes.
+//!
+//! [FailoverHttpClient] sends every request to one active endpoint. When a request fails with a
+//! transport error or times out, the endpoint is marked as unhealthy and the client fails over to
+//! the next healthy endpoint. Read requests are then retried with exponential backoff, while
+//! requests which are not idempotent, such as transaction execution, fail right away as they may
+//! have reached the fullnode. Unhealthy endpoints are health-checked in the background and used
+//! again once they respond.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use jsonrpsee::core::client::{BatchResponse, ClientT, Subscription, SubscriptionClientT};
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::rpc_params;
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+use tracing::{debug, info, warn};
+
+use crate::error::{Error, SuiRpcResult};
+
+/// Methods which must not be sent twice, as the first attempt may have reached the fullnode.
+const NON_IDEMPOTENT_METHODS: &[&str] = &["sui_executeTransactionBlock"];
+
+/// Method used to check the health of an endpoint.
+const HEALTH_CHECK_METHOD: &str = "sui_getLatestCheckpointSequenceNumber";
+
+/// How requests are retried and endpoints health-checked by a [FailoverHttpClient].
+#[derive(Clone, Debug)]
+pub struct RetryConfig {
+    /// Maximum number of times an idempotent request is retried after a transport error.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each following retry.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Interval between two health checks of the endpoints.
+    pub health_check_interval: Duration,
+}
+
+impl RetryConfig {
+    /// Never retries requests, which is how a client connected to a single endpoint behaves by
+    /// default.
+    pub fn no_retries() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            health_check_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Requests and failures of one endpoint of a [FailoverHttpClient].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointMetrics {
+    pub url: String,
+    pub healthy: bool,
+    /// Whether requests are currently sent to this endpoint.
+    pub active: bool,
+    pub requests: u64,
+    /// Requests which failed with a transport error or timed out.
+    pub transport_errors: u64,
+    /// Requests sent to this endpoint which retried a request that failed before.
+    pub retries: u64,
+    /// Number of times the client failed over from this endpoint to another one.
+    pub failovers: u64,
+    /// Latency of the last request which got a response from this endpoint.
+    pub last_latency: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    client: HttpClient,
+    healthy: AtomicBool,
+    requests: AtomicU64,
+    transport_errors: AtomicU64,
+    retries: AtomicU64,
+    failovers: AtomicU64,
+    last_latency_us: AtomicU64,
+}
+
+impl Endpoint {
+    fn new(url: String, client: HttpClient) -> Self {
+        Self {
+            url,
+            client,
+            healthy: AtomicBool::new(true),
+            requests: AtomicU64::new(0),
+            transport_errors: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            failovers: AtomicU64::new(0),
+            last_latency_us: AtomicU64::new(u64::MAX),
+        }
+    }
+
+    fn record<T>(&self, result: &Result<T, RpcError>, latency: Duration) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        match result {
+            Err(e) if is_transport_error(e) => {
+                self.transport_errors.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {
+                self.healthy.store(true, Ordering::Relaxed);
+                self.last_latency_us
+                    .store(latency.as_micros() as u64, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    async fn check_health(&self) {
+        let response: Result<serde_json::Value, _> = self
+            .client
+            .request(HEALTH_CHECK_METHOD, rpc_params![])
+            .await;
+        let healthy = response.is_ok();
+        if healthy != self.healthy.swap(healthy, Ordering::Relaxed) {
+            info!(url = %self.url, healthy, "RPC endpoint health changed");
+        }
+    }
+}
+
+struct Inner {
+    endpoints: Vec<Endpoint>,
+    active: AtomicUsize,
+    retry_config: RetryConfig,
+}
+
+impl Inner {
+    /// Moves the active endpoint away from `failed`, to the next healthy endpoint if any.
+    fn fail_over(&self, failed: usize) {
+        self.endpoints[failed]
+            .healthy
+            .store(false, Ordering::Relaxed);
+        let next = self
+            .next_healthy(failed)
+            .unwrap_or((failed + 1) % self.endpoints.len());
+        if next != failed
+            && self
+                .active
+                .compare_exchange(failed, next, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            self.endpoints[failed]
+                .failovers
+                .fetch_add(1, Ordering::Relaxed);
+            warn!(
+                from = %self.endpoints[failed].url,
+                to = %self.endpoints[next].url,
+                "Failing over to another RPC endpoint"
+            );
+        }
+    }
+
+    /// First healthy endpoint after `index`, in round robin order.
+    fn next_healthy(&self, index: usize) -> Option<usize> {
+        let len = self.endpoints.len();
+        (1..len)
+            .map(|offset| (index + offset) % len)
+            .find(|i| self.endpoints[*i].is_healthy())
+    }
+
+    async fn check_health(&self) {
+        join_all(
+            self.endpoints
+                .iter()
+                .map(|endpoint| endpoint.check_health()),
+        )
+        .await;
+        let active = self.active.load(Ordering::Relaxed);
+        if !self.endpoints[active].is_healthy() {
+            if let Some(next) = self.next_healthy(active) {
+                let _ = self.active.compare_exchange(
+                    active,
+                    next,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                );
+            }
+        }
+    }
+}
+
+/// A JSON-RPC client over HTTP which fails over between several endpoints, see the
+/// [module documentation](self).
+#[derive(Clone)]
+pub struct FailoverHttpClient {
+    inner: Arc<Inner>,
+}
+
+impl FailoverHttpClient {
+    /// Creates a client for the given endpoints, preferring them in order. When there is more
+    /// than one endpoint, they are health-checked and must all be on the same chain.
+    pub async fn new(
+        endpoints: Vec<(String, HttpClient)>,
+        retry_config: RetryConfig,
+    ) -> SuiRpcResult<Self> {
+        if endpoints.is_empty() {
+            return Err(Error::DataError(
+                "At least one RPC endpoint is required".to_string(),
+            ));
+        }
+        let inner = Arc::new(Inner {
+            endpoints: endpoints
+                .into_iter()
+                .map(|(url, client)| Endpoint::new(url, client))
+                .collect(),
+            active: AtomicUsize::new(0),
+            retry_config,
+        });
+
+        if inner.endpoints.len() > 1 {
+            Self::check_chain_identifiers(&inner).await?;
+            inner.check_health().await;
+            if inner
+                .endpoints
+                .iter()
+                .all(|endpoint| !endpoint.is_healthy())
+            {
+                return Err(Error::DataError(
+                    "None of the RPC endpoints is healthy".to_string(),
+                ));
+            }
+            tokio::spawn(Self::health_check_loop(
+                Arc::downgrade(&inner),
+                inner.retry_config.health_check_interval,
+            ));
+        }
+        Ok(Self { inner })
+    }
+
+    /// Makes sure that the endpoints which respond all serve the same chain.
+    async fn check_chain_identifiers(inner: &Inner) -> SuiRpcResult<()> {
+        let identifiers: Vec<Result<String, _>> =
+            join_all(inner.endpoints.iter().map(|endpoint| {
+                endpoint
+                    .client
+                    .request("sui_getChainIdentifier", rpc_params![])
+            }))
+            .await;
+        let mut expected: Option<(&str, String)> = None;
+        for (endpoint, identifier) in inner.endpoints.iter().zip(identifiers) {
+            let Ok(identifier) = identifier else {
+                continue;
+            };
+            match &expected {
+                Some((url, expected)) if *expected != identifier => {
+                    return Err(Error::DataError(format!(
+                        "RPC endpoints are on different chains: {url} is on {expected}, {} is \
+                         on {identifier}",
+                        endpoint.url
+                    )));
+                }
+                Some(_) => {}
+                None => expected = Some((&endpoint.url, identifier)),
+            }
+        }
+        Ok(())
+    }
+
+    async fn health_check_loop(inner: Weak<Inner>, interval: Duration) {
+        loop {
+            tokio::time::sleep(interval).await;
+            // Stop once the client is dropped.
+            let Some(inner) = inner.upgrade() else {
+                return;
+            };
+            inner.check_health().await;
+        }
+    }
+
+    /// The client of the endpoint requests are currently sent to.
+    pub fn active_client(&self) -> &HttpClient {
+        &self.inner.endpoints[self.active_index()].client
+    }
+
+    /// Requests and failures of each endpoint, in the order they were given.
+    pub fn endpoint_metrics(&self) -> Vec<EndpointMetrics> {
+        let active = self.active_index();
+        self.inner
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, endpoint)| {
+                let last_latency_us = endpoint.last_latency_us.load(Ordering::Relaxed);
+                EndpointMetrics {
+                    url: endpoint.url.clone(),
+                    healthy: endpoint.is_healthy(),
+                    active: i == active,
+                    requests: endpoint.requests.load(Ordering::Relaxed),
+                    transport_errors: endpoint.transport_errors.load(Ordering::Relaxed),
+                    retries: endpoint.retries.load(Ordering::Relaxed),
+                    failovers: endpoint.failovers.load(Ordering::Relaxed),
+                    last_latency: (last_latency_us != u64::MAX)
+                        .then(|| Duration::from_micros(last_latency_us)),
+                }
+            })
+            .collect()
+    }
+
+    fn active_index(&self) -> usize {
+        self.inner.active.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for FailoverHttpClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let urls: Vec<_> = self.inner.endpoints.iter().map(|e| &e.url).collect();
+        f.debug_struct("FailoverHttpClient")
+            .field("endpoints", &urls)
+            .field("active", &self.active_index())
+            .finish()
+    }
+}
+
+/// Parameters which were already serialized, so that a request can be sent more than once.
+struct RawParams(Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, RpcError> {
+        Ok(self.0)
+    }
+}
+
+fn is_transport_error(error: &RpcError) -> bool {
+    matches!(error, RpcError::Transport(_) | RpcError::RequestTimeout)
+}
+
+#[async_trait]
+impl ClientT for FailoverHttpClient {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), RpcError>
+    where
+        Params: ToRpcParams + Send,
+    {
+        let index = self.active_index();
+        let endpoint = &self.inner.endpoints[index];
+        let start = Instant::now();
+        let result = endpoint.client.notification(method, params).await;
+        endpoint.record(&result, start.elapsed());
+        if result.as_ref().is_err_and(is_transport_error) {
+            self.inner.fail_over(index);
+        }
+        result
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, RpcError>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let params = params.to_rpc_params()?;
+        let max_retries = if NON_IDEMPOTENT_METHODS.contains(&method) {
+            0
+        } else {
+            self.inner.retry_config.max_retries
+        };
+        let mut retry = 0;
+        loop {
+            let index = self.active_index();
+            let endpoint = &self.inner.endpoints[index];
+            if retry > 0 {
+                endpoint.retries.fetch_add(1, Ordering::Relaxed);
+            }
+            let error = {
+                let start = Instant::now();
+                let result = endpoint
+                    .client
+                    .request(method, RawParams(params.clone()))
+                    .await;
+                endpoint.record(&result, start.elapsed());
+                match result {
+                    Err(e) if is_transport_error(&e) => e,
+                    result => return result,
+                }
+            };
+            self.inner.fail_over(index);
+            if retry >= max_retries {
+                return Err(error);
+            }
+            let backoff = self.inner.retry_config.backoff(retry);
+            debug!(
+                method,
+                url = %endpoint.url,
+                "Retrying request in {backoff:?} after error: {error}"
+            );
+            tokio::time::sleep(backoff).await;
+            retry += 1;
+        }
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, RpcError>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        let index = self.active_index();
+        let endpoint = &self.inner.endpoints[index];
+        let start = Instant::now();
+        let result = endpoint.client.batch_request(batch).await;
+        endpoint.record(&result, start.elapsed());
+        if result.as_ref().is_err_and(is_transport_error) {
+            self.inner.fail_over(index);
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl SubscriptionClientT for FailoverHttpClient {
+    /// Subscriptions are not supported over HTTP, use the WebSocket client instead.
+    async fn subscribe<'a, N, Params>(
+        &self,
+        _subscribe_method: &'a str,
+        _params: Params,
+        _unsubscribe_method: &'a str,
+    ) -> Result<Subscription<N>, RpcError>
+    where
+        Params: ToRpcParams + Send,
+        N: DeserializeOwned,
+    {
+        Err(RpcError::HttpNotImplemented)
+    }
+
+    /// Subscriptions are not supported over HTTP, use the WebSocket client instead.
+    async fn subscribe_to_method<'a, N>(
+        &self,
+        _method: &'a str,
+    ) -> Result<Subscription<N>, RpcError>
+    where
+        N: DeserializeOwned,
+    {
+        Err(RpcError::HttpNotImplemented)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpsee::http_client::HttpClientBuilder;
+    use jsonrpsee::server::{ServerBuilder, ServerHandle};
+    use jsonrpsee::RpcModule;
+
+    use super::*;
+
+    const TEST_METHOD: &str = "sui_getTotalTransactionBlocks";
+
+    fn retry_config() -> RetryConfig {
+        RetryConfig {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(10),
+            // Health checks only run when the tests trigger them.
+            health_check_interval: Duration::from_secs(3600),
+        }
+    }
+
+    /// Starts a fullnode stub on `chain` answering every method the client sends.
+    async fn start_server(chain: &'static str) -> (String, ServerHandle) {
+        let server = ServerBuilder::default().build("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", server.local_addr().unwrap());
+        let mut module = RpcModule::new(());
+        module
+            .register_method("sui_getChainIdentifier", move |_, _| Ok(chain))
+            .unwrap();
+        for method in [HEALTH_CHECK_METHOD, TEST_METHOD, NON_IDEMPOTENT_METHODS[0]] {
+            module.register_method(method, |_, _| Ok(7u64)).unwrap();
+        }
+        (url, server.start(module).unwrap())
+    }
+
+    async fn stop_server(handle: ServerHandle) {
+        handle.stop().unwrap();
+        handle.stopped().await;
+    }
+
+    fn endpoints(urls: &[&str]) -> Vec<(String, HttpClient)> {
+        urls.iter()
+            .map(|url| {
+                let client = HttpClientBuilder::default().build(url).unwrap();
+                (url.to_string(), client)
+            })
+            .collect()
+    }
+
+    fn inner(num_endpoints: usize) -> Inner {
+        let urls: Vec<_> = (0..num_endpoints)
+            .map(|i| format!("http://127.0.0.1:{}", i + 1))
+            .collect();
+        let urls: Vec<_> = urls.iter().map(String::as_str).collect();
+        Inner {
+            endpoints: endpoints(&urls)
+                .into_iter()
+                .map(|(url, client)| Endpoint::new(url, client))
+                .collect(),
+            active: AtomicUsize::new(0),
+            retry_config: retry_config(),
+        }
+    }
+
+    fn set_healthy(inner: &Inner, healthy: &[bool]) {
+        for (endpoint, healthy) in inner.endpoints.iter().zip(healthy) {
+            endpoint.healthy.store(*healthy, Ordering::Relaxed);
+        }
+    }
+
+    fn failovers(inner: &Inner) -> Vec<u64> {
+        inner
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.failovers.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = RetryConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let backoffs: Vec<_> = (0..6).map(|retry| config.backoff(retry)).collect();
+        assert_eq!(
+            backoffs,
+            [100, 200, 400, 800, 1000, 1000].map(Duration::from_millis)
+        );
+        // The backoff saturates instead of overflowing.
+        assert_eq!(config.backoff(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_next_healthy() {
+        let inner = inner(4);
+        assert_eq!(inner.next_healthy(0), Some(1));
+        assert_eq!(inner.next_healthy(3), Some(0));
+
+        set_healthy(&inner, &[true, false, false, true]);
+        assert_eq!(inner.next_healthy(0), Some(3));
+        assert_eq!(inner.next_healthy(3), Some(0));
+        // The endpoint itself is never returned.
+        set_healthy(&inner, &[true, false, false, false]);
+        assert_eq!(inner.next_healthy(0), None);
+        assert_eq!(inner.next_healthy(2), Some(0));
+    }
+
+    #[test]
+    fn test_fail_over() {
+        let inner = inner(3);
+        inner.fail_over(0);
+        assert_eq!(inner.active.load(Ordering::Relaxed), 1);
+        assert!(!inner.endpoints[0].is_healthy());
+        assert_eq!(failovers(&inner), vec![1, 0, 0]);
+
+        // Skips unhealthy endpoints, wrapping around.
+        set_healthy(&inner, &[true, true, false]);
+        inner.fail_over(1);
+        assert_eq!(inner.active.load(Ordering::Relaxed), 0);
+        assert_eq!(failovers(&inner), vec![1, 1, 0]);
+
+        // A failure of an endpoint which is not active any more, reported by a concurrent
+        // request, marks it as unhealthy but leaves the active endpoint alone.
+        set_healthy(&inner, &[true, true, true]);
+        inner.fail_over(2);
+        assert_eq!(inner.active.load(Ordering::Relaxed), 0);
+        assert!(!inner.endpoints[2].is_healthy());
+        assert_eq!(failovers(&inner), vec![1, 1, 0]);
+
+        // With no healthy endpoint left, moves on to the next one in round robin order.
+        set_healthy(&inner, &[true, false, false]);
+        inner.fail_over(0);
+        assert_eq!(inner.active.load(Ordering::Relaxed), 1);
+        assert_eq!(failovers(&inner), vec![2, 1, 0]);
+
+        // A single endpoint stays active.
+        let inner = self::inner(1);
+        inner.fail_over(0);
+        assert_eq!(inner.active.load(Ordering::Relaxed), 0);
+        assert_eq!(failovers(&inner), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_and_retries() {
+        let (url_a, handle_a) = start_server("chain").await;
+        let (url_b, _handle_b) = start_server("chain").await;
+        let client = FailoverHttpClient::new(endpoints(&[&url_a, &url_b]), retry_config())
+            .await
+            .unwrap();
+        let value: u64 = client.request(TEST_METHOD, rpc_params![]).await.unwrap();
+        assert_eq!(value, 7);
+
+        stop_server(handle_a).await;
+        let value: u64 = client.request(TEST_METHOD, rpc_params![]).await.unwrap();
+        assert_eq!(value, 7);
+
+        let metrics = client.endpoint_metrics();
+        assert_eq!(metrics[0].url, url_a);
+        assert!(!metrics[0].healthy);
+        assert!(!metrics[0].active);
+        assert_eq!(metrics[0].requests, 2);
+        assert_eq!(metrics[0].transport_errors, 1);
+        assert_eq!(metrics[0].retries, 0);
+        assert_eq!(metrics[0].failovers, 1);
+        assert!(metrics[0].last_latency.is_some());
+
+        assert_eq!(metrics[1].url, url_b);
+        assert!(metrics[1].healthy);
+        assert!(metrics[1].active);
+        assert_eq!(metrics[1].requests, 1);
+        assert_eq!(metrics[1].transport_errors, 0);
+        assert_eq!(metrics[1].retries, 1);
+        assert_eq!(metrics[1].failovers, 0);
+        assert!(metrics[1].last_latency.is_some());
+        // The active client is the one of the endpoint which is up.
+        assert_eq!(
+            client
+                .active_client()
+                .request::<u64, _>(TEST_METHOD, rpc_params![])
+                .await
+                .unwrap(),
+            7
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_bounded() {
+        let (url_a, handle_a) = start_server("chain").await;
+        let (url_b, handle_b) = start_server("chain").await;
+        let client = FailoverHttpClient::new(endpoints(&[&url_a, &url_b]), retry_config())
+            .await
+            .unwrap();
+        stop_server(handle_a).await;
+        stop_server(handle_b).await;
+
+        // The first attempt and `max_retries` retries, alternating between the endpoints.
+        let error = client
+            .request::<u64, _>(TEST_METHOD, rpc_params![])
+            .await
+            .unwrap_err();
+        assert!(is_transport_error(&error));
+        let metrics = client.endpoint_metrics();
+        assert_eq!(
+            metrics.iter().map(|m| m.requests).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert_eq!(
+            metrics
+                .iter()
+                .map(|m| m.transport_errors)
+                .collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert_eq!(
+            metrics.iter().map(|m| m.retries).collect::<Vec<_>>(),
+            vec![1, 1]
+        );
+        assert!(metrics.iter().all(|m| !m.healthy));
+        assert!(metrics.iter().all(|m| m.last_latency.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_transaction_execution() {
+        let (url_a, handle_a) = start_server("chain").await;
+        let (url_b, _handle_b) = start_server("chain").await;
+        let client = FailoverHttpClient::new(endpoints(&[&url_a, &url_b]), retry_config())
+            .await
+            .unwrap();
+        stop_server(handle_a).await;
+
+        // The request may have reached the fullnode, so it fails even though the other endpoint
+        // is up, but later requests go to the other endpoint.
+        client
+            .request::<u64, _>(NON_IDEMPOTENT_METHODS[0], rpc_params![])
+            .await
+            .unwrap_err();
+        let metrics = client.endpoint_metrics();
+        assert_eq!(metrics[0].requests, 1);
+        assert_eq!(metrics[0].failovers, 1);
+        assert_eq!(metrics[1].requests, 0);
+        assert!(metrics[1].active);
+
+        let value: u64 = client
+            .request(NON_IDEMPOTENT_METHODS[0], rpc_params![])
+            .await
+            .unwrap();
+        assert_eq!(value, 7);
+        assert_eq!(client.endpoint_metrics()[1].retries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoints() {
+        let (url_a, handle_a) = start_server("chain").await;
+        let (url_b, _handle_b) = start_server("chain").await;
+        stop_server(handle_a).await;
+
+        // Endpoints which are down when the client is created start out unhealthy.
+        let client = FailoverHttpClient::new(endpoints(&[&url_a, &url_b]), retry_config())
+            .await
+            .unwrap();
+        let metrics = client.endpoint_metrics();
+        assert!(!metrics[0].healthy);
+        assert!(metrics[1].healthy && metrics[1].active);
+        let value: u64 = client.request(TEST_METHOD, rpc_params![]).await.unwrap();
+        assert_eq!(value, 7);
+        assert_eq!(client.endpoint_metrics()[0].requests, 0);
+
+        // At least one endpoint must be up.
+        FailoverHttpClient::new(endpoints(&[&url_a, &url_a]), retry_config())
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_rejects_endpoints_on_different_chains() {
+        let (url_a, _handle_a) = start_server("chain").await;
+        let (url_b, _handle_b) = start_server("chain").await;
+        let (url_c, _handle_c) = start_server("other chain").await;
+
+        FailoverHttpClient::new(endpoints(&[&url_a, &url_b]), retry_config())
+            .await
+            .unwrap();
+        let Err(Error::DataError(error)) =
+            FailoverHttpClient::new(endpoints(&[&url_a, &url_b, &url_c]), retry_config()).await
+        else {
+            panic!("Expected endpoints on different chains to be rejected");
+        };
+        assert!(error.contains(&url_c), "{error}");
+
+        // The chain of a single endpoint is not checked, but there must be one.
+        FailoverHttpClient::new(endpoints(&[&url_c]), retry_config())
+            .await
+            .unwrap();
+        FailoverHttpClient::new(vec![], retry_config())
+            .await
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,538 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Local verification of the finality of transactions.
+//!
+//! Instead of trusting the effects returned by a fullnode, [FinalityVerifier] fetches the
+//! checkpoint including a transaction, verifies the signatures of the checkpoint against the
+//! committee of its epoch, and checks that the checkpoint contents commit to the effects and
+//! events of the transaction. The verifier starts from a trusted committee, such as the genesis
+//! committee, and moves on to later committees by verifying the end-of-epoch checkpoint of each
+//! epoch with the committee of that epoch.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_rest_api::Client as RestClient;
+use sui_types::base_types::TransactionDigest;
+use sui_types::committee::{Committee, EpochId};
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::apis::ReadApi;
+use crate::error::{Error, SuiRpcResult};
+
+/// How often to check whether an executed transaction has been included in a checkpoint.
+const CHECKPOINT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Effects and events of a transaction, along with the certified checkpoint including them.
+#[derive(Clone, Debug)]
+pub struct VerifiedEffects {
+    pub checkpoint: CertifiedCheckpointSummary,
+    pub effects: TransactionEffects,
+    pub events: Option<TransactionEvents>,
+}
+
+struct TrackedCommittee {
+    committee: Committee,
+    /// A checkpoint from the epoch of `committee` or earlier, from which to look for the end of
+    /// the epoch.
+    lower_bound: CheckpointSequenceNumber,
+}
+
+/// Verifies that transactions are final using certified checkpoints, see the
+/// [module documentation](self).
+pub struct FinalityVerifier {
+    read_api: Arc<ReadApi>,
+    rest: RestClient,
+    committee: Mutex<TrackedCommittee>,
+    checkpoint_timeout: Duration,
+}
+
+impl FinalityVerifier {
+    /// Creates a verifier fetching checkpoints from the REST API at `rest_url`, usually the
+    /// `/rest` path of the fullnode, and trusting `committee`. Only transactions from the epoch
+    /// of `committee` or later can be verified.
+    pub fn new(read_api: Arc<ReadApi>, rest_url: impl Into<String>, committee: Committee) -> Self {
+        Self {
+            read_api,
+            rest: RestClient::new(rest_url),
+            committee: Mutex::new(TrackedCommittee {
+                committee,
+                lower_bound: 0,
+            }),
+            checkpoint_timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Sets how long to wait for a transaction to be included in a checkpoint. Defaults to 60
+    /// seconds.
+    pub fn with_checkpoint_timeout(mut self, checkpoint_timeout: Duration) -> Self {
+        self.checkpoint_timeout = checkpoint_timeout;
+        self
+    }
+
+    /// The latest committee the verifier has verified.
+    pub async fn committee(&self) -> Committee {
+        self.committee.lock().await.committee.clone()
+    }
+
+    /// Waits for the transaction to be included in a checkpoint, and returns its effects and
+    /// events once the checkpoint is verified to be certified by the committee of its epoch.
+    pub async fn verify_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> SuiRpcResult<VerifiedEffects> {
+        let sequence_number = self.wait_for_checkpoint(digest).await?;
+        let checkpoint = self
+            .rest
+            .get_full_checkpoint(sequence_number)
+            .await
+            .map_err(|e| {
+                Error::DataError(format!("Failed to fetch checkpoint {sequence_number}: {e}"))
+            })?;
+        let committee = self
+            .committee_for_epoch(checkpoint.checkpoint_summary.epoch(), sequence_number)
+            .await?;
+        verify_checkpoint_transaction(checkpoint, &committee, digest)
+    }
+
+    async fn wait_for_checkpoint(
+        &self,
+        digest: TransactionDigest,
+    ) -> SuiRpcResult<CheckpointSequenceNumber> {
+        let start = Instant::now();
+        loop {
+            let response = self
+                .read_api
+                .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+                .await;
+            if let Ok(Some(checkpoint)) = response.map(|response| response.checkpoint) {
+                return Ok(checkpoint);
+            }
+            if start.elapsed() > self.checkpoint_timeout {
+                return Err(Error::FailToConfirmTransactionStatus(
+                    digest,
+                    start.elapsed().as_secs(),
+                ));
+            }
+            tokio::time::sleep(CHECKPOINT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Returns the committee of `epoch`, verifying the end-of-epoch checkpoints of the epochs
+    /// in between if the tracked committee is from an earlier epoch. `checkpoint` is known to be
+    /// from `epoch`.
+    async fn committee_for_epoch(
+        &self,
+        epoch: EpochId,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiRpcResult<Committee> {
+        let mut tracked = self.committee.lock().await;
+        if epoch < tracked.committee.epoch {
+            return Err(Error::DataError(format!(
+                "Checkpoint {checkpoint} is from epoch {epoch}, before the epoch {} of the \
+                 trusted committee",
+                tracked.committee.epoch
+            )));
+        }
+        while tracked.committee.epoch < epoch {
+            let end_of_epoch = find_end_of_epoch(
+                tracked.committee.epoch,
+                tracked.lower_bound,
+                checkpoint,
+                |sequence_number| async move {
+                    Ok(self.get_checkpoint_summary(sequence_number).await?.epoch())
+                },
+            )
+            .await?;
+            let summary = self.get_checkpoint_summary(end_of_epoch).await?;
+            tracked.committee = next_committee(&summary, &tracked.committee)?;
+            tracked.lower_bound = end_of_epoch + 1;
+            info!(
+                "Verified the committee of epoch {} from checkpoint {end_of_epoch}",
+                tracked.committee.epoch
+            );
+        }
+        Ok(tracked.committee.clone())
+    }
+
+    async fn get_checkpoint_summary(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiRpcResult<CertifiedCheckpointSummary> {
+        self.rest
+            .get_checkpoint_summary(sequence_number)
+            .await
+            .map_err(|e| {
+                Error::DataError(format!("Failed to fetch checkpoint {sequence_number}: {e}"))
+            })
+    }
+}
+
+/// Checks that `checkpoint` is certified by `committee` and commits to the effects and events of
+/// transaction `digest`.
+fn verify_checkpoint_transaction(
+    checkpoint: CheckpointData,
+    committee: &Committee,
+    digest: TransactionDigest,
+) -> SuiRpcResult<VerifiedEffects> {
+    let summary = checkpoint.checkpoint_summary;
+    let sequence_number = summary.sequence_number;
+    let verification_error = |reason: String| Error::FinalityVerification { digest, reason };
+
+    summary
+        .verify_with_contents(committee, Some(&checkpoint.checkpoint_contents))
+        .map_err(|e| verification_error(format!("invalid checkpoint {sequence_number}: {e}")))?;
+
+    // The effects are only authenticated if their digest is the one in the checkpoint contents.
+    let (transaction, _) = checkpoint
+        .transactions
+        .into_iter()
+        .zip(checkpoint.checkpoint_contents.iter())
+        .find(|(transaction, digests)| {
+            digests.transaction == digest && transaction.effects.execution_digests() == **digests
+        })
+        .ok_or_else(|| {
+            verification_error(format!("not included in checkpoint {sequence_number}"))
+        })?;
+    let events_digest = transaction.events.as_ref().map(|events| events.digest());
+    if events_digest.as_ref() != transaction.effects.events_digest() {
+        return Err(verification_error(
+            "events do not match the effects".to_string(),
+        ));
+    }
+
+    Ok(VerifiedEffects {
+        checkpoint: summary,
+        effects: transaction.effects,
+        events: transaction.events,
+    })
+}
+
+/// Returns the committee of the epoch following the one of `committee`, given the last checkpoint
+/// of that epoch, which must be certified by `committee`.
+fn next_committee(
+    end_of_epoch: &CertifiedCheckpointSummary,
+    committee: &Committee,
+) -> SuiRpcResult<Committee> {
+    let sequence_number = end_of_epoch.sequence_number;
+    end_of_epoch
+        .verify_authority_signatures(committee)
+        .map_err(|e| {
+            Error::DataError(format!(
+                "Invalid end-of-epoch checkpoint {sequence_number}: {e}"
+            ))
+        })?;
+    let next_committee = end_of_epoch
+        .end_of_epoch_data
+        .as_ref()
+        .ok_or_else(|| {
+            Error::DataError(format!(
+                "Checkpoint {sequence_number} is not the last checkpoint of its epoch"
+            ))
+        })?
+        .next_epoch_committee
+        .iter()
+        .cloned()
+        .collect();
+    Ok(Committee::new(end_of_epoch.epoch() + 1, next_committee))
+}
+
+/// Finds the last checkpoint of `epoch`, given a checkpoint `low` from that epoch or an earlier
+/// one, a checkpoint `high` from a later epoch, and a way to get the epoch of a checkpoint.
+async fn find_end_of_epoch<F, Fut>(
+    epoch: EpochId,
+    mut low: CheckpointSequenceNumber,
+    mut high: CheckpointSequenceNumber,
+    mut epoch_of: F,
+) -> SuiRpcResult<CheckpointSequenceNumber>
+where
+    F: FnMut(CheckpointSequenceNumber) -> Fut,
+    Fut: Future<Output = SuiRpcResult<EpochId>>,
+{
+    // Look for the first checkpoint of a later epoch.
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if epoch_of(mid).await? > epoch {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    low.checked_sub(1).ok_or_else(|| {
+        Error::DataError(format!("Cannot find the last checkpoint of epoch {epoch}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::base_types::{random_object_ref, ExecutionDigests, SuiAddress};
+    use sui_types::committee::ProtocolVersion;
+    use sui_types::crypto::{get_key_pair, AccountKeyPair, AuthorityKeyPair, KeypairTraits};
+    use sui_types::effects::TestEffectsBuilder;
+    use sui_types::full_checkpoint_content::CheckpointTransaction;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{CheckpointContents, CheckpointSummary, EndOfEpochData};
+    use sui_types::transaction::{Transaction, TransactionData};
+
+    use super::*;
+
+    fn committee(epoch: EpochId) -> (Committee, Vec<AuthorityKeyPair>) {
+        let keys: Vec<AuthorityKeyPair> = (0..4).map(|_| get_key_pair().1).collect();
+        let committee = Committee::new_for_testing_with_normalized_voting_power(
+            epoch,
+            keys.iter().map(|key| (key.public().into(), 1)).collect(),
+        );
+        (committee, keys)
+    }
+
+    fn certified_checkpoint(
+        committee: &Committee,
+        keys: &[AuthorityKeyPair],
+        sequence_number: CheckpointSequenceNumber,
+        contents: &CheckpointContents,
+        end_of_epoch_data: Option<EndOfEpochData>,
+    ) -> CertifiedCheckpointSummary {
+        let summary = CheckpointSummary::new(
+            committee.epoch,
+            sequence_number,
+            contents.size() as u64,
+            contents,
+            None,
+            GasCostSummary::default(),
+            end_of_epoch_data,
+            0,
+        );
+        CertifiedCheckpointSummary::new_from_keypairs_for_testing(summary, keys, committee)
+    }
+
+    fn end_of_epoch_data(next_committee: &Committee) -> EndOfEpochData {
+        EndOfEpochData {
+            next_epoch_committee: next_committee.voting_rights.clone(),
+            next_epoch_protocol_version: ProtocolVersion::MAX,
+            epoch_commitments: vec![],
+        }
+    }
+
+    fn checkpoint_transaction() -> CheckpointTransaction {
+        let (sender, key): (_, AccountKeyPair) = get_key_pair();
+        let data = TransactionData::new_transfer_sui(
+            SuiAddress::random_for_testing_only(),
+            sender,
+            Some(1),
+            random_object_ref(),
+            1_000_000,
+            1_000,
+        );
+        let transaction = Transaction::from_data_and_signer(data, vec![&key]);
+        let events = TransactionEvents { data: vec![] };
+        let effects = TestEffectsBuilder::new(transaction.data())
+            .with_events_digest(events.digest())
+            .build();
+        CheckpointTransaction {
+            transaction,
+            effects,
+            events: Some(events),
+            input_objects: vec![],
+            output_objects: vec![],
+        }
+    }
+
+    fn checkpoint_data(
+        committee: &Committee,
+        keys: &[AuthorityKeyPair],
+        transactions: Vec<CheckpointTransaction>,
+    ) -> CheckpointData {
+        let contents = CheckpointContents::new_with_digests_only_for_tests(
+            transactions
+                .iter()
+                .map(|transaction| transaction.effects.execution_digests()),
+        );
+        CheckpointData {
+            checkpoint_summary: certified_checkpoint(committee, keys, 7, &contents, None),
+            checkpoint_contents: contents,
+            transactions,
+        }
+    }
+
+    fn is_verification_error(result: SuiRpcResult<VerifiedEffects>) -> bool {
+        matches!(result, Err(Error::FinalityVerification { .. }))
+    }
+
+    #[tokio::test]
+    async fn test_find_end_of_epoch() {
+        // Checkpoints 0 to 2 are from epoch 0, 3 and 4 from epoch 1, 5 to 10 from epoch 2.
+        let epochs = [0, 0, 0, 1, 1, 2, 2, 2, 2, 2, 2];
+        let find = |epoch, low, high| {
+            find_end_of_epoch(epoch, low, high, |sequence_number| async move {
+                Ok(epochs[sequence_number as usize])
+            })
+        };
+        for high in 3..=10 {
+            assert_eq!(find(0, 0, high).await.unwrap(), 2);
+        }
+        for low in 0..=4 {
+            for high in 5..=10 {
+                assert_eq!(find(1, low, high).await.unwrap(), 4);
+            }
+        }
+        // Low can be a checkpoint of an earlier epoch.
+        assert_eq!(find(1, 1, 5).await.unwrap(), 4);
+        // No checkpoint of epoch 0 comes before checkpoint 0.
+        find(0, 0, 0).await.unwrap_err();
+
+        // Only the checkpoints between low and high are fetched, logarithmically many of them.
+        let mut fetched = vec![];
+        let end = find_end_of_epoch(0, 0, 1_000_000, |sequence_number| {
+            fetched.push(sequence_number);
+            async move { Ok(if sequence_number > 1234 { 1 } else { 0 }) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(end, 1234);
+        assert!(fetched.len() <= 20, "{fetched:?}");
+        assert!(fetched.iter().all(|s| *s < 1_000_000));
+
+        // Errors fetching checkpoints are returned.
+        find_end_of_epoch(0, 0, 10, |_| async {
+            Err(Error::DataError("unavailable".to_string()))
+        })
+        .await
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_next_committee() {
+        let (committee_0, keys_0) = committee(0);
+        let (committee_1, keys_1) = committee(1);
+        let contents = CheckpointContents::new_with_digests_only_for_tests([]);
+        let end_of_epoch_0 = certified_checkpoint(
+            &committee_0,
+            &keys_0,
+            10,
+            &contents,
+            Some(end_of_epoch_data(&committee_1)),
+        );
+        assert_eq!(
+            next_committee(&end_of_epoch_0, &committee_0).unwrap(),
+            committee_1
+        );
+
+        // The committee of epoch 1 hands off to the committee of epoch 2 in turn.
+        let (committee_2, _) = committee(2);
+        let end_of_epoch_1 = certified_checkpoint(
+            &committee_1,
+            &keys_1,
+            20,
+            &contents,
+            Some(end_of_epoch_data(&committee_2)),
+        );
+        assert_eq!(
+            next_committee(&end_of_epoch_1, &committee_1).unwrap(),
+            committee_2
+        );
+        // The end of epoch 1 is not certified by the committee of epoch 0.
+        next_committee(&end_of_epoch_1, &committee_0).unwrap_err();
+
+        // A checkpoint signed by another committee of the same epoch is rejected.
+        let (other_committee_0, other_keys_0) = committee(0);
+        let forged = certified_checkpoint(
+            &other_committee_0,
+            &other_keys_0,
+            10,
+            &contents,
+            Some(end_of_epoch_data(&other_committee_0)),
+        );
+        next_committee(&forged, &committee_0).unwrap_err();
+
+        // A checkpoint which does not end its epoch has no next committee.
+        let checkpoint = certified_checkpoint(&committee_0, &keys_0, 9, &contents, None);
+        next_committee(&checkpoint, &committee_0).unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_checkpoint_transaction() {
+        let (committee_0, keys) = committee(0);
+        let transactions: Vec<_> = (0..3).map(|_| checkpoint_transaction()).collect();
+        let digest = *transactions[1].transaction.digest();
+        let effects = transactions[1].effects.clone();
+        let events = transactions[1].events.clone();
+        let checkpoint = checkpoint_data(&committee_0, &keys, transactions.clone());
+
+        let verified =
+            verify_checkpoint_transaction(checkpoint.clone(), &committee_0, digest).unwrap();
+        assert_eq!(verified.checkpoint, checkpoint.checkpoint_summary);
+        assert_eq!(verified.effects, effects);
+        assert_eq!(verified.events, events);
+
+        // The checkpoint must be certified by the committee of its epoch.
+        let (other_committee, other_keys) = committee(0);
+        let forged = checkpoint_data(&other_committee, &other_keys, transactions.clone());
+        assert!(is_verification_error(verify_checkpoint_transaction(
+            forged,
+            &committee_0,
+            digest
+        )));
+
+        // The contents must be the ones the checkpoint commits to.
+        let mut tampered = checkpoint.clone();
+        tampered.checkpoint_contents =
+            CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+        assert!(is_verification_error(verify_checkpoint_transaction(
+            tampered,
+            &committee_0,
+            digest
+        )));
+
+        // Transactions missing from the contents are not verified, even if the fullnode returns
+        // them along with the checkpoint.
+        let other = checkpoint_transaction();
+        let mut tampered = checkpoint.clone();
+        tampered.transactions[1] = other.clone();
+        assert!(is_verification_error(verify_checkpoint_transaction(
+            tampered.clone(),
+            &committee_0,
+            *other.transaction.digest()
+        )));
+        assert!(is_verification_error(verify_checkpoint_transaction(
+            tampered,
+            &committee_0,
+            digest
+        )));
+        assert!(is_verification_error(verify_checkpoint_transaction(
+            checkpoint.clone(),
+            &committee_0,
+            *other.transaction.digest()
+        )));
+
+        // The effects must be the ones in the contents.
+        let mut tampered = checkpoint.clone();
+        tampered.transactions[1].effects =
+            TestEffectsBuilder::new(transactions[1].transaction.data())
+                .with_status(sui_types::execution_status::ExecutionStatus::new_failure(
+                    sui_types::execution_status::ExecutionFailureStatus::InsufficientGas,
+                    None,
+                ))
+                .build();
+        assert!(is_verification_error(verify_checkpoint_transaction(
+            tampered,
+            &committee_0,
+            digest
+        )));
+
+        // The events must be the ones the effects commit to.
+        let mut tampered = checkpoint;
+        tampered.transactions[1].events = None;
+        assert!(is_verification_error(verify_checkpoint_transaction(
+            tampered,
+            &committee_0,
+            digest
+        )));
+    }
+}
@@ -17,6 +17,8 @@
 //! block and submit it to the fullnode(s)
 //! * [ReadApi] - provides functions for retrieving data about different
 //! objects and transactions
+//! * [PtbBuilder](ptb_builder::PtbBuilder) - builds programmable transactions, resolving
+//! their object inputs from the chain
 //! * <a href="../sui_transaction_builder/struct.TransactionBuilder.html" title="struct sui_transaction_builder::TransactionBuilder">TransactionBuilder</a> - provides functions for building transactions
 //!
 //! # Usage
@@ -92,16 +94,24 @@ use sui_json_rpc_types::{
     ObjectsPage, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
     SuiObjectResponseQuery,
 };
+use sui_package_resolver::{PackageStoreWithLruCache, Resolver};
 use sui_transaction_builder::{DataReader, TransactionBuilder};
 pub use sui_types as types;
 use sui_types::base_types::{ObjectID, ObjectInfo, SuiAddress};
+use sui_types::committee::Committee;
 
 use crate::apis::{CoinReadApi, EventApi, GovernanceApi, QuorumDriverApi, ReadApi};
 use crate::error::{Error, SuiRpcResult};
+use crate::failover_client::{EndpointMetrics, FailoverHttpClient, RetryConfig};
+use crate::finality::FinalityVerifier;
+use crate::ptb_builder::{PackageResolver, PtbBuilder, ReadApiPackageStore};
 
 pub mod apis;
 pub mod error;
+pub mod failover_client;
+pub mod finality;
 pub mod json_rpc_error;
+pub mod ptb_builder;
 pub mod sui_client_config;
 pub mod wallet_context;
 
@@ -120,6 +130,10 @@ pub const SUI_TESTNET_URL: &str = "https://fullnode.testnet.sui.io:443";
 /// value of your choice to prevent the inactive WS subscription being
 /// disconnected due to proxy timeout.
 ///
+/// The client can also be connected to several fullnodes with `build_with_failover`,
+/// in which case requests fail over between them, and read requests are retried
+/// according to the `retry_config`.
+///
 /// # Examples
 ///
 /// ```rust,no_run
@@ -139,6 +153,7 @@ pub struct SuiClientBuilder {
     max_concurrent_requests: usize,
     ws_url: Option<String>,
     ws_ping_interval: Option<Duration>,
+    retry_config: Option<RetryConfig>,
 }
 
 impl Default for SuiClientBuilder {
@@ -148,6 +163,7 @@ impl Default for SuiClientBuilder {
             max_concurrent_requests: 256,
             ws_url: None,
             ws_ping_interval: None,
+            retry_config: None,
         }
     }
 }
@@ -177,6 +193,13 @@ impl SuiClientBuilder {
         self
     }
 
+    /// Set how read requests are retried after transport errors. Defaults to no retries when
+    /// connecting to a single endpoint, and to [RetryConfig::default] otherwise.
+    pub fn retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = Some(retry_config);
+        self
+    }
+
     /// Returns a [SuiClient] object connected to the Sui network running at the URI provided.
     ///
     /// # Examples
@@ -195,6 +218,34 @@ impl SuiClientBuilder {
     /// }
     /// ```
     pub async fn build(self, http: impl AsRef<str>) -> SuiRpcResult<SuiClient> {
+        self.build_with_failover([http]).await
+    }
+
+    /// Returns a [SuiClient] object connected to several fullnodes of the same Sui network.
+    ///
+    /// Requests are sent to the first healthy endpoint, in the order they are given, and fail
+    /// over to the next one on transport errors and timeouts. Read requests are retried with
+    /// backoff according to the `retry_config`. See [FailoverHttpClient] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use sui_sdk::SuiClientBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), anyhow::Error> {
+    ///     let sui = SuiClientBuilder::default()
+    ///         .build_with_failover(["http://127.0.0.1:9000", "http://127.0.0.1:9001"])
+    ///         .await?;
+    ///
+    ///     println!("{:?}", sui.endpoint_metrics());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn build_with_failover(
+        self,
+        urls: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> SuiRpcResult<SuiClient> {
         let client_version = env!("CARGO_PKG_VERSION");
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -224,12 +275,26 @@ impl SuiClientBuilder {
             None
         };
 
-        let http = HttpClientBuilder::default()
-            .max_request_body_size(2 << 30)
-            .max_concurrent_requests(self.max_concurrent_requests)
-            .set_headers(headers.clone())
-            .request_timeout(self.request_timeout)
-            .build(http)?;
+        let endpoints = urls
+            .into_iter()
+            .map(|url| {
+                let client = HttpClientBuilder::default()
+                    .max_request_body_size(2 << 30)
+                    .max_concurrent_requests(self.max_concurrent_requests)
+                    .set_headers(headers.clone())
+                    .request_timeout(self.request_timeout)
+                    .build(url.as_ref())?;
+                Ok((url.as_ref().to_string(), client))
+            })
+            .collect::<SuiRpcResult<Vec<_>>>()?;
+        let retry_config = self.retry_config.unwrap_or_else(|| {
+            if endpoints.len() > 1 {
+                RetryConfig::default()
+            } else {
+                RetryConfig::no_retries()
+            }
+        });
+        let http = FailoverHttpClient::new(endpoints, retry_config).await?;
 
         let info = Self::get_server_info(&http, &ws).await?;
 
@@ -241,10 +306,14 @@ impl SuiClientBuilder {
         let transaction_builder = TransactionBuilder::new(read_api.clone());
         let coin_read_api = CoinReadApi::new(api.clone());
         let governance_api = GovernanceApi::new(api.clone());
+        let package_resolver = Arc::new(Resolver::new(PackageStoreWithLruCache::new(
+            ReadApiPackageStore::new(read_api.clone()),
+        )));
 
         Ok(SuiClient {
             api,
             transaction_builder,
+            package_resolver,
             read_api,
             coin_read_api,
             event_api,
@@ -328,7 +397,7 @@ impl SuiClientBuilder {
     ///
     /// Fails with an error if it cannot call the RPC discover.
     async fn get_server_info(
-        http: &HttpClient,
+        http: &FailoverHttpClient,
         ws: &Option<WsClient>,
     ) -> Result<ServerInfo, Error> {
         let rpc_spec: Value = http.request("rpc.discover", rpc_params![]).await?;
@@ -409,6 +478,7 @@ impl SuiClientBuilder {
 pub struct SuiClient {
     api: Arc<RpcClient>,
     transaction_builder: TransactionBuilder,
+    package_resolver: Arc<PackageResolver>,
     read_api: Arc<ReadApi>,
     coin_read_api: CoinReadApi,
     event_api: EventApi,
@@ -417,7 +487,7 @@ pub struct SuiClient {
 }
 
 pub(crate) struct RpcClient {
-    http: HttpClient,
+    http: FailoverHttpClient,
     ws: Option<WsClient>,
     info: ServerInfo,
 }
@@ -501,9 +571,37 @@ impl SuiClient {
         &self.transaction_builder
     }
 
-    /// Returns a reference to the underlying http client.
+    /// Returns a builder for a programmable transaction sent by `sender`, which resolves the object
+    /// inputs of the transaction from the chain. See [PtbBuilder].
+    pub fn ptb_builder(&self, sender: SuiAddress) -> PtbBuilder {
+        PtbBuilder::new(
+            self.read_api.clone(),
+            self.transaction_builder.clone(),
+            self.package_resolver.clone(),
+            sender,
+        )
+    }
+
+    /// Returns a verifier checking that transactions are final using the certified checkpoints
+    /// served by the REST API at `rest_url`, starting from the trusted `committee`. See
+    /// [FinalityVerifier].
+    pub fn finality_verifier(
+        &self,
+        rest_url: impl Into<String>,
+        committee: Committee,
+    ) -> FinalityVerifier {
+        FinalityVerifier::new(self.read_api.clone(), rest_url, committee)
+    }
+
+    /// Returns a reference to the underlying http client. When connected to several endpoints,
+    /// this is the client of the endpoint requests are currently sent to.
     pub fn http(&self) -> &HttpClient {
-        &self.api.http
+        self.api.http.active_client()
+    }
+
+    /// Returns the requests and failures of each endpoint the client is connected to.
+    pub fn endpoint_metrics(&self) -> Vec<EndpointMetrics> {
+        self.api.http.endpoint_metrics()
     }
 
     /// Returns a reference to the underlying WebSocket client, if any.
@@ -0,0 +1,776 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A builder for programmable transactions which resolves its object inputs from the chain.
+//!
+//! [PtbBuilder] takes plain object ids as inputs, and works out how each object has to be passed
+//! to the transaction when the transaction is built: owned and immutable objects by reference at
+//! their latest version, shared objects at their initial shared version, and objects sent to
+//! another object as `Receiving` arguments. Whether a Move call takes an object by reference, by
+//! mutable reference, by value or as `Receiving` is read from the signature of the function being
+//! called, so that shared objects are only requested mutably when a command needs to mutate them.
+//!
+//! ```rust,no_run
+//! use std::str::FromStr;
+//! use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+//! use sui_sdk::SuiClientBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), anyhow::Error> {
+//!     let sui = SuiClientBuilder::default().build_localnet().await?;
+//!     let sender = SuiAddress::from_str("0x1c2b...")?; // the address of the sender
+//!     let counter = ObjectID::from_hex_literal("0x9c1a...")?; // a shared counter object
+//!
+//!     let mut ptb = sui.ptb_builder(sender);
+//!     let counter = ptb.object(counter);
+//!     ptb.move_call("0x9a3b...::counter::increment", vec![], vec![counter])?;
+//!     let coins = ptb.split_coins(ptb.gas(), vec![1_000, 2_000])?;
+//!     ptb.transfer_objects(coins, sender)?;
+//!
+//!     let tx_data = ptb.gas_budget(10_000_000).build().await?;
+//!     println!("{:?}", tx_data);
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, ensure};
+use async_trait::async_trait;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::TypeTag;
+use serde::Serialize;
+use sui_json_rpc_types::SuiObjectDataOptions;
+use sui_package_resolver::error::Error as PackageResolverError;
+use sui_package_resolver::{
+    OpenSignature, OpenSignatureBody, Package, PackageStore, PackageStoreWithLruCache, Reference,
+    Resolver,
+};
+use sui_transaction_builder::TransactionBuilder;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::object::{Object, Owner};
+use sui_types::transaction::{
+    Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall, ProgrammableTransaction,
+    TransactionData, TransactionKind,
+};
+use sui_types::transfer::RESOLVED_RECEIVING_STRUCT;
+
+use crate::apis::ReadApi;
+
+pub(crate) type PackageResolver = Resolver<PackageStoreWithLruCache<ReadApiPackageStore>>;
+
+/// Package store reading packages through the JSON-RPC read API.
+pub(crate) struct ReadApiPackageStore {
+    read_api: Arc<ReadApi>,
+}
+
+impl ReadApiPackageStore {
+    pub(crate) fn new(read_api: Arc<ReadApi>) -> Self {
+        Self { read_api }
+    }
+
+    async fn fetch_package(&self, id: AccountAddress) -> anyhow::Result<Package> {
+        let object: Object = self
+            .read_api
+            .get_object_with_options(id.into(), SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .into_object()?
+            .try_into()?;
+        Ok(Package::read_from_object(&object)?)
+    }
+}
+
+#[async_trait]
+impl PackageStore for ReadApiPackageStore {
+    async fn fetch(&self, id: AccountAddress) -> Result<Arc<Package>, PackageResolverError> {
+        let package = self
+            .fetch_package(id)
+            .await
+            .map_err(|e| PackageResolverError::Store {
+                store: "JSON-RPC",
+                source: Arc::from(Box::<dyn std::error::Error + Send + Sync>::from(e)),
+            })?;
+        Ok(Arc::new(package))
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Input {
+    /// An input which needs no resolution.
+    Resolved(CallArg),
+    /// An object which is passed to the transaction according to its ownership and to the way
+    /// the commands of the transaction use it.
+    Object(ObjectID),
+}
+
+/// How the commands of a transaction use one of its object inputs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ObjectUsage {
+    mutable: bool,
+    receiving: bool,
+    other: bool,
+}
+
+/// Builds a programmable transaction out of object ids and Move call targets, resolving the
+/// versions, ownership and mutability of its object inputs when the transaction is built.
+///
+/// Created with [SuiClient::ptb_builder](crate::SuiClient::ptb_builder).
+#[derive(Clone)]
+pub struct PtbBuilder {
+    read_api: Arc<ReadApi>,
+    transaction_builder: TransactionBuilder,
+    package_resolver: Arc<PackageResolver>,
+    sender: SuiAddress,
+    inputs: Vec<Input>,
+    objects: HashMap<ObjectID, u16>,
+    commands: Vec<Command>,
+    gas_budget: Option<u64>,
+    gas_price: Option<u64>,
+    gas_payment: Vec<ObjectID>,
+    gas_sponsor: Option<SuiAddress>,
+}
+
+impl PtbBuilder {
+    pub(crate) fn new(
+        read_api: Arc<ReadApi>,
+        transaction_builder: TransactionBuilder,
+        package_resolver: Arc<PackageResolver>,
+        sender: SuiAddress,
+    ) -> Self {
+        Self {
+            read_api,
+            transaction_builder,
+            package_resolver,
+            sender,
+            inputs: vec![],
+            objects: HashMap::new(),
+            commands: vec![],
+            gas_budget: None,
+            gas_price: None,
+            gas_payment: vec![],
+            gas_sponsor: None,
+        }
+    }
+
+    /// Adds an object input. Adding the same object more than once returns the same argument.
+    pub fn object(&mut self, object_id: ObjectID) -> Argument {
+        if let Some(index) = self.objects.get(&object_id) {
+            return Argument::Input(*index);
+        }
+        let index = self.inputs.len() as u16;
+        self.inputs.push(Input::Object(object_id));
+        self.objects.insert(object_id, index);
+        Argument::Input(index)
+    }
+
+    /// Adds a pure input holding the BCS serialization of `value`.
+    pub fn pure<T: Serialize>(&mut self, value: T) -> anyhow::Result<Argument> {
+        Ok(self.input(CallArg::Pure(bcs::to_bytes(&value)?)))
+    }
+
+    /// Adds an input which is passed to the transaction as is, without being resolved.
+    pub fn input(&mut self, call_arg: CallArg) -> Argument {
+        self.inputs.push(Input::Resolved(call_arg));
+        Argument::Input(self.inputs.len() as u16 - 1)
+    }
+
+    /// The coin paying for the gas of the transaction.
+    pub fn gas(&self) -> Argument {
+        Argument::GasCoin
+    }
+
+    /// Adds a call to `target`, given as `<package>::<module>::<function>`.
+    pub fn move_call(
+        &mut self,
+        target: &str,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<Argument>,
+    ) -> anyhow::Result<Argument> {
+        let parts: Vec<_> = target.split("::").collect();
+        let [package, module, function] = parts.as_slice() else {
+            bail!("Invalid Move call target {target}, expected <package>::<module>::<function>");
+        };
+        Ok(self.programmable_move_call(
+            ObjectID::from_hex_literal(package)?,
+            Identifier::new(*module)?,
+            Identifier::new(*function)?,
+            type_arguments,
+            arguments,
+        ))
+    }
+
+    pub fn programmable_move_call(
+        &mut self,
+        package: ObjectID,
+        module: Identifier,
+        function: Identifier,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<Argument>,
+    ) -> Argument {
+        self.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package,
+            module,
+            function,
+            type_arguments,
+            arguments,
+        })))
+    }
+
+    pub fn transfer_objects(
+        &mut self,
+        objects: Vec<Argument>,
+        recipient: SuiAddress,
+    ) -> anyhow::Result<()> {
+        let recipient = self.pure(recipient)?;
+        self.command(Command::TransferObjects(objects, recipient));
+        Ok(())
+    }
+
+    /// Splits coins of the given amounts off `coin`, returning one argument per new coin.
+    pub fn split_coins(
+        &mut self,
+        coin: Argument,
+        amounts: Vec<u64>,
+    ) -> anyhow::Result<Vec<Argument>> {
+        let num_amounts = amounts.len() as u16;
+        let amounts = amounts
+            .into_iter()
+            .map(|amount| self.pure(amount))
+            .collect::<anyhow::Result<_>>()?;
+        let Argument::Result(index) = self.command(Command::SplitCoins(coin, amounts)) else {
+            unreachable!("commands always return a result argument");
+        };
+        Ok((0..num_amounts)
+            .map(|i| Argument::NestedResult(index, i))
+            .collect())
+    }
+
+    pub fn merge_coins(&mut self, coin: Argument, coins: Vec<Argument>) {
+        self.command(Command::MergeCoins(coin, coins));
+    }
+
+    pub fn make_move_vec(&mut self, type_: Option<TypeTag>, elements: Vec<Argument>) -> Argument {
+        self.command(Command::MakeMoveVec(type_, elements))
+    }
+
+    /// Adds a command, returning the argument referring to its result.
+    pub fn command(&mut self, command: Command) -> Argument {
+        self.commands.push(command);
+        Argument::Result(self.commands.len() as u16 - 1)
+    }
+
+    pub fn gas_budget(&mut self, gas_budget: u64) -> &mut Self {
+        self.gas_budget = Some(gas_budget);
+        self
+    }
+
+    /// Sets the gas price, which defaults to the reference gas price.
+    pub fn gas_price(&mut self, gas_price: u64) -> &mut Self {
+        self.gas_price = Some(gas_price);
+        self
+    }
+
+    /// Sets the coins paying for gas. When not set, gas coins of the sender which are not inputs
+    /// of the transaction are selected.
+    pub fn gas_payment(&mut self, gas_payment: Vec<ObjectID>) -> &mut Self {
+        self.gas_payment = gas_payment;
+        self
+    }
+
+    pub fn gas_sponsor(&mut self, gas_sponsor: SuiAddress) -> &mut Self {
+        self.gas_sponsor = Some(gas_sponsor);
+        self
+    }
+
+    /// Resolves the object inputs of the transaction and returns its transaction data.
+    pub async fn build(&self) -> anyhow::Result<TransactionData> {
+        let gas_budget = self
+            .gas_budget
+            .ok_or_else(|| anyhow!("The gas budget of the transaction must be set"))?;
+        let gas_price = match self.gas_price {
+            Some(gas_price) => gas_price,
+            None => self.read_api.get_reference_gas_price().await?,
+        };
+        let kind = TransactionKind::programmable(self.build_programmable().await?);
+        self.transaction_builder
+            .tx_data(
+                self.sender,
+                kind,
+                gas_budget,
+                gas_price,
+                self.gas_payment.clone(),
+                self.gas_sponsor,
+            )
+            .await
+    }
+
+    /// Resolves the object inputs of the transaction and returns its programmable transaction.
+    pub async fn build_programmable(&self) -> anyhow::Result<ProgrammableTransaction> {
+        let usages = self.object_usages().await?;
+        let object_ids: Vec<_> = self
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                Input::Object(object_id) => Some(*object_id),
+                Input::Resolved(_) => None,
+            })
+            .collect();
+        let responses = if object_ids.is_empty() {
+            vec![]
+        } else {
+            self.read_api
+                .multi_get_object_with_options(
+                    object_ids.clone(),
+                    SuiObjectDataOptions::new().with_owner(),
+                )
+                .await?
+        };
+        ensure!(
+            responses.len() == object_ids.len(),
+            "Expected {} objects, got {}",
+            object_ids.len(),
+            responses.len()
+        );
+
+        let mut object_args = HashMap::new();
+        for (object_id, response) in object_ids.into_iter().zip(responses) {
+            let object = response
+                .into_object()
+                .map_err(|e| anyhow!("Cannot resolve object {object_id}: {e}"))?;
+            let owner = object
+                .owner
+                .ok_or_else(|| anyhow!("Unable to determine ownership of object {object_id}"))?;
+            let index = self.objects[&object_id];
+            let object_arg =
+                resolve_object_arg(object_id, object.object_ref(), owner, usages[&index])?;
+            object_args.insert(index, object_arg);
+        }
+
+        let inputs = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| match input {
+                Input::Resolved(call_arg) => call_arg.clone(),
+                Input::Object(_) => CallArg::Object(object_args[&(index as u16)]),
+            })
+            .collect();
+        Ok(ProgrammableTransaction {
+            inputs,
+            commands: self.commands.clone(),
+        })
+    }
+
+    /// Works out how the commands of the transaction use each of its object inputs, keyed by
+    /// input index.
+    async fn object_usages(&self) -> anyhow::Result<BTreeMap<u16, ObjectUsage>> {
+        let mut move_call_parameters = vec![];
+        for command in &self.commands {
+            if let Command::MoveCall(call) = command {
+                move_call_parameters.push(
+                    self.package_resolver
+                        .function_parameters(
+                            call.package.into(),
+                            call.module.as_str(),
+                            call.function.as_str(),
+                        )
+                        .await?,
+                );
+            }
+        }
+        Ok(object_usages(
+            &self.inputs,
+            &self.commands,
+            &move_call_parameters,
+        ))
+    }
+}
+
+/// Works out how `commands` use each object input, keyed by input index, given the parameters of
+/// the function called by each Move call command, in order.
+fn object_usages(
+    inputs: &[Input],
+    commands: &[Command],
+    move_call_parameters: &[Vec<OpenSignature>],
+) -> BTreeMap<u16, ObjectUsage> {
+    let mut usages: BTreeMap<_, _> = inputs
+        .iter()
+        .enumerate()
+        .filter(|(_, input)| matches!(input, Input::Object(_)))
+        .map(|(index, _)| (index as u16, ObjectUsage::default()))
+        .collect();
+
+    let mut move_call_parameters = move_call_parameters.iter();
+    for command in commands {
+        match command {
+            Command::MoveCall(call) => {
+                let parameters = move_call_parameters
+                    .next()
+                    .expect("parameters are given for every Move call");
+                for (i, argument) in call.arguments.iter().enumerate() {
+                    let Argument::Input(index) = argument else {
+                        continue;
+                    };
+                    let Some(usage) = usages.get_mut(index) else {
+                        continue;
+                    };
+                    match parameters.get(i) {
+                        Some(parameter) if is_receiving(parameter) => usage.receiving = true,
+                        Some(OpenSignature {
+                            ref_: Some(Reference::Immutable),
+                            ..
+                        }) => usage.other = true,
+                        _ => {
+                            usage.other = true;
+                            usage.mutable = true;
+                        }
+                    }
+                }
+            }
+            Command::TransferObjects(objects, recipient) => {
+                mark_mutable(&mut usages, objects.iter().chain([recipient]))
+            }
+            Command::SplitCoins(coin, amounts) => {
+                mark_mutable(&mut usages, [coin].into_iter().chain(amounts))
+            }
+            Command::MergeCoins(coin, coins) => {
+                mark_mutable(&mut usages, [coin].into_iter().chain(coins))
+            }
+            Command::MakeMoveVec(_, elements) => mark_mutable(&mut usages, elements.iter()),
+            Command::Upgrade(_, _, _, ticket) => mark_mutable(&mut usages, [ticket]),
+            Command::Publish(_, _) => {}
+        }
+    }
+    usages
+}
+
+fn mark_mutable<'a>(
+    usages: &mut BTreeMap<u16, ObjectUsage>,
+    arguments: impl IntoIterator<Item = &'a Argument>,
+) {
+    for argument in arguments {
+        if let Argument::Input(index) = argument {
+            if let Some(usage) = usages.get_mut(index) {
+                usage.other = true;
+                usage.mutable = true;
+            }
+        }
+    }
+}
+
+fn is_receiving(parameter: &OpenSignature) -> bool {
+    let (address, module, name) = RESOLVED_RECEIVING_STRUCT;
+    matches!(
+        &parameter.body,
+        OpenSignatureBody::Datatype(key, _)
+            if key.package == *address && key.module == module.as_str() && key.name == name.as_str()
+    )
+}
+
+fn resolve_object_arg(
+    object_id: ObjectID,
+    object_ref: ObjectRef,
+    owner: Owner,
+    usage: ObjectUsage,
+) -> anyhow::Result<ObjectArg> {
+    if usage.receiving {
+        ensure!(
+            !usage.other,
+            "Object {object_id} cannot be both received and used as another argument"
+        );
+        ensure!(
+            !owner.is_shared(),
+            "Shared object {object_id} cannot be received"
+        );
+        return Ok(ObjectArg::Receiving(object_ref));
+    }
+    Ok(match owner {
+        Owner::Shared {
+            initial_shared_version,
+        } => ObjectArg::SharedObject {
+            id: object_id,
+            initial_shared_version,
+            mutable: usage.mutable,
+        },
+        Owner::AddressOwner(_) | Owner::ObjectOwner(_) | Owner::Immutable => {
+            ObjectArg::ImmOrOwnedObject(object_ref)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpsee::http_client::HttpClientBuilder;
+    use sui_package_resolver::DatatypeKey;
+    use sui_types::base_types::{random_object_ref, SequenceNumber};
+    use sui_types::SUI_FRAMEWORK_ADDRESS;
+
+    use super::*;
+    use crate::failover_client::{FailoverHttpClient, RetryConfig};
+    use crate::{RpcClient, ServerInfo};
+
+    /// A builder whose client is not connected to any fullnode, for the parts of the builder which
+    /// do not read from the chain.
+    async fn builder() -> PtbBuilder {
+        let url = "http://127.0.0.1:1".to_string();
+        let client = HttpClientBuilder::default().build(&url).unwrap();
+        let http = FailoverHttpClient::new(vec![(url, client)], RetryConfig::no_retries())
+            .await
+            .unwrap();
+        let api = Arc::new(RpcClient {
+            http,
+            ws: None,
+            info: ServerInfo {
+                rpc_methods: vec![],
+                subscriptions: vec![],
+                version: String::new(),
+            },
+        });
+        let read_api = Arc::new(ReadApi::new(api));
+        let package_resolver = Arc::new(Resolver::new(PackageStoreWithLruCache::new(
+            ReadApiPackageStore::new(read_api.clone()),
+        )));
+        PtbBuilder::new(
+            read_api.clone(),
+            TransactionBuilder::new(read_api),
+            package_resolver,
+            SuiAddress::random_for_testing_only(),
+        )
+    }
+
+    fn parameter(ref_: Option<Reference>, module: &str, name: &str) -> OpenSignature {
+        OpenSignature {
+            ref_,
+            body: OpenSignatureBody::Datatype(
+                DatatypeKey {
+                    package: AccountAddress::from_hex_literal("0x42").unwrap(),
+                    module: module.to_string().into(),
+                    name: name.to_string().into(),
+                },
+                vec![],
+            ),
+        }
+    }
+
+    fn receiving() -> OpenSignature {
+        let (address, module, name) = RESOLVED_RECEIVING_STRUCT;
+        OpenSignature {
+            ref_: None,
+            body: OpenSignatureBody::Datatype(
+                DatatypeKey {
+                    package: *address,
+                    module: module.to_string().into(),
+                    name: name.to_string().into(),
+                },
+                vec![OpenSignatureBody::TypeParameter(0)],
+            ),
+        }
+    }
+
+    fn call(arguments: Vec<Argument>) -> Command {
+        Command::move_call(
+            ObjectID::from_hex_literal("0x42").unwrap(),
+            Identifier::new("counter").unwrap(),
+            Identifier::new("f").unwrap(),
+            vec![],
+            arguments,
+        )
+    }
+
+    fn usage(mutable: bool, receiving: bool, other: bool) -> ObjectUsage {
+        ObjectUsage {
+            mutable,
+            receiving,
+            other,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_object_deduplication() {
+        let mut ptb = builder().await;
+        let (a, b) = (ObjectID::random(), ObjectID::random());
+        assert_eq!(ptb.object(a), Argument::Input(0));
+        assert_eq!(ptb.pure(1u64).unwrap(), Argument::Input(1));
+        assert_eq!(ptb.object(b), Argument::Input(2));
+        assert_eq!(ptb.object(a), Argument::Input(0));
+        assert_eq!(ptb.object(b), Argument::Input(2));
+        assert_eq!(ptb.inputs.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_move_call_target() {
+        let mut ptb = builder().await;
+        let counter = ptb.object(ObjectID::random());
+        assert_eq!(
+            ptb.move_call("0x42::counter::increment", vec![], vec![counter])
+                .unwrap(),
+            Argument::Result(0)
+        );
+        let Command::MoveCall(call) = &ptb.commands[0] else {
+            panic!("Expected a Move call");
+        };
+        assert_eq!(call.package, ObjectID::from_hex_literal("0x42").unwrap());
+        assert_eq!(call.module.as_str(), "counter");
+        assert_eq!(call.function.as_str(), "increment");
+        assert_eq!(call.arguments, vec![counter]);
+
+        for target in [
+            "",
+            "0x42",
+            "0x42::counter",
+            "0x42::counter::increment::extra",
+            "counter::increment::0x42",
+            "0x42::1counter::increment",
+            "0x42::counter::",
+        ] {
+            ptb.move_call(target, vec![], vec![]).unwrap_err();
+        }
+        // Malformed targets add no command.
+        assert_eq!(ptb.commands.len(), 1);
+    }
+
+    #[test]
+    fn test_is_receiving() {
+        assert!(is_receiving(&receiving()));
+        assert!(!is_receiving(&parameter(None, "transfer", "Receiving")));
+        assert!(!is_receiving(&parameter(None, "counter", "Counter")));
+        assert!(!is_receiving(&OpenSignature {
+            ref_: None,
+            body: OpenSignatureBody::U64,
+        }));
+    }
+
+    #[test]
+    fn test_mark_mutable() {
+        let mut usages = BTreeMap::from([(0, ObjectUsage::default()), (2, ObjectUsage::default())]);
+        mark_mutable(
+            &mut usages,
+            &[
+                Argument::Input(0),
+                // Pure inputs and results are not tracked.
+                Argument::Input(1),
+                Argument::GasCoin,
+                Argument::Result(0),
+            ],
+        );
+        assert_eq!(usages[&0], usage(true, false, true));
+        assert_eq!(usages[&2], ObjectUsage::default());
+        assert_eq!(usages.len(), 2);
+    }
+
+    #[test]
+    fn test_object_usages() {
+        let inputs = vec![
+            Input::Object(ObjectID::random()),
+            Input::Object(ObjectID::random()),
+            Input::Object(ObjectID::random()),
+            Input::Resolved(CallArg::Pure(vec![])),
+            Input::Object(ObjectID::random()),
+            Input::Object(ObjectID::random()),
+        ];
+        let args = |indices: &[u16]| indices.iter().map(|i| Argument::Input(*i)).collect();
+        let commands = vec![
+            // Input 0 by immutable reference, 1 by mutable reference, 2 by value.
+            call(args(&[0, 1, 2, 3])),
+            // Input 4 received.
+            call(args(&[4])),
+            // Input 5 only transferred.
+            Command::TransferObjects(vec![Argument::Input(5)], Argument::Input(3)),
+        ];
+        let move_call_parameters = vec![
+            vec![
+                parameter(Some(Reference::Immutable), "counter", "Counter"),
+                parameter(Some(Reference::Mutable), "counter", "Counter"),
+                parameter(None, "counter", "Counter"),
+                OpenSignature {
+                    ref_: None,
+                    body: OpenSignatureBody::U64,
+                },
+            ],
+            vec![receiving()],
+        ];
+        let usages = object_usages(&inputs, &commands, &move_call_parameters);
+        assert_eq!(
+            usages,
+            BTreeMap::from([
+                (0, usage(false, false, true)),
+                (1, usage(true, false, true)),
+                (2, usage(true, false, true)),
+                (4, usage(false, true, false)),
+                (5, usage(true, false, true)),
+            ])
+        );
+
+        // Using an object immutably in one command and mutably in another makes it mutable.
+        let commands = vec![
+            call(args(&[0])),
+            Command::MergeCoins(Argument::GasCoin, args(&[0])),
+        ];
+        let move_call_parameters =
+            vec![vec![parameter(Some(Reference::Immutable), "coin", "Coin")]];
+        let usages = object_usages(&inputs[..1], &commands, &move_call_parameters);
+        assert_eq!(usages[&0], usage(true, false, true));
+
+        // Receiving an object and passing it to another command.
+        let commands = vec![
+            call(args(&[0])),
+            Command::TransferObjects(args(&[0]), Argument::Input(3)),
+        ];
+        let usages = object_usages(&inputs, &commands, &[vec![receiving()]]);
+        assert_eq!(usages[&0], usage(true, true, true));
+    }
+
+    #[test]
+    fn test_resolve_object_arg() {
+        let object_ref = random_object_ref();
+        let object_id = object_ref.0;
+        let initial_shared_version = SequenceNumber::from_u64(3);
+        let shared = Owner::Shared {
+            initial_shared_version,
+        };
+        let shared_arg = |mutable| ObjectArg::SharedObject {
+            id: object_id,
+            initial_shared_version,
+            mutable,
+        };
+
+        // Shared objects are only requested mutably when a command needs to mutate them.
+        assert_eq!(
+            resolve_object_arg(object_id, object_ref, shared, usage(false, false, true)).unwrap(),
+            shared_arg(false)
+        );
+        assert_eq!(
+            resolve_object_arg(object_id, object_ref, shared, usage(true, false, true)).unwrap(),
+            shared_arg(true)
+        );
+
+        // Other objects are passed at their latest version, however they are used.
+        for owner in [
+            Owner::AddressOwner(SuiAddress::random_for_testing_only()),
+            Owner::ObjectOwner(SuiAddress::random_for_testing_only()),
+            Owner::Immutable,
+        ] {
+            for mutable in [false, true] {
+                assert_eq!(
+                    resolve_object_arg(object_id, object_ref, owner, usage(mutable, false, true))
+                        .unwrap(),
+                    ObjectArg::ImmOrOwnedObject(object_ref)
+                );
+            }
+        }
+
+        // Objects sent to another object are received.
+        let owner = Owner::ObjectOwner(SUI_FRAMEWORK_ADDRESS.into());
+        assert_eq!(
+            resolve_object_arg(object_id, object_ref, owner, usage(false, true, false)).unwrap(),
+            ObjectArg::Receiving(object_ref)
+        );
+        // An object cannot be both received and used as another argument.
+        resolve_object_arg(object_id, object_ref, owner, usage(true, true, true)).unwrap_err();
+        resolve_object_arg(object_id, object_ref, owner, usage(false, true, true)).unwrap_err();
+        // Shared objects cannot be received.
+        resolve_object_arg(object_id, object_ref, shared, usage(false, true, false)).unwrap_err();
+    }
+}
@@ -17,7 +17,7 @@ use crate::{
     surfer_state::{EntryFunction, SurferState},
 };
 
-enum InputObjectPassKind {
+pub(crate) enum InputObjectPassKind {
     Value,
     ByRef,
     MutRef,
@@ -36,6 +36,10 @@ impl SurfStrategy for DefaultSurfStrategy {
     ) {
         entry_functions.shuffle(&mut state.rng);
         for entry in entry_functions {
+            // Values returned by a function are only handled when chaining calls
+            if !entry.returns.is_empty() {
+                continue;
+            }
             let Some(args) = Self::choose_function_call_args(state, entry.parameters).await else {
                 debug!(
                     "Failed to choose arguments for Move function {:?}::{:?}",
@@ -141,7 +145,7 @@ impl DefaultSurfStrategy {
         }
     }
 
-    async fn choose_object_call_arg(
+    pub(crate) async fn choose_object_call_arg(
         state: &mut SurferState,
         kind: InputObjectPassKind,
         arg_type: Type,
@@ -18,8 +18,9 @@ use crate::surfer_state::SurfStatistics;
 use crate::surfer_task::SurferTask;
 
 pub mod default_surf_strategy;
-mod surf_strategy;
-mod surfer_state;
+pub mod move_aware_surf_strategy;
+pub mod surf_strategy;
+pub mod surfer_state;
 mod surfer_task;
 
 const VALIDATOR_COUNT: usize = 7;
@@ -1,11 +1,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use std::{path::PathBuf, time::Duration};
 use tracing::info;
 
 use sui_surfer::default_surf_strategy::DefaultSurfStrategy;
+use sui_surfer::move_aware_surf_strategy::MoveAwareSurfStrategy;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Strategy {
+    /// Calls one entry function per transaction with randomly chosen arguments
+    Default,
+    /// Chains calls in programmable transactions, with arguments derived from Move types
+    MoveAware,
+}
 
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
@@ -18,6 +27,14 @@ struct Args {
 
     #[clap(long, help = "List of package paths to surf")]
     packages: Vec<PathBuf>,
+
+    #[clap(
+        long,
+        value_enum,
+        default_value = "default",
+        help = "Strategy used to surf"
+    )]
+    strategy: Strategy,
 }
 
 const DEFAULT_RUN_DURATION: u64 = 30;
@@ -36,12 +53,19 @@ async fn main() {
         .with_env()
         .init();
 
-    let results = sui_surfer::run::<DefaultSurfStrategy>(
-        Duration::from_secs(args.run_duration.unwrap_or(DEFAULT_RUN_DURATION)),
-        Duration::from_secs(args.run_duration.unwrap_or(DEFAULT_EPOCH_DURATION)),
-        args.packages,
-    )
-    .await;
+    let run_duration = Duration::from_secs(args.run_duration.unwrap_or(DEFAULT_RUN_DURATION));
+    let epoch_duration = Duration::from_secs(args.epoch_duration.unwrap_or(DEFAULT_EPOCH_DURATION));
+    let results = match args.strategy {
+        Strategy::Default => {
+            sui_surfer::run::<DefaultSurfStrategy>(run_duration, epoch_duration, args.packages)
+                .await
+        }
+        Strategy::MoveAware => {
+            sui_surfer::run::<MoveAwareSurfStrategy>(run_duration, epoch_duration, args.packages)
+                .await
+        }
+    };
     results.print_stats();
+    results.print_report();
     info!("Finished surfing");
 }
@@ -0,0 +1,501 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use move_binary_format::file_format::AbilitySet;
+use move_binary_format::normalized::Type;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::StructTag;
+use rand::distributions::Alphanumeric;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use std::collections::HashMap;
+use sui_types::{
+    base_types::{ObjectID, ObjectRef, SuiAddress},
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{Argument, CallArg, Command},
+    Identifier, MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS,
+};
+use tokio::sync::watch;
+use tracing::debug;
+
+use crate::{
+    default_surf_strategy::{DefaultSurfStrategy, InputObjectPassKind},
+    surf_strategy::SurfStrategy,
+    surfer_state::{EntryFunction, SurferState},
+};
+
+/// Maximum number of Move calls chained in a single programmable transaction
+const MAX_CALLS_PER_TRANSACTION: usize = 4;
+const MAX_VECTOR_LENGTH: usize = 8;
+const MAX_STRING_LENGTH: usize = 16;
+
+/// A strategy that derives argument values from function signatures, including vectors,
+/// strings and options, and chains the values returned by a call into the following calls of
+/// the same programmable transaction.
+#[derive(Default)]
+pub struct MoveAwareSurfStrategy {}
+
+#[async_trait]
+impl SurfStrategy for MoveAwareSurfStrategy {
+    async fn surf_for_a_while(
+        &mut self,
+        state: &mut SurferState,
+        mut entry_functions: Vec<EntryFunction>,
+        exit: &watch::Receiver<()>,
+    ) {
+        entry_functions.shuffle(&mut state.rng);
+        for entry in &entry_functions {
+            Self::surf_from(state, entry, &entry_functions).await;
+            if exit.has_changed().unwrap() {
+                return;
+            }
+        }
+    }
+}
+
+/// Where the value of an argument comes from.
+enum ArgumentChoice {
+    Input(CallArg),
+    /// Index in `ChainedCalls::results`, and whether the value is moved
+    Result(usize, bool),
+}
+
+/// A programmable transaction under construction.
+#[derive(Default)]
+struct ChainedCalls {
+    builder: ProgrammableTransactionBuilder,
+    /// Values returned by previous calls that have not been moved yet
+    results: Vec<(Type, Argument)>,
+    calls: Vec<(ObjectID, String, String)>,
+    chosen_owned_objects: Vec<(StructTag, ObjectRef)>,
+}
+
+impl MoveAwareSurfStrategy {
+    /// Calls `entry`, followed by functions that take the values it returned.
+    async fn surf_from(
+        state: &mut SurferState,
+        entry: &EntryFunction,
+        entry_functions: &[EntryFunction],
+    ) {
+        let mut chained = ChainedCalls::default();
+        let mut next = Some(entry);
+        while let Some(function) = next.take() {
+            if !Self::returns_can_be_disposed(state, function).await {
+                break;
+            }
+            let Some(args) = Self::choose_function_call_args(state, &mut chained, function).await
+            else {
+                debug!(
+                    "Failed to choose arguments for Move function {:?}::{:?}",
+                    function.module, function.function
+                );
+                break;
+            };
+            Self::add_call(&mut chained, function, args);
+            if chained.calls.len() >= MAX_CALLS_PER_TRANSACTION {
+                break;
+            }
+            let consumers: Vec<_> = entry_functions
+                .iter()
+                .filter(|candidate| {
+                    candidate.parameters.iter().any(|param| {
+                        chained
+                            .results
+                            .iter()
+                            .any(|(ty, _)| is_struct(ty) && accepts(param, ty))
+                    })
+                })
+                .collect();
+            next = consumers.choose(&mut state.rng).copied();
+        }
+        if chained.calls.is_empty() {
+            return;
+        }
+        Self::dispose_results(state, &mut chained).await;
+        let ChainedCalls { builder, calls, .. } = chained;
+        state
+            .execute_programmable_transaction(builder.finish(), calls)
+            .await;
+    }
+
+    /// Values that are neither moved by a later call nor dropped are transferred to the sender,
+    /// so only functions whose return values can be dropped or transferred are called.
+    async fn returns_can_be_disposed(state: &SurferState, function: &EntryFunction) -> bool {
+        let abilities = state.struct_abilities.read().await;
+        function
+            .returns
+            .iter()
+            .all(|ty| has_drop(&abilities, ty) || is_transferable(&abilities, ty))
+    }
+
+    async fn choose_function_call_args(
+        state: &mut SurferState,
+        chained: &mut ChainedCalls,
+        function: &EntryFunction,
+    ) -> Option<Vec<ArgumentChoice>> {
+        let mut args = vec![];
+        let mut chosen_owned_objects = vec![];
+        let mut failed = false;
+        for param in &function.parameters {
+            let arg =
+                Self::choose_call_arg(state, chained, &args, param, &mut chosen_owned_objects)
+                    .await;
+            match arg {
+                Some(arg) => args.push(arg),
+                None => {
+                    failed = true;
+                    break;
+                }
+            }
+        }
+        if failed {
+            for (struct_tag, obj_ref) in chosen_owned_objects {
+                state
+                    .owned_objects
+                    .get_mut(&struct_tag)
+                    .unwrap()
+                    .insert(obj_ref);
+            }
+            None
+        } else {
+            chained.chosen_owned_objects.extend(chosen_owned_objects);
+            Some(args)
+        }
+    }
+
+    async fn choose_call_arg(
+        state: &mut SurferState,
+        chained: &ChainedCalls,
+        chosen: &[ArgumentChoice],
+        param: &Type,
+        chosen_owned_objects: &mut Vec<(StructTag, ObjectRef)>,
+    ) -> Option<ArgumentChoice> {
+        // Pure values can be passed by value or by immutable reference
+        let pure_type = match param {
+            Type::Reference(ty) => ty.as_ref(),
+            ty => ty,
+        };
+        let addresses = state.cluster.get_addresses();
+        if let Some(bytes) = Self::generate_pure_value(&mut state.rng, &addresses, pure_type) {
+            return Some(ArgumentChoice::Input(CallArg::Pure(bytes)));
+        }
+        let (kind, ty) = match param {
+            Type::Reference(ty) => (InputObjectPassKind::ByRef, ty.as_ref()),
+            Type::MutableReference(ty) => (InputObjectPassKind::MutRef, ty.as_ref()),
+            ty @ Type::Struct { .. } => (InputObjectPassKind::Value, ty),
+            _ => return None,
+        };
+        // Prefer the values returned by previous calls over existing objects
+        let matching_results: Vec<_> = chained
+            .results
+            .iter()
+            .enumerate()
+            .filter(|(index, (result_ty, _))| {
+                result_ty == ty
+                    && !chosen
+                        .iter()
+                        .any(|arg| matches!(arg, ArgumentChoice::Result(i, _) if i == index))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if let Some(index) = matching_results.choose(&mut state.rng) {
+            let by_value = matches!(kind, InputObjectPassKind::Value);
+            return Some(ArgumentChoice::Result(*index, by_value));
+        }
+        DefaultSurfStrategy::choose_object_call_arg(state, kind, ty.clone(), chosen_owned_objects)
+            .await
+            .map(ArgumentChoice::Input)
+    }
+
+    /// Generates a BCS encoded value of `ty`, if it can be passed as a pure argument. Addresses
+    /// are chosen from `addresses`.
+    fn generate_pure_value(
+        rng: &mut StdRng,
+        addresses: &[SuiAddress],
+        ty: &Type,
+    ) -> Option<Vec<u8>> {
+        let bytes = match ty {
+            Type::Bool => bcs::to_bytes(&rng.gen::<bool>()).unwrap(),
+            Type::U8 => bcs::to_bytes(&rng.gen::<u8>()).unwrap(),
+            Type::U16 => bcs::to_bytes(&rng.gen::<u16>()).unwrap(),
+            Type::U32 => bcs::to_bytes(&rng.gen::<u32>()).unwrap(),
+            Type::U64 => bcs::to_bytes(&rng.gen::<u64>()).unwrap(),
+            Type::U128 => bcs::to_bytes(&rng.gen::<u128>()).unwrap(),
+            Type::U256 => rng.gen::<[u8; 32]>().to_vec(),
+            Type::Address => bcs::to_bytes(addresses.choose(rng)?).unwrap(),
+            Type::Vector(inner) => {
+                // An empty vector would otherwise be generated for any element type
+                if !is_pure_type(inner) {
+                    return None;
+                }
+                let len = rng.gen_range(0..=MAX_VECTOR_LENGTH);
+                let mut bytes = vec![];
+                write_uleb128(len, &mut bytes);
+                for _ in 0..len {
+                    bytes.extend(Self::generate_pure_value(rng, addresses, inner)?);
+                }
+                bytes
+            }
+            Type::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => match (address, module.as_str(), name.as_str()) {
+                (&MOVE_STDLIB_ADDRESS, "string" | "ascii", "String") => {
+                    let len = rng.gen_range(0..=MAX_STRING_LENGTH);
+                    let value: String =
+                        (0..len).map(|_| rng.sample(Alphanumeric) as char).collect();
+                    bcs::to_bytes(&value).unwrap()
+                }
+                (&MOVE_STDLIB_ADDRESS, "option", "Option") => {
+                    // Like vectors, `None` would otherwise be generated for any type
+                    if !is_pure_type(&type_arguments[0]) {
+                        return None;
+                    }
+                    // Options are encoded as vectors with zero or one element
+                    if rng.gen_bool(0.5) {
+                        vec![0]
+                    } else {
+                        let mut bytes = vec![1];
+                        bytes.extend(Self::generate_pure_value(
+                            rng,
+                            addresses,
+                            &type_arguments[0],
+                        )?);
+                        bytes
+                    }
+                }
+                (&SUI_FRAMEWORK_ADDRESS, "object", "ID") => {
+                    bcs::to_bytes(&ObjectID::random_from_rng(rng)).unwrap()
+                }
+                _ => return None,
+            },
+            Type::Signer
+            | Type::TypeParameter(_)
+            | Type::Reference(_)
+            | Type::MutableReference(_) => return None,
+        };
+        Some(bytes)
+    }
+
+    fn add_call(chained: &mut ChainedCalls, function: &EntryFunction, args: Vec<ArgumentChoice>) {
+        let mut moved = vec![];
+        let arguments = args
+            .into_iter()
+            .map(|arg| match arg {
+                ArgumentChoice::Input(call_arg) => chained.builder.input(call_arg).unwrap(),
+                ArgumentChoice::Result(index, by_value) => {
+                    if by_value {
+                        moved.push(index);
+                    }
+                    chained.results[index].1
+                }
+            })
+            .collect();
+        let Argument::Result(command) = chained.builder.programmable_move_call(
+            function.package,
+            Identifier::new(function.module.as_str()).unwrap(),
+            Identifier::new(function.function.as_str()).unwrap(),
+            vec![],
+            arguments,
+        ) else {
+            unreachable!("Commands always return a result");
+        };
+        moved.sort();
+        for index in moved.into_iter().rev() {
+            chained.results.remove(index);
+        }
+        for (index, ty) in function.returns.iter().enumerate() {
+            chained
+                .results
+                .push((ty.clone(), Argument::NestedResult(command, index as u16)));
+        }
+        chained.calls.push(function.key());
+    }
+
+    async fn dispose_results(state: &SurferState, chained: &mut ChainedCalls) {
+        let abilities = state.struct_abilities.read().await;
+        let objects: Vec<_> = chained
+            .results
+            .drain(..)
+            .filter(|(ty, _)| !has_drop(&abilities, ty))
+            .map(|(_, arg)| arg)
+            .collect();
+        if objects.is_empty() {
+            return;
+        }
+        let recipient = chained.builder.pure(state.address).unwrap();
+        chained
+            .builder
+            .command(Command::TransferObjects(objects, recipient));
+    }
+}
+
+fn is_struct(ty: &Type) -> bool {
+    matches!(ty, Type::Struct { .. })
+}
+
+/// Whether a parameter of type `param` can take a value of type `ty`.
+fn accepts(param: &Type, ty: &Type) -> bool {
+    match param {
+        Type::Reference(inner) | Type::MutableReference(inner) => inner.as_ref() == ty,
+        _ => param == ty,
+    }
+}
+
+fn struct_abilities(
+    abilities: &HashMap<(AccountAddress, String, String), AbilitySet>,
+    ty: &Type,
+) -> Option<AbilitySet> {
+    match ty {
+        Type::Struct {
+            address,
+            module,
+            name,
+            ..
+        } => abilities
+            .get(&(*address, module.to_string(), name.to_string()))
+            .copied(),
+        _ => None,
+    }
+}
+
+fn has_drop(abilities: &HashMap<(AccountAddress, String, String), AbilitySet>, ty: &Type) -> bool {
+    match ty {
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => match (address, module.as_str(), name.as_str()) {
+            (&MOVE_STDLIB_ADDRESS, "string" | "ascii", "String") => true,
+            (&MOVE_STDLIB_ADDRESS, "option", "Option") => has_drop(abilities, &type_arguments[0]),
+            (&SUI_FRAMEWORK_ADDRESS, "object", "ID") => true,
+            _ => struct_abilities(abilities, ty).is_some_and(|abilities| abilities.has_drop()),
+        },
+        Type::Vector(inner) => has_drop(abilities, inner),
+        Type::Signer | Type::TypeParameter(_) => false,
+        _ => true,
+    }
+}
+
+fn is_transferable(
+    abilities: &HashMap<(AccountAddress, String, String), AbilitySet>,
+    ty: &Type,
+) -> bool {
+    struct_abilities(abilities, ty)
+        .is_some_and(|abilities| abilities.has_key() && abilities.has_store())
+}
+
+/// Whether values of `ty` can be passed as pure arguments.
+fn is_pure_type(ty: &Type) -> bool {
+    match ty {
+        Type::Bool
+        | Type::U8
+        | Type::U16
+        | Type::U32
+        | Type::U64
+        | Type::U128
+        | Type::U256
+        | Type::Address => true,
+        Type::Vector(inner) => is_pure_type(inner),
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => match (address, module.as_str(), name.as_str()) {
+            (&MOVE_STDLIB_ADDRESS, "string" | "ascii", "String")
+            | (&SUI_FRAMEWORK_ADDRESS, "object", "ID") => true,
+            (&MOVE_STDLIB_ADDRESS, "option", "Option") => is_pure_type(&type_arguments[0]),
+            _ => false,
+        },
+        Type::Signer | Type::TypeParameter(_) | Type::Reference(_) | Type::MutableReference(_) => {
+            false
+        }
+    }
+}
+
+fn write_uleb128(mut value: usize, bytes: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn struct_type(address: AccountAddress, module: &str, name: &str, args: Vec<Type>) -> Type {
+        Type::Struct {
+            address,
+            module: Identifier::new(module).unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_arguments: args,
+        }
+    }
+
+    fn option_type(inner: Type) -> Type {
+        struct_type(MOVE_STDLIB_ADDRESS, "option", "Option", vec![inner])
+    }
+
+    #[test]
+    fn test_generate_pure_value() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let addresses = vec![SuiAddress::random_for_testing_only()];
+        let string = struct_type(MOVE_STDLIB_ADDRESS, "string", "String", vec![]);
+        let object = struct_type(AccountAddress::ONE, "counter", "Counter", vec![]);
+        for _ in 0..100 {
+            let mut generate =
+                |ty: &Type| MoveAwareSurfStrategy::generate_pure_value(&mut rng, &addresses, ty);
+            let bytes = generate(&Type::U64).unwrap();
+            bcs::from_bytes::<u64>(&bytes).unwrap();
+            let bytes = generate(&Type::Address).unwrap();
+            assert_eq!(bcs::from_bytes::<SuiAddress>(&bytes).unwrap(), addresses[0]);
+            let bytes = generate(&Type::Vector(Box::new(Type::U16))).unwrap();
+            assert!(bcs::from_bytes::<Vec<u16>>(&bytes).unwrap().len() <= MAX_VECTOR_LENGTH);
+            let bytes = generate(&string).unwrap();
+            assert!(bcs::from_bytes::<String>(&bytes).unwrap().len() <= MAX_STRING_LENGTH);
+            let bytes = generate(&option_type(Type::Vector(Box::new(Type::U8)))).unwrap();
+            bcs::from_bytes::<Option<Vec<u8>>>(&bytes).unwrap();
+
+            // Neither empty vectors nor `None` are generated for types that are not pure
+            assert!(generate(&Type::Vector(Box::new(object.clone()))).is_none());
+            assert!(generate(&Type::Vector(Box::new(Type::Signer))).is_none());
+            assert!(generate(&option_type(object.clone())).is_none());
+            assert!(generate(&option_type(Type::Vector(Box::new(object.clone())))).is_none());
+            assert!(generate(&Type::Reference(Box::new(Type::U8))).is_none());
+        }
+        // Addresses can only be generated when there are some to choose from
+        assert!(
+            MoveAwareSurfStrategy::generate_pure_value(&mut rng, &[], &Type::Address).is_none()
+        );
+    }
+
+    #[test]
+    fn test_write_uleb128() {
+        for (value, expected) in [
+            (0, vec![0x00]),
+            (1, vec![0x01]),
+            (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]),
+            (300, vec![0xac, 0x02]),
+            (16384, vec![0x80, 0x80, 0x01]),
+        ] {
+            let mut bytes = vec![];
+            write_uleb128(value, &mut bytes);
+            assert_eq!(bytes, expected, "uleb128 encoding of {value}");
+            // Vector lengths are encoded the same way by BCS
+            let vector = bcs::to_bytes(&vec![0u8; value]).unwrap();
+            assert_eq!(&vector[..bytes.len()], bytes);
+        }
+    }
+}
@@ -2,22 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use indexmap::IndexSet;
-use move_binary_format::file_format::Visibility;
+use move_binary_format::file_format::{AbilitySet, Visibility};
 use move_binary_format::normalized::Type;
+use move_core_types::account_address::AccountAddress;
 use move_core_types::language_storage::StructTag;
 use rand::rngs::StdRng;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use sui_json_rpc_types::{SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI};
+use sui_json_rpc_types::{
+    SuiExecutionStatus, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+};
 use sui_move_build::BuildConfig;
 use sui_protocol_config::ProtocolConfig;
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
 use sui_types::execution_config_utils::to_binary_config;
 use sui_types::object::{Object, Owner};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::storage::WriteKind;
-use sui_types::transaction::{CallArg, ObjectArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_PUBLISH};
+use sui_types::transaction::{
+    CallArg, ObjectArg, ProgrammableTransaction, TransactionData, TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+};
 use sui_types::{Identifier, SUI_FRAMEWORK_ADDRESS};
 use test_cluster::TestCluster;
 use tokio::sync::RwLock;
@@ -29,6 +35,37 @@ pub struct EntryFunction {
     pub module: String,
     pub function: String,
     pub parameters: Vec<Type>,
+    pub returns: Vec<Type>,
+}
+
+impl EntryFunction {
+    pub fn key(&self) -> (ObjectID, String, String) {
+        (self.package, self.module.clone(), self.function.clone())
+    }
+}
+
+/// Outcome of the calls made to a Move function.
+#[derive(Debug, Default, Clone)]
+pub struct FunctionReport {
+    pub num_calls: u64,
+    pub num_successes: u64,
+    /// Number of calls that aborted, by abort code
+    pub aborts: BTreeMap<u64, u64>,
+    /// Number of calls that failed without aborting, by error kind
+    pub other_failures: BTreeMap<String, u64>,
+}
+
+impl FunctionReport {
+    fn merge(&mut self, other: FunctionReport) {
+        self.num_calls += other.num_calls;
+        self.num_successes += other.num_successes;
+        for (code, count) in other.aborts {
+            *self.aborts.entry(code).or_default() += count;
+        }
+        for (kind, count) in other.other_failures {
+            *self.other_failures.entry(kind).or_default() += count;
+        }
+    }
 }
 
 #[derive(Default)]
@@ -37,30 +74,66 @@ pub struct SurfStatistics {
     pub num_failed_transactions: u64,
     pub num_owned_obj_transactions: u64,
     pub num_shared_obj_transactions: u64,
+    /// Failed transactions whose trailing `TransferObjects` command, which disposes of the
+    /// call results, failed
+    pub num_transfer_failures: u64,
     pub unique_move_functions_called: HashSet<(ObjectID, String, String)>,
+    /// All the functions discovered in the published packages
+    pub discovered_move_functions: HashSet<(ObjectID, String, String)>,
+    pub function_reports: BTreeMap<(ObjectID, String, String), FunctionReport>,
 }
 
 impl SurfStatistics {
+    /// Records a transaction made of `calls`, in command order, optionally followed by a
+    /// `TransferObjects` command. When the transaction fails, the failure is attributed to the
+    /// call of the failing command, to the transfer when it is the trailing command, or to all
+    /// calls when it is not tied to a command. The other calls count as neither successful nor
+    /// failed.
     pub fn record_transaction(
         &mut self,
         has_shared_object: bool,
-        tx_succeeded: bool,
-        package: ObjectID,
-        module: String,
-        function: String,
+        status: &SuiExecutionStatus,
+        calls: Vec<(ObjectID, String, String)>,
     ) {
-        if tx_succeeded {
-            self.num_successful_transactions += 1;
-        } else {
-            self.num_failed_transactions += 1;
-        }
+        let failure = match status {
+            SuiExecutionStatus::Success => {
+                self.num_successful_transactions += 1;
+                None
+            }
+            SuiExecutionStatus::Failure { error } => {
+                self.num_failed_transactions += 1;
+                Some(parse_execution_failure(error))
+            }
+        };
         if has_shared_object {
             self.num_shared_obj_transactions += 1;
         } else {
             self.num_owned_obj_transactions += 1;
         }
-        self.unique_move_functions_called
-            .insert((package, module, function));
+        if let Some((Some(command), _)) = &failure {
+            if *command == calls.len() {
+                self.num_transfer_failures += 1;
+            }
+        }
+        for (index, call) in calls.into_iter().enumerate() {
+            let report = self.function_reports.entry(call.clone()).or_default();
+            report.num_calls += 1;
+            match &failure {
+                None => report.num_successes += 1,
+                Some((command, failure)) if command.map_or(true, |command| command == index) => {
+                    match failure {
+                        ExecutionFailure::Abort(code) => {
+                            *report.aborts.entry(*code).or_default() += 1
+                        }
+                        ExecutionFailure::Other(kind) => {
+                            *report.other_failures.entry(kind.clone()).or_default() += 1
+                        }
+                    }
+                }
+                Some(_) => (),
+            }
+            self.unique_move_functions_called.insert(call);
+        }
     }
 
     pub fn aggregate(stats: Vec<Self>) -> Self {
@@ -70,9 +143,20 @@ impl SurfStatistics {
             result.num_failed_transactions += stat.num_failed_transactions;
             result.num_owned_obj_transactions += stat.num_owned_obj_transactions;
             result.num_shared_obj_transactions += stat.num_shared_obj_transactions;
+            result.num_transfer_failures += stat.num_transfer_failures;
             result
                 .unique_move_functions_called
                 .extend(stat.unique_move_functions_called);
+            result
+                .discovered_move_functions
+                .extend(stat.discovered_move_functions);
+            for (function, report) in stat.function_reports {
+                result
+                    .function_reports
+                    .entry(function)
+                    .or_default()
+                    .merge(report);
+            }
         }
         result
     }
@@ -88,11 +172,91 @@ impl SurfStatistics {
             "{} are owned object transactions, {} are shared object transactions",
             self.num_owned_obj_transactions, self.num_shared_obj_transactions
         );
+        info!(
+            "{} transactions failed to transfer the call results",
+            self.num_transfer_failures
+        );
         info!(
             "Unique move functions called: {}",
             self.unique_move_functions_called.len()
         );
     }
+
+    /// Prints, for each package, which functions were called and how their calls failed.
+    pub fn print_report(&self) {
+        let mut packages: BTreeMap<ObjectID, Vec<(&String, &String)>> = BTreeMap::new();
+        for (package, module, function) in &self.discovered_move_functions {
+            packages
+                .entry(*package)
+                .or_default()
+                .push((module, function));
+        }
+        for (package, mut functions) in packages {
+            functions.sort();
+            let reports: Vec<_> = functions
+                .iter()
+                .map(|(module, function)| {
+                    self.function_reports
+                        .get(&(package, module.to_string(), function.to_string()))
+                })
+                .collect();
+            info!(
+                "Package {}: {} of {} functions called, {} succeeded at least once",
+                package,
+                reports.iter().flatten().count(),
+                functions.len(),
+                reports
+                    .iter()
+                    .flatten()
+                    .filter(|report| report.num_successes > 0)
+                    .count(),
+            );
+            for ((module, function), report) in functions.iter().zip(reports) {
+                match report {
+                    Some(report) => info!(
+                        "  {}::{}: {} calls, {} succeeded, aborts by code: {:?}, other failures: {:?}",
+                        module,
+                        function,
+                        report.num_calls,
+                        report.num_successes,
+                        report.aborts,
+                        report.other_failures
+                    ),
+                    None => info!("  {}::{}: never called", module, function),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ExecutionFailure {
+    Abort(u64),
+    Other(String),
+}
+
+/// Parses the failing command and the failure out of the error of a failed transaction, which
+/// is the debug representation of `ExecutionFailureStatus`, e.g.
+/// `MoveAbort(MoveLocation { .. }, 3) in command 0`.
+fn parse_execution_failure(error: &str) -> (Option<usize>, ExecutionFailure) {
+    let (error, command) = match error.rsplit_once(" in command ") {
+        Some((error, command)) => (error, command.parse().ok()),
+        None => (error, None),
+    };
+    let failure = error
+        .strip_prefix("MoveAbort(")
+        .and_then(|abort| abort.strip_suffix(')'))
+        .and_then(|abort| abort.rsplit_once(", "))
+        .and_then(|(_, code)| code.parse().ok())
+        .map(ExecutionFailure::Abort)
+        .unwrap_or_else(|| {
+            let kind = error
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap_or(error);
+            ExecutionFailure::Other(kind.to_string())
+        });
+    (command, failure)
 }
 
 pub type OwnedObjects = HashMap<StructTag, IndexSet<ObjectRef>>;
@@ -103,6 +267,9 @@ pub type ImmObjects = Arc<RwLock<HashMap<StructTag, Vec<ObjectRef>>>>;
 /// (object ID, initial shared version).
 pub type SharedObjects = Arc<RwLock<HashMap<StructTag, Vec<(ObjectID, SequenceNumber)>>>>;
 
+/// Map from (address, module, name) of the structs in the published packages to their abilities.
+pub type StructAbilities = Arc<RwLock<HashMap<(AccountAddress, String, String), AbilitySet>>>;
+
 pub struct SurferState {
     pub cluster: Arc<TestCluster>,
     pub rng: StdRng,
@@ -113,6 +280,7 @@ pub struct SurferState {
     pub immutable_objects: ImmObjects,
     pub shared_objects: SharedObjects,
     pub entry_functions: Arc<RwLock<Vec<EntryFunction>>>,
+    pub struct_abilities: StructAbilities,
 
     pub stats: SurfStatistics,
 }
@@ -127,6 +295,7 @@ impl SurferState {
         immutable_objects: ImmObjects,
         shared_objects: SharedObjects,
         entry_functions: Arc<RwLock<Vec<EntryFunction>>>,
+        struct_abilities: StructAbilities,
     ) -> Self {
         Self {
             cluster,
@@ -137,6 +306,7 @@ impl SurferState {
             immutable_objects,
             shared_objects,
             entry_functions,
+            struct_abilities,
             stats: Default::default(),
         }
     }
@@ -147,23 +317,39 @@ impl SurferState {
         module: String,
         function: String,
         args: Vec<CallArg>,
+    ) {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .move_call(
+                package,
+                Identifier::new(module.as_str()).unwrap(),
+                Identifier::new(function.as_str()).unwrap(),
+                vec![],
+                args,
+            )
+            .unwrap();
+        self.execute_programmable_transaction(builder.finish(), vec![(package, module, function)])
+            .await;
+    }
+
+    /// Executes `pt`, where `calls` are the Move functions called by its commands, in order.
+    pub async fn execute_programmable_transaction(
+        &mut self,
+        pt: ProgrammableTransaction,
+        calls: Vec<(ObjectID, String, String)>,
     ) {
         let rgp = self.cluster.get_reference_gas_price().await;
-        let use_shared_object = args
+        let use_shared_object = pt
+            .inputs
             .iter()
             .any(|arg| matches!(arg, CallArg::Object(ObjectArg::SharedObject { .. })));
-        let tx_data = TransactionData::new_move_call(
+        let tx_data = TransactionData::new_programmable(
             self.address,
-            package,
-            Identifier::new(module.as_str()).unwrap(),
-            Identifier::new(function.as_str()).unwrap(),
-            vec![],
-            self.gas_object,
-            args,
+            vec![self.gas_object],
+            pt,
             TEST_ONLY_GAS_UNIT_FOR_PUBLISH * rgp,
             rgp,
-        )
-        .unwrap();
+        );
         let tx = self.cluster.wallet.sign_transaction(&tx_data);
         let response = loop {
             match self
@@ -185,19 +371,16 @@ impl SurferState {
         );
         let effects = response.effects.unwrap();
         info!(
-            "[{:?}] Calling Move function {:?}::{:?} returned {:?}",
+            "[{:?}] Calling Move functions {:?} returned {:?}",
             self.address,
-            module,
-            function,
+            calls
+                .iter()
+                .map(|(_, module, function)| format!("{module}::{function}"))
+                .collect::<Vec<_>>(),
             effects.status()
         );
-        self.stats.record_transaction(
-            use_shared_object,
-            effects.status().is_ok(),
-            package,
-            module,
-            function,
-        );
+        self.stats
+            .record_transaction(use_shared_object, effects.status(), calls);
         self.process_tx_effects(&effects).await;
     }
 
@@ -264,43 +447,57 @@ impl SurferState {
         let move_package = package.into_inner().data.try_into_package().unwrap();
         let config = ProtocolConfig::get_for_max_version_UNSAFE();
         let binary_config = to_binary_config(&config);
-        let entry_functions: Vec<_> = move_package
-            .normalize(&binary_config)
-            .unwrap()
-            .into_iter()
-            .flat_map(|(module_name, module)| {
-                module
-                    .functions
-                    .into_iter()
-                    .filter_map(|(func_name, func)| {
-                        // Either public function or entry function is callable.
-                        if !matches!(func.visibility, Visibility::Public) && !func.is_entry {
-                            return None;
-                        }
-                        // Surfer doesn't support chaining transactions in a programmable transaction yet.
-                        if !func.return_.is_empty() {
-                            return None;
-                        }
-                        // Surfer doesn't support type parameter yet.
-                        if !func.type_parameters.is_empty() {
-                            return None;
-                        }
-                        let mut parameters = func.parameters;
-                        if let Some(last_param) = parameters.last().as_ref() {
-                            if is_type_tx_context(last_param) {
-                                parameters.pop();
+        let modules = move_package.normalize(&binary_config).unwrap();
+        {
+            let mut struct_abilities = self.struct_abilities.write().await;
+            for module in modules.values() {
+                for (name, struct_) in &module.structs {
+                    struct_abilities.insert(
+                        (module.address, module.name.to_string(), name.to_string()),
+                        struct_.abilities,
+                    );
+                }
+            }
+        }
+        let entry_functions: Vec<_> =
+            modules
+                .into_iter()
+                .flat_map(|(module_name, module)| {
+                    module
+                        .functions
+                        .into_iter()
+                        .filter_map(|(func_name, func)| {
+                            // Either public function or entry function is callable.
+                            if !matches!(func.visibility, Visibility::Public) && !func.is_entry {
+                                return None;
                             }
-                        }
-                        Some(EntryFunction {
-                            package: package_id,
-                            module: module_name.clone(),
-                            function: func_name.to_string(),
-                            parameters,
+                            // References cannot be returned to a programmable transaction.
+                            if func.return_.iter().any(|ty| {
+                                matches!(ty, Type::Reference(_) | Type::MutableReference(_))
+                            }) {
+                                return None;
+                            }
+                            // Surfer doesn't support type parameter yet.
+                            if !func.type_parameters.is_empty() {
+                                return None;
+                            }
+                            let mut parameters = func.parameters;
+                            if let Some(last_param) = parameters.last().as_ref() {
+                                if is_type_tx_context(last_param) {
+                                    parameters.pop();
+                                }
+                            }
+                            Some(EntryFunction {
+                                package: package_id,
+                                module: module_name.clone(),
+                                function: func_name.to_string(),
+                                parameters,
+                                returns: func.return_,
+                            })
                         })
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect();
+                        .collect::<Vec<_>>()
+                })
+                .collect();
         info!(
             "Number of entry functions discovered: {:?}",
             entry_functions.len()
@@ -407,3 +604,80 @@ fn is_type_tx_context(ty: &Type) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_execution_failure() {
+        let abort = "MoveAbort(MoveLocation { module: ModuleId { address: \
+            0000000000000000000000000000000000000000000000000000000000000002, name: \
+            Identifier(\"m\") }, function: 1, instruction: 4, function_name: Some(\"f\") }, 3)";
+        assert_eq!(
+            parse_execution_failure(&format!("{abort} in command 1")),
+            (Some(1), ExecutionFailure::Abort(3))
+        );
+        assert_eq!(
+            parse_execution_failure(abort),
+            (None, ExecutionFailure::Abort(3))
+        );
+        assert_eq!(
+            parse_execution_failure("InsufficientCoinBalance in command 0"),
+            (
+                Some(0),
+                ExecutionFailure::Other("InsufficientCoinBalance".to_string())
+            )
+        );
+        assert_eq!(
+            parse_execution_failure(
+                "CommandArgumentError { arg_idx: 0, kind: TypeMismatch } in command 2"
+            ),
+            (
+                Some(2),
+                ExecutionFailure::Other("CommandArgumentError".to_string())
+            )
+        );
+        assert_eq!(
+            parse_execution_failure("InsufficientGas"),
+            (None, ExecutionFailure::Other("InsufficientGas".to_string()))
+        );
+        // A malformed abort code is reported as a failure of its own kind
+        assert_eq!(
+            parse_execution_failure("MoveAbort(MoveLocation { .. }, code) in command x"),
+            (None, ExecutionFailure::Other("MoveAbort".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_record_transfer_failure() {
+        let call = (ObjectID::ZERO, "m".to_string(), "f".to_string());
+        let mut stats = SurfStatistics::default();
+        stats.record_transaction(
+            false,
+            &SuiExecutionStatus::Failure {
+                error: "InsufficientCoinBalance in command 1".to_string(),
+            },
+            vec![call.clone()],
+        );
+        assert_eq!(stats.num_failed_transactions, 1);
+        assert_eq!(stats.num_transfer_failures, 1);
+        let report = &stats.function_reports[&call];
+        assert_eq!(report.num_calls, 1);
+        assert_eq!(report.num_successes, 0);
+        assert!(report.other_failures.is_empty());
+
+        stats.record_transaction(
+            false,
+            &SuiExecutionStatus::Failure {
+                error: "InsufficientCoinBalance in command 0".to_string(),
+            },
+            vec![call.clone()],
+        );
+        assert_eq!(stats.num_transfer_failures, 1);
+        assert_eq!(
+            stats.function_reports[&call].other_failures["InsufficientCoinBalance"],
+            1
+        );
+    }
+}
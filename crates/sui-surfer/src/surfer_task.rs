@@ -15,7 +15,9 @@ use tokio::sync::{watch, RwLock};
 
 use crate::{
     surf_strategy::SurfStrategy,
-    surfer_state::{ImmObjects, OwnedObjects, SharedObjects, SurfStatistics, SurferState},
+    surfer_state::{
+        ImmObjects, OwnedObjects, SharedObjects, StructAbilities, SurfStatistics, SurferState,
+    },
 };
 
 pub struct SurferTask {
@@ -98,6 +100,7 @@ impl SurferTask {
             }
         }
         let entry_functions = Arc::new(RwLock::new(vec![]));
+        let struct_abilities: StructAbilities = Arc::new(RwLock::new(HashMap::new()));
         accounts
             .into_iter()
             .map(|(address, (gas_object, owned_objects))| {
@@ -112,6 +115,7 @@ impl SurferTask {
                     immutable_objects.clone(),
                     shared_objects.clone(),
                     entry_functions.clone(),
+                    struct_abilities.clone(),
                 );
                 SurferTask {
                     state,
@@ -129,6 +133,14 @@ impl SurferTask {
                 .surf_for_a_while(&mut self.state, entry_functions, &self.exit_rcv)
                 .await;
             if self.exit_rcv.has_changed().unwrap() {
+                self.state.stats.discovered_move_functions = self
+                    .state
+                    .entry_functions
+                    .read()
+                    .await
+                    .iter()
+                    .map(|entry| entry.key())
+                    .collect();
                 return self.state.stats;
             }
         }
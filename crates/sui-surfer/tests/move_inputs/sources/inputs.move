@@ -0,0 +1,56 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module move_inputs::inputs {
+    use std::string::{Self, String};
+
+    const EEmptyName: u64 = 0;
+    const ETooManyTags: u64 = 1;
+
+    public struct Item has key, store {
+        id: UID,
+        name: String,
+        tags: vector<u64>,
+        parent: Option<ID>,
+    }
+
+    public struct Receipt has drop {
+        item: ID,
+        count: u64,
+    }
+
+    public fun new_item(
+        name: String,
+        tags: vector<u64>,
+        parent: Option<ID>,
+        ctx: &mut TxContext,
+    ): Item {
+        assert!(!string::is_empty(&name), EEmptyName);
+        assert!(vector::length(&tags) <= 4, ETooManyTags);
+        Item { id: object::new(ctx), name, tags, parent }
+    }
+
+    public fun rename(item: &mut Item, name: String) {
+        assert!(!string::is_empty(&name), EEmptyName);
+        item.name = name;
+    }
+
+    public fun add_tags(item: &mut Item, tags: vector<u64>): Receipt {
+        vector::append(&mut item.tags, tags);
+        assert!(vector::length(&item.tags) <= 8, ETooManyTags);
+        Receipt { item: object::id(item), count: vector::length(&item.tags) }
+    }
+
+    public fun adopt(parent: &Item, child: &mut Item) {
+        child.parent = option::some(object::id(parent));
+    }
+
+    public fun destroy(item: Item) {
+        let Item { id, name: _, tags: _, parent: _ } = item;
+        object::delete(id);
+    }
+
+    public fun keep(item: Item, ctx: &TxContext) {
+        transfer::public_transfer(item, tx_context::sender(ctx));
+    }
+}
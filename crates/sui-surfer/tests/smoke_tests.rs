@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use std::time::Duration;
 use sui_macros::sim_test;
 use sui_surfer::default_surf_strategy::DefaultSurfStrategy;
+use sui_surfer::move_aware_surf_strategy::MoveAwareSurfStrategy;
 
 #[sim_test]
 async fn smoke_test() {
@@ -20,3 +21,23 @@ async fn smoke_test() {
     assert!(results.num_successful_transactions > 0);
     assert!(!results.unique_move_functions_called.is_empty());
 }
+
+#[sim_test]
+async fn move_aware_smoke_test() {
+    // This test makes sure that the move-aware strategy can call functions taking vectors,
+    // strings and options, and functions returning objects.
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.extend(["tests", "move_inputs"]);
+    let results = sui_surfer::run::<MoveAwareSurfStrategy>(
+        Duration::from_secs(30),
+        Duration::from_secs(15),
+        vec![path],
+    )
+    .await;
+    assert!(results.num_successful_transactions > 0);
+    assert!(results
+        .unique_move_functions_called
+        .iter()
+        .any(|(_, _, function)| function == "new_item"));
+    assert!(!results.function_reports.is_empty());
+}
@@ -3,6 +3,7 @@
 
 use crate::{
     check_completed_snapshot,
+    consensus_dag::{dump_consensus_dag, DagOutputFormat},
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
     download_db_snapshot, download_formal_snapshot, dump_checkpoints_from_archive,
     get_latest_available_epoch, get_object, get_transaction_block, make_clients, pkg_dump,
@@ -140,6 +141,36 @@ pub enum ToolCommand {
         cmd: Option<DbToolCommand>,
     },
 
+    /// Dump the consensus DAG (blocks, commits, committed leaders and per-authority round
+    /// progress) from a Mysticeti consensus db. The db is opened as a RocksDB secondary
+    /// instance, so this can run against the db of a live node.
+    #[command(name = "dump-consensus-dag")]
+    DumpConsensusDag {
+        /// Path of the consensus db for one epoch, i.e. `<consensus db-path>/<epoch>`
+        #[arg(long = "db-path")]
+        db_path: PathBuf,
+        /// Directory for the files of the secondary instance. Defaults to `SECONDARY`
+        /// next to the db path.
+        #[arg(long = "secondary-path")]
+        secondary_path: Option<PathBuf>,
+        /// First round to dump. Defaults to 50 rounds before the last committed round.
+        #[arg(long = "start-round")]
+        start_round: Option<u32>,
+        /// Last round to dump (inclusive). Defaults to 50 rounds after the last committed round.
+        #[arg(long = "end-round")]
+        end_round: Option<u32>,
+        #[arg(
+            value_enum,
+            long = "format",
+            default_value = "text",
+            ignore_case = true
+        )]
+        format: DagOutputFormat,
+        /// File to write the output to. Prints to stdout if not set.
+        #[arg(long = "output")]
+        output: Option<PathBuf>,
+    },
+
     /// Tool to verify the archive store
     #[command(name = "verify-archive")]
     VerifyArchive {
@@ -529,6 +560,23 @@ impl ToolCommand {
                     None => print_db_all_tables(path)?,
                 }
             }
+            ToolCommand::DumpConsensusDag {
+                db_path,
+                secondary_path,
+                start_round,
+                end_round,
+                format,
+                output,
+            } => {
+                dump_consensus_dag(
+                    db_path,
+                    secondary_path,
+                    start_round,
+                    end_round,
+                    format,
+                    output,
+                )?;
+            }
             ToolCommand::DumpPackages {
                 db_url,
                 output_dir,
@@ -0,0 +1,134 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use clap::ValueEnum;
+use consensus_core::{DagInspector, DagSnapshot};
+use tracing::info;
+
+/// Number of rounds dumped when no explicit start round is given.
+const DEFAULT_NUM_ROUNDS: u32 = 50;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum DagOutputFormat {
+    /// Human readable summary of blocks, commits and per-authority progress.
+    Text,
+    Json,
+    /// Graphviz graph, e.g. render with `dot -Tsvg dag.dot -o dag.svg`.
+    Dot,
+}
+
+pub(crate) fn dump_consensus_dag(
+    db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
+    start_round: Option<u32>,
+    end_round: Option<u32>,
+    format: DagOutputFormat,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let inspector = DagInspector::open_rocksdb(&db_path, secondary_path.as_deref())
+        .map_err(|e| anyhow!("Failed to open consensus db at {}: {e}", db_path.display()))?;
+
+    let last_committed_round = inspector
+        .last_committed_round()
+        .map_err(|e| anyhow!("Failed to read last commit: {e}"))?;
+    // Without explicit bounds, dump the rounds around the last commit, which is where a
+    // stalled authority is usually stuck, or the first rounds when nothing is committed yet.
+    let end_round = end_round.unwrap_or_else(|| {
+        last_committed_round
+            .unwrap_or_default()
+            .saturating_add(DEFAULT_NUM_ROUNDS)
+    });
+    let start_round = start_round.unwrap_or_else(|| {
+        last_committed_round.map_or(0, |round| round.saturating_sub(DEFAULT_NUM_ROUNDS))
+    });
+    ensure!(
+        start_round <= end_round,
+        "Start round {start_round} is after end round {end_round}"
+    );
+    info!(
+        "Reading consensus DAG from rounds {start_round} to {end_round}, last committed round: {last_committed_round:?}"
+    );
+
+    let snapshot = inspector
+        .snapshot(start_round, end_round)
+        .map_err(|e| anyhow!("Failed to read consensus DAG: {e}"))?;
+    let content = match format {
+        DagOutputFormat::Text => format_text(&snapshot),
+        DagOutputFormat::Json => serde_json::to_string_pretty(&snapshot)?,
+        DagOutputFormat::Dot => snapshot.to_dot(),
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(&path, content)
+                .with_context(|| format!("Failed to write to {}", path.display()))?;
+            info!("Wrote consensus DAG to {}", path.display());
+        }
+        None => println!("{content}"),
+    }
+    Ok(())
+}
+
+fn format_text(snapshot: &DagSnapshot) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "Rounds {} to {}: {} blocks, {} commits\n",
+        snapshot.start_round,
+        snapshot.end_round,
+        snapshot.blocks.len(),
+        snapshot.commits.len()
+    ));
+
+    out.push_str("\nAuthority progress:\n");
+    for progress in &snapshot.authority_progress {
+        out.push_str(&format!(
+            "  {}: {} blocks, rounds {}..={}, committed leaders {}, missing rounds {:?}, equivocating rounds {:?}\n",
+            progress.authority,
+            progress.num_blocks,
+            progress.lowest_round,
+            progress.highest_round,
+            progress.committed_leaders,
+            progress.missing_rounds,
+            progress.equivocating_rounds,
+        ));
+    }
+
+    out.push_str("\nCommits:\n");
+    for commit in &snapshot.commits {
+        out.push_str(&format!(
+            "  C{} leader {} at {}ms, {} blocks\n",
+            commit.index,
+            commit.leader,
+            commit.timestamp_ms,
+            commit.blocks.len()
+        ));
+    }
+
+    out.push_str("\nBlocks:\n");
+    for block in &snapshot.blocks {
+        let status = match (block.is_committed_leader, block.committed_in) {
+            (true, Some(index)) => format!("leader of C{index}"),
+            (false, Some(index)) => format!("committed in C{index}"),
+            _ => "uncommitted".to_string(),
+        };
+        out.push_str(&format!(
+            "  {} at {}ms, {} txs, {} ancestors, {}\n",
+            block.reference,
+            block.timestamp_ms,
+            block.num_transactions,
+            block.ancestors.len(),
+            status
+        ));
+    }
+
+    if let Some(scores) = &snapshot.last_reputation_scores {
+        out.push_str(&format!(
+            "\nReputation scores for commits {}..{}: {:?}\n",
+            scores.commit_range.0, scores.commit_range.1, scores.scores_per_authority
+        ));
+    }
+    out
+}
@@ -66,6 +66,7 @@ use tracing::info;
 use typed_store::rocks::MetricConf;
 
 pub mod commands;
+pub mod consensus_dag;
 pub mod db_tool;
 pub mod pkg_dump;
 
@@ -0,0 +1,303 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use sui_types::base_types::{ObjectID, ObjectRef};
+
+/// Maximum number of coins a transaction can pay for gas with, matching the
+/// `max_gas_payment_objects` protocol config.
+pub const MAX_GAS_PAYMENT_OBJECTS: usize = 256;
+
+/// A gas coin owned by the gas payer, along with its balance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasCoinInfo {
+    pub object_ref: ObjectRef,
+    pub balance: u64,
+}
+
+impl GasCoinInfo {
+    pub fn id(&self) -> ObjectID {
+        self.object_ref.0
+    }
+}
+
+/// Picks the gas coins paying for a transaction.
+pub trait CoinSelectionStrategy: Send + Sync {
+    /// Selects coins out of `candidates` with a total balance of at least `gas_budget`, or returns
+    /// `None` if there is no such selection. When several coins are returned they are all used as
+    /// gas payment, and are merged into the first one when the transaction executes.
+    fn select(&self, candidates: &[GasCoinInfo], gas_budget: u64) -> Option<Vec<ObjectRef>>;
+
+    /// Whether more candidates can't change the selection out of `candidates`, so that callers
+    /// can stop looking up the balances of the other coins of the gas payer. By default all the
+    /// coins are looked up.
+    fn is_final(&self, _candidates: &[GasCoinInfo], _gas_budget: u64) -> bool {
+        false
+    }
+}
+
+/// Picks the first coin which can cover the gas budget on its own.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FirstSufficient;
+
+impl CoinSelectionStrategy for FirstSufficient {
+    fn select(&self, candidates: &[GasCoinInfo], gas_budget: u64) -> Option<Vec<ObjectRef>> {
+        candidates
+            .iter()
+            .find(|coin| coin.balance >= gas_budget)
+            .map(|coin| vec![coin.object_ref])
+    }
+
+    fn is_final(&self, candidates: &[GasCoinInfo], gas_budget: u64) -> bool {
+        candidates.iter().any(|coin| coin.balance >= gas_budget)
+    }
+}
+
+/// Picks the coins with the largest balances until they cover the gas budget, so that a budget
+/// no single coin can cover can still be paid for.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LargestFirst;
+
+impl CoinSelectionStrategy for LargestFirst {
+    fn select(&self, candidates: &[GasCoinInfo], gas_budget: u64) -> Option<Vec<ObjectRef>> {
+        let mut sorted: Vec<_> = candidates.iter().collect();
+        sorted.sort_by_key(|coin| std::cmp::Reverse(coin.balance));
+        let mut selected = vec![];
+        let mut total: u64 = 0;
+        for coin in sorted.into_iter().take(MAX_GAS_PAYMENT_OBJECTS) {
+            selected.push(coin.object_ref);
+            total = total.saturating_add(coin.balance);
+            if total >= gas_budget {
+                return Some(selected);
+            }
+        }
+        None
+    }
+}
+
+/// Picks the coin with the smallest balance which can cover the gas budget on its own, keeping
+/// large coins available for other transactions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SmallestSufficient;
+
+impl CoinSelectionStrategy for SmallestSufficient {
+    fn select(&self, candidates: &[GasCoinInfo], gas_budget: u64) -> Option<Vec<ObjectRef>> {
+        candidates
+            .iter()
+            .filter(|coin| coin.balance >= gas_budget)
+            .min_by_key(|coin| coin.balance)
+            .map(|coin| vec![coin.object_ref])
+    }
+}
+
+/// Set of coins which are in use by transactions in flight, and which must not be used to pay for
+/// another transaction until that transaction is done.
+#[derive(Clone, Debug, Default)]
+pub struct GasCoinLocks(Arc<Mutex<HashSet<ObjectID>>>);
+
+impl GasCoinLocks {
+    /// Locks `coin`, returning false if it was already locked.
+    pub fn lock(&self, coin: ObjectID) -> bool {
+        self.0.lock().unwrap().insert(coin)
+    }
+
+    pub fn unlock(&self, coin: &ObjectID) {
+        self.0.lock().unwrap().remove(coin);
+    }
+
+    pub fn is_locked(&self, coin: &ObjectID) -> bool {
+        self.0.lock().unwrap().contains(coin)
+    }
+}
+
+/// Never picks coins which are locked, delegating the selection among the other coins.
+pub struct AvoidLocked<S> {
+    inner: S,
+    locks: GasCoinLocks,
+}
+
+impl<S> AvoidLocked<S> {
+    pub fn new(inner: S, locks: GasCoinLocks) -> Self {
+        Self { inner, locks }
+    }
+
+    fn unlocked(&self, candidates: &[GasCoinInfo]) -> Vec<GasCoinInfo> {
+        candidates
+            .iter()
+            .filter(|coin| !self.locks.is_locked(&coin.id()))
+            .copied()
+            .collect()
+    }
+}
+
+impl<S: CoinSelectionStrategy> CoinSelectionStrategy for AvoidLocked<S> {
+    fn select(&self, candidates: &[GasCoinInfo], gas_budget: u64) -> Option<Vec<ObjectRef>> {
+        self.inner.select(&self.unlocked(candidates), gas_budget)
+    }
+
+    fn is_final(&self, candidates: &[GasCoinInfo], gas_budget: u64) -> bool {
+        self.inner.is_final(&self.unlocked(candidates), gas_budget)
+    }
+}
+
+/// Adds coins with a balance below a threshold to the coins picked by another strategy. Those
+/// get merged into the first gas coin when the transaction executes, which keeps the number of
+/// coins owned by an address in check.
+pub struct MergeDust<S> {
+    inner: S,
+    dust_threshold: u64,
+    max_dust_coins: usize,
+}
+
+impl<S> MergeDust<S> {
+    pub fn new(inner: S, dust_threshold: u64, max_dust_coins: usize) -> Self {
+        Self {
+            inner,
+            dust_threshold,
+            max_dust_coins,
+        }
+    }
+
+    fn dust<'a>(
+        &'a self,
+        candidates: &'a [GasCoinInfo],
+        selected: &'a [ObjectRef],
+    ) -> impl Iterator<Item = &'a GasCoinInfo> {
+        candidates
+            .iter()
+            .filter(|coin| coin.balance < self.dust_threshold)
+            .filter(|coin| !selected.contains(&coin.object_ref))
+            .take(self.max_dust_coins(selected.len()))
+    }
+
+    fn max_dust_coins(&self, num_selected: usize) -> usize {
+        self.max_dust_coins
+            .min(MAX_GAS_PAYMENT_OBJECTS.saturating_sub(num_selected))
+    }
+}
+
+impl<S: CoinSelectionStrategy> CoinSelectionStrategy for MergeDust<S> {
+    fn select(&self, candidates: &[GasCoinInfo], gas_budget: u64) -> Option<Vec<ObjectRef>> {
+        let mut selected = self.inner.select(candidates, gas_budget)?;
+        let dust: Vec<_> = self
+            .dust(candidates, &selected)
+            .map(|coin| coin.object_ref)
+            .collect();
+        selected.extend(dust);
+        Some(selected)
+    }
+
+    /// Final once the inner selection is, and as many dust coins as can be merged were found.
+    fn is_final(&self, candidates: &[GasCoinInfo], gas_budget: u64) -> bool {
+        if !self.inner.is_final(candidates, gas_budget) {
+            return false;
+        }
+        let Some(selected) = self.inner.select(candidates, gas_budget) else {
+            return false;
+        };
+        self.dust(candidates, &selected).count() == self.max_dust_coins(selected.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::{ObjectDigest, SequenceNumber};
+
+    fn coins(balances: &[u64]) -> Vec<GasCoinInfo> {
+        balances
+            .iter()
+            .map(|balance| GasCoinInfo {
+                object_ref: (
+                    ObjectID::random(),
+                    SequenceNumber::new(),
+                    ObjectDigest::random(),
+                ),
+                balance: *balance,
+            })
+            .collect()
+    }
+
+    fn refs(coins: &[GasCoinInfo], indexes: &[usize]) -> Vec<ObjectRef> {
+        indexes.iter().map(|i| coins[*i].object_ref).collect()
+    }
+
+    #[test]
+    fn test_single_coin_strategies() {
+        let candidates = coins(&[50, 500, 200, 1_000]);
+        assert_eq!(
+            FirstSufficient.select(&candidates, 100),
+            Some(refs(&candidates, &[1]))
+        );
+        assert_eq!(
+            SmallestSufficient.select(&candidates, 100),
+            Some(refs(&candidates, &[2]))
+        );
+        assert_eq!(FirstSufficient.select(&candidates, 2_000), None);
+        assert_eq!(SmallestSufficient.select(&candidates, 2_000), None);
+    }
+
+    #[test]
+    fn test_largest_first() {
+        let candidates = coins(&[50, 500, 200, 1_000]);
+        assert_eq!(
+            LargestFirst.select(&candidates, 100),
+            Some(refs(&candidates, &[3]))
+        );
+        assert_eq!(
+            LargestFirst.select(&candidates, 1_600),
+            Some(refs(&candidates, &[3, 1, 2]))
+        );
+        assert_eq!(LargestFirst.select(&candidates, 2_000), None);
+    }
+
+    #[test]
+    fn test_avoid_locked() {
+        let candidates = coins(&[50, 500, 200, 1_000]);
+        let locks = GasCoinLocks::default();
+        let strategy = AvoidLocked::new(SmallestSufficient, locks.clone());
+        assert!(locks.lock(candidates[2].id()));
+        assert!(!locks.lock(candidates[2].id()));
+        assert_eq!(
+            strategy.select(&candidates, 100),
+            Some(refs(&candidates, &[1]))
+        );
+        locks.unlock(&candidates[2].id());
+        assert_eq!(
+            strategy.select(&candidates, 100),
+            Some(refs(&candidates, &[2]))
+        );
+    }
+
+    #[test]
+    fn test_merge_dust() {
+        let candidates = coins(&[5, 500, 10, 1_000, 1]);
+        let strategy = MergeDust::new(SmallestSufficient, 20, 2);
+        assert_eq!(
+            strategy.select(&candidates, 100),
+            Some(refs(&candidates, &[1, 0, 2]))
+        );
+        // Dust coins are only added on top of a valid selection
+        assert_eq!(strategy.select(&candidates, 2_000), None);
+    }
+
+    #[test]
+    fn test_is_final() {
+        let candidates = coins(&[50, 500, 10]);
+        // The first sufficient coin can't be displaced by coins found later
+        assert!(FirstSufficient.is_final(&candidates, 100));
+        assert!(!FirstSufficient.is_final(&candidates, 1_000));
+        // A smaller sufficient coin may still be found
+        assert!(!SmallestSufficient.is_final(&candidates, 100));
+
+        let locks = GasCoinLocks::default();
+        locks.lock(candidates[1].id());
+        assert!(!AvoidLocked::new(FirstSufficient, locks).is_final(&candidates, 100));
+
+        // Dust merging is final once enough dust coins were found
+        assert!(MergeDust::new(FirstSufficient, 100, 2).is_final(&candidates, 100));
+        assert!(!MergeDust::new(FirstSufficient, 100, 3).is_final(&candidates, 100));
+    }
+}
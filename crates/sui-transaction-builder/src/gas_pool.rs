@@ -0,0 +1,286 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
+
+use crate::coin_selection::{CoinSelectionStrategy, GasCoinInfo, GasCoinLocks};
+use crate::{get_gas_coins, DataReader};
+
+/// Hands out the gas coins of an address to transactions built concurrently, making sure that no
+/// two transactions in flight pay for gas with the same coin. Using the same coin in concurrent
+/// transactions leads to one of them failing, or to the coin being locked until the end of the
+/// epoch if the transactions equivocate.
+///
+/// Coins are leased with [`GasCoinPool::acquire`] and stay locked until the lease is released.
+#[derive(Clone)]
+pub struct GasCoinPool {
+    owner: SuiAddress,
+    strategy: Arc<dyn CoinSelectionStrategy>,
+    coins: Arc<Mutex<PoolCoins>>,
+    locks: GasCoinLocks,
+}
+
+#[derive(Default)]
+struct PoolCoins {
+    coins: BTreeMap<ObjectID, GasCoinInfo>,
+    /// Newest version seen of each coin, including the coins merged away by the transactions of
+    /// the pool, so that stale reads don't bring back old versions.
+    versions: BTreeMap<ObjectID, SequenceNumber>,
+}
+
+impl PoolCoins {
+    /// Adds `coin` unless a newer version of it was seen, or it was spent at this version.
+    fn insert(&mut self, coin: GasCoinInfo) {
+        let (id, version, _) = coin.object_ref;
+        let is_stale = self.versions.get(&id).is_some_and(|seen| {
+            *seen > version || (*seen == version && !self.coins.contains_key(&id))
+        });
+        if !is_stale {
+            self.versions.insert(id, version);
+            self.coins.insert(id, coin);
+        }
+    }
+}
+
+impl GasCoinPool {
+    pub fn new(owner: SuiAddress, strategy: Arc<dyn CoinSelectionStrategy>) -> Self {
+        Self {
+            owner,
+            strategy,
+            coins: Default::default(),
+            locks: Default::default(),
+        }
+    }
+
+    /// Address owning the gas coins of this pool, which pays for the gas of its transactions.
+    pub fn owner(&self) -> SuiAddress {
+        self.owner
+    }
+
+    /// Coins currently leased to transactions in flight.
+    pub fn locks(&self) -> GasCoinLocks {
+        self.locks.clone()
+    }
+
+    /// Number of coins in the pool, whether they are leased or not.
+    pub fn len(&self) -> usize {
+        self.coins.lock().unwrap().coins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Loads the gas coins of the owner. Coins which are leased are left as they are until their
+    /// lease is released, and coins keep the newest version seen, as the transactions of the pool
+    /// may not be visible to `data_reader` yet.
+    pub async fn refresh(
+        &self,
+        data_reader: &(dyn DataReader + Sync + Send),
+    ) -> anyhow::Result<()> {
+        let fetched: BTreeMap<_, _> = get_gas_coins(data_reader, self.owner, &[], |_| false)
+            .await?
+            .into_iter()
+            .map(|coin| (coin.id(), coin))
+            .collect();
+        let mut pool_coins = self.coins.lock().unwrap();
+        let PoolCoins { coins, versions } = &mut *pool_coins;
+        coins.retain(|id, _| self.locks.is_locked(id) || fetched.contains_key(id));
+        // Coins which are no longer listed can't come back from a stale read
+        versions.retain(|id, _| coins.contains_key(id) || fetched.contains_key(id));
+        for (id, coin) in fetched {
+            if !self.locks.is_locked(&id) {
+                pool_coins.insert(coin);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a coin to the pool, or updates its version and balance if it's already there. Older
+    /// versions than the one in the pool are ignored.
+    pub fn insert(&self, coin: GasCoinInfo) {
+        self.coins.lock().unwrap().insert(coin);
+    }
+
+    /// Leases coins covering `gas_budget`, none of which is leased already or in `exclude`.
+    pub fn acquire(&self, gas_budget: u64, exclude: &[ObjectID]) -> anyhow::Result<GasCoinLease> {
+        // Holding the lock on the coins while locking the selected ones prevents concurrent calls
+        // from picking the same coins.
+        let pool_coins = self.coins.lock().unwrap();
+        let coins = &pool_coins.coins;
+        let candidates: Vec<_> = coins
+            .values()
+            .filter(|coin| !exclude.contains(&coin.id()) && !self.locks.is_locked(&coin.id()))
+            .copied()
+            .collect();
+        let selected = self
+            .strategy
+            .select(&candidates, gas_budget)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Cannot find available gas coins owned by {} for the gas budget {gas_budget}, \
+                     {} of {} coins are leased or excluded",
+                    self.owner,
+                    coins.len() - candidates.len(),
+                    coins.len()
+                )
+            })?;
+        for coin in &selected {
+            self.locks.lock(coin.0);
+        }
+        Ok(GasCoinLease {
+            pool: self.clone(),
+            coins: selected,
+            released: false,
+        })
+    }
+
+    fn release(&self, coins: &[ObjectRef], gas_coin: Option<GasCoinInfo>) {
+        let mut pool_coins = self.coins.lock().unwrap();
+        for coin in coins {
+            pool_coins.coins.remove(&coin.0);
+            self.locks.unlock(&coin.0);
+            // The coins of a lease which was dropped may not have been used, so they can come
+            // back with the next refresh
+            if gas_coin.is_none() {
+                pool_coins.versions.remove(&coin.0);
+            }
+        }
+        if let Some(gas_coin) = gas_coin {
+            pool_coins.insert(gas_coin);
+        }
+    }
+}
+
+/// Gas coins leased from a [`GasCoinPool`] to a single transaction.
+///
+/// Once the transaction is executed, the lease should be released with the new version and
+/// balance of the gas coin. A lease which is dropped without being released removes its coins
+/// from the pool, until the next [`GasCoinPool::refresh`].
+pub struct GasCoinLease {
+    pool: GasCoinPool,
+    coins: Vec<ObjectRef>,
+    released: bool,
+}
+
+impl GasCoinLease {
+    /// Coins to pay for gas with. The first one is the gas coin the others get merged into.
+    pub fn coins(&self) -> &[ObjectRef] {
+        &self.coins
+    }
+
+    /// Returns the gas coin to the pool, with its version and balance after the transaction was
+    /// executed. The other coins were merged into it and no longer exist.
+    pub fn release(mut self, gas_coin: ObjectRef, balance: u64) {
+        self.released = true;
+        self.pool.release(
+            &self.coins,
+            Some(GasCoinInfo {
+                object_ref: gas_coin,
+                balance,
+            }),
+        );
+    }
+}
+
+impl Drop for GasCoinLease {
+    fn drop(&mut self) {
+        if !self.released {
+            self.pool.release(&self.coins, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coin_selection::SmallestSufficient;
+    use sui_types::base_types::{ObjectDigest, SequenceNumber};
+
+    fn coin(balance: u64) -> GasCoinInfo {
+        GasCoinInfo {
+            object_ref: (
+                ObjectID::random(),
+                SequenceNumber::new(),
+                ObjectDigest::random(),
+            ),
+            balance,
+        }
+    }
+
+    #[test]
+    fn test_concurrent_leases_do_not_share_coins() {
+        let pool = GasCoinPool::new(
+            SuiAddress::random_for_testing_only(),
+            Arc::new(SmallestSufficient),
+        );
+        let (small, large) = (coin(100), coin(1_000));
+        pool.insert(small);
+        pool.insert(large);
+
+        let first = pool.acquire(50, &[]).unwrap();
+        assert_eq!(first.coins(), &[small.object_ref]);
+        let second = pool.acquire(50, &[]).unwrap();
+        assert_eq!(second.coins(), &[large.object_ref]);
+        assert!(pool.acquire(50, &[]).is_err());
+
+        // Releasing a lease makes the new version of its gas coin available again
+        let new_version = (
+            small.id(),
+            SequenceNumber::from_u64(1),
+            ObjectDigest::random(),
+        );
+        first.release(new_version, 80);
+        let third = pool.acquire(50, &[]).unwrap();
+        assert_eq!(third.coins(), &[new_version]);
+
+        // Dropping a lease forgets about its coins
+        drop(second);
+        assert_eq!(pool.len(), 1);
+        assert!(pool.acquire(50, &[small.id()]).is_err());
+    }
+
+    #[test]
+    fn test_stale_coins_are_ignored() {
+        let pool = GasCoinPool::new(
+            SuiAddress::random_for_testing_only(),
+            Arc::new(SmallestSufficient),
+        );
+        let (gas, merged) = (coin(100), coin(100));
+        pool.insert(gas);
+        pool.insert(merged);
+
+        let lease = pool.acquire(150, &[]).unwrap();
+        assert_eq!(lease.coins().len(), 2);
+        let gas_ref = lease.coins()[0];
+        let merged_ref = lease.coins()[1];
+        let new_version = (
+            gas_ref.0,
+            SequenceNumber::from_u64(1),
+            ObjectDigest::random(),
+        );
+        lease.release(new_version, 180);
+
+        // Reads which don't include the transaction yet don't bring back the old versions
+        for object_ref in [gas_ref, merged_ref] {
+            pool.insert(GasCoinInfo {
+                object_ref,
+                balance: 100,
+            });
+        }
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.acquire(150, &[]).unwrap().coins(), &[new_version]);
+
+        // The coins of a dropped lease come back with the next read
+        assert_eq!(pool.len(), 0);
+        pool.insert(GasCoinInfo {
+            object_ref: new_version,
+            balance: 180,
+        });
+        assert_eq!(pool.len(), 1);
+    }
+}
@@ -34,6 +34,12 @@ use sui_types::transaction::{
 };
 use sui_types::{coin, fp_ensure, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
 
+use crate::coin_selection::{CoinSelectionStrategy, FirstSufficient, GasCoinInfo};
+use crate::gas_pool::{GasCoinLease, GasCoinPool};
+
+pub mod coin_selection;
+pub mod gas_pool;
+
 #[async_trait]
 pub trait DataReader {
     async fn get_owned_objects(
@@ -51,12 +57,88 @@ pub trait DataReader {
     async fn get_reference_gas_price(&self) -> Result<u64, anyhow::Error>;
 }
 
+/// Number of gas coins fetched at once when looking for gas coins.
+const GAS_COIN_PAGE_SIZE: usize = 50;
+
+/// Fetches the gas coins owned by `owner` which aren't in `exclude`, along with their balances.
+/// Coins are fetched a page at a time, and fetching stops early once `is_done` returns true for
+/// the coins fetched so far.
+pub(crate) async fn get_gas_coins(
+    data_reader: &(dyn DataReader + Sync + Send),
+    owner: SuiAddress,
+    exclude: &[ObjectID],
+    is_done: impl Fn(&[GasCoinInfo]) -> bool,
+) -> Result<Vec<GasCoinInfo>, anyhow::Error> {
+    let gas_objs: Vec<_> = data_reader
+        .get_owned_objects(owner, GasCoin::type_())
+        .await?
+        .into_iter()
+        .filter(|obj| !exclude.contains(&obj.object_id))
+        .collect();
+    let mut coins = vec![];
+    for page in gas_objs.chunks(GAS_COIN_PAGE_SIZE) {
+        let responses = join_all(page.iter().map(|obj| {
+            data_reader
+                .get_object_with_options(obj.object_id, SuiObjectDataOptions::new().with_bcs())
+        }))
+        .await;
+        for response in responses {
+            let obj = response?.into_object()?;
+            let gas: GasCoin = bcs::from_bytes(
+                &obj.bcs
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("bcs field is unexpectedly empty"))?
+                    .try_as_move()
+                    .ok_or_else(|| anyhow!("Cannot parse move object to gas object"))?
+                    .bcs_bytes,
+            )?;
+            coins.push(GasCoinInfo {
+                object_ref: obj.object_ref(),
+                balance: gas.value(),
+            });
+        }
+        if is_done(&coins) {
+            break;
+        }
+    }
+    Ok(coins)
+}
+
+fn check_gas_budget(gas_budget: u64, gas_price: u64) -> Result<(), anyhow::Error> {
+    if gas_budget < gas_price {
+        bail!("Gas budget {gas_budget} is less than the reference gas price {gas_price}. The gas budget must be at least the current reference gas price of {gas_price}.")
+    }
+    Ok(())
+}
+
+/// Ids of the owned objects a transaction takes as input, which can't also pay for its gas.
+fn owned_input_objects(kind: &TransactionKind) -> Result<Vec<ObjectID>, anyhow::Error> {
+    Ok(kind
+        .input_objects()?
+        .iter()
+        .flat_map(|obj| match obj {
+            InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
+            _ => None,
+        })
+        .collect())
+}
+
 #[derive(Clone)]
-pub struct TransactionBuilder(Arc<dyn DataReader + Sync + Send>);
+pub struct TransactionBuilder(
+    Arc<dyn DataReader + Sync + Send>,
+    Arc<dyn CoinSelectionStrategy>,
+);
 
 impl TransactionBuilder {
     pub fn new(data_reader: Arc<dyn DataReader + Sync + Send>) -> Self {
-        Self(data_reader)
+        Self(data_reader, Arc::new(FirstSufficient))
+    }
+
+    /// Sets the strategy used to pick gas coins when none are provided. Defaults to
+    /// [`FirstSufficient`].
+    pub fn with_coin_selection(mut self, coin_selection: Arc<dyn CoinSelectionStrategy>) -> Self {
+        self.1 = coin_selection;
+        self
     }
 
     async fn select_gas(
@@ -66,34 +148,18 @@ impl TransactionBuilder {
         gas_budget: u64,
         input_objects: Vec<ObjectID>,
         gas_price: u64,
-    ) -> Result<ObjectRef, anyhow::Error> {
-        if gas_budget < gas_price {
-            bail!("Gas budget {gas_budget} is less than the reference gas price {gas_price}. The gas budget must be at least the current reference gas price of {gas_price}.")
-        }
+    ) -> Result<Vec<ObjectRef>, anyhow::Error> {
+        check_gas_budget(gas_budget, gas_price)?;
         if let Some(gas) = input_gas {
-            self.get_object_ref(gas).await
+            Ok(vec![self.get_object_ref(gas).await?])
         } else {
-            let gas_objs = self.0.get_owned_objects(signer, GasCoin::type_()).await?;
-
-            for obj in gas_objs {
-                let response = self
-                    .0
-                    .get_object_with_options(obj.object_id, SuiObjectDataOptions::new().with_bcs())
-                    .await?;
-                let obj = response.object()?;
-                let gas: GasCoin = bcs::from_bytes(
-                    &obj.bcs
-                        .as_ref()
-                        .ok_or_else(|| anyhow!("bcs field is unexpectedly empty"))?
-                        .try_as_move()
-                        .ok_or_else(|| anyhow!("Cannot parse move object to gas object"))?
-                        .bcs_bytes,
-                )?;
-                if !input_objects.contains(&obj.object_id) && gas.value() >= gas_budget {
-                    return Ok(obj.object_ref());
-                }
-            }
-            Err(anyhow!("Cannot find gas coin for signer address {signer} with amount sufficient for the required gas budget {gas_budget}. If you are using the pay or transfer commands, you can use pay-sui or transfer-sui commands instead, which will use the only object as gas payment."))
+            let candidates = get_gas_coins(self.0.as_ref(), signer, &input_objects, |coins| {
+                self.1.is_final(coins, gas_budget)
+            })
+            .await?;
+            self.1
+                .select(&candidates, gas_budget)
+                .ok_or_else(|| anyhow!("Cannot find gas coin for signer address {signer} with amount sufficient for the required gas budget {gas_budget}. If you are using the pay or transfer commands, you can use pay-sui or transfer-sui commands instead, which will use the only object as gas payment."))
         }
     }
 
@@ -123,8 +189,8 @@ impl TransactionBuilder {
     }
 
     /// Construct the transaction data from a transaction kind, and other parameters.
-    /// If the gas_payment list is empty, it will pick the first gas coin that has at least
-    /// the required gas budget that is not in the input coins.
+    /// If the gas_payment list is empty, gas coins that are not in the input coins are picked
+    /// with the coin selection strategy of the builder.
     pub async fn tx_data(
         &self,
         sender: SuiAddress,
@@ -135,18 +201,9 @@ impl TransactionBuilder {
         gas_sponsor: Option<SuiAddress>,
     ) -> Result<TransactionData, anyhow::Error> {
         let gas_payment = if gas_payment.is_empty() {
-            let input_objs = kind
-                .input_objects()?
-                .iter()
-                .flat_map(|obj| match obj {
-                    InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
-                    _ => None,
-                })
-                .collect();
-            vec![
-                self.select_gas(sender, None, gas_budget, input_objs, gas_price)
-                    .await?,
-            ]
+            let input_objs = owned_input_objects(&kind)?;
+            self.select_gas(sender, None, gas_budget, input_objs, gas_price)
+                .await?
         } else {
             self.input_refs(&gas_payment).await?
         };
@@ -160,6 +217,31 @@ impl TransactionBuilder {
         ))
     }
 
+    /// Construct the transaction data from a transaction kind, paying for gas with coins leased
+    /// from `pool`. The coins stay leased until the returned lease is released or dropped, so
+    /// that transactions built concurrently from the same pool never share a gas coin.
+    pub fn tx_data_with_gas_pool(
+        &self,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        gas_budget: u64,
+        gas_price: u64,
+        pool: &GasCoinPool,
+    ) -> Result<(TransactionData, GasCoinLease), anyhow::Error> {
+        check_gas_budget(gas_budget, gas_price)?;
+        let input_objs = owned_input_objects(&kind)?;
+        let lease = pool.acquire(gas_budget, &input_objs)?;
+        let tx_data = TransactionData::new_with_gas_coins_allow_sponsor(
+            kind,
+            sender,
+            lease.coins().to_vec(),
+            gas_budget,
+            gas_price,
+            pool.owner(),
+        );
+        Ok((tx_data, lease))
+    }
+
     pub async fn transfer_object_tx_kind(
         &self,
         object_id: ObjectID,
@@ -187,7 +269,7 @@ impl TransactionBuilder {
             .select_gas(signer, gas, gas_budget, vec![object_id], gas_price)
             .await?;
 
-        Ok(TransactionData::new(
+        Ok(TransactionData::new_with_gas_coins(
             TransactionKind::programmable(builder.finish()),
             signer,
             gas,
@@ -265,9 +347,14 @@ impl TransactionBuilder {
             .select_gas(signer, gas, gas_budget, input_coins, gas_price)
             .await?;
 
-        TransactionData::new_pay(
-            signer, coin_refs, recipients, amounts, gas, gas_budget, gas_price,
-        )
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.pay(coin_refs, recipients, amounts)?;
+            builder.finish()
+        };
+        Ok(TransactionData::new_programmable(
+            signer, gas, pt, gas_budget, gas_price,
+        ))
     }
 
     /// Get the object references for a list of object IDs
@@ -419,7 +506,7 @@ impl TransactionBuilder {
             .select_gas(signer, gas, gas_budget, input_objects, gas_price)
             .await?;
 
-        Ok(TransactionData::new(
+        Ok(TransactionData::new_with_gas_coins(
             TransactionKind::programmable(pt),
             signer,
             gas,
@@ -597,13 +684,11 @@ impl TransactionBuilder {
         let gas = self
             .select_gas(sender, gas, gas_budget, vec![], gas_price)
             .await?;
-        Ok(TransactionData::new_module(
-            sender,
-            gas,
-            compiled_modules,
-            dep_ids,
-            gas_budget,
-            gas_price,
+        let kind = self
+            .publish_tx_kind(sender, compiled_modules, dep_ids)
+            .await?;
+        Ok(TransactionData::new_with_gas_coins(
+            kind, sender, gas, gas_budget, gas_price,
         ))
     }
 
@@ -688,26 +773,19 @@ impl TransactionBuilder {
         let gas = self
             .select_gas(sender, gas, gas_budget, vec![], gas_price)
             .await?;
-        let upgrade_cap = self
-            .0
-            .get_object_with_options(upgrade_capability, SuiObjectDataOptions::new().with_owner())
-            .await?
-            .into_object()?;
-        let cap_owner = upgrade_cap
-            .owner
-            .ok_or_else(|| anyhow!("Unable to determine ownership of upgrade capability"))?;
-        TransactionData::new_upgrade(
-            sender,
-            gas,
-            package_id,
-            compiled_modules,
-            dep_ids,
-            (upgrade_cap.object_ref(), cap_owner),
-            upgrade_policy,
-            digest,
-            gas_budget,
-            gas_price,
-        )
+        let kind = self
+            .upgrade_tx_kind(
+                package_id,
+                compiled_modules,
+                dep_ids,
+                upgrade_capability,
+                upgrade_policy,
+                digest,
+            )
+            .await?;
+        Ok(TransactionData::new_with_gas_coins(
+            kind, sender, gas, gas_budget, gas_price,
+        ))
     }
 
     /// Construct a transaction kind for the SplitCoin transaction type
@@ -781,7 +859,7 @@ impl TransactionBuilder {
             .select_gas(signer, gas, gas_budget, vec![coin_object_id], gas_price)
             .await?;
 
-        TransactionData::new_move_call(
+        TransactionData::new_move_call_with_gas_coins(
             signer,
             SUI_FRAMEWORK_PACKAGE_ID,
             coin::PAY_MODULE_NAME.to_owned(),
@@ -819,7 +897,7 @@ impl TransactionBuilder {
             .select_gas(signer, gas, gas_budget, vec![coin_object_id], gas_price)
             .await?;
 
-        TransactionData::new_move_call(
+        TransactionData::new_move_call_with_gas_coins(
             signer,
             SUI_FRAMEWORK_PACKAGE_ID,
             coin::PAY_MODULE_NAME.to_owned(),
@@ -894,7 +972,7 @@ impl TransactionBuilder {
             )
             .await?;
 
-        TransactionData::new_move_call(
+        TransactionData::new_move_call_with_gas_coins(
             signer,
             SUI_FRAMEWORK_PACKAGE_ID,
             coin::PAY_MODULE_NAME.to_owned(),
@@ -958,7 +1036,7 @@ impl TransactionBuilder {
             .select_gas(signer, gas, gas_budget, inputs, gas_price)
             .await?;
 
-        Ok(TransactionData::new(
+        Ok(TransactionData::new_with_gas_coins(
             TransactionKind::programmable(pt),
             signer,
             gas,
@@ -1027,11 +1105,7 @@ impl TransactionBuilder {
             builder.finish()
         };
         Ok(TransactionData::new_programmable(
-            signer,
-            vec![gas],
-            pt,
-            gas_budget,
-            gas_price,
+            signer, gas, pt, gas_budget, gas_price,
         ))
     }
 
@@ -1047,7 +1121,7 @@ impl TransactionBuilder {
         let gas = self
             .select_gas(signer, gas, gas_budget, vec![], gas_price)
             .await?;
-        TransactionData::new_move_call(
+        TransactionData::new_move_call_with_gas_coins(
             signer,
             SUI_SYSTEM_PACKAGE_ID,
             SUI_SYSTEM_MODULE_NAME.to_owned(),
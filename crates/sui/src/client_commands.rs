@@ -3,13 +3,14 @@
 
 use crate::{
     client_ptb::ptb::PTB,
+    upgrade_compatibility::{check_upgrade, parse_upgrade_policy, PolicyReport},
     verifier_meter::{AccumulatingMeter, Accumulator},
 };
 use std::{
     collections::{btree_map::Entry, BTreeMap},
     fmt::{Debug, Display, Formatter, Write},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
 };
@@ -23,9 +24,9 @@ use fastcrypto::{
     traits::ToFromBytes,
 };
 
-use move_binary_format::CompiledModule;
+use move_binary_format::{normalized, CompiledModule};
 use move_bytecode_verifier_meter::Scope;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
 use move_package::BuildConfig as MoveBuildConfig;
 use prometheus::Registry;
 use serde::Serialize;
@@ -47,6 +48,9 @@ use sui_move_build::{
     build_from_resolution_graph, check_invalid_dependencies, check_unpublished_dependencies,
     gather_published_ids, BuildConfig, CompiledPackage, PackageDependencies, PublishedAtError,
 };
+use sui_package_management::workspace::{
+    publish_workspace, Workspace, WorkspaceAction, WorkspacePackageOutcome,
+};
 use sui_package_management::LockCommand;
 use sui_replay::ReplayToolCommand;
 use sui_sdk::{
@@ -64,7 +68,7 @@ use sui_types::{
     gas_coin::GasCoin,
     message_envelope::Envelope,
     metrics::BytecodeVerifierMetrics,
-    move_package::UpgradeCap,
+    move_package::{UpgradeCap, UpgradePolicy},
     object::Owner,
     parse_sui_type_tag,
     signature::GenericSignature,
@@ -199,6 +203,34 @@ pub enum SuiClientCommands {
     #[clap(name = "chain-identifier")]
     ChainIdentifier,
 
+    /// Check whether the package can upgrade its previous version, under each upgrade policy,
+    /// without submitting the upgrade. The previous version is fetched from chain, or read from
+    /// a directory of compiled modules. Fails if the changes are not allowed by the given policy.
+    #[clap(name = "check-upgrade-compatibility")]
+    CheckUpgradeCompatibility {
+        /// Path to directory containing a Move package
+        #[clap(name = "package_path", global = true, default_value = ".")]
+        package_path: PathBuf,
+
+        /// Package build options
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+
+        /// ID of the on-chain package to check against (defaults to the `published-at` address of
+        /// the package)
+        #[clap(long, conflicts_with = "previous_build")]
+        package_id: Option<ObjectID>,
+
+        /// Directory containing the compiled modules (`.mv` files) of the previous version,
+        /// instead of fetching it from chain
+        #[clap(long)]
+        previous_build: Option<PathBuf>,
+
+        /// Upgrade policy the changes must be allowed by: compatible, additive or dep-only
+        #[clap(long, value_parser = parse_upgrade_policy, default_value = "compatible")]
+        policy: UpgradePolicy,
+    },
+
     /// Query a dynamic field by its address.
     #[clap(name = "dynamic-field")]
     DynamicFieldQuery {
@@ -395,6 +427,25 @@ pub enum SuiClientCommands {
         with_unpublished_dependencies: bool,
     },
 
+    /// Publish or upgrade a set of packages depending on each other, in dependency order.
+    /// Packages which were never published in the active environment are published, and
+    /// packages whose bytecode changed since they were last published are upgraded. The
+    /// addresses of published packages are recorded in their `Move.lock`.
+    #[clap(name = "publish-workspace")]
+    PublishWorkspace {
+        /// Paths to the directories containing the Move packages of the workspace
+        #[clap(name = "package_paths", required = true, num_args(1..))]
+        package_paths: Vec<PathBuf>,
+
+        /// Package build options
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+
+        /// Gas budget of each publish or upgrade transaction (in MIST)
+        #[clap(long)]
+        gas_budget: u64,
+    },
+
     /// Split a coin object into multiple coins.
     #[clap(group(ArgGroup::new("split").required(true).args(&["amounts", "count"])))]
     SplitCoin {
@@ -1082,6 +1133,25 @@ impl SuiClientCommands {
                 result
             }
 
+            SuiClientCommands::PublishWorkspace {
+                package_paths,
+                build_config,
+                gas_budget,
+            } => {
+                if build_config.test_mode {
+                    return Err(SuiError::ModulePublishFailure {
+                        error: "The `publish-workspace` subcommand should not be used with the \
+                                `--test` flag"
+                            .to_string(),
+                    }
+                    .into());
+                }
+                let workspace = Workspace::load(&package_paths)?;
+                let outcomes =
+                    publish_workspace(context, &workspace, build_config, gas_budget).await?;
+                SuiClientCommandResult::PublishWorkspace(outcomes)
+            }
+
             SuiClientCommands::VerifyBytecodeMeter {
                 protocol_version,
                 module_paths,
@@ -1693,6 +1763,66 @@ impl SuiClientCommands {
                     .await?;
                 SuiClientCommandResult::ChainIdentifier(ci)
             }
+            SuiClientCommands::CheckUpgradeCompatibility {
+                package_path,
+                build_config,
+                package_id,
+                previous_build,
+                policy,
+            } => {
+                let compiled_package = compile_package_simple(build_config, package_path)?;
+                let previous = match previous_build {
+                    Some(path) => read_compiled_modules(&path)?,
+                    None => {
+                        let package_id = match package_id {
+                            Some(id) => id,
+                            None => compiled_package.published_at.clone().map_err(|_| {
+                                anyhow!(
+                                    "No 'published-at' field in manifest, pass the ID of the \
+                                     package to check against with --package-id"
+                                )
+                            })?,
+                        };
+                        let client = context.get_client().await?;
+                        fetch_package_modules(client.read_api(), package_id).await?
+                    }
+                };
+
+                let previous: BTreeMap<_, _> = previous
+                    .iter()
+                    .map(|m| (m.self_id().name().to_string(), normalized::Module::new(m)))
+                    .collect();
+                let new: BTreeMap<_, _> = compiled_package
+                    .get_modules()
+                    .map(|m| {
+                        let mut module = m.clone();
+                        // Packages which were never published, or which are published through
+                        // their `Move.lock`, are built at `0x0`.
+                        let self_address = module.self_handle().address.0 as usize;
+                        let name = module.self_id().name().to_string();
+                        if let Some(previous) = previous.get(&name) {
+                            if module.address_identifiers[self_address] == AccountAddress::ZERO {
+                                module.address_identifiers[self_address] = previous.address;
+                            }
+                        }
+                        (name, normalized::Module::new(&module))
+                    })
+                    .collect();
+
+                let reports = check_upgrade(&previous, &new);
+                if reports
+                    .iter()
+                    .any(|r| r.policy as u8 == policy as u8 && !r.is_compatible())
+                {
+                    let mut message =
+                        format!("Upgrade is not allowed by the {policy} upgrade policy:\n");
+                    for report in &reports {
+                        write!(message, "{report}")?;
+                    }
+                    bail!(message.trim_end().to_string());
+                }
+                SuiClientCommandResult::CheckUpgradeCompatibility(reports)
+            }
             SuiClientCommands::SplitCoin {
                 coin_id,
                 amounts,
@@ -1968,6 +2098,51 @@ fn compile_package_simple(
     )?)
 }
 
+/// Reads the compiled modules (`.mv` files) in `path`.
+fn read_compiled_modules(path: &Path) -> Result<Vec<CompiledModule>, anyhow::Error> {
+    let mut modules = vec![];
+    for entry in fs::read_dir(path)
+        .with_context(|| format!("Failed to read directory {}", path.display()))?
+    {
+        let path = entry?.path();
+        if path.extension().map_or(true, |ext| ext != "mv") {
+            continue;
+        }
+        let bytes = fs::read(&path)
+            .with_context(|| format!("Failed to read module file {}", path.display()))?;
+        let module = CompiledModule::deserialize_with_defaults(&bytes)
+            .with_context(|| format!("Failed to deserialize module {}", path.display()))?;
+        modules.push(module);
+    }
+    ensure!(
+        !modules.is_empty(),
+        "No compiled modules found in {}",
+        path.display()
+    );
+    Ok(modules)
+}
+
+/// Fetches the modules of the on-chain package `package_id`.
+async fn fetch_package_modules(
+    read_api: &ReadApi,
+    package_id: ObjectID,
+) -> Result<Vec<CompiledModule>, anyhow::Error> {
+    let response = read_api
+        .get_object_with_options(package_id, SuiObjectDataOptions::default().with_bcs())
+        .await?;
+    let Some(SuiRawData::Package(package)) = response.data.and_then(|data| data.bcs) else {
+        bail!("Could not find package {package_id} on chain");
+    };
+    package
+        .module_map
+        .values()
+        .map(|bytes| {
+            CompiledModule::deserialize_with_defaults(bytes)
+                .with_context(|| format!("Failed to deserialize a module of package {package_id}"))
+        })
+        .collect()
+}
+
 pub(crate) async fn upgrade_package(
     read_api: &ReadApi,
     build_config: MoveBuildConfig,
@@ -2271,6 +2446,20 @@ impl Display for SuiClientCommandResult {
             | SuiClientCommandResult::Publish(response) => {
                 write!(writer, "{}", response)?;
             }
+            SuiClientCommandResult::PublishWorkspace(outcomes) => {
+                for outcome in outcomes {
+                    let action = match outcome.action {
+                        WorkspaceAction::Published => "Published",
+                        WorkspaceAction::Upgraded => "Upgraded",
+                        WorkspaceAction::Unchanged => "Unchanged",
+                    };
+                    write!(writer, "{action} {} at {}", outcome.name, outcome.latest_id)?;
+                    if let Some(digest) = outcome.digest {
+                        write!(writer, " in transaction {digest}")?;
+                    }
+                    writeln!(writer)?;
+                }
+            }
             SuiClientCommandResult::TransactionBlock(response) => {
                 write!(writer, "{}", response)?;
             }
@@ -2333,6 +2522,11 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::ChainIdentifier(ci) => {
                 writeln!(writer, "{}", ci)?;
             }
+            SuiClientCommandResult::CheckUpgradeCompatibility(reports) => {
+                for report in reports {
+                    write!(writer, "{report}")?;
+                }
+            }
             SuiClientCommandResult::SplitCoin(response) => {
                 write!(writer, "{}", response)?;
             }
@@ -2775,6 +2969,7 @@ pub enum SuiClientCommandResult {
     Balance(Vec<(Option<SuiCoinMetadata>, Vec<Coin>)>, bool),
     Call(SuiTransactionBlockResponse),
     ChainIdentifier(String),
+    CheckUpgradeCompatibility(Vec<PolicyReport>),
     DynamicFieldQuery(DynamicFieldPage),
     DryRun(DryRunTransactionBlockResponse),
     Envs(Vec<SuiEnv>, Option<String>),
@@ -2791,6 +2986,7 @@ pub enum SuiClientCommandResult {
     PaySui(SuiTransactionBlockResponse),
     PTB(SuiTransactionBlockResponse),
     Publish(SuiTransactionBlockResponse),
+    PublishWorkspace(Vec<WorkspacePackageOutcome>),
     RawObject(SuiObjectResponse),
     SerializedSignedTransaction(SenderSignedData),
     SerializedUnsignedTransaction(TransactionData),
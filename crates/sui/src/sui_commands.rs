@@ -10,8 +10,10 @@ use crate::validator_commands::SuiValidatorCommand;
 use anyhow::{anyhow, bail};
 use clap::*;
 use fastcrypto::traits::KeyPair;
+use move_package::source_package::layout::SourcePackageLayout;
 use move_package::BuildConfig;
 use rand::rngs::OsRng;
+use std::collections::BTreeMap;
 use std::io::{stderr, stdout, Write};
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
@@ -25,9 +27,11 @@ use sui_config::{
 use sui_config::{
     SUI_BENCHMARK_GENESIS_GAS_KEYSTORE_FILENAME, SUI_GENESIS_FILENAME, SUI_KEYSTORE_FILENAME,
 };
+use sui_json_rpc_types::{SuiObjectData, SuiObjectDataOptions, SuiRawData};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_move::{self, execute_move_command};
-use sui_move_build::SuiPackageHooks;
+use sui_move_build::dependency_verification::published_dependencies;
+use sui_move_build::{build_from_resolution_graph, BuildConfig as SuiBuildConfig, SuiPackageHooks};
 use sui_sdk::sui_client_config::{SuiClientConfig, SuiEnv};
 use sui_sdk::wallet_context::WalletContext;
 use sui_swarm::memory::Swarm;
@@ -318,12 +322,74 @@ impl SuiCommand {
                 package_path,
                 build_config,
                 cmd,
-            } => execute_move_command(package_path, build_config, cmd),
+            } => match cmd {
+                sui_move::Command::Build(build) if build.verify_deps_on_chain => {
+                    build_verifying_deps_on_chain(package_path, build_config, &build).await
+                }
+                cmd => execute_move_command(package_path, build_config, cmd),
+            },
             SuiCommand::FireDrill { fire_drill } => run_fire_drill(fire_drill).await,
         }
     }
 }
 
+/// Builds the package at `package_path` like `build`, checking that its dependencies with a
+/// published address match their bytecode on the network of the active client environment
+/// before outputting anything.
+async fn build_verifying_deps_on_chain(
+    package_path: Option<PathBuf>,
+    mut build_config: BuildConfig,
+    build: &sui_move::build::Build,
+) -> Result<(), anyhow::Error> {
+    let config_path = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
+    let context = WalletContext::new(&config_path, None, None)?;
+    let client = context.get_client().await?;
+    let chain_id = client.read_api().get_chain_identifier().await?;
+
+    let package_path = package_path.unwrap_or_else(|| PathBuf::from("."));
+    let package_root = SourcePackageLayout::try_find_root(&package_path.canonicalize()?)?;
+    if build_config.lock_file.is_none() {
+        build_config.lock_file = Some(package_root.join(SourcePackageLayout::Lock.path()));
+    }
+    let resolution_graph = SuiBuildConfig {
+        config: build_config,
+        run_bytecode_verifier: true,
+        print_diags_to_stderr: true,
+    }
+    .resolution_graph(&package_root)?;
+    let dependencies = published_dependencies(&resolution_graph, &chain_id)?;
+    let compiled = build_from_resolution_graph(
+        package_root.clone(),
+        resolution_graph,
+        /* run_bytecode_verifier */ true,
+        /* print_diags_to_stderr */ true,
+    )?;
+
+    let mut on_chain = BTreeMap::new();
+    for dependency in &dependencies {
+        let response = client
+            .read_api()
+            .get_object_with_options(dependency.storage_id, SuiObjectDataOptions::bcs_lossless())
+            .await?;
+        // Packages which cannot be found are reported as such by the verification.
+        if let Ok(SuiObjectData {
+            bcs: Some(SuiRawData::Package(package)),
+            ..
+        }) = response.into_object()
+        {
+            on_chain.insert(dependency.storage_id, package.module_map);
+        }
+    }
+    compiled.verify_published_dependencies(&dependencies, &on_chain)?;
+    sui_move::build::Build::output_package(
+        compiled,
+        &package_root,
+        build.with_unpublished_dependencies,
+        build.dump_bytecode_as_base64,
+        build.generate_struct_layouts,
+    )
+}
+
 async fn genesis(
     from_config: Option<PathBuf>,
     write_config: Option<PathBuf>,
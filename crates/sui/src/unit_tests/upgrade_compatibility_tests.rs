@@ -0,0 +1,173 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use move_binary_format::file_format::{Ability, AbilitySet, Visibility};
+use move_binary_format::normalized::{Bytecode, Field, Function, Module, Struct, Type};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use sui_types::move_package::UpgradePolicy;
+
+use crate::upgrade_compatibility::{check_upgrade, PolicyReport};
+
+fn module(structs: Vec<(&str, Struct)>, functions: Vec<(&str, Function)>) -> Module {
+    Module {
+        file_format_version: 6,
+        address: AccountAddress::ONE,
+        name: Identifier::new("m").unwrap(),
+        dependencies: vec![],
+        friends: vec![],
+        structs: structs
+            .into_iter()
+            .map(|(name, s)| (Identifier::new(name).unwrap(), s))
+            .collect(),
+        functions: functions
+            .into_iter()
+            .map(|(name, f)| (Identifier::new(name).unwrap(), f))
+            .collect(),
+        constants: vec![],
+    }
+}
+
+fn coin(abilities: AbilitySet, fields: Vec<(&str, Type)>) -> Struct {
+    Struct {
+        abilities,
+        type_parameters: vec![],
+        fields: fields
+            .into_iter()
+            .map(|(name, type_)| Field {
+                name: Identifier::new(name).unwrap(),
+                type_,
+            })
+            .collect(),
+    }
+}
+
+fn function(visibility: Visibility, parameters: Vec<Type>, code: Vec<Bytecode>) -> Function {
+    Function {
+        visibility,
+        is_entry: false,
+        type_parameters: vec![],
+        parameters,
+        return_: vec![],
+        code,
+    }
+}
+
+fn package(module: Module) -> BTreeMap<String, Module> {
+    BTreeMap::from([(module.name.to_string(), module)])
+}
+
+fn report(reports: &[PolicyReport], policy: UpgradePolicy) -> Vec<String> {
+    reports
+        .iter()
+        .find(|r| r.policy as u8 == policy as u8)
+        .unwrap()
+        .violations
+        .iter()
+        .map(|v| format!("{}: {}", v.item.as_deref().unwrap_or("-"), v.explanation))
+        .collect()
+}
+
+#[test]
+fn test_identical_package_is_compatible_under_all_policies() {
+    let m = module(
+        vec![("Coin", coin(AbilitySet::EMPTY, vec![("value", Type::U64)]))],
+        vec![(
+            "f",
+            function(Visibility::Public, vec![], vec![Bytecode::Ret]),
+        )],
+    );
+    let reports = check_upgrade(&package(m.clone()), &package(m));
+    assert!(reports.iter().all(|r| r.is_compatible()));
+}
+
+#[test]
+fn test_private_changes_only_allowed_by_compatible() {
+    let previous = module(
+        vec![],
+        vec![(
+            "f",
+            function(Visibility::Private, vec![], vec![Bytecode::Ret]),
+        )],
+    );
+    let new = module(
+        vec![],
+        vec![
+            (
+                "f",
+                function(Visibility::Private, vec![Type::U64], vec![Bytecode::Ret]),
+            ),
+            (
+                "g",
+                function(Visibility::Public, vec![], vec![Bytecode::Ret]),
+            ),
+        ],
+    );
+    let reports = check_upgrade(&package(previous), &package(new));
+    assert!(report(&reports, UpgradePolicy::Compatible).is_empty());
+    assert_eq!(
+        report(&reports, UpgradePolicy::Additive),
+        ["function f: function signature changed"]
+    );
+    assert_eq!(
+        report(&reports, UpgradePolicy::DepOnly),
+        [
+            "function f: function signature changed",
+            "function g: function was added"
+        ]
+    );
+}
+
+#[test]
+fn test_every_incompatible_change_is_listed() {
+    let previous = module(
+        vec![(
+            "Coin",
+            coin(
+                AbilitySet::EMPTY | Ability::Store,
+                vec![("value", Type::U64)],
+            ),
+        )],
+        vec![
+            (
+                "f",
+                function(Visibility::Public, vec![], vec![Bytecode::Ret]),
+            ),
+            (
+                "g",
+                function(Visibility::Public, vec![], vec![Bytecode::Ret]),
+            ),
+        ],
+    );
+    let new = module(
+        vec![("Coin", coin(AbilitySet::EMPTY, vec![("value", Type::U128)]))],
+        vec![(
+            "f",
+            function(Visibility::Friend, vec![Type::Bool], vec![Bytecode::Ret]),
+        )],
+    );
+    let reports = check_upgrade(&package(previous), &package(new));
+    assert_eq!(
+        report(&reports, UpgradePolicy::Compatible),
+        [
+            "struct Coin: struct lost abilities store",
+            "struct Coin: fields changed from { value: u64 } to { value: u128 }, which changes \
+             the layout of existing values",
+            "function f: public function became public(friend), but other packages may call it",
+            "function f: parameters changed from () to (bool)",
+            "function g: public function was removed, but other packages may call it",
+        ]
+    );
+}
+
+#[test]
+fn test_removed_module() {
+    let previous = module(vec![], vec![]);
+    let reports = check_upgrade(&package(previous), &BTreeMap::new());
+    for report in reports {
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].module, "m");
+    }
+}
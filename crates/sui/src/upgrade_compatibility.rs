@@ -0,0 +1,472 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks whether a new version of a package can upgrade its previous version under each upgrade
+//! policy, without submitting the upgrade.
+//!
+//! The checks follow the ones the adapter runs when executing an upgrade, but instead of stopping
+//! at the first incompatibility they list every change a policy does not allow.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use move_binary_format::compatibility::{Compatibility, InclusionCheck};
+use move_binary_format::file_format::{AbilitySet, Visibility};
+use move_binary_format::normalized::{Function, Module, Struct, Type};
+use serde::{Serialize, Serializer};
+use sui_types::move_package::UpgradePolicy;
+
+/// A change between two versions of a package which an upgrade policy does not allow.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UpgradeViolation {
+    pub module: String,
+    /// The struct or function the change is about, if any.
+    pub item: Option<String>,
+    pub explanation: String,
+}
+
+/// Changes which an upgrade policy does not allow.
+#[derive(Clone, Debug, Serialize)]
+pub struct PolicyReport {
+    #[serde(serialize_with = "serialize_policy")]
+    pub policy: UpgradePolicy,
+    pub violations: Vec<UpgradeViolation>,
+}
+
+impl PolicyReport {
+    pub fn is_compatible(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+fn serialize_policy<S: Serializer>(policy: &UpgradePolicy, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_str(policy)
+}
+
+/// Parses an upgrade policy from its name, as a command line argument.
+pub fn parse_upgrade_policy(policy: &str) -> Result<UpgradePolicy, String> {
+    match policy.to_ascii_lowercase().replace('_', "-").as_str() {
+        "compatible" => Ok(UpgradePolicy::Compatible),
+        "additive" => Ok(UpgradePolicy::Additive),
+        "dep-only" => Ok(UpgradePolicy::DepOnly),
+        _ => Err(format!(
+            "Unknown upgrade policy {policy}, expected one of compatible, additive or dep-only"
+        )),
+    }
+}
+
+/// Checks the modules of the new version of a package against the modules of its previous
+/// version, under every upgrade policy from the most to the least permissive.
+///
+/// The modules of the new version must have the same self address as the previous ones.
+pub fn check_upgrade(
+    previous: &BTreeMap<String, Module>,
+    new: &BTreeMap<String, Module>,
+) -> Vec<PolicyReport> {
+    [
+        UpgradePolicy::Compatible,
+        UpgradePolicy::Additive,
+        UpgradePolicy::DepOnly,
+    ]
+    .into_iter()
+    .map(|policy| PolicyReport {
+        policy,
+        violations: check_policy(policy, previous, new),
+    })
+    .collect()
+}
+
+fn check_policy(
+    policy: UpgradePolicy,
+    previous: &BTreeMap<String, Module>,
+    new: &BTreeMap<String, Module>,
+) -> Vec<UpgradeViolation> {
+    let mut violations = vec![];
+    for (name, previous_module) in previous {
+        let Some(new_module) = new.get(name) else {
+            violations.push(UpgradeViolation {
+                module: name.clone(),
+                item: None,
+                explanation: "module was removed, packages cannot remove modules".to_string(),
+            });
+            continue;
+        };
+
+        let mut module_violations = ModuleChecker {
+            module: name,
+            violations: vec![],
+        };
+        match policy {
+            UpgradePolicy::Compatible => {
+                module_violations.check_compatible(previous_module, new_module)
+            }
+            UpgradePolicy::Additive => {
+                module_violations.check_inclusion(previous_module, new_module, false)
+            }
+            UpgradePolicy::DepOnly => {
+                module_violations.check_inclusion(previous_module, new_module, true)
+            }
+        }
+
+        // The explanations are derived from the checks the adapter runs, which are the source of
+        // truth: make sure that a module they reject is never reported as compatible.
+        if module_violations.violations.is_empty()
+            && !adapter_accepts(policy, previous_module, new_module)
+        {
+            module_violations.violation(None, "module is incompatible with its previous version");
+        }
+        violations.extend(module_violations.violations);
+    }
+    violations
+}
+
+/// Runs the same compatibility check as the adapter when executing an upgrade with `policy`.
+fn adapter_accepts(policy: UpgradePolicy, previous: &Module, new: &Module) -> bool {
+    match policy {
+        UpgradePolicy::Additive => InclusionCheck::Subset.check(previous, new),
+        UpgradePolicy::DepOnly => InclusionCheck::Equal.check(previous, new),
+        UpgradePolicy::Compatible => Compatibility {
+            check_struct_and_pub_function_linking: true,
+            check_struct_layout: true,
+            check_friend_linking: false,
+            check_private_entry_linking: false,
+            disallowed_new_abilities: AbilitySet::ALL,
+            disallow_change_struct_type_params: true,
+        }
+        .check(previous, new),
+    }
+    .is_ok()
+}
+
+struct ModuleChecker<'a> {
+    module: &'a str,
+    violations: Vec<UpgradeViolation>,
+}
+
+impl ModuleChecker<'_> {
+    fn violation(&mut self, item: Option<String>, explanation: impl Into<String>) {
+        self.violations.push(UpgradeViolation {
+            module: self.module.to_string(),
+            item,
+            explanation: explanation.into(),
+        })
+    }
+
+    /// Checks that code depending on the previous version of the module still links against the
+    /// new one, and that values of its structs keep their layout.
+    fn check_compatible(&mut self, previous: &Module, new: &Module) {
+        self.check_identity(previous, new);
+
+        for (name, previous_struct) in &previous.structs {
+            let item = Some(format!("struct {name}"));
+            let Some(new_struct) = new.structs.get(name) else {
+                self.violation(
+                    item,
+                    "struct was removed, but code and objects using it may already exist",
+                );
+                continue;
+            };
+            self.check_struct_signature(item, previous_struct, new_struct);
+        }
+
+        for (name, previous_function) in &previous.functions {
+            // Only public functions can be linked against from other packages.
+            if previous_function.visibility != Visibility::Public {
+                continue;
+            }
+            let item = Some(format!("function {name}"));
+            let Some(new_function) = new.functions.get(name) else {
+                self.violation(
+                    item,
+                    "public function was removed, but other packages may call it",
+                );
+                continue;
+            };
+            if new_function.visibility != Visibility::Public {
+                self.violation(
+                    item.clone(),
+                    format!(
+                        "public function became {}, but other packages may call it",
+                        visibility(new_function.visibility)
+                    ),
+                );
+            }
+            self.check_function_signature(item, previous_function, new_function);
+        }
+    }
+
+    /// Checks that the previous version of the module is included in the new one, with the exact
+    /// same structs and functions, including their code. With `exact`, the new version can't add
+    /// structs, functions or friends either.
+    fn check_inclusion(&mut self, previous: &Module, new: &Module, exact: bool) {
+        self.check_identity(previous, new);
+        if previous.file_format_version > new.file_format_version {
+            self.violation(
+                None,
+                format!(
+                    "bytecode version went down from {} to {}",
+                    previous.file_format_version, new.file_format_version
+                ),
+            );
+        }
+
+        for (name, previous_struct) in &previous.structs {
+            let item = Some(format!("struct {name}"));
+            match new.structs.get(name) {
+                None => self.violation(item, "struct was removed"),
+                Some(new_struct) if new_struct != previous_struct => {
+                    // Any difference is a violation, but the signature checks explain most.
+                    let reported = self.violations.len();
+                    self.check_struct_signature(item.clone(), previous_struct, new_struct);
+                    if self.violations.len() == reported {
+                        self.violation(item, "struct declaration changed");
+                    }
+                }
+                Some(_) => (),
+            }
+        }
+
+        for (name, previous_function) in &previous.functions {
+            let item = Some(format!("function {name}"));
+            match new.functions.get(name) {
+                None => self.violation(item, "function was removed"),
+                Some(new_function) if new_function == previous_function => (),
+                Some(new_function) => {
+                    if !same_signature(previous_function, new_function) {
+                        self.violation(item, "function signature changed")
+                    } else if previous_function.code != new_function.code {
+                        self.violation(item, "function body changed")
+                    } else {
+                        self.violation(item, "function visibility or entry modifier changed")
+                    }
+                }
+            }
+        }
+
+        if exact {
+            for name in new.structs.keys() {
+                if !previous.structs.contains_key(name) {
+                    self.violation(Some(format!("struct {name}")), "struct was added");
+                }
+            }
+            for name in new.functions.keys() {
+                if !previous.functions.contains_key(name) {
+                    self.violation(Some(format!("function {name}")), "function was added");
+                }
+            }
+            if previous.friends.len() != new.friends.len() {
+                self.violation(None, "friend declarations changed");
+            }
+        }
+    }
+
+    fn check_identity(&mut self, previous: &Module, new: &Module) {
+        if previous.address != new.address || previous.name != new.name {
+            self.violation(
+                None,
+                format!(
+                    "module is {}::{} in the new version, instead of {}::{}",
+                    new.address.to_canonical_display(/* with_prefix */ true),
+                    new.name,
+                    previous
+                        .address
+                        .to_canonical_display(/* with_prefix */ true),
+                    previous.name,
+                ),
+            );
+        }
+    }
+
+    fn check_struct_signature(&mut self, item: Option<String>, previous: &Struct, new: &Struct) {
+        if previous.abilities != new.abilities {
+            let removed = abilities_not_in(previous.abilities, new.abilities);
+            let added = abilities_not_in(new.abilities, previous.abilities);
+            let explanation = match (removed.is_empty(), added.is_empty()) {
+                (false, true) => format!("struct lost abilities {removed}"),
+                (true, false) => format!("struct gained abilities {added}"),
+                _ => format!("struct lost abilities {removed} and gained abilities {added}"),
+            };
+            self.violation(item.clone(), explanation);
+        }
+
+        if previous.type_parameters.len() != new.type_parameters.len() {
+            self.violation(
+                item.clone(),
+                format!(
+                    "number of type parameters changed from {} to {}",
+                    previous.type_parameters.len(),
+                    new.type_parameters.len()
+                ),
+            );
+        } else {
+            for (i, (previous_param, new_param)) in previous
+                .type_parameters
+                .iter()
+                .zip(&new.type_parameters)
+                .enumerate()
+            {
+                if previous_param.is_phantom != new_param.is_phantom {
+                    self.violation(
+                        item.clone(),
+                        format!("type parameter {i} was made phantom or non-phantom"),
+                    );
+                }
+                if previous_param.constraints != new_param.constraints {
+                    self.violation(
+                        item.clone(),
+                        format!(
+                            "constraints of type parameter {i} changed from {} to {}",
+                            constraints(previous_param.constraints),
+                            constraints(new_param.constraints)
+                        ),
+                    );
+                }
+            }
+        }
+
+        if previous.fields != new.fields {
+            let previous_fields: Vec<_> = previous
+                .fields
+                .iter()
+                .map(|f| format!("{}: {}", f.name, f.type_))
+                .collect();
+            let new_fields: Vec<_> = new
+                .fields
+                .iter()
+                .map(|f| format!("{}: {}", f.name, f.type_))
+                .collect();
+            self.violation(
+                item,
+                format!(
+                    "fields changed from {{ {} }} to {{ {} }}, which changes the layout of \
+                     existing values",
+                    previous_fields.join(", "),
+                    new_fields.join(", ")
+                ),
+            );
+        }
+    }
+
+    fn check_function_signature(
+        &mut self,
+        item: Option<String>,
+        previous: &Function,
+        new: &Function,
+    ) {
+        if previous.parameters != new.parameters {
+            self.violation(
+                item.clone(),
+                format!(
+                    "parameters changed from ({}) to ({})",
+                    types(&previous.parameters),
+                    types(&new.parameters)
+                ),
+            );
+        }
+        if previous.return_ != new.return_ {
+            self.violation(
+                item.clone(),
+                format!(
+                    "return type changed from ({}) to ({})",
+                    types(&previous.return_),
+                    types(&new.return_)
+                ),
+            );
+        }
+        if previous.type_parameters.len() != new.type_parameters.len() {
+            self.violation(
+                item,
+                format!(
+                    "number of type parameters changed from {} to {}",
+                    previous.type_parameters.len(),
+                    new.type_parameters.len()
+                ),
+            );
+            return;
+        }
+        for (i, (previous_param, new_param)) in previous
+            .type_parameters
+            .iter()
+            .zip(&new.type_parameters)
+            .enumerate()
+        {
+            // Constraints can be relaxed, as existing callers satisfy the previous ones.
+            if !new_param.is_subset(*previous_param) {
+                self.violation(
+                    item.clone(),
+                    format!(
+                        "constraints of type parameter {i} were tightened from {} to {}",
+                        constraints(*previous_param),
+                        constraints(*new_param)
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn same_signature(previous: &Function, new: &Function) -> bool {
+    previous.parameters == new.parameters
+        && previous.return_ == new.return_
+        && previous.type_parameters == new.type_parameters
+}
+
+fn visibility(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Private => "private",
+        Visibility::Public => "public",
+        Visibility::Friend => "public(friend)",
+    }
+}
+
+/// Lists the abilities of `set` which are not in `other`.
+fn abilities_not_in(set: AbilitySet, other: AbilitySet) -> String {
+    let names: Vec<_> = set
+        .into_iter()
+        .filter(|ability| !other.has_ability(*ability))
+        .map(|ability| format!("{ability:?}").to_lowercase())
+        .collect();
+    names.join(", ")
+}
+
+fn constraints(set: AbilitySet) -> String {
+    if set == AbilitySet::EMPTY {
+        "none".to_string()
+    } else {
+        abilities_not_in(set, AbilitySet::EMPTY).replace(", ", " + ")
+    }
+}
+
+fn types(types: &[Type]) -> String {
+    let types: Vec<_> = types.iter().map(|t| t.to_string()).collect();
+    types.join(", ")
+}
+
+impl fmt::Display for PolicyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.violations.is_empty() {
+            return writeln!(f, "{}: compatible", self.policy);
+        }
+        writeln!(
+            f,
+            "{}: {} incompatible change(s)",
+            self.policy,
+            self.violations.len()
+        )?;
+        for violation in &self.violations {
+            match &violation.item {
+                Some(item) => writeln!(
+                    f,
+                    "  {}::{item}: {}",
+                    violation.module, violation.explanation
+                )?,
+                None => writeln!(f, "  {}: {}", violation.module, violation.explanation)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "unit_tests/upgrade_compatibility_tests.rs"]
+mod upgrade_compatibility_tests;
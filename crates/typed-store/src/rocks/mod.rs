@@ -708,6 +708,19 @@ impl MetricConf {
 const CF_METRICS_REPORT_PERIOD_MILLIS: u64 = 1000;
 const METRICS_ERROR: i64 = -1;
 
+/// Size and compaction state of a column family, as reported by RocksDB properties.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ColumnFamilyStats {
+    pub total_sst_files_size: i64,
+    pub total_blob_files_size: i64,
+    pub size_all_mem_tables: i64,
+    pub estimated_num_keys: i64,
+    pub mem_table_flush_pending: i64,
+    pub compaction_pending: i64,
+    pub num_running_compactions: i64,
+    pub estimate_pending_compaction_bytes: i64,
+}
+
 /// An interface to a rocksDB database, keyed by a columnfamily
 #[derive(Clone, Debug)]
 pub struct DBMap<K, V> {
@@ -1145,6 +1158,36 @@ impl<K, V> DBMap<K, V> {
         })
     }
 
+    /// Returns the size and compaction state of every column family in the DB this table
+    /// belongs to, keyed by column family name.
+    pub fn column_family_stats(
+        &self,
+    ) -> Result<BTreeMap<String, ColumnFamilyStats>, TypedStoreError> {
+        let cf_names = list_tables(self.rocksdb.path().to_path_buf())
+            .map_err(|e| TypedStoreError::RocksDBError(e.to_string()))?;
+        let mut stats = BTreeMap::new();
+        for cf_name in cf_names {
+            let Some(cf) = self.rocksdb.cf_handle(&cf_name) else {
+                continue;
+            };
+            let property = |name| Self::get_int_property(&self.rocksdb, &cf, name);
+            let cf_stats = ColumnFamilyStats {
+                total_sst_files_size: property(properties::TOTAL_SST_FILES_SIZE)?,
+                total_blob_files_size: property(ROCKSDB_PROPERTY_TOTAL_BLOB_FILES_SIZE)?,
+                size_all_mem_tables: property(properties::SIZE_ALL_MEM_TABLES)?,
+                estimated_num_keys: property(properties::ESTIMATE_NUM_KEYS)?,
+                mem_table_flush_pending: property(properties::MEM_TABLE_FLUSH_PENDING)?,
+                compaction_pending: property(properties::COMPACTION_PENDING)?,
+                num_running_compactions: property(properties::NUM_RUNNING_COMPACTIONS)?,
+                estimate_pending_compaction_bytes: property(
+                    properties::ESTIMATE_PENDING_COMPACTION_BYTES,
+                )?,
+            };
+            stats.insert(cf_name, cf_stats);
+        }
+        Ok(stats)
+    }
+
     // Creates metrics and context for tracking an iterator usage and performance.
     fn create_iter_context(
         &self,
@@ -1014,6 +1014,22 @@ async fn test_multi_insert(#[values(true, false)] is_transactional: bool) {
     }
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_column_family_stats(#[values(true, false)] is_transactional: bool) {
+    let db: DBMap<i32, String> = open_map(temp_dir(), Some("table"), is_transactional);
+    db.multi_insert((0..101).map(|i| (i, i.to_string())))
+        .expect("Failed to multi-insert");
+    db.flush().expect("Failed to flush");
+
+    let stats = db
+        .column_family_stats()
+        .expect("Failed to get column family stats");
+    let table = stats.get("table").expect("Missing table stats");
+    assert!(table.total_sst_files_size > 0);
+    assert!(table.estimated_num_keys > 0);
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_checkpoint(#[values(true, false)] is_transactional: bool) {